@@ -0,0 +1,213 @@
+//! Dead branch detection
+//!
+//! `find_unreachable` only sees blocks with no path from entry. A block can
+//! also be dead while still graph-reachable, when every path to it goes
+//! through the never-taken arm of a branch on a literal constant (`if false`,
+//! `while true`, `switchInt(const false)`). This module finds those arms and
+//! the blocks that only they lead to.
+//!
+//! The check is deliberately conservative: only a literal `true`/`false`
+//! condition counts, never a variable or expression that merely happens to be
+//! constant.
+
+use crate::cfg::analysis::find_entry;
+use crate::cfg::{BasicBlock, BlockId, Cfg, EdgeType};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// A branch on a literal constant and what its never-taken arm hides
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeadBranch {
+    /// The branching block
+    pub block_id: BlockId,
+    /// The constant the condition always evaluates to
+    pub condition: bool,
+    /// Targets of the arms that are never taken
+    pub dead_arms: Vec<BlockId>,
+    /// Blocks reachable from entry only through the dead arms, sorted
+    pub dead_blocks: Vec<BlockId>,
+}
+
+/// Find branch arms never taken because their condition is a literal constant
+///
+/// A branch qualifies when its block's last statement is a literal
+/// condition and it keeps at least one live arm. With a `false` condition the
+/// `TrueBranch` arms are dead; with `true` the `FalseBranch` and `LoopExit`
+/// arms are. Blocks already unreachable in the graph are not repeated in
+/// `dead_blocks`. Results are sorted by block ID.
+pub fn detect_dead_branches(cfg: &Cfg) -> Vec<DeadBranch> {
+    let Some(entry) = find_entry(cfg) else {
+        return Vec::new();
+    };
+    let reachable = reachable_without(cfg, entry, &HashSet::new());
+
+    let mut branches: Vec<DeadBranch> = cfg
+        .node_indices()
+        .filter(|node| reachable.contains(node))
+        .filter_map(|node| {
+            let condition = literal_condition(&cfg[node])?;
+            let (dead, live): (Vec<_>, Vec<_>) = cfg
+                .edges_directed(node, Direction::Outgoing)
+                .partition(|edge| arm_is_dead(edge.weight(), condition));
+            if dead.is_empty() || live.is_empty() {
+                return None;
+            }
+
+            let dead_edges: HashSet<EdgeIndex> = dead.iter().map(|edge| edge.id()).collect();
+            let still_reachable = reachable_without(cfg, entry, &dead_edges);
+            let mut dead_arms: Vec<BlockId> = dead.iter().map(|edge| cfg[edge.target()].id).collect();
+            dead_arms.sort_unstable();
+            dead_arms.dedup();
+            let mut dead_blocks: Vec<BlockId> = reachable
+                .difference(&still_reachable)
+                .map(|&n| cfg[n].id)
+                .collect();
+            dead_blocks.sort_unstable();
+
+            Some(DeadBranch {
+                block_id: cfg[node].id,
+                condition,
+                dead_arms,
+                dead_blocks,
+            })
+        })
+        .collect();
+    branches.sort_by_key(|branch| branch.block_id);
+    branches
+}
+
+/// The literal value of the condition ending `block`, if it is one
+///
+/// Recognizes a bare literal, `if`/`elif`/`while` headers (with an optional
+/// body after `{` or `:`), and MIR's `switchInt(const ...)`.
+fn literal_condition(block: &BasicBlock) -> Option<bool> {
+    let statement = block.statements.last()?.trim();
+    let condition = if let Some(operand) = statement
+        .strip_prefix("switchInt(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        operand.trim().strip_prefix("const ")?
+    } else {
+        let header = ["if ", "elif ", "while "]
+            .iter()
+            .find_map(|keyword| statement.strip_prefix(keyword))
+            .unwrap_or(statement);
+        header.split(['{', ':']).next()?
+    };
+
+    let mut condition = condition.trim();
+    while let Some(inner) = condition.strip_prefix('(').and_then(|c| c.strip_suffix(')')) {
+        condition = inner.trim();
+    }
+    match condition {
+        "true" | "True" => Some(true),
+        "false" | "False" => Some(false),
+        _ => None,
+    }
+}
+
+fn arm_is_dead(edge: &EdgeType, condition: bool) -> bool {
+    if condition {
+        matches!(edge, EdgeType::FalseBranch | EdgeType::LoopExit)
+    } else {
+        matches!(edge, EdgeType::TrueBranch)
+    }
+}
+
+/// Blocks reachable from `entry` when `removed` edges are never followed
+fn reachable_without(cfg: &Cfg, entry: NodeIndex, removed: &HashSet<EdgeIndex>) -> HashSet<NodeIndex> {
+    let mut seen = HashSet::from([entry]);
+    let mut stack = vec![entry];
+    while let Some(node) = stack.pop() {
+        for edge in cfg.edges_directed(node, Direction::Outgoing) {
+            if !removed.contains(&edge.id()) && seen.insert(edge.target()) {
+                stack.push(edge.target());
+            }
+        }
+    }
+    seen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{BlockKind, Terminator};
+    use petgraph::graph::DiGraph;
+
+    fn block(id: BlockId, statements: &[&str], terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id,
+            kind: if id == 0 { BlockKind::Entry } else { BlockKind::Normal },
+            statements: statements.iter().map(|s| s.to_string()).collect(),
+            terminator,
+            source_location: None,
+        }
+    }
+
+    /// `if <condition> { launch(); log() } else { idle() }; finish()`
+    fn guarded(condition: &str) -> Cfg {
+        let mut g: Cfg = DiGraph::new();
+        let b0 = g.add_node(block(0, &[condition], Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, &["launch()"], Terminator::Goto { target: 4 }));
+        let b2 = g.add_node(block(2, &["idle()"], Terminator::Goto { target: 3 }));
+        let b3 = g.add_node(block(3, &["finish()"], Terminator::Return));
+        let b4 = g.add_node(block(4, &["log()"], Terminator::Goto { target: 3 }));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b4, EdgeType::Fallthrough);
+        g.add_edge(b4, b3, EdgeType::Fallthrough);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+        g
+    }
+
+    #[test]
+    fn test_if_false_hides_then_branch() {
+        let dead = detect_dead_branches(&guarded("if false"));
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].block_id, 0);
+        assert!(!dead[0].condition);
+        assert_eq!(dead[0].dead_arms, vec![1]);
+        // The merge block stays live through the else arm
+        assert_eq!(dead[0].dead_blocks, vec![1, 4]);
+    }
+
+    #[test]
+    fn test_if_true_hides_else_branch() {
+        let dead = detect_dead_branches(&guarded("if (true) {"));
+        assert_eq!(dead.len(), 1);
+        assert!(dead[0].condition);
+        assert_eq!(dead[0].dead_arms, vec![2]);
+        assert_eq!(dead[0].dead_blocks, vec![2]);
+    }
+
+    #[test]
+    fn test_literal_condition_forms() {
+        let cond = |s: &str| literal_condition(&block(0, &[s], Terminator::Return));
+        assert_eq!(cond("false"), Some(false));
+        assert_eq!(cond("if False:"), Some(false));
+        assert_eq!(cond("while true { tick() }"), Some(true));
+        assert_eq!(cond("switchInt(const false)"), Some(false));
+        assert_eq!(cond("if x == false"), None);
+        assert_eq!(cond("if DEBUG"), None);
+        assert_eq!(cond("switchInt(move _2)"), None);
+        assert_eq!(literal_condition(&block(0, &[], Terminator::Return)), None);
+    }
+
+    #[test]
+    fn test_non_literal_guard_not_reported() {
+        assert!(detect_dead_branches(&guarded("if enabled")).is_empty());
+    }
+
+    #[test]
+    fn test_branch_without_live_arm_not_reported() {
+        // `if false { body }` built without an edge past the body: nothing to keep alive
+        let mut g: Cfg = DiGraph::new();
+        let b0 = g.add_node(block(0, &["if false"], Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, &["body()"], Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        assert!(detect_dead_branches(&g).is_empty());
+    }
+}
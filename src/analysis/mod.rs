@@ -15,6 +15,12 @@ use anyhow::Result;
 use serde::Serialize;
 use std::collections::HashMap;
 
+pub mod dead_branches;
+pub mod redundant;
+
+pub use dead_branches::detect_dead_branches;
+pub use redundant::{detect_redundant_branches, RedundantBranch};
+
 // Re-export key types from Magellan for convenience
 pub use magellan::CodeGraph;
 
@@ -791,6 +797,118 @@ impl MagellanBridge {
     }
 }
 
+/// Direction of a call-graph traversal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallDirection {
+    /// Functions that (in)directly call the target
+    Callers,
+    /// Functions the target (in)directly calls
+    Callees,
+}
+
+/// Find transitive callers or callees of a function with their call-graph distance
+///
+/// Walks Magellan's call graph breadth-first. Magellan models each call site
+/// as a `Call` node: `caller -CALLER-> call -CALLS-> callee`, so one level of
+/// the call graph is two hops in the underlying graph.
+///
+/// # Arguments
+///
+/// * `backend` - GraphBackend holding Magellan's call graph
+/// * `function_id` - Entity ID of the target function
+/// * `direction` - Whether to walk towards callers or callees
+/// * `max_depth` - Maximum call-graph distance to explore
+///
+/// # Returns
+///
+/// `(entity_id, distance)` pairs sorted by distance then ID. The target
+/// itself is excluded. Errors if the call graph cannot be queried.
+pub fn call_graph_distances(
+    backend: &dyn sqlitegraph::GraphBackend,
+    function_id: i64,
+    direction: CallDirection,
+    max_depth: usize,
+) -> Result<Vec<(i64, usize)>> {
+    use sqlitegraph::{BackendDirection, NeighborQuery, SnapshotId};
+    use std::collections::{HashSet, VecDeque};
+
+    let snapshot = SnapshotId::current();
+    let (backend_direction, first_edge, second_edge) = match direction {
+        CallDirection::Callees => (BackendDirection::Outgoing, "CALLER", "CALLS"),
+        CallDirection::Callers => (BackendDirection::Incoming, "CALLS", "CALLER"),
+    };
+    let query = |edge_type: &str| NeighborQuery {
+        direction: backend_direction,
+        edge_type: Some(edge_type.to_string()),
+    };
+
+    let mut visited = HashSet::new();
+    visited.insert(function_id);
+    let mut queue = VecDeque::new();
+    queue.push_back((function_id, 0));
+    let mut result = Vec::new();
+
+    while let Some((current, distance)) = queue.pop_front() {
+        if distance >= max_depth {
+            continue;
+        }
+
+        for call_node in backend.neighbors(snapshot, current, query(first_edge))? {
+            for next in backend.neighbors(snapshot, call_node, query(second_edge))? {
+                if visited.insert(next) {
+                    result.push((next, distance + 1));
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+    }
+
+    result.sort_by_key(|&(id, distance)| (distance, id));
+    Ok(result)
+}
+
+/// Load the call sites in a function body from Magellan's call graph
+///
+/// Follows the function's `CALLER` edges to its `Call` nodes and reads the
+/// callee name and byte span from each node's data. Call nodes without a
+/// callee or span are skipped.
+///
+/// # Returns
+///
+/// Call sites sorted by byte offset. Errors if the call graph cannot be queried.
+pub fn function_call_sites(
+    backend: &dyn sqlitegraph::GraphBackend,
+    function_id: i64,
+) -> Result<Vec<crate::cfg::paths::CallSite>> {
+    use sqlitegraph::{BackendDirection, NeighborQuery, SnapshotId};
+
+    let snapshot = SnapshotId::current();
+    let query = NeighborQuery {
+        direction: BackendDirection::Outgoing,
+        edge_type: Some("CALLER".to_string()),
+    };
+
+    let mut call_sites = Vec::new();
+    for call_node in backend.neighbors(snapshot, function_id, query)? {
+        let entity = backend.get_node(snapshot, call_node)?;
+        let data = &entity.data;
+        let callee = data.get("callee").and_then(|v| v.as_str());
+        let byte_start = data.get("byte_start").and_then(|v| v.as_u64());
+        let byte_end = data.get("byte_end").and_then(|v| v.as_u64());
+        if let (Some(callee), Some(byte_start), Some(byte_end)) = (callee, byte_start, byte_end) {
+            call_sites.push(crate::cfg::paths::CallSite {
+                callee: callee.to_string(),
+                byte_start: byte_start as usize,
+                byte_end: byte_end as usize,
+            });
+        }
+    }
+
+    call_sites.sort_by_key(|c| c.byte_start);
+    Ok(call_sites)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1558,4 +1676,105 @@ mod tests {
         assert!(json.contains("9999.99"));
         assert!(json.contains("100000"));
     }
+
+    /// Build the call chain `a -> b -> c` using Magellan's Call-node layout
+    fn create_call_chain_backend() -> (sqlitegraph::SqliteGraphBackend, [i64; 3]) {
+        use sqlitegraph::{EdgeSpec, GraphBackend, NodeSpec, SqliteGraphBackend};
+
+        let backend = SqliteGraphBackend::in_memory().unwrap();
+        let function = |name: &str| NodeSpec {
+            kind: "Symbol".to_string(),
+            name: name.to_string(),
+            file_path: Some("lib.rs".to_string()),
+            data: serde_json::json!({}),
+        };
+        let a = backend.insert_node(function("a")).unwrap();
+        let b = backend.insert_node(function("b")).unwrap();
+        let c = backend.insert_node(function("c")).unwrap();
+
+        for (caller, callee) in [(a, b), (b, c)] {
+            let call = backend.insert_node(NodeSpec {
+                kind: "Call".to_string(),
+                name: "call".to_string(),
+                file_path: Some("lib.rs".to_string()),
+                data: serde_json::json!({}),
+            }).unwrap();
+            for (from, to, edge_type) in [(caller, call, "CALLER"), (call, callee, "CALLS")] {
+                backend.insert_edge(EdgeSpec {
+                    from,
+                    to,
+                    edge_type: edge_type.to_string(),
+                    data: serde_json::json!({}),
+                }).unwrap();
+            }
+        }
+
+        (backend, [a, b, c])
+    }
+
+    #[test]
+    fn test_function_call_sites_reads_call_nodes() {
+        use sqlitegraph::{EdgeSpec, GraphBackend, NodeSpec, SqliteGraphBackend};
+
+        let backend = SqliteGraphBackend::in_memory().unwrap();
+        let caller = backend.insert_node(NodeSpec {
+            kind: "Symbol".to_string(),
+            name: "caller".to_string(),
+            file_path: Some("lib.rs".to_string()),
+            data: serde_json::json!({}),
+        }).unwrap();
+        for data in [
+            serde_json::json!({"callee": "unsafe_helper", "byte_start": 40, "byte_end": 55}),
+            serde_json::json!({"callee": "setup", "byte_start": 10, "byte_end": 17}),
+            serde_json::json!({}),
+        ] {
+            let call = backend.insert_node(NodeSpec {
+                kind: "Call".to_string(),
+                name: "call".to_string(),
+                file_path: Some("lib.rs".to_string()),
+                data,
+            }).unwrap();
+            backend.insert_edge(EdgeSpec {
+                from: caller,
+                to: call,
+                edge_type: "CALLER".to_string(),
+                data: serde_json::json!({}),
+            }).unwrap();
+        }
+
+        let sites = function_call_sites(&backend, caller).unwrap();
+        let names: Vec<&str> = sites.iter().map(|c| c.callee.as_str()).collect();
+        assert_eq!(names, vec!["setup", "unsafe_helper"]);
+        assert_eq!((sites[1].byte_start, sites[1].byte_end), (40, 55));
+    }
+
+    #[test]
+    fn test_call_graph_distances_callees() {
+        let (backend, [a, b, c]) = create_call_chain_backend();
+
+        let callees = call_graph_distances(&backend, a, CallDirection::Callees, 10).unwrap();
+        assert_eq!(callees, vec![(b, 1), (c, 2)]);
+    }
+
+    #[test]
+    fn test_call_graph_distances_callers() {
+        let (backend, [a, b, c]) = create_call_chain_backend();
+
+        let callers = call_graph_distances(&backend, c, CallDirection::Callers, 10).unwrap();
+        assert_eq!(callers, vec![(b, 1), (a, 2)]);
+    }
+
+    #[test]
+    fn test_call_graph_distances_respects_max_depth() {
+        let (backend, [a, b, _c]) = create_call_chain_backend();
+
+        let direct = call_graph_distances(&backend, a, CallDirection::Callees, 1).unwrap();
+        assert_eq!(direct, vec![(b, 1)]);
+
+        let none = call_graph_distances(&backend, a, CallDirection::Callees, 0).unwrap();
+        assert!(none.is_empty());
+
+        let callers = call_graph_distances(&backend, a, CallDirection::Callers, 10).unwrap();
+        assert!(callers.is_empty());
+    }
 }
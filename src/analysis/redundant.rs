@@ -0,0 +1,210 @@
+//! Redundant branch detection
+//!
+//! A `SwitchInt` is redundant when its outcome does not matter or cannot
+//! vary: either every arm reaches the same block without doing anything on
+//! the way, or every arm but one ends in `Unreachable`, so the branch always
+//! takes the remaining arm. Both are found purely from the CFG, so the
+//! detector works on any indexed function.
+
+use crate::cfg::{BlockId, Cfg, Terminator};
+use petgraph::graph::NodeIndex;
+use petgraph::Direction;
+use serde::Serialize;
+
+/// Why a branch is redundant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedundancyKind {
+    /// All arms merge at one block with no statements in between
+    ArmsMerge,
+    /// All arms but one end in `Unreachable`; the branch is always taken
+    UnreachableArm,
+}
+
+/// A branch block whose condition does not affect control flow
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RedundantBranch {
+    /// The branching (`SwitchInt`) block
+    pub block_id: BlockId,
+    pub kind: RedundancyKind,
+    /// The merge block (`ArmsMerge`) or the only live arm (`UnreachableArm`)
+    pub target: BlockId,
+    /// Arms ending in `Unreachable` (empty for `ArmsMerge`)
+    pub unreachable_arms: Vec<BlockId>,
+    /// Human-readable explanation
+    pub reason: String,
+}
+
+/// Find `SwitchInt` blocks whose arms all merge immediately or whose every arm but one is unreachable
+///
+/// An arm "merges immediately" when its successor is either the merge
+/// block itself or a block with no statements that only jumps on to it.
+/// Results are sorted by block ID.
+pub fn detect_redundant_branches(cfg: &Cfg) -> Vec<RedundantBranch> {
+    let mut redundant: Vec<RedundantBranch> = cfg
+        .node_indices()
+        .filter(|&node| matches!(cfg[node].terminator, Terminator::SwitchInt { .. }))
+        .filter_map(|node| check_branch(cfg, node))
+        .collect();
+    redundant.sort_by_key(|r| r.block_id);
+    redundant
+}
+
+fn check_branch(cfg: &Cfg, node: NodeIndex) -> Option<RedundantBranch> {
+    let mut arms: Vec<NodeIndex> = cfg.neighbors_directed(node, Direction::Outgoing).collect();
+    arms.sort();
+    arms.dedup();
+    if arms.is_empty() {
+        return None;
+    }
+    let block_id = cfg[node].id;
+
+    // (a) Every arm lands on the same block without executing anything
+    let first = pass_through(cfg, arms[0]);
+    if arms.iter().all(|&arm| pass_through(cfg, arm) == first) {
+        let target = cfg[first].id;
+        return Some(RedundantBranch {
+            block_id,
+            kind: RedundancyKind::ArmsMerge,
+            target,
+            unreachable_arms: Vec::new(),
+            reason: format!("all arms reach block {} with no statements in between", target),
+        });
+    }
+
+    // (b) Only one arm can actually be taken
+    let (dead, live): (Vec<NodeIndex>, Vec<NodeIndex>) = arms.iter().partition(|&&arm| is_unreachable_arm(cfg, arm));
+    if dead.is_empty() || live.len() != 1 {
+        return None;
+    }
+    let target = cfg[live[0]].id;
+    let unreachable_arms: Vec<BlockId> = dead.iter().map(|&arm| cfg[arm].id).collect();
+    Some(RedundantBranch {
+        block_id,
+        kind: RedundancyKind::UnreachableArm,
+        target,
+        reason: format!(
+            "arm(s) {:?} end in Unreachable, so the branch always goes to block {}",
+            unreachable_arms, target
+        ),
+        unreachable_arms,
+    })
+}
+
+/// Where an arm ends up: past a statement-free `Goto` block, or the arm itself
+fn pass_through(cfg: &Cfg, arm: NodeIndex) -> NodeIndex {
+    let block = &cfg[arm];
+    if !block.statements.is_empty() || !matches!(block.terminator, Terminator::Goto { .. }) {
+        return arm;
+    }
+    let mut successors = cfg.neighbors_directed(arm, Direction::Outgoing);
+    match (successors.next(), successors.next()) {
+        (Some(next), None) => next,
+        _ => arm,
+    }
+}
+
+/// A statement-free block that ends in `Unreachable`
+fn is_unreachable_arm(cfg: &Cfg, arm: NodeIndex) -> bool {
+    let block = &cfg[arm];
+    block.statements.is_empty() && block.terminator == Terminator::Unreachable
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{BasicBlock, BlockKind, EdgeType};
+    use petgraph::graph::DiGraph;
+
+    fn block(id: BlockId, statements: &[&str], terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id,
+            kind: if id == 0 { BlockKind::Entry } else { BlockKind::Normal },
+            statements: statements.iter().map(|s| s.to_string()).collect(),
+            terminator,
+            source_location: None,
+        }
+    }
+
+    fn switch() -> Terminator {
+        Terminator::SwitchInt { targets: vec![1], otherwise: 2 }
+    }
+
+    fn goto(target: BlockId) -> Terminator {
+        Terminator::Goto { target }
+    }
+
+    #[test]
+    fn test_arms_merge_without_statements() {
+        // 0 (switch) -> 1 (empty) -> 3; 0 -> 2 (empty) -> 3
+        let mut g: Cfg = DiGraph::new();
+        let b0 = g.add_node(block(0, &[], switch()));
+        let b1 = g.add_node(block(1, &[], goto(3)));
+        let b2 = g.add_node(block(2, &[], goto(3)));
+        let b3 = g.add_node(block(3, &[], Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b3, EdgeType::Fallthrough);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+
+        let redundant = detect_redundant_branches(&g);
+        assert_eq!(redundant.len(), 1);
+        assert_eq!(redundant[0].block_id, 0);
+        assert_eq!(redundant[0].kind, RedundancyKind::ArmsMerge);
+        assert_eq!(redundant[0].target, 3);
+
+        // An arm that does work makes the branch meaningful
+        g[b1].statements.push("x = 1".to_string());
+        assert!(detect_redundant_branches(&g).is_empty());
+    }
+
+    #[test]
+    fn test_empty_arm_and_direct_edge_merge() {
+        // `if c {}`: one arm is an empty block, the other jumps straight to the join
+        let mut g: Cfg = DiGraph::new();
+        let b0 = g.add_node(block(0, &[], switch()));
+        let b1 = g.add_node(block(1, &[], goto(2)));
+        let b2 = g.add_node(block(2, &["y = 2"], Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b2, EdgeType::Fallthrough);
+
+        let redundant = detect_redundant_branches(&g);
+        assert_eq!(redundant.len(), 1);
+        assert_eq!(redundant[0].target, 2);
+    }
+
+    #[test]
+    fn test_unreachable_arm() {
+        // 0 (switch) -> 1 (return) | 2 (unreachable)
+        let mut g: Cfg = DiGraph::new();
+        let b0 = g.add_node(block(0, &[], switch()));
+        let b1 = g.add_node(block(1, &["ret = 0"], Terminator::Return));
+        let b2 = g.add_node(block(2, &[], Terminator::Unreachable));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+
+        let redundant = detect_redundant_branches(&g);
+        assert_eq!(redundant.len(), 1);
+        assert_eq!(redundant[0].kind, RedundancyKind::UnreachableArm);
+        assert_eq!(redundant[0].target, 1);
+        assert_eq!(redundant[0].unreachable_arms, vec![2]);
+
+        // With two live arms left, the unreachable default is just exhaustiveness
+        let b3 = g.add_node(block(3, &["ret = 1"], Terminator::Return));
+        g.add_edge(b0, b3, EdgeType::TrueBranch);
+        assert!(detect_redundant_branches(&g).is_empty());
+    }
+
+    #[test]
+    fn test_meaningful_branch_not_reported() {
+        let mut g: Cfg = DiGraph::new();
+        let b0 = g.add_node(block(0, &[], switch()));
+        let b1 = g.add_node(block(1, &["a()"], Terminator::Return));
+        let b2 = g.add_node(block(2, &["b()"], Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+
+        assert!(detect_redundant_branches(&g).is_empty());
+    }
+}
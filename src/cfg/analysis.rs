@@ -1,7 +1,21 @@
 //! CFG analysis: entry/exit detection, dominance preparation
 
-use crate::cfg::{BlockKind, Cfg, Terminator};
+use crate::cfg::{BasicBlock, BlockId, BlockKind, Cfg, Terminator};
 use petgraph::graph::NodeIndex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// Statement prefixes of panic-family calls
+const PANIC_CALL_PREFIXES: &[&str] = &[
+    "panic!",
+    "unreachable!",
+    "todo!",
+    "unimplemented!",
+    "core::panicking::",
+    "std::panicking::",
+    "std::process::abort",
+    "core::intrinsics::abort",
+];
 
 /// Find the entry node of a CFG
 ///
@@ -11,6 +25,27 @@ pub fn find_entry(cfg: &Cfg) -> Option<NodeIndex> {
     cfg.node_indices().next()
 }
 
+/// Find all entry-like nodes of a CFG
+///
+/// The entry from [`find_entry`] comes first, followed by every other block
+/// with no predecessors. Well-formed CFGs have exactly one; irreducible or
+/// malformed ones can have several, and dominance computed from the first
+/// says nothing about blocks reachable only from the others.
+pub fn find_entry_blocks(cfg: &Cfg) -> Vec<NodeIndex> {
+    let Some(entry) = find_entry(cfg) else {
+        return Vec::new();
+    };
+    std::iter::once(entry)
+        .chain(cfg.node_indices().filter(|&idx| {
+            idx != entry
+                && cfg
+                    .neighbors_directed(idx, petgraph::Direction::Incoming)
+                    .next()
+                    .is_none()
+        }))
+        .collect()
+}
+
 /// Find all exit nodes in a CFG
 ///
 /// Exits are blocks that terminate execution:
@@ -66,6 +101,90 @@ pub fn is_branch_point(cfg: &Cfg, block_idx: NodeIndex) -> bool {
     out_degree(cfg, block_idx) > 1
 }
 
+/// Check if a block only exists to panic or abort
+///
+/// A panic block either has an `Unreachable` or `Abort("panic")` terminator,
+/// or its sole statement is a call to a panic-family function
+/// (`panic!`, `unreachable!`, `core::panicking::*`, `std::process::abort`, ...).
+pub fn is_panic_block(block: &BasicBlock) -> bool {
+    match &block.terminator {
+        Terminator::Unreachable => return true,
+        Terminator::Abort(msg) if msg == "panic" => return true,
+        _ => {}
+    }
+
+    match block.statements.as_slice() {
+        [only] => {
+            let stmt = only.trim_start();
+            PANIC_CALL_PREFIXES.iter().any(|prefix| stmt.starts_with(prefix))
+        }
+        _ => false,
+    }
+}
+
+/// Reclassify panic landing blocks as `BlockKind::Panic`
+///
+/// The entry block is never reclassified, so a function whose body is a
+/// single `panic!()` keeps its entry.
+pub fn classify_panic_blocks(cfg: &mut Cfg) {
+    for node in cfg.node_indices() {
+        let block = &mut cfg[node];
+        if block.kind != BlockKind::Entry && is_panic_block(block) {
+            block.kind = BlockKind::Panic;
+        }
+    }
+}
+
+/// Block IDs of all `BlockKind::Panic` blocks
+pub fn panic_block_ids(cfg: &Cfg) -> HashSet<BlockId> {
+    cfg.node_weights()
+        .filter(|b| b.kind == BlockKind::Panic)
+        .map(|b| b.id)
+        .collect()
+}
+
+/// Every name [`terminator_kind`] returns, with its meaning
+pub const TERMINATOR_KINDS: [(&str, &str); 6] = [
+    ("Goto", "Unconditional jump to one block"),
+    ("SwitchInt", "Branch on a value: one or more targets plus an otherwise block"),
+    ("Return", "Return from the function"),
+    ("Unreachable", "Point the compiler proved cannot be reached"),
+    ("Call", "Function call, with an optional return block and unwind block"),
+    ("Abort", "Abort the process (e.g. a failed assertion or overflow check)"),
+];
+
+/// Terminator variant name, without block targets
+pub fn terminator_kind(terminator: &Terminator) -> &'static str {
+    match terminator {
+        Terminator::Goto { .. } => "Goto",
+        Terminator::SwitchInt { .. } => "SwitchInt",
+        Terminator::Return => "Return",
+        Terminator::Unreachable => "Unreachable",
+        Terminator::Call { .. } => "Call",
+        Terminator::Abort(_) => "Abort",
+    }
+}
+
+/// Histogram of terminator kinds across a function's blocks
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminatorStats {
+    pub function: String,
+    /// Block count per terminator kind; kinds that never occur are omitted
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// Count each terminator kind across all blocks of `cfg`
+pub fn count_terminators(cfg: &Cfg, function: &str) -> TerminatorStats {
+    let mut counts = BTreeMap::new();
+    for block in cfg.node_weights() {
+        *counts.entry(terminator_kind(&block.terminator).to_string()).or_insert(0) += 1;
+    }
+    TerminatorStats {
+        function: function.to_string(),
+        counts,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,6 +249,32 @@ mod tests {
         assert_eq!(entry.unwrap().index(), 0);
     }
 
+    #[test]
+    fn test_find_entry_blocks_multiple_entries() {
+        let mut cfg = create_test_cfg();
+        assert_eq!(find_entry_blocks(&cfg), vec![NodeIndex::new(0)]);
+
+        // Block 4 has no predecessors and jumps into the false branch
+        let b4 = cfg.add_node(BasicBlock {
+            id: 4,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Goto { target: 3 },
+            source_location: None,
+        });
+        cfg.add_edge(b4, NodeIndex::new(3), EdgeType::Fallthrough);
+
+        let entries = find_entry_blocks(&cfg);
+        assert_eq!(entries, vec![NodeIndex::new(0), b4]);
+
+        // Dominance is computed from the first entry only, which leaves the
+        // second entry outside the tree
+        let dom_tree = crate::cfg::DominatorTree::new(&cfg).unwrap();
+        assert_eq!(dom_tree.root(), entries[0]);
+        assert!(!dom_tree.dominates(entries[0], b4));
+        assert_eq!(dom_tree.immediate_dominator(b4), None);
+    }
+
     #[test]
     fn test_find_exits() {
         let cfg = create_test_cfg();
@@ -345,4 +490,70 @@ mod tests {
         assert!(exit_ids.contains(&1)); // Return
         assert!(exit_ids.contains(&2)); // Unreachable
     }
+
+    #[test]
+    fn test_classify_panic_blocks() {
+        let mut g = DiGraph::new();
+        let block = |id, kind, statements: Vec<&str>, terminator| BasicBlock {
+            id,
+            kind,
+            statements: statements.into_iter().map(String::from).collect(),
+            terminator,
+            source_location: None,
+        };
+
+        let b0 = g.add_node(block(0, BlockKind::Entry, vec![], Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, vec!["return x"], Terminator::Return));
+        let b2 = g.add_node(block(2, BlockKind::Normal, vec!["core::panicking::panic(\"overflow\")"], Terminator::Goto { target: 3 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, vec![], Terminator::Unreachable));
+        let b4 = g.add_node(block(4, BlockKind::Normal, vec!["log()", "panic!()"], Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+        g.add_edge(b0, b4, EdgeType::Fallthrough);
+
+        classify_panic_blocks(&mut g);
+
+        assert_eq!(g[b0].kind, BlockKind::Entry);
+        assert_eq!(g[b1].kind, BlockKind::Exit);
+        assert_eq!(g[b2].kind, BlockKind::Panic); // sole panic-family call
+        assert_eq!(g[b3].kind, BlockKind::Panic); // Unreachable terminator
+        assert_eq!(g[b4].kind, BlockKind::Normal); // panic is not the only statement
+
+        let ids = panic_block_ids(&g);
+        assert_eq!(ids, [2, 3].into_iter().collect());
+    }
+
+    #[test]
+    fn test_count_terminators_diamond() {
+        let cfg = create_test_cfg();
+        let stats = count_terminators(&cfg, "diamond");
+
+        assert_eq!(stats.function, "diamond");
+        assert_eq!(stats.counts.get("Goto"), Some(&1));
+        assert_eq!(stats.counts.get("SwitchInt"), Some(&1));
+        assert_eq!(stats.counts.get("Return"), Some(&2));
+        assert_eq!(stats.counts.get("Call"), None, "Absent kinds are omitted");
+        assert_eq!(stats.counts.values().sum::<usize>(), cfg.node_count());
+    }
+
+    #[test]
+    fn test_terminator_kinds_cover_every_variant() {
+        // The variant declared after `terminator`; exhaustive, so a new
+        // variant does not compile until it is placed in the chain
+        let next = |terminator: &Terminator| match terminator {
+            Terminator::Goto { .. } => Some(Terminator::SwitchInt { targets: vec![1], otherwise: 2 }),
+            Terminator::SwitchInt { .. } => Some(Terminator::Return),
+            Terminator::Return => Some(Terminator::Unreachable),
+            Terminator::Unreachable => Some(Terminator::Call { target: None, unwind: None }),
+            Terminator::Call { .. } => Some(Terminator::Abort("overflow".to_string())),
+            Terminator::Abort(_) => None,
+        };
+        let terminators: Vec<Terminator> =
+            std::iter::successors(Some(Terminator::Goto { target: 0 }), |t| next(t)).collect();
+
+        let names: Vec<&str> = TERMINATOR_KINDS.iter().map(|(name, _)| *name).collect();
+        let kinds: Vec<&str> = terminators.iter().map(terminator_kind).collect();
+        assert_eq!(kinds, names);
+    }
 }
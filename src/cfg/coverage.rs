@@ -0,0 +1,101 @@
+//! Edge coverage of enumerated paths
+//!
+//! Reports which CFG edges at least one enumerated path traverses. An edge
+//! no path takes is either behind a dead branch or was cut off by the
+//! enumeration limits (`--max-length`, `max_paths`), so uncovered edges are
+//! worth a look either way.
+
+use crate::cfg::{BlockId, Cfg, Path};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+use std::collections::{HashMap, HashSet};
+
+/// Split the CFG's edges into those some path traverses and those none does
+///
+/// Every step `a -> b` of a path covers all edges from block `a` to block
+/// `b`, so parallel edges (a switch with two values routed to one target)
+/// count as covered together. Uncovered edges are returned as
+/// `(from_block, to_block)`, sorted.
+pub fn edge_coverage(cfg: &Cfg, paths: &[Path]) -> (HashSet<EdgeIndex>, Vec<(BlockId, BlockId)>) {
+    let nodes: HashMap<BlockId, NodeIndex> = cfg.node_indices().map(|n| (cfg[n].id, n)).collect();
+
+    let mut covered = HashSet::new();
+    let mut steps = HashSet::new();
+    for path in paths {
+        for pair in path.blocks.windows(2) {
+            if !steps.insert((pair[0], pair[1])) {
+                continue;
+            }
+            let (Some(&from), Some(&to)) = (nodes.get(&pair[0]), nodes.get(&pair[1])) else {
+                continue;
+            };
+            covered.extend(cfg.edges_connecting(from, to).map(|edge| edge.id()));
+        }
+    }
+
+    let mut uncovered: Vec<(BlockId, BlockId)> = cfg
+        .edge_references()
+        .filter(|edge| !covered.contains(&edge.id()))
+        .map(|edge| (cfg[edge.source()].id, cfg[edge.target()].id))
+        .collect();
+    uncovered.sort_unstable();
+    (covered, uncovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{enumerate_paths, BasicBlock, BlockKind, EdgeType, PathLimits, Terminator};
+    use petgraph::graph::DiGraph;
+
+    /// 0 -> 1 -> 3 (short arm), 0 -> 2 -> 4 -> 3 (long arm)
+    fn uneven_diamond() -> Cfg {
+        let mut g: Cfg = DiGraph::new();
+        let block = |id: BlockId, kind: BlockKind, terminator: Terminator| BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        };
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 4 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        let b4 = g.add_node(block(4, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b3, EdgeType::Fallthrough);
+        g.add_edge(b2, b4, EdgeType::Fallthrough);
+        g.add_edge(b4, b3, EdgeType::Fallthrough);
+        g
+    }
+
+    #[test]
+    fn test_all_paths_cover_every_edge() {
+        let cfg = uneven_diamond();
+        let paths = enumerate_paths(&cfg, &PathLimits::default());
+        let (covered, uncovered) = edge_coverage(&cfg, &paths);
+        assert_eq!(covered.len(), cfg.edge_count());
+        assert!(uncovered.is_empty());
+    }
+
+    #[test]
+    fn test_max_length_leaves_long_arm_uncovered() {
+        let cfg = uneven_diamond();
+        let paths = enumerate_paths(&cfg, &PathLimits::default().with_max_length(3));
+        assert_eq!(paths.len(), 1, "Only the short arm fits in 3 blocks");
+
+        let (covered, uncovered) = edge_coverage(&cfg, &paths);
+        assert_eq!(covered.len(), 2);
+        assert_eq!(uncovered, vec![(0, 2), (2, 4), (4, 3)]);
+    }
+
+    #[test]
+    fn test_no_paths_covers_nothing() {
+        let cfg = uneven_diamond();
+        let (covered, uncovered) = edge_coverage(&cfg, &[]);
+        assert!(covered.is_empty());
+        assert_eq!(uncovered.len(), cfg.edge_count());
+    }
+}
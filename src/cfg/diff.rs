@@ -30,6 +30,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 
 use crate::storage::{Backend, CfgBlockData};
+use crate::cfg::analysis::terminator_kind;
 
 // ============================================================================
 // Diff Output Structures
@@ -446,6 +447,138 @@ pub fn blocks_to_petgraph(blocks: &[CfgBlockData]) -> DiGraph<i64, ()> {
     graph
 }
 
+// ============================================================================
+// Structural Comparison
+// ============================================================================
+
+/// Structural comparison of two functions' CFGs
+///
+/// Unlike `CfgDiff`, which matches blocks by ID across snapshots of one
+/// function, this compares shape only: block IDs are ignored, so a function
+/// and a mechanically refactored copy compare as `isomorphic`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuralCfgDiff {
+    /// First function (the "before" side)
+    pub function_a: String,
+    /// Second function (the "after" side)
+    pub function_b: String,
+    pub block_count_a: usize,
+    pub block_count_b: usize,
+    pub edge_count_a: usize,
+    pub edge_count_b: usize,
+    /// Block counts per kind (entry, normal, exit, panic) that differ
+    pub block_kinds: Vec<KindCountDiff>,
+    /// Block counts per terminator kind that differ
+    pub terminators: Vec<KindCountDiff>,
+    /// Edge counts per edge type that differ
+    pub edge_types: Vec<KindCountDiff>,
+    /// Sum of the absolute count differences above; 0 when the counts match
+    ///
+    /// A cheap lower-bound-style estimate, not a true graph edit distance.
+    pub edit_distance: usize,
+    /// Whether the CFGs are identical up to block-ID renaming
+    pub isomorphic: bool,
+}
+
+/// Count of one kind of block, terminator or edge on each side
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KindCountDiff {
+    pub kind: String,
+    pub count_a: usize,
+    pub count_b: usize,
+    /// `count_b - count_a`: positive means added in B, negative removed
+    pub delta: i64,
+}
+
+/// Compare the structure of two CFGs, ignoring block IDs
+///
+/// Blocks match when their kind, terminator kind and number of switch
+/// targets agree; edges match when their types agree.
+pub fn compare_cfg_structure(
+    cfg_a: &crate::cfg::Cfg,
+    function_a: &str,
+    cfg_b: &crate::cfg::Cfg,
+    function_b: &str,
+) -> StructuralCfgDiff {
+    use petgraph::algo::is_isomorphic_matching;
+
+    let block_kinds = count_diffs(
+        cfg_a.node_weights().map(|b| format!("{:?}", b.kind)),
+        cfg_b.node_weights().map(|b| format!("{:?}", b.kind)),
+    );
+    let terminators = count_diffs(
+        cfg_a.node_weights().map(|b| terminator_kind(&b.terminator).to_string()),
+        cfg_b.node_weights().map(|b| terminator_kind(&b.terminator).to_string()),
+    );
+    let edge_types = count_diffs(
+        cfg_a.edge_weights().map(|e| format!("{:?}", e)),
+        cfg_b.edge_weights().map(|e| format!("{:?}", e)),
+    );
+
+    let edit_distance = block_kinds.iter()
+        .chain(&terminators)
+        .chain(&edge_types)
+        .map(|diff| diff.delta.unsigned_abs() as usize)
+        .sum();
+
+    let isomorphic = edit_distance == 0
+        && is_isomorphic_matching(
+            cfg_a,
+            cfg_b,
+            |a, b| {
+                a.kind == b.kind
+                    && terminator_kind(&a.terminator) == terminator_kind(&b.terminator)
+                    && switch_arity(&a.terminator) == switch_arity(&b.terminator)
+            },
+            |a, b| a == b,
+        );
+
+    StructuralCfgDiff {
+        function_a: function_a.to_string(),
+        function_b: function_b.to_string(),
+        block_count_a: cfg_a.node_count(),
+        block_count_b: cfg_b.node_count(),
+        edge_count_a: cfg_a.edge_count(),
+        edge_count_b: cfg_b.edge_count(),
+        block_kinds,
+        terminators,
+        edge_types,
+        edit_distance,
+        isomorphic,
+    }
+}
+
+/// Per-kind counts on each side, keeping only kinds whose counts differ
+fn count_diffs(
+    a: impl Iterator<Item = String>,
+    b: impl Iterator<Item = String>,
+) -> Vec<KindCountDiff> {
+    let mut counts: std::collections::BTreeMap<String, (usize, usize)> = std::collections::BTreeMap::new();
+    for kind in a {
+        counts.entry(kind).or_default().0 += 1;
+    }
+    for kind in b {
+        counts.entry(kind).or_default().1 += 1;
+    }
+
+    counts.into_iter()
+        .filter(|(_, (count_a, count_b))| count_a != count_b)
+        .map(|(kind, (count_a, count_b))| KindCountDiff {
+            kind,
+            count_a,
+            count_b,
+            delta: count_b as i64 - count_a as i64,
+        })
+        .collect()
+}
+
+fn switch_arity(terminator: &crate::cfg::Terminator) -> usize {
+    match terminator {
+        crate::cfg::Terminator::SwitchInt { targets, .. } => targets.len(),
+        _ => 0,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -585,4 +718,87 @@ mod tests {
         assert_eq!(added.len(), 0);
         assert_eq!(deleted.len(), 1);
     }
+
+    fn build_diamond(ids: [usize; 4], exit_terminator: crate::cfg::Terminator) -> crate::cfg::Cfg {
+        use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
+
+        let mut g = DiGraph::new();
+        // Insert the merge block first so node order differs from ID order
+        let merge = g.add_node(BasicBlock {
+            id: ids[3],
+            kind: BlockKind::Exit,
+            statements: vec![],
+            terminator: exit_terminator,
+            source_location: None,
+        });
+        let entry = g.add_node(BasicBlock {
+            id: ids[0],
+            kind: BlockKind::Entry,
+            statements: vec![],
+            terminator: Terminator::SwitchInt { targets: vec![ids[1]], otherwise: ids[2] },
+            source_location: None,
+        });
+        let branches: Vec<_> = [ids[1], ids[2]].iter().map(|&id| g.add_node(BasicBlock {
+            id,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Goto { target: ids[3] },
+            source_location: None,
+        })).collect();
+
+        g.add_edge(entry, branches[0], EdgeType::TrueBranch);
+        g.add_edge(entry, branches[1], EdgeType::FalseBranch);
+        g.add_edge(branches[0], merge, EdgeType::Fallthrough);
+        g.add_edge(branches[1], merge, EdgeType::Fallthrough);
+        g
+    }
+
+    #[test]
+    fn test_compare_cfg_structure_isomorphic_after_renaming() {
+        use crate::cfg::Terminator;
+
+        let a = build_diamond([0, 1, 2, 3], Terminator::Return);
+        let b = build_diamond([10, 12, 11, 13], Terminator::Return);
+
+        let diff = compare_cfg_structure(&a, "a", &b, "b");
+        assert!(diff.isomorphic);
+        assert_eq!(diff.edit_distance, 0);
+        assert!(diff.block_kinds.is_empty());
+        assert_eq!((diff.block_count_a, diff.block_count_b), (4, 4));
+    }
+
+    #[test]
+    fn test_compare_cfg_structure_reports_changes() {
+        use crate::cfg::{EdgeType, Terminator};
+
+        let a = build_diamond([0, 1, 2, 3], Terminator::Return);
+
+        // Same counts, different terminator on the merge block
+        let b = build_diamond([0, 1, 2, 3], Terminator::Unreachable);
+        let diff = compare_cfg_structure(&a, "a", &b, "b");
+        assert!(!diff.isomorphic);
+        assert_eq!(diff.terminators, vec![
+            KindCountDiff { kind: "Return".to_string(), count_a: 1, count_b: 0, delta: -1 },
+            KindCountDiff { kind: "Unreachable".to_string(), count_a: 0, count_b: 1, delta: 1 },
+        ]);
+        assert_eq!(diff.edit_distance, 2);
+
+        // An extra block and edge
+        let mut c = build_diamond([0, 1, 2, 3], Terminator::Return);
+        let exit = c.node_indices().find(|&n| c[n].id == 3).unwrap();
+        let extra = c.add_node(crate::cfg::BasicBlock {
+            id: 4,
+            kind: crate::cfg::BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Return,
+            source_location: None,
+        });
+        c.add_edge(exit, extra, EdgeType::Fallthrough);
+        let diff = compare_cfg_structure(&a, "a", &c, "c");
+        assert!(!diff.isomorphic);
+        assert_eq!(diff.block_kinds, vec![
+            KindCountDiff { kind: "Normal".to_string(), count_a: 2, count_b: 3, delta: 1 },
+        ]);
+        assert_eq!(diff.edge_count_b - diff.edge_count_a, 1);
+    }
 }
@@ -8,10 +8,16 @@
 //! - SSA phi-node placement (where variables merge from multiple paths)
 //! - Control dependence analysis
 //! - Identifying join points in control flow
+//!
+//! The post-dominance frontier is the same construction on the reversed
+//! CFG: the post-dominance frontier of n is the set of branch blocks that
+//! decide whether n executes, i.e. n's control dependences.
 
 use crate::cfg::Cfg;
 use crate::cfg::dominators::DominatorTree;
+use crate::cfg::post_dominators::PostDominatorTree;
 use petgraph::graph::NodeIndex;
+use petgraph::Direction;
 use std::collections::{HashSet, HashMap};
 
 /// Dominance frontiers for all nodes in a CFG
@@ -66,46 +72,19 @@ impl DominanceFrontiers {
     /// let frontiers = DominanceFrontiers::new(&graph, dom_tree);
     /// ```
     pub fn new(cfg: &Cfg, dominator_tree: DominatorTree) -> Self {
-        let mut frontiers: HashMap<NodeIndex, HashSet<NodeIndex>> = HashMap::new();
-
-        // Process nodes in reverse post-order (deep nodes first)
-        // We'll use depth in dominator tree as approximation
-        let mut nodes: Vec<NodeIndex> = cfg.node_indices().collect();
-        nodes.sort_by_key(|&n| std::cmp::Reverse(dominator_tree.depth(n)));
-
-        // Compute frontier for each node
-        for &n in &nodes {
-            let mut df = HashSet::new();
-
-            // Rule 1: Strict dominance boundary
-            // For each node v, check if n dominates a predecessor of v
-            // but does NOT strictly dominate v itself
-            for &v in &nodes {
-                for p in cfg.neighbors_directed(v, petgraph::Direction::Incoming) {
-                    if dominator_tree.dominates(n, p)
-                        && !dominator_tree.strictly_dominates(n, v)
-                    {
-                        df.insert(v);
-                    }
-                }
-            }
-
-            // Rule 2: Propagate children's frontiers
-            // If child c's frontier contains v and n does not strictly dominate v
-            // then v is in n's dominance frontier
-            for &child in dominator_tree.children(n) {
-                if let Some(child_df) = frontiers.get(&child) {
-                    for &v in child_df {
-                        if !dominator_tree.strictly_dominates(n, v) {
-                            df.insert(v);
-                        }
-                    }
-                }
-            }
-
-            frontiers.insert(n, df);
-        }
+        let frontiers = compute_frontiers(cfg, &dominator_tree, Direction::Incoming);
+        Self { frontiers, dominator_tree }
+    }
 
+    /// Compute post-dominance frontiers: the same algorithm on the reversed CFG
+    ///
+    /// The frontier of n is the set of blocks v such that n post-dominates a
+    /// successor of v but does not strictly post-dominate v, i.e. the branch
+    /// blocks n is control dependent on. [`dominator_tree`](Self::dominator_tree)
+    /// then returns the post-dominator tree (dominance on the reversed CFG).
+    pub fn post(cfg: &Cfg, post_dominator_tree: PostDominatorTree) -> Self {
+        let dominator_tree = post_dominator_tree.as_dominator_tree().clone();
+        let frontiers = compute_frontiers(cfg, &dominator_tree, Direction::Outgoing);
         Self { frontiers, dominator_tree }
     }
 
@@ -205,6 +184,57 @@ impl DominanceFrontiers {
     }
 }
 
+/// Cytron et al. frontiers of `tree`, whose predecessors are the CFG neighbors in `incoming`
+///
+/// `Direction::Incoming` gives dominance frontiers. For a post-dominator tree
+/// (dominance on the reversed CFG), predecessors are CFG successors, so
+/// `Direction::Outgoing` gives post-dominance frontiers.
+fn compute_frontiers(
+    cfg: &Cfg,
+    tree: &DominatorTree,
+    incoming: Direction,
+) -> HashMap<NodeIndex, HashSet<NodeIndex>> {
+    let mut frontiers: HashMap<NodeIndex, HashSet<NodeIndex>> = HashMap::new();
+
+    // Process nodes in reverse post-order (deep nodes first)
+    // We'll use depth in dominator tree as approximation
+    let mut nodes: Vec<NodeIndex> = cfg.node_indices().collect();
+    nodes.sort_by_key(|&n| std::cmp::Reverse(tree.depth(n)));
+
+    // Compute frontier for each node
+    for &n in &nodes {
+        let mut df = HashSet::new();
+
+        // Rule 1: Strict dominance boundary
+        // For each node v, check if n dominates a predecessor of v
+        // but does NOT strictly dominate v itself
+        for &v in &nodes {
+            for p in cfg.neighbors_directed(v, incoming) {
+                if tree.dominates(n, p) && !tree.strictly_dominates(n, v) {
+                    df.insert(v);
+                }
+            }
+        }
+
+        // Rule 2: Propagate children's frontiers
+        // If child c's frontier contains v and n does not strictly dominate v
+        // then v is in n's dominance frontier
+        for &child in tree.children(n) {
+            if let Some(child_df) = frontiers.get(&child) {
+                for &v in child_df {
+                    if !tree.strictly_dominates(n, v) {
+                        df.insert(v);
+                    }
+                }
+            }
+        }
+
+        frontiers.insert(n, df);
+    }
+
+    frontiers
+}
+
 /// Convenience function to compute dominance frontiers
 ///
 /// This is a shorthand for DominanceFrontiers::new().
@@ -222,6 +252,25 @@ pub fn compute_dominance_frontiers(cfg: &Cfg, dominator_tree: DominatorTree) ->
     DominanceFrontiers::new(cfg, dominator_tree)
 }
 
+/// Convenience function to compute post-dominance frontiers (control dependences)
+///
+/// This is a shorthand for DominanceFrontiers::post().
+///
+/// # Example
+/// ```rust,no_run
+/// # use mirage_analyzer::cfg::dominance_frontiers::compute_post_dominance_frontiers;
+/// # use mirage_analyzer::cfg::post_dominators::PostDominatorTree;
+/// # use mirage_analyzer::cfg::Cfg;
+/// # use petgraph::graph::NodeIndex;
+/// # let graph: Cfg = unimplemented!();
+/// # let post_dom_tree = PostDominatorTree::new(&graph).unwrap();
+/// let frontiers = compute_post_dominance_frontiers(&graph, post_dom_tree);
+/// let controlling_branches = frontiers.frontier(NodeIndex::new(1));
+/// ```
+pub fn compute_post_dominance_frontiers(cfg: &Cfg, post_dominator_tree: PostDominatorTree) -> DominanceFrontiers {
+    DominanceFrontiers::post(cfg, post_dominator_tree)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -355,6 +404,23 @@ mod tests {
         assert_eq!(df2.len(), 1);
     }
 
+    #[test]
+    fn test_post_dominance_frontiers_diamond() {
+        let cfg = create_diamond_cfg();
+        let post_dom_tree = PostDominatorTree::new(&cfg).expect("CFG has exit");
+        let frontiers = compute_post_dominance_frontiers(&cfg, post_dom_tree);
+
+        // Each arm runs only if the branch in 0 goes its way:
+        // both arms are control dependent on block 0
+        let branch: HashSet<NodeIndex> = [NodeIndex::new(0)].into_iter().collect();
+        assert_eq!(frontiers.frontier(NodeIndex::new(1)), branch);
+        assert_eq!(frontiers.frontier(NodeIndex::new(2)), branch);
+
+        // Entry and merge point always run
+        assert!(frontiers.frontier(NodeIndex::new(0)).is_empty());
+        assert!(frontiers.frontier(NodeIndex::new(3)).is_empty());
+    }
+
     #[test]
     fn test_dominance_frontiers_loop() {
         let cfg = create_loop_cfg();
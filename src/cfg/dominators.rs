@@ -81,6 +81,32 @@ impl DominatorTree {
         })
     }
 
+    /// Rebuild a dominator tree from a stored immediate dominator map
+    ///
+    /// Used when loading a cached tree from the database. `immediate_dominator`
+    /// should contain every CFG node, with None for the root and unreachable nodes.
+    pub(crate) fn from_immediate_dominators(
+        root: NodeIndex,
+        immediate_dominator: HashMap<NodeIndex, Option<NodeIndex>>,
+    ) -> Self {
+        let mut nodes: Vec<NodeIndex> = immediate_dominator.keys().copied().collect();
+        nodes.sort();
+
+        // Same child order as DominatorTree::new (node index order)
+        let mut children: HashMap<NodeIndex, Vec<NodeIndex>> = HashMap::new();
+        for node in nodes {
+            if let Some(parent) = immediate_dominator[&node] {
+                children.entry(parent).or_default().push(node);
+            }
+        }
+
+        Self {
+            root,
+            immediate_dominator,
+            children,
+        }
+    }
+
     /// Get the root node of the dominator tree
     ///
     /// The root is the entry node of the CFG.
@@ -88,6 +114,19 @@ impl DominatorTree {
         self.root
     }
 
+    /// Nodes the entry cannot reach, in node index order
+    ///
+    /// Every node other than the root that has no immediate dominator. Gives
+    /// the same blocks as `find_unreachable`, so a cached tree can answer it.
+    pub fn unreachable_nodes(&self) -> Vec<NodeIndex> {
+        let mut nodes: Vec<NodeIndex> = self.immediate_dominator.iter()
+            .filter(|&(&node, idom)| node != self.root && idom.is_none())
+            .map(|(&node, _)| node)
+            .collect();
+        nodes.sort();
+        nodes
+    }
+
     /// Get immediate dominator of a node
     ///
     /// Returns None for the root node (which has no dominator).
@@ -277,6 +316,39 @@ pub fn compute_dominator_tree(cfg: &Cfg) -> Option<DominatorTree> {
     DominatorTree::new(cfg)
 }
 
+/// Get dominator tree with hash-based caching
+///
+/// Loads the tree from `cfg_dominators` if it was stored for the same
+/// `function_hash`. On a cache miss (no rows, or the function changed),
+/// computes the tree and stores it for next time.
+///
+/// Returns Ok(None) if the CFG has no entry node.
+pub fn get_or_compute_dominators(
+    cfg: &Cfg,
+    function_id: i64,
+    function_hash: &str,
+    db_conn: &mut rusqlite::Connection,
+) -> Result<Option<DominatorTree>, String> {
+    use crate::storage::dominators::{load_dominators_from_db, store_dominators};
+
+    // Cache hit - tree was computed from the same function content
+    if let Some(tree) = load_dominators_from_db(db_conn, function_id, function_hash, cfg)
+        .map_err(|e| format!("Failed to load cached dominators: {}", e))?
+    {
+        return Ok(Some(tree));
+    }
+
+    // Cache miss or hash changed - compute and replace stored rows
+    let Some(tree) = DominatorTree::new(cfg) else {
+        return Ok(None);
+    };
+
+    store_dominators(db_conn, function_id, function_hash, cfg, &tree)
+        .map_err(|e| format!("Failed to store dominators: {}", e))?;
+
+    Ok(Some(tree))
+}
+
 /// Get immediate dominator as BlockId
 ///
 /// Convenience function that converts NodeIndex to BlockId.
@@ -519,4 +591,107 @@ mod tests {
         assert_eq!(dom_tree.immediate_dominator(b2), Some(b1));
         assert_eq!(dom_tree.immediate_dominator(b3), Some(b2));
     }
+
+    // get_or_compute_dominators tests
+
+    fn create_cache_test_db() -> rusqlite::Connection {
+        use crate::storage::create_schema;
+
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+
+        conn.execute(
+            "CREATE TABLE magellan_meta (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                magellan_schema_version INTEGER NOT NULL,
+                sqlitegraph_schema_version INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        ).unwrap();
+
+        conn.execute(
+            "CREATE TABLE graph_entities (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                file_path TEXT,
+                data TEXT NOT NULL
+            )",
+            [],
+        ).unwrap();
+
+        conn.execute(
+            "INSERT INTO magellan_meta (id, magellan_schema_version, sqlitegraph_schema_version, created_at)
+             VALUES (1, 4, 3, 0)",
+            [],
+        ).unwrap();
+
+        create_schema(&mut conn, crate::storage::TEST_MAGELLAN_SCHEMA_VERSION).unwrap();
+
+        conn.execute(
+            "INSERT INTO graph_entities (kind, name, file_path, data) VALUES (?, ?, ?, ?)",
+            rusqlite::params!("function", "test_func", "test.rs", "{}"),
+        ).unwrap();
+
+        conn
+    }
+
+    /// Point block 3's stored idom at block 1 so a cache hit is observable
+    fn tamper_cached_idom(conn: &rusqlite::Connection) {
+        conn.execute(
+            "UPDATE cfg_dominators SET dominator_id = 1 WHERE function_id = 1 AND block_id = 3 AND is_strict = 1",
+            [],
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_get_or_compute_dominators_cache_miss_computes() {
+        let mut conn = create_cache_test_db();
+        let cfg = create_diamond_cfg();
+
+        let tree = get_or_compute_dominators(&cfg, 1, "hash_123", &mut conn).unwrap().unwrap();
+        assert_eq!(tree.immediate_dominator(NodeIndex::new(3)), Some(NodeIndex::new(0)));
+
+        // Root row plus one row per non-root block
+        let rows: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM cfg_dominators WHERE function_id = 1 AND function_hash = 'hash_123'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(rows, 4);
+    }
+
+    #[test]
+    fn test_get_or_compute_dominators_cache_hit_loads() {
+        let mut conn = create_cache_test_db();
+        let cfg = create_diamond_cfg();
+
+        get_or_compute_dominators(&cfg, 1, "hash_456", &mut conn).unwrap();
+        tamper_cached_idom(&conn);
+
+        // Same hash - stored rows are returned without recomputing
+        let tree = get_or_compute_dominators(&cfg, 1, "hash_456", &mut conn).unwrap().unwrap();
+        assert_eq!(tree.immediate_dominator(NodeIndex::new(3)), Some(NodeIndex::new(1)));
+        assert_eq!(tree.children(NodeIndex::new(1)), &[NodeIndex::new(3)]);
+    }
+
+    #[test]
+    fn test_get_or_compute_dominators_hash_change_invalidates() {
+        let mut conn = create_cache_test_db();
+        let cfg = create_diamond_cfg();
+
+        get_or_compute_dominators(&cfg, 1, "hash_v1", &mut conn).unwrap();
+        tamper_cached_idom(&conn);
+
+        // Different hash - stale rows are ignored and replaced
+        let tree = get_or_compute_dominators(&cfg, 1, "hash_v2", &mut conn).unwrap().unwrap();
+        assert_eq!(tree.immediate_dominator(NodeIndex::new(3)), Some(NodeIndex::new(0)));
+
+        let stale: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM cfg_dominators WHERE function_id = 1 AND function_hash = 'hash_v1'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(stale, 0);
+    }
 }
@@ -21,9 +21,43 @@ pub enum EdgeType {
     Call,
     /// Function return (explicit)
     Return,
+    /// Call unwinding into a cleanup (landing pad) block
+    Unwind,
+    /// Cleanup block resuming an in-progress unwind
+    Resume,
 }
 
 impl EdgeType {
+    /// Every edge type, in declaration order
+    pub const ALL: [EdgeType; 10] = [
+        EdgeType::TrueBranch,
+        EdgeType::FalseBranch,
+        EdgeType::Fallthrough,
+        EdgeType::LoopBack,
+        EdgeType::LoopExit,
+        EdgeType::Exception,
+        EdgeType::Call,
+        EdgeType::Return,
+        EdgeType::Unwind,
+        EdgeType::Resume,
+    ];
+
+    /// One-line meaning, as listed by `mirage edge-types`
+    pub fn description(&self) -> &'static str {
+        match self {
+            EdgeType::TrueBranch => "Conditional branch taken (condition true, or a matched switch arm)",
+            EdgeType::FalseBranch => "Conditional branch not taken (condition false, or the switch otherwise arm)",
+            EdgeType::Fallthrough => "Unconditional flow to the next block (goto or fallthrough)",
+            EdgeType::LoopBack => "Jump back to a loop header",
+            EdgeType::LoopExit => "Leaving a loop when its condition fails",
+            EdgeType::Exception => "Exceptional control flow out of a call",
+            EdgeType::Call => "Normal return from a call to the block after it",
+            EdgeType::Return => "Explicit function return",
+            EdgeType::Unwind => "Call unwinding into a cleanup (landing pad) block",
+            EdgeType::Resume => "Cleanup block resuming an in-progress unwind",
+        }
+    }
+
     /// Color for DOT visualization
     pub fn dot_color(&self) -> &'static str {
         match self {
@@ -35,6 +69,23 @@ impl EdgeType {
             EdgeType::Exception => "purple",
             EdgeType::Call => "gray",
             EdgeType::Return => "darkgray",
+            EdgeType::Unwind => "orange",
+            EdgeType::Resume => "purple",
+        }
+    }
+
+    /// Line style for DOT visualization
+    pub fn dot_style(&self) -> &'static str {
+        match self {
+            EdgeType::Fallthrough | EdgeType::Unwind => "dashed",
+            EdgeType::Resume => "dotted",
+            EdgeType::TrueBranch
+            | EdgeType::FalseBranch
+            | EdgeType::LoopBack
+            | EdgeType::LoopExit
+            | EdgeType::Exception
+            | EdgeType::Call
+            | EdgeType::Return => "solid",
         }
     }
 
@@ -49,10 +100,27 @@ impl EdgeType {
             EdgeType::Exception => "unwind",
             EdgeType::Call => "call",
             EdgeType::Return => "ret",
+            EdgeType::Unwind => "unwind",
+            EdgeType::Resume => "resume",
         }
     }
 }
 
+impl std::str::FromStr for EdgeType {
+    type Err = String;
+
+    /// Parse a variant name such as `TrueBranch`, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EdgeType::ALL
+            .into_iter()
+            .find(|t| format!("{:?}", t).eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let names: Vec<String> = EdgeType::ALL.iter().map(|t| format!("{:?}", t)).collect();
+                format!("unknown edge type '{}' (expected one of: {})", s, names.join(", "))
+            })
+    }
+}
+
 /// Classify edges from a simplified terminator
 ///
 /// This is a simplified version for our core types.
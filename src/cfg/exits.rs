@@ -0,0 +1,174 @@
+//! Exit block reachability and path classification
+//!
+//! Answers "can this function ever return normally?" and "which exit does
+//! the happy path hit?" by listing every exit block with whether it is
+//! reachable from the entry and which kinds of paths end there.
+
+use crate::cfg::analysis::is_exit_block;
+use crate::cfg::reachability::find_reachable;
+use crate::cfg::{BlockId, BlockKind, Cfg, Path, PathKind, Terminator};
+use petgraph::graph::NodeIndex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// How an exit block leaves the function
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExitKind {
+    /// `Return` terminator
+    Return,
+    /// Panic landing block (`BlockKind::Panic`)
+    Panic,
+    /// `Abort` terminator
+    Abort,
+    /// `Unreachable` terminator
+    Unreachable,
+    /// Any other block without successors, e.g. a call that never returns
+    Diverge,
+}
+
+/// One exit block of a function
+#[derive(Debug, Clone, Serialize)]
+pub struct ExitInfo {
+    pub block_id: BlockId,
+    pub exit_kind: ExitKind,
+    /// Whether any path from the entry reaches this block
+    pub reachable: bool,
+    /// Number of enumerated paths ending at this block
+    pub path_count: usize,
+    /// Distinct kinds of the paths ending here, in `PathKind` declaration order
+    pub path_kinds: Vec<PathKind>,
+}
+
+/// Classify an exit block
+fn exit_kind(cfg: &Cfg, node: NodeIndex) -> ExitKind {
+    let block = &cfg[node];
+    if block.kind == BlockKind::Panic {
+        return ExitKind::Panic;
+    }
+    match block.terminator {
+        Terminator::Return => ExitKind::Return,
+        Terminator::Abort(_) => ExitKind::Abort,
+        Terminator::Unreachable => ExitKind::Unreachable,
+        _ => ExitKind::Diverge,
+    }
+}
+
+/// List every exit block with its reachability and the paths ending there
+///
+/// Exits are blocks with an exiting terminator (see
+/// [`is_exit_block`](crate::cfg::analysis::is_exit_block)) plus any other
+/// block without successors. `paths` should come from
+/// [`enumerate_paths`](crate::cfg::enumerate_paths) on the same CFG; when
+/// enumeration was truncated by its limits, path counts are lower bounds.
+/// Results are sorted by block ID.
+pub fn analyze_exits(cfg: &Cfg, paths: &[Path]) -> Vec<ExitInfo> {
+    let reachable: HashSet<NodeIndex> = find_reachable(cfg).into_iter().collect();
+
+    let mut kinds_by_exit: HashMap<BlockId, (usize, HashSet<PathKind>)> = HashMap::new();
+    for path in paths {
+        let entry = kinds_by_exit.entry(path.exit).or_default();
+        entry.0 += 1;
+        entry.1.insert(path.kind);
+    }
+
+    let mut exits: Vec<ExitInfo> = cfg
+        .node_indices()
+        .filter(|&node| {
+            is_exit_block(cfg, node)
+                || cfg.neighbors_directed(node, petgraph::Direction::Outgoing).next().is_none()
+        })
+        .map(|node| {
+            let block_id = cfg[node].id;
+            let (path_count, kinds) = kinds_by_exit.remove(&block_id).unwrap_or_default();
+            ExitInfo {
+                block_id,
+                exit_kind: exit_kind(cfg, node),
+                reachable: reachable.contains(&node),
+                path_count,
+                path_kinds: [PathKind::Normal, PathKind::Error, PathKind::Degenerate, PathKind::Unreachable]
+                    .into_iter()
+                    .filter(|kind| kinds.contains(kind))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    exits.sort_by_key(|exit| exit.block_id);
+    exits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{enumerate_paths, BasicBlock, EdgeType, PathLimits};
+    use petgraph::graph::DiGraph;
+
+    fn block(id: BlockId, kind: BlockKind, terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        }
+    }
+
+    /// 0 -> 1 (switch) -> 2 (return) | 3 (abort); block 4 is a dead return
+    fn create_exits_cfg() -> Cfg {
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::SwitchInt { targets: vec![2], otherwise: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Exit, Terminator::Return));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Abort("overflow".to_string())));
+        g.add_node(block(4, BlockKind::Exit, Terminator::Return));
+
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+        g.add_edge(b1, b2, EdgeType::TrueBranch);
+        g.add_edge(b1, b3, EdgeType::FalseBranch);
+        g
+    }
+
+    #[test]
+    fn test_analyze_exits_reachable_and_unreachable() {
+        let cfg = create_exits_cfg();
+        let paths = enumerate_paths(&cfg, &PathLimits::default());
+        let exits = analyze_exits(&cfg, &paths);
+
+        let ids: Vec<BlockId> = exits.iter().map(|e| e.block_id).collect();
+        assert_eq!(ids, vec![2, 3, 4]);
+
+        // Happy path returns through block 2
+        assert_eq!(exits[0].exit_kind, ExitKind::Return);
+        assert!(exits[0].reachable);
+        assert_eq!(exits[0].path_count, 1);
+        assert_eq!(exits[0].path_kinds, vec![PathKind::Normal]);
+
+        // Abort is reachable, but only on an error path
+        assert_eq!(exits[1].exit_kind, ExitKind::Abort);
+        assert!(exits[1].reachable);
+        assert_eq!(exits[1].path_kinds, vec![PathKind::Error]);
+
+        // Dead return: no path from the entry reaches it
+        assert_eq!(exits[2].exit_kind, ExitKind::Return);
+        assert!(!exits[2].reachable);
+        assert_eq!(exits[2].path_count, 0);
+        assert!(exits[2].path_kinds.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_exits_panic_block() {
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Panic, Terminator::Unreachable));
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+
+        let paths = enumerate_paths(&g, &PathLimits::default());
+        let exits = analyze_exits(&g, &paths);
+
+        assert_eq!(exits.len(), 1);
+        assert_eq!(exits[0].exit_kind, ExitKind::Panic);
+        assert!(exits[0].reachable);
+        assert!(!exits[0].path_kinds.contains(&PathKind::Normal), "A panic exit never returns normally");
+    }
+}
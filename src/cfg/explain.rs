@@ -0,0 +1,364 @@
+//! Whole-function control flow explanations in natural language
+//!
+//! Composes the pattern, loop and dominator detectors into one structured
+//! description of a function: where it starts, how it branches, where it
+//! loops and how it exits. Every list is sorted by block ID so the generated
+//! text is stable across runs.
+
+use crate::cfg::analysis::{find_entry, find_exits, is_branch_point};
+use crate::cfg::dominators::DominatorTree;
+use crate::cfg::loops::detect_natural_loops;
+use crate::cfg::patterns::{detect_if_else_patterns, detect_match_patterns};
+use crate::cfg::{BlockId, BlockKind, Cfg, Terminator};
+use petgraph::graph::NodeIndex;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Structured explanation of a function's control flow
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    /// One-paragraph overview
+    pub summary: String,
+    /// Branch points, sorted by block ID
+    pub branches: Vec<BranchExplanation>,
+    /// Natural loops, sorted by header block ID
+    pub loops: Vec<LoopExplanation>,
+    /// Exit blocks, sorted by block ID
+    pub exits: Vec<ExitExplanation>,
+}
+
+impl Explanation {
+    /// Summary followed by every branch, loop and exit sentence
+    pub fn paragraph(&self) -> String {
+        std::iter::once(self.summary.as_str())
+            .chain(self.branches.iter().map(|b| b.description.as_str()))
+            .chain(self.loops.iter().map(|l| l.description.as_str()))
+            .chain(self.exits.iter().map(|e| e.description.as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A branch point and where its arms go
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchExplanation {
+    /// Block ending in the branch
+    pub block: BlockId,
+    /// "if-else", "if", "match" or "loop-condition"
+    pub kind: String,
+    /// Last statement of the branch block, if statements were recorded
+    pub condition: Option<String>,
+    /// Successor blocks, sorted
+    pub targets: Vec<BlockId>,
+    /// Block where the arms reconverge, if they do
+    pub merge_point: Option<BlockId>,
+    /// Source line of the branch block
+    pub line: Option<usize>,
+    /// Sentence describing the branch
+    pub description: String,
+}
+
+/// A natural loop
+#[derive(Debug, Clone, Serialize)]
+pub struct LoopExplanation {
+    /// Loop header block
+    pub header: BlockId,
+    /// Source of the back edge
+    pub back_edge_from: BlockId,
+    /// Number of blocks in the body (including the header)
+    pub body_size: usize,
+    /// 0 for outermost loops
+    pub nesting_level: usize,
+    /// Sentence describing the loop
+    pub description: String,
+}
+
+/// A block that leaves the function
+#[derive(Debug, Clone, Serialize)]
+pub struct ExitExplanation {
+    /// Exit block
+    pub block: BlockId,
+    /// "return", "early-return" or "panic"
+    pub kind: String,
+    /// Nearest dominating branch block, i.e. the decision that leads here
+    pub guarded_by: Option<BlockId>,
+    /// Source line of the exit block
+    pub line: Option<usize>,
+    /// Sentence describing the exit
+    pub description: String,
+}
+
+/// Explain the control flow of a function
+///
+/// Branches come from `detect_if_else_patterns` / `detect_match_patterns`,
+/// with loop headers reported as loop conditions. Of several `Return` exits,
+/// the one with the highest block ID is the normal return and the others are
+/// early returns. Each exit's guard is its nearest dominator that is a branch
+/// point.
+pub fn explain_function(function_name: &str, cfg: &Cfg) -> Explanation {
+    let loops = detect_natural_loops(cfg);
+    let dom_tree = DominatorTree::new(cfg);
+
+    let mut loop_headers: Vec<NodeIndex> = loops.iter().map(|l| l.header).collect();
+    loop_headers.sort_by_key(|&n| cfg[n].id);
+    loop_headers.dedup();
+
+    // Branches
+    let mut merge_points: HashMap<NodeIndex, Option<NodeIndex>> = detect_if_else_patterns(cfg)
+        .into_iter()
+        .map(|p| (p.condition, p.merge_point))
+        .collect();
+    let match_nodes: Vec<NodeIndex> = detect_match_patterns(cfg)
+        .into_iter()
+        .map(|p| p.switch_node)
+        .collect();
+
+    let mut branch_nodes: Vec<NodeIndex> = cfg.node_indices()
+        .filter(|&n| is_branch_point(cfg, n))
+        .collect();
+    branch_nodes.sort_by_key(|&n| cfg[n].id);
+
+    let branches: Vec<BranchExplanation> = branch_nodes.iter().map(|&node| {
+        let block = &cfg[node];
+        let mut targets: Vec<BlockId> = cfg.neighbors(node).map(|n| cfg[n].id).collect();
+        targets.sort_unstable();
+        targets.dedup();
+
+        let merge_point = merge_points.remove(&node).flatten().map(|n| cfg[n].id);
+        let kind = if loop_headers.contains(&node) {
+            "loop-condition"
+        } else if match_nodes.contains(&node) {
+            "match"
+        } else if merge_point.is_some() {
+            "if-else"
+        } else {
+            "if"
+        };
+        let condition = block.statements.last().cloned();
+
+        let on = condition.as_ref()
+            .map(|c| format!(" on `{}`", c))
+            .unwrap_or_default();
+        let arms = join_blocks(&targets);
+        let description = match (kind, merge_point) {
+            ("match", Some(m)) => format!("Block b{} matches{} into {} arms ({}), rejoining at b{}.", block.id, on, targets.len(), arms, m),
+            ("match", None) => format!("Block b{} matches{} into {} arms ({}).", block.id, on, targets.len(), arms),
+            ("loop-condition", _) => format!("Block b{} decides{} whether to continue the loop, going to {}.", block.id, on, arms),
+            (_, Some(m)) => format!("Block b{} branches{} to {}, rejoining at b{}.", block.id, on, arms, m),
+            (_, None) => format!("Block b{} branches{} to {} without rejoining.", block.id, on, arms),
+        };
+
+        BranchExplanation {
+            block: block.id,
+            kind: kind.to_string(),
+            condition,
+            targets,
+            merge_point,
+            line: block.source_location.as_ref().map(|l| l.start_line),
+            description,
+        }
+    }).collect();
+
+    // Loops
+    let mut loop_explanations: Vec<LoopExplanation> = loops.iter().map(|l| {
+        let header = cfg[l.header].id;
+        let nesting_level = l.nesting_level(&loops);
+        let nested = if nesting_level > 0 {
+            format!(" nested {} level(s) deep", nesting_level)
+        } else {
+            String::new()
+        };
+        LoopExplanation {
+            header,
+            back_edge_from: cfg[l.back_edge.0].id,
+            body_size: l.size(),
+            nesting_level,
+            description: format!(
+                "A loop{} headed at b{} spans {} block(s) and repeats from b{}.",
+                nested, header, l.size(), cfg[l.back_edge.0].id
+            ),
+        }
+    }).collect();
+    loop_explanations.sort_by_key(|l| (l.header, l.back_edge_from));
+
+    // Exits
+    let mut exit_nodes = find_exits(cfg);
+    exit_nodes.sort_by_key(|&n| cfg[n].id);
+    let last_return = exit_nodes.iter()
+        .filter(|&&n| matches!(cfg[n].terminator, Terminator::Return) && cfg[n].kind != BlockKind::Panic)
+        .map(|&n| cfg[n].id)
+        .max();
+
+    let exits: Vec<ExitExplanation> = exit_nodes.iter().map(|&node| {
+        let block = &cfg[node];
+        let kind = if block.kind == BlockKind::Panic || !matches!(block.terminator, Terminator::Return) {
+            "panic"
+        } else if Some(block.id) == last_return {
+            "return"
+        } else {
+            "early-return"
+        };
+
+        let guarded_by = dom_tree.as_ref().and_then(|tree| {
+            tree.dominators(node)
+                .skip(1)
+                .find(|&d| is_branch_point(cfg, d))
+                .map(|d| cfg[d].id)
+        });
+        let guard = guarded_by
+            .map(|g| format!(" when b{} takes that arm", g))
+            .unwrap_or_default();
+        let description = match kind {
+            "return" => format!("The function returns normally at b{}{}.", block.id, guard),
+            "early-return" => format!("It returns early at b{}{}.", block.id, guard),
+            _ => format!("It panics at b{}{}.", block.id, guard),
+        };
+
+        ExitExplanation {
+            block: block.id,
+            kind: kind.to_string(),
+            guarded_by,
+            line: block.source_location.as_ref().map(|l| l.start_line),
+            description,
+        }
+    }).collect();
+
+    let summary = compose_summary(function_name, cfg, &branches, &loop_explanations, &exits);
+
+    Explanation {
+        summary,
+        branches,
+        loops: loop_explanations,
+        exits,
+    }
+}
+
+/// Overview sentence: entry, size, and counts of branches, loops and exits
+fn compose_summary(
+    function_name: &str,
+    cfg: &Cfg,
+    branches: &[BranchExplanation],
+    loops: &[LoopExplanation],
+    exits: &[ExitExplanation],
+) -> String {
+    let entry = find_entry(cfg)
+        .map(|n| format!("b{}", cfg[n].id))
+        .unwrap_or_else(|| "no entry block".to_string());
+
+    let count_kind = |kinds: &[&str], items: &[&str]| -> String {
+        let parts: Vec<String> = kinds.iter()
+            .map(|k| (k, items.iter().filter(|i| *i == k).count()))
+            .filter(|(_, n)| *n > 0)
+            .map(|(k, n)| format!("{} {}", n, k))
+            .collect();
+        if parts.is_empty() { "none".to_string() } else { parts.join(", ") }
+    };
+
+    let branch_kinds: Vec<&str> = branches.iter().map(|b| b.kind.as_str()).collect();
+    let exit_kinds: Vec<&str> = exits.iter().map(|e| e.kind.as_str()).collect();
+
+    format!(
+        "Function '{}' starts at {} and has {} block(s). It has {} branch point(s) ({}), {} loop(s), and {} exit(s) ({}).",
+        function_name,
+        entry,
+        cfg.node_count(),
+        branches.len(),
+        count_kind(&["if-else", "if", "match", "loop-condition"], &branch_kinds),
+        loops.len(),
+        exits.len(),
+        count_kind(&["return", "early-return", "panic"], &exit_kinds),
+    )
+}
+
+/// "b1, b2 and b3"
+fn join_blocks(blocks: &[BlockId]) -> String {
+    let names: Vec<String> = blocks.iter().map(|b| format!("b{}", b)).collect();
+    match names.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{BasicBlock, EdgeType};
+    use petgraph::graph::DiGraph;
+
+    fn block(id: BlockId, kind: BlockKind, statements: Vec<&str>, terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id,
+            kind,
+            statements: statements.into_iter().map(String::from).collect(),
+            terminator,
+            source_location: None,
+        }
+    }
+
+    /// b0 checks input (early return at b1), b2 loops over b3, then returns at b4
+    /// or panics at b5
+    fn create_guarded_loop_cfg() -> Cfg {
+        let mut g = DiGraph::new();
+
+        let b0 = g.add_node(block(0, BlockKind::Entry, vec!["input.is_empty()"],
+            Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, vec![], Terminator::Return));
+        let b2 = g.add_node(block(2, BlockKind::Normal, vec!["i < n"],
+            Terminator::SwitchInt { targets: vec![3], otherwise: 6 }));
+        let b3 = g.add_node(block(3, BlockKind::Normal, vec![], Terminator::Goto { target: 2 }));
+        let b6 = g.add_node(block(6, BlockKind::Normal, vec!["ok"],
+            Terminator::SwitchInt { targets: vec![4], otherwise: 5 }));
+        let b4 = g.add_node(block(4, BlockKind::Exit, vec![], Terminator::Return));
+        let b5 = g.add_node(block(5, BlockKind::Panic, vec![], Terminator::Unreachable));
+
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b2, b3, EdgeType::TrueBranch);
+        g.add_edge(b2, b6, EdgeType::FalseBranch);
+        g.add_edge(b3, b2, EdgeType::LoopBack);
+        g.add_edge(b6, b4, EdgeType::TrueBranch);
+        g.add_edge(b6, b5, EdgeType::FalseBranch);
+
+        g
+    }
+
+    #[test]
+    fn test_explain_function_structure() {
+        let cfg = create_guarded_loop_cfg();
+        let explanation = explain_function("process", &cfg);
+
+        let branches: Vec<(BlockId, &str)> = explanation.branches.iter()
+            .map(|b| (b.block, b.kind.as_str()))
+            .collect();
+        assert_eq!(branches, vec![(0, "if"), (2, "loop-condition"), (6, "if")]);
+        assert_eq!(explanation.branches[0].condition.as_deref(), Some("input.is_empty()"));
+
+        assert_eq!(explanation.loops.len(), 1);
+        assert_eq!(explanation.loops[0].header, 2);
+        assert_eq!(explanation.loops[0].back_edge_from, 3);
+
+        let exits: Vec<(BlockId, &str, Option<BlockId>)> = explanation.exits.iter()
+            .map(|e| (e.block, e.kind.as_str(), e.guarded_by))
+            .collect();
+        assert_eq!(exits, vec![
+            (1, "early-return", Some(0)),
+            (4, "return", Some(6)),
+            (5, "panic", Some(6)),
+        ]);
+
+        assert!(explanation.summary.starts_with("Function 'process' starts at b0 and has 7 block(s)."));
+        assert!(explanation.summary.contains("1 return, 1 early-return, 1 panic"));
+    }
+
+    #[test]
+    fn test_explain_function_is_deterministic() {
+        let cfg = create_guarded_loop_cfg();
+        let first = explain_function("process", &cfg).paragraph();
+
+        for _ in 0..5 {
+            assert_eq!(explain_function("process", &cfg).paragraph(), first);
+        }
+        assert!(first.contains("Block b0 branches on `input.is_empty()` to b1 and b2 without rejoining."));
+    }
+}
@@ -1,32 +1,182 @@
 //! CFG export to DOT and JSON formats
 
-use crate::cfg::{BlockKind, Cfg, EdgeType, Terminator};
+use crate::cfg::dominators::DominatorTree;
+use crate::cfg::loops::NaturalLoop;
+use crate::cfg::{BlockKind, Cfg, EdgeType, SourceLocation, Terminator};
+use petgraph::graph::NodeIndex;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Write;
 
+/// Graph direction for DOT output (Graphviz `rankdir`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RankDir {
+    /// Top to bottom
+    #[default]
+    TB,
+    /// Left to right
+    LR,
+    /// Bottom to top
+    BT,
+    /// Right to left
+    RL,
+}
+
+impl RankDir {
+    fn as_dot(self) -> &'static str {
+        match self {
+            RankDir::TB => "TB",
+            RankDir::LR => "LR",
+            RankDir::BT => "BT",
+            RankDir::RL => "RL",
+        }
+    }
+}
+
+/// Edge routing for DOT output (Graphviz `splines`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Splines {
+    /// Straight line segments
+    Line,
+    /// Axis-aligned segments
+    Ortho,
+    /// Curved edges
+    #[default]
+    Curved,
+}
+
+impl Splines {
+    fn as_dot(self) -> &'static str {
+        match self {
+            Splines::Line => "line",
+            Splines::Ortho => "ortho",
+            Splines::Curved => "curved",
+        }
+    }
+}
+
+/// Graph-level layout attributes for DOT output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DotOptions {
+    pub rankdir: RankDir,
+    pub splines: Splines,
+}
+
 /// Export CFG to DOT format for Graphviz
 pub fn export_dot(cfg: &Cfg) -> String {
-    let mut dot = String::from("digraph CFG {\n");
+    export_dot_with_options(cfg, &DotOptions::default())
+}
+
+/// Export CFG to DOT with the given layout attributes
+pub fn export_dot_with_options(cfg: &Cfg, options: &DotOptions) -> String {
+    write_dot(cfg, None, None, options)
+}
+
+/// Export CFG to DOT, labelling each block with its loop depth
+///
+/// Blocks inside loops get a "loop depth N" label line, and normal blocks
+/// are shaded darker the more loops enclose them.
+pub fn export_dot_with_loop_depths(
+    cfg: &Cfg,
+    loop_depths: &HashMap<NodeIndex, usize>,
+    options: &DotOptions,
+) -> String {
+    write_dot(cfg, Some(loop_depths), None, options)
+}
+
+/// Export CFG to DOT, grouping each natural loop's body in a cluster
+///
+/// Each loop header gets a `subgraph cluster_N { label="loop @H" }`;
+/// nested loops become nested clusters and blocks outside any loop stay
+/// at the top level. Loops sharing a header are merged into one cluster.
+pub fn export_dot_clustered(
+    cfg: &Cfg,
+    loops: &[NaturalLoop],
+    loop_depths: Option<&HashMap<NodeIndex, usize>>,
+    options: &DotOptions,
+) -> String {
+    write_dot(cfg, loop_depths, Some(loops), options)
+}
+
+/// Export a dominator tree to DOT as `digraph DomTree`
+///
+/// Each block points to the blocks it immediately dominates. With
+/// `with_cfg`, the original CFG edges are overlaid as gray dashed edges
+/// that do not affect the layout. Pass `PostDominatorTree::as_dominator_tree`
+/// to draw the post-dominator tree.
+pub fn export_dominator_tree_dot(cfg: &Cfg, tree: &DominatorTree, with_cfg: bool) -> String {
+    let mut dot = String::from("digraph DomTree {\n");
     dot.push_str("  rankdir=TB;\n");
     dot.push_str("  node [shape=box, style=rounded];\n\n");
 
-    // Define nodes
-    for node_idx in cfg.node_indices() {
-        if let Some(block) = cfg.node_weight(node_idx) {
-            let label = escape_dot_string(&format!(
-                "Block {}\\n{}\\n{}",
-                block.id,
-                format_block_kind(&block.kind),
-                format_terminator(&block.terminator)
-            ));
-
-            let style = match block.kind {
-                BlockKind::Entry => "fillcolor=lightgreen, style=filled",
-                BlockKind::Exit => "fillcolor=lightcoral, style=filled",
-                BlockKind::Normal => "",
-            };
+    // Walk the tree from the root so unreachable blocks are left out
+    let mut order = vec![tree.root()];
+    let mut next = 0;
+    while next < order.len() {
+        order.extend_from_slice(tree.children(order[next]));
+        next += 1;
+    }
+    for &node in &order {
+        write_dot_node(&mut dot, cfg, node, None, 1);
+    }
+
+    dot.push('\n');
+    for &node in &order {
+        for &child in tree.children(node) {
+            writeln!(dot, "  \"{}\" -> \"{}\";", node.index(), child.index()).ok();
+        }
+    }
+
+    if with_cfg {
+        let in_tree: HashSet<NodeIndex> = order.iter().copied().collect();
+        dot.push('\n');
+        for edge_idx in cfg.edge_indices() {
+            let (from, to) = cfg.edge_endpoints(edge_idx).unwrap();
+            if in_tree.contains(&from) && in_tree.contains(&to) {
+                writeln!(
+                    dot,
+                    "  \"{}\" -> \"{}\" [color=gray, style=dashed, constraint=false];",
+                    from.index(),
+                    to.index()
+                )
+                .ok();
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn write_dot(
+    cfg: &Cfg,
+    loop_depths: Option<&HashMap<NodeIndex, usize>>,
+    loops: Option<&[NaturalLoop]>,
+    options: &DotOptions,
+) -> String {
+    let mut dot = String::from("digraph CFG {\n");
+    writeln!(dot, "  rankdir={};", options.rankdir.as_dot()).ok();
+    writeln!(dot, "  splines={};", options.splines.as_dot()).ok();
+    dot.push_str("  node [shape=box, style=rounded];\n\n");
 
-            writeln!(dot, "  \"{}\" [label=\"{}\" {}];", node_idx.index(), label, style).ok();
+    // Define nodes
+    match loops {
+        Some(loops) if !loops.is_empty() => {
+            let clusters = LoopClusters::new(cfg, loops);
+            let mut next_cluster = 0;
+            for node_idx in cfg.node_indices() {
+                if clusters.innermost(node_idx).is_none() {
+                    write_dot_node(&mut dot, cfg, node_idx, loop_depths, 1);
+                }
+            }
+            for root in clusters.children_of(None) {
+                clusters.write(&mut dot, cfg, root, loop_depths, 1, &mut next_cluster);
+            }
+        }
+        _ => {
+            for node_idx in cfg.node_indices() {
+                write_dot_node(&mut dot, cfg, node_idx, loop_depths, 1);
+            }
         }
     }
 
@@ -49,11 +199,7 @@ pub fn export_dot(cfg: &Cfg) -> String {
                 from.index(),
                 to.index(),
                 color,
-                if *edge_type == EdgeType::Fallthrough {
-                    "dashed"
-                } else {
-                    "solid"
-                },
+                edge_type.dot_style(),
                 label_attr
             )
             .ok();
@@ -64,6 +210,130 @@ pub fn export_dot(cfg: &Cfg) -> String {
     dot
 }
 
+fn write_dot_node(
+    dot: &mut String,
+    cfg: &Cfg,
+    node_idx: NodeIndex,
+    loop_depths: Option<&HashMap<NodeIndex, usize>>,
+    indent: usize,
+) {
+    if let Some(block) = cfg.node_weight(node_idx) {
+        let depth = loop_depths
+            .and_then(|depths| depths.get(&node_idx).copied())
+            .unwrap_or(0);
+
+        let mut label = format!(
+            "Block {}\\n{}\\n{}",
+            block.id,
+            format_block_kind(&block.kind),
+            format_terminator(&block.terminator)
+        );
+        if depth > 0 {
+            write!(label, "\\nloop depth {}", depth).ok();
+        }
+        let label = escape_dot_string(&label);
+
+        let style = match block.kind {
+            BlockKind::Entry => "fillcolor=lightgreen, style=filled",
+            BlockKind::Exit => "fillcolor=lightcoral, style=filled",
+            BlockKind::Panic => "fillcolor=lightgray, style=\"filled,dashed\"",
+            BlockKind::Normal => loop_depth_style(depth),
+        };
+
+        // Hovering a node in rendered SVG shows where the block comes from
+        let tooltip = block.source_location.as_ref()
+            .map(|location| format!(" tooltip=\"{}\"", escape_dot_string(&dot_tooltip(location))))
+            .unwrap_or_default();
+
+        writeln!(dot, "{:indent$}\"{}\" [label=\"{}\"{} {}];", "", node_idx.index(), label, tooltip, style, indent = indent * 2).ok();
+    }
+}
+
+/// Loop bodies grouped by header, with the nesting between them
+struct LoopClusters {
+    /// (header, body), ordered by header block ID
+    clusters: Vec<(NodeIndex, HashSet<NodeIndex>)>,
+    /// Index of the enclosing cluster, if any
+    parents: Vec<Option<usize>>,
+}
+
+impl LoopClusters {
+    fn new(cfg: &Cfg, loops: &[NaturalLoop]) -> Self {
+        let mut by_header: HashMap<NodeIndex, HashSet<NodeIndex>> = HashMap::new();
+        for loop_ in loops {
+            by_header.entry(loop_.header).or_default().extend(loop_.body.iter().copied());
+        }
+        let mut clusters: Vec<(NodeIndex, HashSet<NodeIndex>)> = by_header.into_iter().collect();
+        clusters.sort_by_key(|(header, _)| cfg[*header].id);
+
+        // Natural loops with distinct headers are nested or disjoint, so the
+        // smallest other body containing a header is its parent
+        let parents = clusters.iter().map(|(header, body)| {
+            clusters.iter().enumerate()
+                .filter(|(_, (other, other_body))| other != header && other_body.contains(header) && other_body.len() > body.len())
+                .min_by_key(|(_, (_, other_body))| other_body.len())
+                .map(|(i, _)| i)
+        }).collect();
+
+        Self { clusters, parents }
+    }
+
+    /// Smallest cluster containing `node`
+    fn innermost(&self, node: NodeIndex) -> Option<usize> {
+        self.clusters.iter().enumerate()
+            .filter(|(_, (_, body))| body.contains(&node))
+            .min_by_key(|(_, (_, body))| body.len())
+            .map(|(i, _)| i)
+    }
+
+    fn children_of(&self, parent: Option<usize>) -> Vec<usize> {
+        (0..self.clusters.len()).filter(|&i| self.parents[i] == parent).collect()
+    }
+
+    fn write(
+        &self,
+        dot: &mut String,
+        cfg: &Cfg,
+        cluster: usize,
+        loop_depths: Option<&HashMap<NodeIndex, usize>>,
+        indent: usize,
+        next_cluster: &mut usize,
+    ) {
+        let pad = indent * 2;
+        writeln!(dot, "{:pad$}subgraph cluster_{} {{", "", next_cluster, pad = pad).ok();
+        *next_cluster += 1;
+        writeln!(dot, "{:pad$}  label=\"loop @{}\";", "", cfg[self.clusters[cluster].0].id, pad = pad).ok();
+        for node_idx in cfg.node_indices() {
+            if self.innermost(node_idx) == Some(cluster) {
+                write_dot_node(dot, cfg, node_idx, loop_depths, indent + 1);
+            }
+        }
+        for child in self.children_of(Some(cluster)) {
+            self.write(dot, cfg, child, loop_depths, indent + 1, next_cluster);
+        }
+        writeln!(dot, "{:pad$}}}", "", pad = pad).ok();
+    }
+}
+
+/// Blue fill that deepens with loop nesting (capped at depth 3)
+fn loop_depth_style(depth: usize) -> &'static str {
+    match depth {
+        0 => "",
+        1 => "fillcolor=\"#dbe9ff\", style=filled",
+        2 => "fillcolor=\"#a9c8ff\", style=filled",
+        _ => "fillcolor=\"#6fa3ff\", style=filled",
+    }
+}
+
+/// `file:line` for a block, or the byte range when lines were never resolved
+fn dot_tooltip(location: &SourceLocation) -> String {
+    if location.start_line > 0 {
+        format!("{}:{}", location.file_path.display(), location.start_line)
+    } else {
+        location.display_or_bytes()
+    }
+}
+
 fn escape_dot_string(s: &str) -> String {
     s.replace('"', "\\\"")
 }
@@ -73,10 +343,12 @@ fn format_block_kind(kind: &BlockKind) -> &'static str {
         BlockKind::Entry => "ENTRY",
         BlockKind::Normal => "NORMAL",
         BlockKind::Exit => "EXIT",
+        BlockKind::Panic => "PANIC",
     }
 }
 
-fn format_terminator(term: &Terminator) -> String {
+/// One-line terminator description, also used by the `tui` block view
+pub(crate) fn format_terminator(term: &Terminator) -> String {
     match term {
         Terminator::Goto { target } => format!("goto {}", target),
         Terminator::SwitchInt { targets, otherwise } => {
@@ -99,6 +371,50 @@ pub struct CFGExport {
     pub exits: Vec<usize>,
     pub blocks: Vec<BlockExport>,
     pub edges: Vec<EdgeExport>,
+    /// Block ID -> successor block IDs (only with `with_adjacency`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub successors: Option<BTreeMap<usize, Vec<usize>>>,
+    /// Block ID -> predecessor block IDs (only with `with_adjacency`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub predecessors: Option<BTreeMap<usize, Vec<usize>>>,
+}
+
+impl CFGExport {
+    /// Add `successors` and `predecessors` maps computed from the CFG
+    ///
+    /// Uses the same block numbering as `edges`. Every block has an entry,
+    /// empty when it has no neighbors in that direction; lists are sorted and
+    /// hold each neighbor once even when several edges connect the blocks.
+    pub fn with_adjacency(mut self, cfg: &Cfg) -> Self {
+        let adjacency = |direction| {
+            cfg.node_indices()
+                .map(|node| {
+                    let mut neighbors: Vec<usize> = cfg
+                        .neighbors_directed(node, direction)
+                        .map(|n| n.index())
+                        .collect();
+                    neighbors.sort_unstable();
+                    neighbors.dedup();
+                    (node.index(), neighbors)
+                })
+                .collect()
+        };
+        self.successors = Some(adjacency(petgraph::Direction::Outgoing));
+        self.predecessors = Some(adjacency(petgraph::Direction::Incoming));
+        self
+    }
+
+    /// Set each block's `in_degree` and `out_degree` from the CFG
+    ///
+    /// Degrees count edges, so two edges between the same blocks count
+    /// twice. High fan-in marks merge hotspots, high fan-out branch hubs.
+    pub fn with_degrees(mut self, cfg: &Cfg) -> Self {
+        for (block, node) in self.blocks.iter_mut().zip(cfg.node_indices()) {
+            block.in_degree = Some(cfg.edges_directed(node, petgraph::Direction::Incoming).count());
+            block.out_degree = Some(cfg.edges_directed(node, petgraph::Direction::Outgoing).count());
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,6 +424,15 @@ pub struct BlockExport {
     pub statements: Vec<String>,
     pub terminator: String,
     pub source_location: Option<String>,
+    /// Number of enclosing loops (only with `export_json_with_loop_depths`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loop_depth: Option<usize>,
+    /// Number of incoming edges (only with `with_degrees`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_degree: Option<usize>,
+    /// Number of outgoing edges (only with `with_degrees`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub out_degree: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -117,8 +442,31 @@ pub struct EdgeExport {
     pub kind: String,
 }
 
+/// Copy of `cfg` keeping only edges whose type is in `keep`
+///
+/// Every block is kept, even ones left without edges, so block indices in
+/// the result match the original CFG.
+pub fn retain_edge_types(cfg: &Cfg, keep: &[EdgeType]) -> Cfg {
+    let mut filtered = cfg.clone();
+    filtered.retain_edges(|g, e| keep.contains(&g[e]));
+    filtered
+}
+
 /// Export CFG to JSON format
 pub fn export_json(cfg: &Cfg, function_name: &str) -> CFGExport {
+    build_json(cfg, function_name, None)
+}
+
+/// Export CFG to JSON format with each block's `loop_depth` set
+pub fn export_json_with_loop_depths(
+    cfg: &Cfg,
+    function_name: &str,
+    loop_depths: &HashMap<NodeIndex, usize>,
+) -> CFGExport {
+    build_json(cfg, function_name, Some(loop_depths))
+}
+
+fn build_json(cfg: &Cfg, function_name: &str, loop_depths: Option<&HashMap<NodeIndex, usize>>) -> CFGExport {
     use crate::cfg::analysis;
 
     let entry = analysis::find_entry(cfg).map(|idx| idx.index());
@@ -139,7 +487,10 @@ pub fn export_json(cfg: &Cfg, function_name: &str) -> CFGExport {
                 source_location: block
                     .source_location
                     .as_ref()
-                    .map(|loc| loc.display()),
+                    .map(|loc| loc.display_or_bytes()),
+                loop_depth: loop_depths.map(|depths| depths.get(&idx).copied().unwrap_or(0)),
+                in_degree: None,
+                out_degree: None,
             }
         })
         .collect();
@@ -163,6 +514,8 @@ pub fn export_json(cfg: &Cfg, function_name: &str) -> CFGExport {
         exits,
         blocks,
         edges,
+        successors: None,
+        predecessors: None,
     }
 }
 
@@ -249,6 +602,87 @@ mod tests {
         assert!(export.edges.iter().any(|e| e.kind == "FalseBranch"));
     }
 
+    #[test]
+    fn test_export_json_with_adjacency() {
+        let cfg = create_test_cfg();
+
+        let plain = export_json(&cfg, "test_function");
+        assert!(plain.successors.is_none() && plain.predecessors.is_none());
+        let json = serde_json::to_value(&plain).unwrap();
+        assert!(json.get("successors").is_none(), "Adjacency is omitted by default");
+
+        let export = export_json(&cfg, "test_function").with_adjacency(&cfg);
+        let successors = export.successors.as_ref().unwrap();
+        let predecessors = export.predecessors.as_ref().unwrap();
+
+        assert_eq!(successors[&1], vec![2, 3]);
+        assert_eq!(predecessors[&1], vec![0]);
+        assert!(successors[&2].is_empty());
+        assert!(predecessors[&0].is_empty());
+
+        // Both maps describe exactly the edge list
+        let edges: HashSet<(usize, usize)> = export.edges.iter().map(|e| (e.from, e.to)).collect();
+        let from_successors: HashSet<(usize, usize)> = successors
+            .iter()
+            .flat_map(|(&from, tos)| tos.iter().map(move |&to| (from, to)))
+            .collect();
+        let from_predecessors: HashSet<(usize, usize)> = predecessors
+            .iter()
+            .flat_map(|(&to, froms)| froms.iter().map(move |&from| (from, to)))
+            .collect();
+        assert_eq!(from_successors, edges);
+        assert_eq!(from_predecessors, edges);
+        assert_eq!(successors.len(), export.blocks.len());
+        assert_eq!(predecessors.len(), export.blocks.len());
+    }
+
+    #[test]
+    fn test_export_json_with_degrees() {
+        // 0 -> 1 | 2 -> 3
+        let mut cfg = DiGraph::new();
+        let block = |id, kind, terminator| BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        };
+        let b0 = cfg.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = cfg.add_node(block(1, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b2 = cfg.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b3 = cfg.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        cfg.add_edge(b0, b1, EdgeType::TrueBranch);
+        cfg.add_edge(b0, b2, EdgeType::FalseBranch);
+        cfg.add_edge(b1, b3, EdgeType::Fallthrough);
+        cfg.add_edge(b2, b3, EdgeType::Fallthrough);
+
+        let plain = serde_json::to_value(export_json(&cfg, "diamond")).unwrap();
+        assert!(plain["blocks"][0].get("in_degree").is_none(), "Degrees are omitted by default");
+
+        let export = export_json(&cfg, "diamond").with_degrees(&cfg);
+        let degrees: Vec<_> = export.blocks.iter().map(|b| (b.id, b.in_degree, b.out_degree)).collect();
+        assert_eq!(degrees, vec![
+            (0, Some(0), Some(2)),
+            (1, Some(1), Some(1)),
+            (2, Some(1), Some(1)),
+            (3, Some(2), Some(0)),
+        ]);
+    }
+
+    #[test]
+    fn test_export_dot_source_tooltips() {
+        let mut cfg = create_test_cfg();
+        assert!(!export_dot(&cfg).contains("tooltip="), "Blocks without a location get no tooltip");
+
+        cfg[NodeIndex::new(1)].source_location = Some(SourceLocation::new("src/lib.rs", 40, 60, 12, 5, 14, 6));
+        cfg[NodeIndex::new(2)].source_location = Some(SourceLocation::from_bytes_with_source("src/lib.rs", None, 60, 75));
+        let dot = export_dot(&cfg);
+
+        assert!(dot.contains("otherwise 3)\" tooltip=\"src/lib.rs:12\""), "Tooltip follows the label: {}", dot);
+        assert!(dot.contains("tooltip=\"src/lib.rs:bytes60-75\""), "Unresolved lines fall back to the byte range");
+        assert_eq!(dot.matches("tooltip=").count(), 2);
+    }
+
     #[test]
     fn test_dot_is_valid_graphviz() {
         let cfg = create_test_cfg();
@@ -269,4 +703,196 @@ mod tests {
         assert!(dot.contains("rankdir=TB;"));
         assert!(dot.contains("node [shape=box"));
     }
+
+    #[test]
+    fn test_export_dot_with_options() {
+        let cfg = create_test_cfg();
+        let default = export_dot(&cfg);
+        assert!(default.contains("rankdir=TB;"));
+        assert!(default.contains("splines=curved;"));
+
+        let options = DotOptions { rankdir: RankDir::LR, splines: Splines::Ortho };
+        let dot = export_dot_with_options(&cfg, &options);
+        assert!(dot.contains("rankdir=LR;"));
+        assert!(dot.contains("splines=ortho;"));
+        assert!(!dot.contains("rankdir=TB"));
+
+        // Layout options only touch the graph attributes
+        let body = |dot: &str| dot.lines().skip(3).collect::<Vec<_>>().join("\n");
+        assert_eq!(body(&dot), body(&default));
+    }
+
+    #[test]
+    fn test_export_dominator_tree_dot() {
+        let cfg = create_test_cfg();
+        let tree = DominatorTree::new(&cfg).unwrap();
+        let dot = export_dominator_tree_dot(&cfg, &tree, false);
+        assert!(dot.starts_with("digraph DomTree {"));
+        assert!(dot.contains("\"0\" -> \"1\";"));
+        assert!(dot.contains("\"1\" -> \"2\";"));
+        assert!(dot.contains("\"1\" -> \"3\";"));
+        assert_eq!(dot.matches("->").count(), 3);
+
+        // The overlay adds every CFG edge without constraining the layout
+        let overlaid = export_dominator_tree_dot(&cfg, &tree, true);
+        assert_eq!(overlaid.matches("constraint=false").count(), cfg.edge_count());
+    }
+
+    #[test]
+    fn test_export_dot_panic_block_style() {
+        let mut cfg = create_test_cfg();
+        cfg[petgraph::graph::NodeIndex::new(3)].kind = BlockKind::Panic;
+
+        let dot = export_dot(&cfg);
+
+        assert!(dot.contains("PANIC"));
+        assert!(dot.contains("fillcolor=lightgray"));
+    }
+
+    /// Expected DOT attributes and JSON kind for each edge type
+    ///
+    /// Deliberately has no wildcard arm: adding an `EdgeType` variant fails to
+    /// compile here until its exporter output is decided (and listed in
+    /// `EdgeType::ALL`).
+    fn expected_markers(edge_type: EdgeType) -> (&'static str, &'static str) {
+        match edge_type {
+            EdgeType::TrueBranch => ("[color=green, style=solid, label=\"T\"]", "TrueBranch"),
+            EdgeType::FalseBranch => ("[color=red, style=solid, label=\"F\"]", "FalseBranch"),
+            EdgeType::Fallthrough => ("[color=black, style=dashed]", "Fallthrough"),
+            EdgeType::LoopBack => ("[color=blue, style=solid, label=\"loop\"]", "LoopBack"),
+            EdgeType::LoopExit => ("[color=orange, style=solid, label=\"exit\"]", "LoopExit"),
+            EdgeType::Exception => ("[color=purple, style=solid, label=\"unwind\"]", "Exception"),
+            EdgeType::Call => ("[color=gray, style=solid, label=\"call\"]", "Call"),
+            EdgeType::Return => ("[color=darkgray, style=solid, label=\"ret\"]", "Return"),
+            EdgeType::Unwind => ("[color=orange, style=dashed, label=\"unwind\"]", "Unwind"),
+            EdgeType::Resume => ("[color=purple, style=dotted, label=\"resume\"]", "Resume"),
+        }
+    }
+
+    /// Chain of blocks b0 -> b1 -> ... with one edge of every type
+    fn create_all_edge_types_cfg() -> Cfg {
+        let mut g = DiGraph::new();
+        let nodes: Vec<_> = (0..=EdgeType::ALL.len())
+            .map(|id| g.add_node(BasicBlock {
+                id,
+                kind: BlockKind::Normal,
+                statements: vec![],
+                terminator: Terminator::Goto { target: id + 1 },
+                source_location: None,
+            }))
+            .collect();
+
+        for (i, edge_type) in EdgeType::ALL.iter().enumerate() {
+            g.add_edge(nodes[i], nodes[i + 1], *edge_type);
+        }
+
+        g
+    }
+
+    #[test]
+    fn test_export_dot_covers_every_edge_type() {
+        let cfg = create_all_edge_types_cfg();
+        let dot = export_dot(&cfg);
+
+        for (i, edge_type) in EdgeType::ALL.iter().enumerate() {
+            let (attrs, _) = expected_markers(*edge_type);
+            let line = format!("\"{}\" -> \"{}\" {};", i, i + 1, attrs);
+            assert!(dot.contains(&line), "missing DOT edge for {:?}: {}", edge_type, line);
+        }
+    }
+
+    #[test]
+    fn test_export_json_covers_every_edge_type() {
+        let cfg = create_all_edge_types_cfg();
+        let export = export_json(&cfg, "all_edges");
+
+        let kinds: Vec<&str> = export.edges.iter().map(|e| e.kind.as_str()).collect();
+        let expected: Vec<&str> = EdgeType::ALL.iter().map(|t| expected_markers(*t).1).collect();
+        assert_eq!(kinds, expected);
+
+        // Round trip: the exported kind string deserializes to the same variant
+        for (edge, edge_type) in export.edges.iter().zip(EdgeType::ALL) {
+            let parsed: EdgeType = serde_json::from_value(serde_json::Value::String(edge.kind.clone())).unwrap();
+            assert_eq!(parsed, edge_type);
+        }
+    }
+
+    #[test]
+    fn test_retain_edge_types_drops_fallthrough() {
+        let cfg = create_test_cfg();
+        let filtered = retain_edge_types(&cfg, &[EdgeType::TrueBranch, EdgeType::FalseBranch]);
+        assert_eq!(filtered.node_count(), cfg.node_count(), "Every block is kept");
+        assert_eq!(filtered.edge_count(), 2);
+
+        let dot = export_dot(&filtered);
+        assert!(!dot.contains("\"0\" -> \"1\""), "Fallthrough edge should be dropped");
+        assert!(!dot.contains("color=black"));
+        assert!(dot.contains("color=green"));
+        assert!(dot.contains("color=red"));
+        assert!(dot.contains("\"0\" [label"), "Entry block keeps its node");
+
+        let export = export_json(&filtered, "f");
+        assert_eq!(export.blocks.len(), 4);
+        assert!(export.edges.iter().all(|e| e.kind != "Fallthrough"));
+    }
+
+    #[test]
+    fn test_export_with_loop_depths() {
+        let cfg = create_test_cfg();
+        let depths: HashMap<NodeIndex, usize> = [(NodeIndex::new(1), 2)].into_iter().collect();
+
+        let dot = export_dot_with_loop_depths(&cfg, &depths, &DotOptions::default());
+        assert!(dot.contains("loop depth 2"));
+        assert!(dot.contains("fillcolor=\"#a9c8ff\""), "Depth-2 normal block should be shaded");
+        assert!(!export_dot(&cfg).contains("loop depth"));
+
+        let export = export_json_with_loop_depths(&cfg, "f", &depths);
+        assert_eq!(export.blocks[0].loop_depth, Some(0));
+        assert_eq!(export.blocks[1].loop_depth, Some(2));
+
+        let plain = serde_json::to_value(export_json(&cfg, "f")).unwrap();
+        assert!(plain["blocks"][0].get("loop_depth").is_none(), "Omitted unless requested");
+    }
+
+    #[test]
+    fn test_export_dot_clustered() {
+        use crate::cfg::detect_natural_loops;
+
+        // Linear CFG: no loops, no clusters
+        let linear = create_test_cfg();
+        let dot = export_dot_clustered(&linear, &detect_natural_loops(&linear), None, &DotOptions::default());
+        assert!(!dot.contains("subgraph cluster_"));
+
+        // 0 -> 1 -> 2 -> 3; 2 -> 2 (inner); 3 -> 1 (outer); 1 -> 4
+        let mut g = DiGraph::new();
+        let nodes: Vec<NodeIndex> = (0..5).map(|id| g.add_node(BasicBlock {
+            id,
+            kind: match id {
+                0 => BlockKind::Entry,
+                4 => BlockKind::Exit,
+                _ => BlockKind::Normal,
+            },
+            statements: vec![],
+            terminator: Terminator::Return,
+            source_location: None,
+        })).collect();
+        for (from, to) in [(0, 1), (1, 2), (2, 3), (2, 2), (3, 1), (1, 4)] {
+            g.add_edge(nodes[from], nodes[to], EdgeType::Fallthrough);
+        }
+
+        let dot = export_dot_clustered(&g, &detect_natural_loops(&g), None, &DotOptions::default());
+        assert!(dot.contains("subgraph cluster_0 {"));
+        assert!(dot.contains("label=\"loop @1\""));
+        assert!(dot.contains("label=\"loop @2\""));
+
+        // The inner cluster is nested inside the outer one
+        let outer = dot.find("subgraph cluster_0").unwrap();
+        let inner = dot.find("subgraph cluster_1").unwrap();
+        assert!(outer < inner);
+        assert!(dot.contains("    subgraph cluster_1 {"), "Inner cluster is indented one level deeper");
+
+        // Blocks outside loops stay at the top level, before any cluster
+        let entry = dot.find("\"0\" [label").unwrap();
+        assert!(entry < outer);
+    }
 }
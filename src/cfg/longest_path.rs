@@ -0,0 +1,189 @@
+//! Longest path from the entry to an exit (the "critical path")
+//!
+//! Answers "what's the deepest sequence of blocks a single execution can
+//! run through?". Cycles would make that unbounded, so the search runs over
+//! the condensation of the CFG: each strongly connected component (a loop
+//! body) counts once, with all of its blocks. On an acyclic CFG this is the
+//! longest simple path from the entry to an exit.
+
+use crate::cfg::analysis::{find_entry, is_exit_block};
+use crate::cfg::{BlockId, Cfg};
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::NodeIndex;
+use petgraph::Direction;
+use serde::Serialize;
+
+/// The longest entry-to-exit path over the CFG's condensation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LongestPath {
+    /// Blocks from the entry to `exit`; a collapsed loop lists its blocks in ID order
+    pub blocks: Vec<BlockId>,
+    /// Number of blocks on the path
+    pub length: usize,
+    /// Exit block the path ends at
+    pub exit: BlockId,
+    /// Loop bodies (strongly connected components) the path passes through, by block ID
+    pub collapsed_loops: Vec<Vec<BlockId>>,
+}
+
+/// Find the longest path from the entry to any exit block
+///
+/// Exits are blocks with an exiting terminator plus any other block without
+/// successors, as in [`analyze_exits`](crate::cfg::exits::analyze_exits).
+/// Each strongly connected component weighs as many blocks as it holds.
+/// Ties go to the successor component with the lowest block ID, so results
+/// are stable. Returns `None` when the CFG has no entry or no exit is
+/// reachable from it.
+pub fn longest_acyclic_path(cfg: &Cfg) -> Option<LongestPath> {
+    let entry = find_entry(cfg)?;
+
+    // Tarjan yields components in reverse topological order: successors first
+    let mut components = tarjan_scc(cfg);
+    for component in &mut components {
+        component.sort_by_key(|&node| cfg[node].id);
+    }
+    let mut component_of = vec![0; cfg.node_count()];
+    for (index, component) in components.iter().enumerate() {
+        for &node in component {
+            component_of[node.index()] = index;
+        }
+    }
+
+    let is_exit = |node: NodeIndex| {
+        is_exit_block(cfg, node) || cfg.neighbors_directed(node, Direction::Outgoing).next().is_none()
+    };
+
+    // best[c] = (blocks from c to an exit, next component or None to stop in c)
+    let mut best: Vec<Option<(usize, Option<usize>)>> = vec![None; components.len()];
+    for (index, component) in components.iter().enumerate() {
+        let mut successors: Vec<usize> = component
+            .iter()
+            .flat_map(|&node| cfg.neighbors_directed(node, Direction::Outgoing))
+            .map(|succ| component_of[succ.index()])
+            .filter(|&succ| succ != index)
+            .collect();
+        successors.sort_by_key(|&succ| cfg[components[succ][0]].id);
+        successors.dedup();
+
+        let mut choice = component.iter().any(|&node| is_exit(node)).then_some((component.len(), None));
+        for succ in successors {
+            if let Some((length, _)) = best[succ] {
+                if choice.map_or(true, |(current, _)| component.len() + length > current) {
+                    choice = Some((component.len() + length, Some(succ)));
+                }
+            }
+        }
+        best[index] = choice;
+    }
+
+    let mut current = component_of[entry.index()];
+    let (length, _) = best[current]?;
+    let mut blocks = Vec::with_capacity(length);
+    let mut collapsed_loops = Vec::new();
+    loop {
+        let component = &components[current];
+        let ids: Vec<BlockId> = component.iter().map(|&node| cfg[node].id).collect();
+        let is_loop = component.len() > 1 || cfg.contains_edge(component[0], component[0]);
+        if is_loop {
+            collapsed_loops.push(ids.clone());
+        }
+        blocks.extend(ids);
+
+        match best[current].and_then(|(_, next)| next) {
+            Some(next) => current = next,
+            None => {
+                let exit = component.iter().copied().find(|&node| is_exit(node))?;
+                return Some(LongestPath {
+                    blocks,
+                    length,
+                    exit: cfg[exit].id,
+                    collapsed_loops,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
+    use petgraph::graph::DiGraph;
+
+    fn block(id: BlockId, kind: BlockKind, terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        }
+    }
+
+    #[test]
+    fn test_longest_path_diamond() {
+        // 0 -> 1 | 2 -> 3
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b3, EdgeType::Fallthrough);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+
+        let path = longest_acyclic_path(&g).unwrap();
+        assert_eq!(path.length, 3);
+        assert_eq!(path.blocks, vec![0, 1, 3], "Tie goes to the lower block ID");
+        assert_eq!(path.exit, 3);
+        assert!(path.collapsed_loops.is_empty());
+    }
+
+    #[test]
+    fn test_longest_path_linear_and_early_exit() {
+        // 0 -> 1 -> 2 -> 3 -> 4, with a return straight from 0 to 5
+        let mut g = DiGraph::new();
+        let nodes: Vec<NodeIndex> = (0..5)
+            .map(|id| {
+                let kind = if id == 0 { BlockKind::Entry } else if id == 4 { BlockKind::Exit } else { BlockKind::Normal };
+                let terminator = if id == 4 { Terminator::Return } else { Terminator::Goto { target: id + 1 } };
+                g.add_node(block(id, kind, terminator))
+            })
+            .collect();
+        for pair in nodes.windows(2) {
+            g.add_edge(pair[0], pair[1], EdgeType::Fallthrough);
+        }
+        let early = g.add_node(block(5, BlockKind::Exit, Terminator::Return));
+        g.add_edge(nodes[0], early, EdgeType::TrueBranch);
+
+        let path = longest_acyclic_path(&g).unwrap();
+        assert_eq!(path.blocks, vec![0, 1, 2, 3, 4]);
+        assert_eq!(path.length, 5);
+        assert_eq!(path.exit, 4);
+    }
+
+    #[test]
+    fn test_longest_path_collapses_loops() {
+        // 0 -> 1 <-> 2, 1 -> 3 (exit)
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::SwitchInt { targets: vec![2], otherwise: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 1 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+        g.add_edge(b1, b2, EdgeType::TrueBranch);
+        g.add_edge(b2, b1, EdgeType::LoopBack);
+        g.add_edge(b1, b3, EdgeType::FalseBranch);
+
+        let path = longest_acyclic_path(&g).unwrap();
+        assert_eq!(path.blocks, vec![0, 1, 2, 3]);
+        assert_eq!(path.collapsed_loops, vec![vec![1, 2]]);
+
+        // An infinite loop with no way out has no path to an exit
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 0 }));
+        g.add_edge(b0, b0, EdgeType::LoopBack);
+        assert_eq!(longest_acyclic_path(&g), None);
+    }
+}
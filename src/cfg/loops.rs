@@ -5,7 +5,7 @@ use crate::cfg::analysis::find_entry;
 use petgraph::algo::dominators::simple_fast;
 use petgraph::graph::NodeIndex;
 use petgraph::visit::EdgeRef;
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 /// A natural loop detected in the CFG
 ///
@@ -32,6 +32,11 @@ impl NaturalLoop {
         self.body.len()
     }
 
+    /// Check if this loop is a single block branching to itself
+    pub fn is_self_loop(&self) -> bool {
+        self.back_edge.0 == self.header
+    }
+
     /// Get the loop depth (nesting level) relative to other loops
     ///
     /// Returns 0 for outermost loops, 1 for loops nested inside one outer loop, etc.
@@ -44,6 +49,43 @@ impl NaturalLoop {
         }
         level
     }
+
+    /// Edges leaving the loop: (body block, successor outside the body)
+    ///
+    /// Sorted by node index so output does not depend on edge insertion order.
+    pub fn exit_edges(&self, cfg: &Cfg) -> Vec<(NodeIndex, NodeIndex)> {
+        let mut edges: Vec<(NodeIndex, NodeIndex)> = self.body
+            .iter()
+            .flat_map(|&node| cfg.neighbors(node).map(move |succ| (node, succ)))
+            .filter(|&(_, succ)| !self.contains(succ))
+            .collect();
+        edges.sort();
+        edges.dedup();
+        edges
+    }
+
+    /// Blocks outside the loop that control reaches when leaving it
+    pub fn exit_blocks(&self, cfg: &Cfg) -> Vec<NodeIndex> {
+        let mut blocks: Vec<NodeIndex> = self.exit_edges(cfg)
+            .into_iter()
+            .map(|(_, target)| target)
+            .collect();
+        blocks.sort();
+        blocks.dedup();
+        blocks
+    }
+
+    /// The header's unique predecessor outside the loop, if there is exactly one
+    pub fn preheader(&self, cfg: &Cfg) -> Option<NodeIndex> {
+        let mut outside = cfg
+            .neighbors_directed(self.header, petgraph::Direction::Incoming)
+            .filter(|&pred| !self.contains(pred));
+        let preheader = outside.next()?;
+        if outside.any(|pred| pred != preheader) {
+            return None;
+        }
+        Some(preheader)
+    }
 }
 
 /// Detect all natural loops in a CFG
@@ -192,6 +234,43 @@ pub fn find_nested_loops(cfg: &Cfg) -> Vec<(NaturalLoop, NaturalLoop)> {
     nested
 }
 
+/// Find blocks with an edge to themselves
+///
+/// A self-loop is a degenerate loop whose header, back-edge source and
+/// whole body are the same block. Unlike [`detect_natural_loops`], this also
+/// finds self-loops in blocks unreachable from the entry. Each block is
+/// returned once, in node order.
+pub fn find_self_loops(cfg: &Cfg) -> Vec<NodeIndex> {
+    cfg.node_indices()
+        .filter(|&node| cfg.contains_edge(node, node))
+        .collect()
+}
+
+/// Count the loops enclosing each block
+///
+/// Every node maps to the number of distinct loop headers whose body contains
+/// it: 0 outside loops, 1 in a single loop, 2 in the inner loop of a nest, and
+/// so on. Back edges sharing a header count as one loop.
+pub fn compute_loop_depths(cfg: &Cfg, loops: &[NaturalLoop]) -> HashMap<NodeIndex, usize> {
+    let mut headers_by_node: HashMap<NodeIndex, HashSet<NodeIndex>> = cfg
+        .node_indices()
+        .map(|node| (node, HashSet::new()))
+        .collect();
+
+    for loop_ in loops {
+        for &node in &loop_.body {
+            if let Some(headers) = headers_by_node.get_mut(&node) {
+                headers.insert(loop_.header);
+            }
+        }
+    }
+
+    headers_by_node
+        .into_iter()
+        .map(|(node, headers)| (node, headers.len()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -264,6 +343,31 @@ mod tests {
         assert!(!loop_.contains(NodeIndex::new(3))); // Exit not in loop
     }
 
+    #[test]
+    fn test_loop_exits_and_preheader() {
+        let mut cfg = create_simple_loop_cfg();
+        let loops = detect_natural_loops(&cfg);
+        let loop_ = &loops[0];
+
+        // while loop: the condition in the header is the only way out
+        assert_eq!(loop_.exit_edges(&cfg), vec![(NodeIndex::new(1), NodeIndex::new(3))]);
+        assert_eq!(loop_.exit_blocks(&cfg), vec![NodeIndex::new(3)]);
+        assert_eq!(loop_.preheader(&cfg), Some(NodeIndex::new(0)));
+
+        // A second way into the header means there is no single preheader
+        let b4 = cfg.add_node(BasicBlock {
+            id: 4,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Goto { target: 1 },
+            source_location: None,
+        });
+        cfg.add_edge(NodeIndex::new(0), b4, EdgeType::TrueBranch);
+        cfg.add_edge(b4, NodeIndex::new(1), EdgeType::Fallthrough);
+        let loops = detect_natural_loops(&cfg);
+        assert_eq!(loops[0].preheader(&cfg), None);
+    }
+
     #[test]
     fn test_find_loop_headers() {
         let cfg = create_simple_loop_cfg();
@@ -486,4 +590,69 @@ mod tests {
         // Inner loop has level 1 (nested inside outer)
         assert_eq!(inner_loop.nesting_level(&loops), 1);
     }
+
+    #[test]
+    fn test_compute_loop_depths_nested() {
+        let mut g = DiGraph::new();
+
+        // 0 -> 1 (outer header) -> 2 (inner header) -> 3 -> 2, 2 -> 1, 1 -> 4
+        let blocks: Vec<NodeIndex> = (0..5)
+            .map(|id| g.add_node(BasicBlock {
+                id,
+                kind: match id {
+                    0 => BlockKind::Entry,
+                    4 => BlockKind::Exit,
+                    _ => BlockKind::Normal,
+                },
+                statements: vec![],
+                terminator: Terminator::Return,
+                source_location: None,
+            }))
+            .collect();
+
+        g.add_edge(blocks[0], blocks[1], EdgeType::Fallthrough);
+        g.add_edge(blocks[1], blocks[2], EdgeType::TrueBranch);
+        g.add_edge(blocks[1], blocks[4], EdgeType::FalseBranch);
+        g.add_edge(blocks[2], blocks[3], EdgeType::TrueBranch);
+        g.add_edge(blocks[2], blocks[1], EdgeType::LoopBack);
+        g.add_edge(blocks[3], blocks[2], EdgeType::LoopBack);
+
+        let depths = compute_loop_depths(&g, &detect_natural_loops(&g));
+
+        assert_eq!(depths[&blocks[0]], 0, "Entry is outside all loops");
+        assert_eq!(depths[&blocks[1]], 1, "Outer header is in the outer loop only");
+        assert_eq!(depths[&blocks[2]], 2, "Inner header is in both loops");
+        assert_eq!(depths[&blocks[3]], 2, "Inner body is in both loops");
+        assert_eq!(depths[&blocks[4]], 0, "Exit is outside all loops");
+    }
+
+    #[test]
+    fn test_find_self_loops() {
+        let mut g = DiGraph::new();
+
+        // 0 -> 1 -> 1 (self-loop), 1 -> 2; block 3 is unreachable and loops on itself
+        let blocks: Vec<NodeIndex> = (0..4)
+            .map(|id| g.add_node(BasicBlock {
+                id,
+                kind: if id == 0 { BlockKind::Entry } else { BlockKind::Normal },
+                statements: vec![],
+                terminator: Terminator::Return,
+                source_location: None,
+            }))
+            .collect();
+
+        g.add_edge(blocks[0], blocks[1], EdgeType::Fallthrough);
+        g.add_edge(blocks[1], blocks[1], EdgeType::LoopBack);
+        g.add_edge(blocks[1], blocks[2], EdgeType::LoopExit);
+        g.add_edge(blocks[3], blocks[3], EdgeType::LoopBack);
+
+        assert_eq!(find_self_loops(&g), vec![blocks[1], blocks[3]]);
+
+        // The reachable self-loop is also a natural loop with a body of one
+        let loops = detect_natural_loops(&g);
+        assert_eq!(loops.len(), 1);
+        assert!(loops[0].is_self_loop());
+        assert_eq!(loops[0].size(), 1);
+        assert_eq!(loops[0].nesting_level(&loops), 0);
+    }
 }
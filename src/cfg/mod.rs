@@ -2,45 +2,55 @@
 
 pub mod analysis;
 pub mod ast;
+pub mod coverage;
 pub mod diff;
 pub mod dominance_frontiers;
 pub mod dominators;
 pub mod edge;
+pub mod exits;
+pub mod explain;
 pub mod export;
 pub mod git_utils;
 pub mod hotpaths;
 pub mod icfg;
+pub mod longest_path;
 pub mod loops;
+pub mod path_tree;
 pub mod paths;
 pub mod patterns;
 pub mod post_dominators;
 pub mod reachability;
 pub mod source;
 pub mod summary;
+pub mod validate;
+pub mod weights;
 
-pub use analysis::{find_entry, find_exits};
+pub use analysis::{find_entry, find_entry_blocks, find_exits, classify_panic_blocks, panic_block_ids};
 pub use crate::storage::{load_cfg_from_db, resolve_function_name};
 
 #[cfg(feature = "sqlite")]
 pub use crate::storage::{load_cfg_from_db_with_conn, resolve_function_name_with_conn};
-pub use dominance_frontiers::compute_dominance_frontiers;
+pub use dominance_frontiers::{compute_dominance_frontiers, compute_post_dominance_frontiers};
 pub use dominators::DominatorTree;
 pub use post_dominators::PostDominatorTree;
 pub use edge::EdgeType;
+pub use explain::explain_function;
+#[allow(unused_imports)] // export_dot is only used by tests in the binary
 pub use export::{export_dot, export_json, CFGExport};
 pub use hotpaths::{compute_hot_paths, HotPath, HotpathsOptions};
 pub use loops::detect_natural_loops;
 #[allow(unused_imports)] // Used in tests within the module
 pub use paths::{
-    Path, PathKind, PathLimits, enumerate_paths, enumerate_paths_cached,
+    Path, PathKind, EndKind, PathLimits, PATH_ID_VERSION, enumerate_paths, enumerate_paths_cached,
     enumerate_paths_cached_with_context, enumerate_paths_with_context,
     EnumerationContext, get_or_enumerate_paths,
     enumerate_paths_incremental, IncrementalPathsResult,
 };
-pub use patterns::{detect_if_else_patterns, detect_match_patterns};
-pub use reachability::{find_reachable_from_block, compute_path_impact, PathImpact};
+pub use patterns::{detect_if_else_patterns, detect_match_patterns, find_merge_points};
+pub use reachability::{find_reachable_from_block, find_reachable_until_convergence, find_blocks_reaching, compute_path_impact, ImpactDirection, PathImpact};
 pub use summary::summarize_path;
-pub use source::SourceLocation;
+pub use source::{SnippetLine, SourceLocation, SourceMap};
+pub use weights::compute_edge_weights;
 
 use anyhow::Result;
 use petgraph::graph::DiGraph;
@@ -182,6 +192,60 @@ pub enum BlockKind {
     Entry,
     Normal,
     Exit,
+    /// Landing block that only exists to panic/abort (compiler-inserted scaffolding)
+    Panic,
+}
+
+impl BlockKind {
+    /// Every block kind, in declaration order
+    pub const ALL: [BlockKind; BlockKind::COUNT] = BlockKind::all();
+
+    /// Number of block kinds, counted along [`BlockKind::next`]
+    const COUNT: usize = {
+        let mut count = 1;
+        let mut kind = BlockKind::Entry;
+        while let Some(next) = kind.next() {
+            count += 1;
+            kind = next;
+        }
+        count
+    };
+
+    /// The kind declared after this one
+    ///
+    /// `ALL` is built from this exhaustive match, so a new variant does not
+    /// compile until it is placed in the chain.
+    const fn next(self) -> Option<BlockKind> {
+        match self {
+            BlockKind::Entry => Some(BlockKind::Normal),
+            BlockKind::Normal => Some(BlockKind::Exit),
+            BlockKind::Exit => Some(BlockKind::Panic),
+            BlockKind::Panic => None,
+        }
+    }
+
+    const fn all() -> [BlockKind; BlockKind::COUNT] {
+        let mut all = [BlockKind::Entry; BlockKind::COUNT];
+        let mut i = 1;
+        while i < BlockKind::COUNT {
+            all[i] = match all[i - 1].next() {
+                Some(kind) => kind,
+                None => panic!("BlockKind::COUNT disagrees with BlockKind::next"),
+            };
+            i += 1;
+        }
+        all
+    }
+
+    /// One-line meaning, as listed by `mirage block-kinds`
+    pub fn description(&self) -> &'static str {
+        match self {
+            BlockKind::Entry => "Function entry block",
+            BlockKind::Normal => "Block inside the function body",
+            BlockKind::Exit => "Block that leaves the function (return, abort, unreachable)",
+            BlockKind::Panic => "Landing block that only exists to panic/abort (compiler-inserted scaffolding)",
+        }
+    }
 }
 
 /// Terminator instruction (simplified representation)
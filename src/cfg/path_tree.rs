@@ -0,0 +1,172 @@
+//! Prefix tree over enumerated paths
+//!
+//! A flat path list repeats every shared prefix once per path, which hides
+//! where paths actually diverge. [`PathTree`] merges the block sequences so
+//! each common prefix appears once and every node boundary is a branch point.
+//! Straight runs of blocks are kept in a single node (a radix tree), so the
+//! tree has one node per branch taken, not one per block.
+
+use crate::cfg::{BlockId, Path};
+use serde::Serialize;
+
+/// A run of blocks shared by every path passing through it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathTreeNode {
+    /// Blocks in execution order; the first is where this branch starts
+    pub blocks: Vec<BlockId>,
+    /// Number of paths passing through this node
+    pub path_count: usize,
+    /// IDs of the paths that end at the last block of this node
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub path_ids: Vec<String>,
+    /// Diverging continuations, in the order their first path was given
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<PathTreeNode>,
+}
+
+/// Paths merged by shared prefix
+///
+/// Paths enumerated from one entry share their first block, so there is
+/// normally a single root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathTree {
+    pub roots: Vec<PathTreeNode>,
+}
+
+impl PathTree {
+    /// Merge paths by shared prefix, keeping the given path order among siblings
+    ///
+    /// Empty paths have no blocks to place and are skipped.
+    pub fn from_paths(paths: &[Path]) -> Self {
+        let paths: Vec<&Path> = paths.iter().filter(|p| !p.is_empty()).collect();
+        Self { roots: build_nodes(&paths, 0) }
+    }
+
+    /// Number of nodes in the tree
+    pub fn node_count(&self) -> usize {
+        let mut count = 0;
+        let mut stack: Vec<&PathTreeNode> = self.roots.iter().collect();
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(&node.children);
+        }
+        count
+    }
+}
+
+/// Build the nodes for paths that agree on their first `depth` blocks
+///
+/// Every path here is longer than `depth`. Recursion only happens at branch
+/// points, so its depth is bounded by the branches along one path.
+fn build_nodes(paths: &[&Path], depth: usize) -> Vec<PathTreeNode> {
+    // Group by the next block, in first-seen order
+    let mut groups: Vec<(BlockId, Vec<&Path>)> = Vec::new();
+    for &path in paths {
+        let block = path.blocks[depth];
+        match groups.iter_mut().find(|(b, _)| *b == block) {
+            Some((_, group)) => group.push(path),
+            None => groups.push((block, vec![path])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(first, group)| {
+            // Extend the run while every path continues to the same block
+            let mut blocks = vec![first];
+            let mut end = depth + 1;
+            while let Some(next) = group.first().and_then(|p| p.blocks.get(end)) {
+                if !group.iter().all(|p| p.blocks.get(end) == Some(next)) {
+                    break;
+                }
+                blocks.push(*next);
+                end += 1;
+            }
+
+            let (ended, continuing): (Vec<&Path>, Vec<&Path>) =
+                group.iter().partition(|p| p.len() == end);
+            PathTreeNode {
+                blocks,
+                path_count: group.len(),
+                path_ids: ended.iter().map(|p| p.path_id.clone()).collect(),
+                children: build_nodes(&continuing, end),
+            }
+        })
+        .collect()
+}
+
+impl std::fmt::Display for PathTree {
+    /// One line per node, children indented under their parent
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut stack: Vec<(&PathTreeNode, usize)> = self.roots.iter().rev().map(|n| (n, 0)).collect();
+        while let Some((node, depth)) = stack.pop() {
+            let blocks = node.blocks.iter().map(|b| b.to_string()).collect::<Vec<_>>().join(" -> ");
+            write!(f, "{}{}", "  ".repeat(depth), blocks)?;
+            if node.path_count > 1 {
+                write!(f, " ({} paths)", node.path_count)?;
+            }
+            if !node.path_ids.is_empty() {
+                write!(f, " [{}]", node.path_ids.join(", "))?;
+            }
+            writeln!(f)?;
+            stack.extend(node.children.iter().rev().map(|n| (n, depth + 1)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::PathKind;
+
+    fn path(blocks: &[BlockId]) -> Path {
+        Path::new(blocks.to_vec(), PathKind::Normal)
+    }
+
+    #[test]
+    fn test_diamond_shares_prefix() {
+        // 0 -> 1 -> {2, 3} -> 4
+        let paths = vec![path(&[0, 1, 2, 4]), path(&[0, 1, 3, 4])];
+        let tree = PathTree::from_paths(&paths);
+
+        assert_eq!(tree.roots.len(), 1);
+        let root = &tree.roots[0];
+        assert_eq!(root.blocks, vec![0, 1]);
+        assert_eq!(root.path_count, 2);
+        assert!(root.path_ids.is_empty());
+
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].blocks, vec![2, 4]);
+        assert_eq!(root.children[0].path_ids, vec![paths[0].path_id.clone()]);
+        assert_eq!(root.children[1].blocks, vec![3, 4]);
+        assert_eq!(root.children[1].path_ids, vec![paths[1].path_id.clone()]);
+        assert_eq!(tree.node_count(), 3);
+
+        let text = tree.to_string();
+        assert_eq!(
+            text,
+            format!("0 -> 1 (2 paths)\n  2 -> 4 [{}]\n  3 -> 4 [{}]\n", paths[0].path_id, paths[1].path_id)
+        );
+    }
+
+    #[test]
+    fn test_path_ending_inside_another() {
+        // A path cut off by --max-length can be a prefix of a longer one
+        let paths = vec![path(&[0, 1]), path(&[0, 1, 2])];
+        let tree = PathTree::from_paths(&paths);
+
+        let root = &tree.roots[0];
+        assert_eq!(root.blocks, vec![0, 1]);
+        assert_eq!(root.path_ids, vec![paths[0].path_id.clone()]);
+        assert_eq!(root.children.len(), 1);
+        assert_eq!(root.children[0].blocks, vec![2]);
+    }
+
+    #[test]
+    fn test_no_paths() {
+        let tree = PathTree::from_paths(&[]);
+        assert!(tree.roots.is_empty());
+        assert_eq!(tree.to_string(), "");
+    }
+}
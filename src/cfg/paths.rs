@@ -36,7 +36,7 @@
 //! - **Degenerate:** Dead end, infinite loop, or infeasible path
 //! - **Unreachable:** Statically unreachable code path
 
-use crate::cfg::{BlockId, Cfg, Terminator};
+use crate::cfg::{BlockId, BlockKind, Cfg, EdgeType, Terminator};
 use petgraph::graph::NodeIndex;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -146,6 +146,56 @@ pub enum PathKind {
     Unreachable,
 }
 
+/// How a path ends, at finer grain than [`PathKind`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EndKind {
+    /// Returns through the function's natural exit (see [`natural_exit`])
+    NormalReturn,
+    /// Returns through any other return block
+    EarlyReturn,
+    /// Ends in a panic landing block or an `Abort`
+    Panic,
+    /// Ends at a block that still has successors, i.e. the path was cut off
+    /// by enumeration bounds without leaving the function
+    Loop,
+    /// Ends in `Unreachable`, or in another block without successors that
+    /// does not return (e.g. a call that never returns)
+    Diverge,
+}
+
+/// The function's natural exit: the last non-panic `Return` block by block ID
+///
+/// Blocks are numbered in source order, so this is the return at the end of
+/// the function body. Returns through any other block are early returns.
+pub fn natural_exit(cfg: &Cfg) -> Option<BlockId> {
+    cfg.node_weights()
+        .filter(|block| block.kind != BlockKind::Panic && matches!(block.terminator, Terminator::Return))
+        .map(|block| block.id)
+        .max()
+}
+
+/// Classify how a path ends from its last block
+///
+/// `natural_exit` should be [`natural_exit`] of the same CFG; it is passed in
+/// so classifying many paths computes it once.
+pub fn classify_end(cfg: &Cfg, path: &Path, natural_exit: Option<BlockId>) -> EndKind {
+    let Some(node) = find_node_by_block_id(cfg, path.exit) else {
+        return EndKind::Diverge;
+    };
+    let block = &cfg[node];
+
+    if block.kind == BlockKind::Panic || matches!(block.terminator, Terminator::Abort(_)) {
+        return EndKind::Panic;
+    }
+    match block.terminator {
+        Terminator::Return if Some(block.id) == natural_exit => EndKind::NormalReturn,
+        Terminator::Return => EndKind::EarlyReturn,
+        Terminator::Unreachable => EndKind::Diverge,
+        _ if cfg.neighbors(node).next().is_some() => EndKind::Loop,
+        _ => EndKind::Diverge,
+    }
+}
+
 /// Find the NodeIndex for a given BlockId
 ///
 /// Helper function to convert BlockIds from paths to NodeIndices for CFG queries.
@@ -295,6 +345,16 @@ pub fn is_feasible_path_precomputed(
     cfg: &Cfg,
     blocks: &[BlockId],
     reachable_blocks: &HashSet<BlockId>,
+) -> bool {
+    feasible_precomputed(cfg, blocks, reachable_blocks, true)
+}
+
+/// Shared body of `is_feasible_path_precomputed`; `require_entry` toggles criterion 2
+fn feasible_precomputed(
+    cfg: &Cfg,
+    blocks: &[BlockId],
+    reachable_blocks: &HashSet<BlockId>,
+    require_entry: bool,
 ) -> bool {
     use crate::cfg::BlockKind;
 
@@ -308,7 +368,7 @@ pub fn is_feasible_path_precomputed(
         Some(idx) => idx,
         None => return false, // Block doesn't exist
     };
-    if cfg[first_idx].kind != BlockKind::Entry {
+    if require_entry && cfg[first_idx].kind != BlockKind::Entry {
         return false;
     }
 
@@ -462,6 +522,20 @@ pub fn classify_path_precomputed(
     cfg: &Cfg,
     blocks: &[BlockId],
     reachable_blocks: &HashSet<BlockId>,
+) -> PathKind {
+    classify_precomputed(cfg, blocks, reachable_blocks, true)
+}
+
+/// Shared body of `classify_path_precomputed`
+///
+/// With `require_entry` false, a path may start at any block; this is how
+/// paths enumerated from an interior block (`enumerate_paths_from`) are
+/// classified.
+fn classify_precomputed(
+    cfg: &Cfg,
+    blocks: &[BlockId],
+    reachable_blocks: &HashSet<BlockId>,
+    require_entry: bool,
 ) -> PathKind {
     // Empty path is degenerate
     if blocks.is_empty() {
@@ -499,7 +573,7 @@ pub fn classify_path_precomputed(
 
     // Priority 3: Check static feasibility (dead-end detection)
     // This identifies paths that end in Goto, SwitchInt, or other invalid terminators
-    if !is_feasible_path_precomputed(cfg, blocks, reachable_blocks) {
+    if !feasible_precomputed(cfg, blocks, reachable_blocks, require_entry) {
         return PathKind::Degenerate;
     }
 
@@ -537,29 +611,49 @@ impl PathKind {
 pub struct PathLimits {
     /// Maximum number of blocks per path
     pub max_length: usize,
+    /// Minimum number of blocks per path
+    ///
+    /// Shorter paths cannot be pruned during the walk, but they are not
+    /// recorded, so they never count towards `max_paths`. Defaults to 0 (no
+    /// lower bound).
+    pub min_length: usize,
     /// Maximum number of paths to enumerate
     pub max_paths: usize,
     /// Loop iterations to unroll before stopping
     pub loop_unroll_limit: usize,
+    /// Maximum traversals of each back edge per path
+    ///
+    /// When set, each loop yields its 0-iteration, 1-iteration, ..., n-iteration
+    /// paths as distinct paths, and `loop_unroll_limit` is not used. Defaults to
+    /// `Some(1)`: each loop body is entered at most once. `None` selects the
+    /// older header-count bounding driven by `loop_unroll_limit`.
+    pub max_loop_iterations: Option<usize>,
 }
 
 impl Default for PathLimits {
     fn default() -> Self {
         Self {
             max_length: 1000,
+            min_length: 0,
             max_paths: 10000,
             loop_unroll_limit: 3,
+            max_loop_iterations: Some(1),
         }
     }
 }
 
 impl PathLimits {
     /// Create new path limits with custom values
+    ///
+    /// Loops are bounded by header count (`loop_unroll_limit`), as with
+    /// [`PathLimits::with_loop_unroll_limit`].
     pub fn new(max_length: usize, max_paths: usize, loop_unroll_limit: usize) -> Self {
         Self {
             max_length,
             max_paths,
             loop_unroll_limit,
+            max_loop_iterations: None,
+            ..Self::default()
         }
     }
 
@@ -569,15 +663,53 @@ impl PathLimits {
         self
     }
 
+    /// Create limits with a custom minimum path length
+    pub fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    /// Whether a path of `len` blocks is within `min_length..=max_length`
+    pub fn in_length_band(&self, len: usize) -> bool {
+        (self.min_length..=self.max_length).contains(&len)
+    }
+
+    /// Keep only paths within `min_length..=max_length` blocks
+    ///
+    /// For paths enumerated under other limits, such as cached paths.
+    /// Returns the number of paths removed.
+    pub fn retain_length_band(&self, paths: &mut Vec<Path>) -> usize {
+        let before = paths.len();
+        paths.retain(|p| self.in_length_band(p.len()));
+        before - paths.len()
+    }
+
     /// Create limits with a custom maximum path count
     pub fn with_max_paths(mut self, max_paths: usize) -> Self {
         self.max_paths = max_paths;
         self
     }
 
+    /// Whether enumerating `found` paths means the `max_paths` cap stopped the walk
+    ///
+    /// Enumeration stops as soon as the cap is reached, so any result that
+    /// large may be incomplete.
+    pub fn is_truncated(&self, found: usize) -> bool {
+        found >= self.max_paths
+    }
+
     /// Create limits with a custom loop unroll limit
+    ///
+    /// Switches to header-count loop bounding (clears `max_loop_iterations`).
     pub fn with_loop_unroll_limit(mut self, loop_unroll_limit: usize) -> Self {
         self.loop_unroll_limit = loop_unroll_limit;
+        self.max_loop_iterations = None;
+        self
+    }
+
+    /// Create limits allowing each back edge to be taken at most `n` times per path
+    pub fn with_max_loop_iterations(mut self, n: usize) -> Self {
+        self.max_loop_iterations = Some(n);
         self
     }
 
@@ -595,8 +727,10 @@ impl PathLimits {
     pub fn quick_analysis() -> Self {
         Self {
             max_length: 100,
+            min_length: 0,
             max_paths: 1000,
             loop_unroll_limit: 2,
+            max_loop_iterations: Some(1),
         }
     }
 
@@ -614,12 +748,21 @@ impl PathLimits {
     pub fn thorough() -> Self {
         Self {
             max_length: 10000,
+            min_length: 0,
             max_paths: 100000,
             loop_unroll_limit: 5,
+            max_loop_iterations: Some(2),
         }
     }
 }
 
+/// Version of the path_id scheme implemented by [`hash_path`]
+///
+/// Cached paths record the version they were hashed with. Bump this whenever
+/// `hash_path` changes, so caches written by the old scheme are treated as
+/// invalid and re-enumerated instead of returning ids that no longer match.
+pub const PATH_ID_VERSION: i64 = 1;
+
 /// Compute BLAKE3 hash of a block sequence
 ///
 /// Used to generate unique identifiers for paths. The hash includes
@@ -801,6 +944,18 @@ pub fn enumerate_paths_with_context(
         return vec![]; // No exits means no complete paths
     }
 
+    // Back-edge bounding, as enumerate_paths uses, reusing the context's analyses
+    if let Some(max_loop_iterations) = limits.max_loop_iterations {
+        return bounded_loop_paths(
+            cfg,
+            entry,
+            limits,
+            max_loop_iterations,
+            ctx.exits.clone(),
+            ctx.reachable_blocks.clone(),
+        );
+    }
+
     // Initialize traversal state
     let mut paths = Vec::new();
     let mut current_path = Vec::new();
@@ -851,9 +1006,10 @@ fn dfs_enumerate_with_context(
     // Check if we've reached an exit
     if ctx.is_exit(current) {
         // Classify the path using pre-computed reachable set
-        let kind = classify_path_precomputed(cfg, current_path, &ctx.reachable_blocks);
-        let path = Path::new(current_path.clone(), kind);
-        paths.push(path);
+        if limits.in_length_band(current_path.len()) {
+            let kind = classify_path_precomputed(cfg, current_path, &ctx.reachable_blocks);
+            paths.push(Path::new(current_path.clone(), kind));
+        }
         current_path.pop();
         return;
     }
@@ -941,13 +1097,47 @@ fn dfs_enumerate_with_context(
 /// let paths = enumerate_paths(&graph, &PathLimits::default());
 /// println!("Found {} paths", paths.len());
 /// ```
+#[tracing::instrument(level = "debug", skip_all, fields(blocks = cfg.node_count()))]
 pub fn enumerate_paths(cfg: &Cfg, limits: &PathLimits) -> Vec<Path> {
-    // Get entry block
-    let entry = match crate::cfg::analysis::find_entry(cfg) {
-        Some(e) => e,
-        None => return vec![], // Empty CFG
-    };
+    match crate::cfg::analysis::find_entry(cfg) {
+        Some(entry) => enumerate_paths_from_node(cfg, entry, limits),
+        None => vec![], // Empty CFG
+    }
+}
 
+/// Enumerate paths starting at an arbitrary block instead of the CFG entry
+///
+/// Useful for examining a loop or region in isolation: every path begins at
+/// `start` and runs to a reachable exit under the same `limits` as
+/// [`enumerate_paths`]. `Path::entry` is `start`, and path IDs are still
+/// hashes of the block sequence. A path is not classified as degenerate
+/// merely for starting somewhere other than the entry block.
+pub fn enumerate_paths_from(cfg: &Cfg, start: NodeIndex, limits: &PathLimits) -> Vec<Path> {
+    let mut paths = enumerate_paths_from_node(cfg, start, limits);
+
+    if crate::cfg::analysis::find_entry(cfg) != Some(start) {
+        let reachable_blocks: HashSet<BlockId> = crate::cfg::reachability::find_reachable(cfg)
+            .iter()
+            .map(|&idx| cfg[idx].id)
+            .collect();
+        for path in &mut paths {
+            path.kind = classify_precomputed(cfg, &path.blocks, &reachable_blocks, false);
+        }
+    }
+
+    paths
+}
+
+/// Dispatch to the configured loop-bounding strategy, starting at `start`
+fn enumerate_paths_from_node(cfg: &Cfg, start: NodeIndex, limits: &PathLimits) -> Vec<Path> {
+    match limits.max_loop_iterations {
+        Some(max_loop_iterations) => enumerate_paths_bounded_loops(cfg, start, limits, max_loop_iterations),
+        None => enumerate_paths_unrolled(cfg, start, limits),
+    }
+}
+
+/// Enumerate paths with header-count loop bounding (`loop_unroll_limit`)
+fn enumerate_paths_unrolled(cfg: &Cfg, entry: NodeIndex, limits: &PathLimits) -> Vec<Path> {
     // Get exit blocks
     let exits: HashSet<NodeIndex> = crate::cfg::analysis::find_exits(cfg)
         .into_iter()
@@ -1025,9 +1215,10 @@ fn dfs_enumerate(
     // Check if we've reached an exit
     if exits.contains(&current) {
         // Classify the path using pre-computed reachable set
-        let kind = classify_path_precomputed(cfg, current_path, reachable_blocks);
-        let path = Path::new(current_path.clone(), kind);
-        paths.push(path);
+        if limits.in_length_band(current_path.len()) {
+            let kind = classify_path_precomputed(cfg, current_path, reachable_blocks);
+            paths.push(Path::new(current_path.clone(), kind));
+        }
         current_path.pop();
         return;
     }
@@ -1060,9 +1251,10 @@ fn dfs_enumerate(
     if successors.is_empty() {
         // Dead end (not an exit but no successors)
         // Use classification to determine path kind
-        let kind = classify_path_precomputed(cfg, current_path, reachable_blocks);
-        let path = Path::new(current_path.clone(), kind);
-        paths.push(path);
+        if limits.in_length_band(current_path.len()) {
+            let kind = classify_path_precomputed(cfg, current_path, reachable_blocks);
+            paths.push(Path::new(current_path.clone(), kind));
+        }
     } else {
         for succ in successors {
             // Skip already visited nodes UNLESS it's a back-edge to a loop header
@@ -1115,6 +1307,516 @@ fn dfs_enumerate(
     current_path.pop();
 }
 
+/// Traversal state for `enumerate_paths_bounded_loops`
+struct BoundedLoopState<'a> {
+    cfg: &'a Cfg,
+    exits: HashSet<NodeIndex>,
+    limits: &'a PathLimits,
+    max_loop_iterations: usize,
+    reachable_blocks: HashSet<BlockId>,
+    /// Back edges (tail, header) of all natural loops
+    back_edges: HashSet<(NodeIndex, NodeIndex)>,
+    /// Traversals of each back edge on the current path
+    back_edge_counts: HashMap<(NodeIndex, NodeIndex), usize>,
+    /// Nodes visited since the last back edge traversal
+    visited: HashSet<NodeIndex>,
+    current_path: Vec<BlockId>,
+    paths: Vec<Path>,
+}
+
+/// Enumerate paths, taking each back edge at most `max_loop_iterations` times
+///
+/// Unlike header-count bounding, every loop iteration is a fresh pass over the
+/// loop body: the visited set is reset whenever a back edge is taken, so a
+/// loop produces exactly its 0-, 1-, ..., n-iteration paths. Cycles that
+/// contain no natural-loop back edge (irreducible flow) are still cut by the
+/// visited set.
+fn enumerate_paths_bounded_loops(cfg: &Cfg, entry: NodeIndex, limits: &PathLimits, max_loop_iterations: usize) -> Vec<Path> {
+    let exits: HashSet<NodeIndex> = crate::cfg::analysis::find_exits(cfg)
+        .into_iter()
+        .collect();
+
+    if exits.is_empty() {
+        return vec![]; // No exits means no complete paths
+    }
+
+    let reachable_blocks: HashSet<BlockId> = crate::cfg::reachability::find_reachable(cfg)
+        .iter()
+        .map(|&idx| cfg[idx].id)
+        .collect();
+
+    bounded_loop_paths(cfg, entry, limits, max_loop_iterations, exits, reachable_blocks)
+}
+
+/// Run the back-edge bounded walk from `entry` with exits and reachability already known
+fn bounded_loop_paths(
+    cfg: &Cfg,
+    entry: NodeIndex,
+    limits: &PathLimits,
+    max_loop_iterations: usize,
+    exits: HashSet<NodeIndex>,
+    reachable_blocks: HashSet<BlockId>,
+) -> Vec<Path> {
+    let back_edges = crate::cfg::loops::detect_natural_loops(cfg)
+        .into_iter()
+        .map(|l| l.back_edge)
+        .collect();
+
+    let mut state = BoundedLoopState {
+        cfg,
+        exits,
+        limits,
+        max_loop_iterations,
+        reachable_blocks,
+        back_edges,
+        back_edge_counts: HashMap::new(),
+        visited: HashSet::new(),
+        current_path: Vec::new(),
+        paths: Vec::new(),
+    };
+
+    dfs_enumerate_bounded_loops(&mut state, entry);
+
+    state.paths
+}
+
+/// Recursive DFS helper for `enumerate_paths_bounded_loops`
+fn dfs_enumerate_bounded_loops(state: &mut BoundedLoopState, current: NodeIndex) {
+    let cfg = state.cfg;
+    let block_id = match cfg.node_weight(current) {
+        Some(block) => block.id,
+        None => return,
+    };
+
+    state.current_path.push(block_id);
+
+    if state.current_path.len() > state.limits.max_length {
+        state.current_path.pop();
+        return;
+    }
+
+    if state.exits.contains(&current) {
+        if state.limits.in_length_band(state.current_path.len()) {
+            let kind = classify_path_precomputed(cfg, &state.current_path, &state.reachable_blocks);
+            state.paths.push(Path::new(state.current_path.clone(), kind));
+        }
+        state.current_path.pop();
+        return;
+    }
+
+    if state.paths.len() >= state.limits.max_paths {
+        state.current_path.pop();
+        return;
+    }
+
+    state.visited.insert(current);
+
+    let mut successors: Vec<NodeIndex> = cfg.neighbors(current).collect();
+    successors.sort_by_key(|n| n.index()); // Deterministic order
+    successors.dedup();
+
+    if successors.is_empty() {
+        // Dead end (not an exit but no successors)
+        if state.limits.in_length_band(state.current_path.len()) {
+            let kind = classify_path_precomputed(cfg, &state.current_path, &state.reachable_blocks);
+            state.paths.push(Path::new(state.current_path.clone(), kind));
+        }
+    } else {
+        for succ in successors {
+            let edge = (current, succ);
+            if state.back_edges.contains(&edge) {
+                let count = state.back_edge_counts.get(&edge).copied().unwrap_or(0);
+                if count >= state.max_loop_iterations {
+                    continue;
+                }
+
+                // New iteration: the loop body may be visited again
+                state.back_edge_counts.insert(edge, count + 1);
+                let outer_visited = std::mem::take(&mut state.visited);
+                dfs_enumerate_bounded_loops(state, succ);
+                state.visited = outer_visited;
+                state.back_edge_counts.insert(edge, count);
+            } else if !state.visited.contains(&succ) {
+                dfs_enumerate_bounded_loops(state, succ);
+            }
+
+            if state.paths.len() >= state.limits.max_paths {
+                break;
+            }
+        }
+    }
+
+    state.visited.remove(&current);
+    state.current_path.pop();
+}
+
+/// Path counts by kind, as produced by [`count_paths`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathCounts {
+    /// All paths `enumerate_paths` would return
+    pub total: usize,
+    /// Paths classified as [`PathKind::Normal`]
+    pub normal: usize,
+    /// Paths classified as [`PathKind::Error`]
+    pub error: usize,
+    /// Paths passing through at least one `BlockKind::Panic` block
+    pub panic: usize,
+    /// Whether `max_paths` was reached, so the counts may be incomplete
+    pub truncated: bool,
+}
+
+/// Count paths without materializing them
+///
+/// Walks the CFG exactly as [`enumerate_paths`] does, with the same limits
+/// and loop bounding, but keeps only a running classification per DFS frame
+/// instead of building block vectors and BLAKE3 path IDs. Both skip paths
+/// outside the length band before checking `max_paths`, so `total` equals
+/// `enumerate_paths(cfg, limits).len()`.
+#[tracing::instrument(level = "debug", skip_all, fields(blocks = cfg.node_count()))]
+pub fn count_paths(cfg: &Cfg, limits: &PathLimits) -> PathCounts {
+    walk_path_counts(cfg, limits).map_or_else(PathCounts::default, |counter| counter.counts)
+}
+
+/// Headline statistics of the paths [`enumerate_paths`] would return
+///
+/// Walks the CFG as [`count_paths`] does, also tracking path lengths, so it
+/// equals `summarize_paths` over the enumerated paths without building them.
+#[tracing::instrument(level = "debug", skip_all, fields(blocks = cfg.node_count()))]
+pub fn count_path_summary(cfg: &Cfg, limits: &PathLimits) -> PathSummaryStats {
+    let Some(counter) = walk_path_counts(cfg, limits) else {
+        return PathSummaryStats::default();
+    };
+    let counts = counter.counts;
+    PathSummaryStats {
+        total: counts.total,
+        normal: counts.normal,
+        error: counts.error,
+        panic: counts.panic,
+        min_len: counter.min_len.unwrap_or(0),
+        max_len: counter.max_len,
+        avg_len: if counts.total == 0 { 0.0 } else { counter.total_len as f64 / counts.total as f64 },
+        truncated: counts.truncated,
+    }
+}
+
+/// Run a [`PathCounter`] from the CFG entry; None for a CFG without entry or exits
+fn walk_path_counts<'a>(cfg: &'a Cfg, limits: &'a PathLimits) -> Option<PathCounter<'a>> {
+    use crate::cfg::BlockKind;
+
+    let entry = crate::cfg::analysis::find_entry(cfg)?;
+
+    let exits: HashSet<NodeIndex> = crate::cfg::analysis::find_exits(cfg)
+        .into_iter()
+        .collect();
+
+    if exits.is_empty() {
+        return None; // No exits means no complete paths
+    }
+
+    let mut counter = PathCounter {
+        cfg,
+        exits,
+        limits,
+        entry_is_entry_kind: cfg[entry].kind == BlockKind::Entry,
+        counts: PathCounts::default(),
+        min_len: None,
+        max_len: 0,
+        total_len: 0,
+        depth: 0,
+        visited: HashSet::new(),
+        loop_headers: HashSet::new(),
+        loop_iterations: HashMap::new(),
+        back_edges: HashSet::new(),
+        back_edge_counts: HashMap::new(),
+    };
+
+    match limits.max_loop_iterations {
+        Some(max_loop_iterations) => {
+            counter.back_edges = crate::cfg::loops::detect_natural_loops(cfg)
+                .into_iter()
+                .map(|l| l.back_edge)
+                .collect();
+            counter.count_bounded_loops(entry, max_loop_iterations, None, false);
+        }
+        None => {
+            counter.loop_headers = crate::cfg::loops::find_loop_headers(cfg);
+            counter.count_unrolled(entry, None, false);
+        }
+    }
+
+    counter.counts.truncated = counter.counts.total >= limits.max_paths;
+    Some(counter)
+}
+
+/// Headline statistics for a set of paths, as produced by [`summarize_paths`]
+/// and [`count_path_summary`]
+///
+/// The kind counts are classified as in [`PathCounts`]; lengths are in blocks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PathSummaryStats {
+    pub total: usize,
+    pub normal: usize,
+    pub error: usize,
+    /// Paths passing through at least one panic block
+    pub panic: usize,
+    /// Shortest path length; 0 when there are no paths
+    pub min_len: usize,
+    /// Longest path length; 0 when there are no paths
+    pub max_len: usize,
+    /// Mean path length; 0.0 when there are no paths
+    pub avg_len: f64,
+    /// Whether enumeration stopped at `max_paths`, so the statistics may be incomplete
+    pub truncated: bool,
+}
+
+/// Summarize already-enumerated paths without keeping per-path detail
+pub fn summarize_paths(cfg: &Cfg, paths: &[Path], truncated: bool) -> PathSummaryStats {
+    let panic_blocks = crate::cfg::panic_block_ids(cfg);
+    let lengths = paths.iter().map(|p| p.len());
+    PathSummaryStats {
+        total: paths.len(),
+        normal: paths.iter().filter(|p| p.kind == PathKind::Normal).count(),
+        error: paths.iter().filter(|p| p.kind == PathKind::Error).count(),
+        panic: paths.iter().filter(|p| p.blocks.iter().any(|b| panic_blocks.contains(b))).count(),
+        min_len: lengths.clone().min().unwrap_or(0),
+        max_len: lengths.clone().max().unwrap_or(0),
+        avg_len: if paths.is_empty() { 0.0 } else { lengths.sum::<usize>() as f64 / paths.len() as f64 },
+        truncated,
+    }
+}
+
+/// Two path sets compared by block sequence, as produced by [`diff_paths`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PathSetDiff {
+    /// Block sequences only in the second set
+    pub added: Vec<Vec<BlockId>>,
+    /// Block sequences only in the first set
+    pub removed: Vec<Vec<BlockId>>,
+    /// Block sequences in both sets
+    pub unchanged: Vec<Vec<BlockId>>,
+}
+
+/// Compare two path sets by block sequence
+///
+/// Path IDs and kinds are ignored, so paths enumerated from separately
+/// indexed databases compare equal when they visit the same block IDs.
+/// Each list is sorted and free of duplicates.
+pub fn diff_paths(before: &[Path], after: &[Path]) -> PathSetDiff {
+    use std::collections::BTreeSet;
+
+    let before: BTreeSet<&[BlockId]> = before.iter().map(|p| p.blocks.as_slice()).collect();
+    let after: BTreeSet<&[BlockId]> = after.iter().map(|p| p.blocks.as_slice()).collect();
+    PathSetDiff {
+        added: after.difference(&before).map(|b| b.to_vec()).collect(),
+        removed: before.difference(&after).map(|b| b.to_vec()).collect(),
+        unchanged: before.intersection(&after).map(|b| b.to_vec()).collect(),
+    }
+}
+
+/// Traversal state for `count_paths`
+///
+/// Mirrors `dfs_enumerate` and `dfs_enumerate_bounded_loops`, but the path
+/// itself is reduced to its length (`depth`), the classification decided by
+/// the earliest error/unreachable terminator on it, and whether it has
+/// passed a panic block.
+struct PathCounter<'a> {
+    cfg: &'a Cfg,
+    exits: HashSet<NodeIndex>,
+    limits: &'a PathLimits,
+    entry_is_entry_kind: bool,
+    counts: PathCounts,
+    /// Length statistics of the recorded paths
+    min_len: Option<usize>,
+    max_len: usize,
+    total_len: usize,
+    depth: usize,
+    visited: HashSet<NodeIndex>,
+    /// Header-count bounding (`max_loop_iterations == None`)
+    loop_headers: HashSet<NodeIndex>,
+    loop_iterations: HashMap<NodeIndex, usize>,
+    /// Back-edge bounding (`max_loop_iterations == Some(_)`)
+    back_edges: HashSet<(NodeIndex, NodeIndex)>,
+    back_edge_counts: HashMap<(NodeIndex, NodeIndex), usize>,
+}
+
+impl PathCounter<'_> {
+    /// Enter `node`: returns the updated running classification and panic flag,
+    /// or None if the path length limit is exceeded
+    fn enter(&mut self, node: NodeIndex, kind: Option<PathKind>, panic: bool) -> Option<(Option<PathKind>, bool)> {
+        use crate::cfg::BlockKind;
+
+        let block = self.cfg.node_weight(node)?;
+        self.depth += 1;
+        if self.depth > self.limits.max_length {
+            self.depth -= 1;
+            return None;
+        }
+
+        // Same priority as classify_path_precomputed: first matching block wins
+        let block_kind = match block.terminator {
+            Terminator::Abort(_) | Terminator::Call { unwind: Some(_), .. } => Some(PathKind::Error),
+            Terminator::Unreachable => Some(PathKind::Degenerate),
+            _ => None,
+        };
+
+        Some((kind.or(block_kind), panic || block.kind == BlockKind::Panic))
+    }
+
+    /// Record a path ending at `last`
+    fn record(&mut self, last: NodeIndex, kind: Option<PathKind>, panic: bool) {
+        if !self.limits.in_length_band(self.depth) {
+            return;
+        }
+        let kind = kind.unwrap_or_else(|| {
+            let feasible_exit = matches!(
+                self.cfg[last].terminator,
+                Terminator::Return
+                    | Terminator::Abort(_)
+                    | Terminator::Call { unwind: None, .. }
+                    | Terminator::Call { unwind: Some(_), target: Some(_) }
+            );
+            if self.entry_is_entry_kind && feasible_exit {
+                PathKind::Normal
+            } else {
+                PathKind::Degenerate
+            }
+        });
+
+        self.counts.total += 1;
+        self.min_len = Some(self.min_len.map_or(self.depth, |len| len.min(self.depth)));
+        self.max_len = self.max_len.max(self.depth);
+        self.total_len += self.depth;
+        match kind {
+            PathKind::Normal => self.counts.normal += 1,
+            PathKind::Error => self.counts.error += 1,
+            PathKind::Degenerate | PathKind::Unreachable => {}
+        }
+        if panic {
+            self.counts.panic += 1;
+        }
+    }
+
+    /// Counting counterpart of `dfs_enumerate`
+    fn count_unrolled(&mut self, current: NodeIndex, kind: Option<PathKind>, panic: bool) {
+        let (kind, panic) = match self.enter(current, kind, panic) {
+            Some(state) => state,
+            None => return,
+        };
+
+        if self.exits.contains(&current) {
+            self.record(current, kind, panic);
+            self.depth -= 1;
+            return;
+        }
+
+        if self.counts.total >= self.limits.max_paths {
+            self.depth -= 1;
+            return;
+        }
+
+        let is_loop_header = self.loop_headers.contains(&current);
+        if is_loop_header {
+            let count = self.loop_iterations.entry(current).or_insert(0);
+            if *count >= self.limits.loop_unroll_limit {
+                self.depth -= 1;
+                return;
+            }
+            *count += 1;
+        }
+
+        let was_visited = self.visited.insert(current);
+
+        let mut successors: Vec<NodeIndex> = self.cfg.neighbors(current).collect();
+        successors.sort_by_key(|n| n.index()); // Deterministic order
+
+        if successors.is_empty() {
+            self.record(current, kind, panic);
+        } else {
+            for succ in successors {
+                let is_back_edge = self.loop_headers.contains(&succ) && self.loop_iterations.contains_key(&succ);
+                if self.visited.contains(&succ) && !is_back_edge {
+                    continue;
+                }
+
+                if is_back_edge {
+                    let count = self.loop_iterations.get(&succ).copied().unwrap_or(0);
+                    if count >= self.limits.loop_unroll_limit {
+                        continue;
+                    }
+                }
+
+                self.count_unrolled(succ, kind, panic);
+
+                if self.counts.total >= self.limits.max_paths {
+                    break;
+                }
+            }
+        }
+
+        if was_visited {
+            self.visited.remove(&current);
+        }
+        if is_loop_header {
+            self.loop_iterations.entry(current).and_modify(|c| *c -= 1);
+        }
+        self.depth -= 1;
+    }
+
+    /// Counting counterpart of `dfs_enumerate_bounded_loops`
+    fn count_bounded_loops(&mut self, current: NodeIndex, max_loop_iterations: usize, kind: Option<PathKind>, panic: bool) {
+        let (kind, panic) = match self.enter(current, kind, panic) {
+            Some(state) => state,
+            None => return,
+        };
+
+        if self.exits.contains(&current) {
+            self.record(current, kind, panic);
+            self.depth -= 1;
+            return;
+        }
+
+        if self.counts.total >= self.limits.max_paths {
+            self.depth -= 1;
+            return;
+        }
+
+        self.visited.insert(current);
+
+        let mut successors: Vec<NodeIndex> = self.cfg.neighbors(current).collect();
+        successors.sort_by_key(|n| n.index()); // Deterministic order
+        successors.dedup();
+
+        if successors.is_empty() {
+            self.record(current, kind, panic);
+        } else {
+            for succ in successors {
+                let edge = (current, succ);
+                if self.back_edges.contains(&edge) {
+                    let count = self.back_edge_counts.get(&edge).copied().unwrap_or(0);
+                    if count >= max_loop_iterations {
+                        continue;
+                    }
+
+                    self.back_edge_counts.insert(edge, count + 1);
+                    let outer_visited = std::mem::take(&mut self.visited);
+                    self.count_bounded_loops(succ, max_loop_iterations, kind, panic);
+                    self.visited = outer_visited;
+                    self.back_edge_counts.insert(edge, count);
+                } else if !self.visited.contains(&succ) {
+                    self.count_bounded_loops(succ, max_loop_iterations, kind, panic);
+                }
+
+                if self.counts.total >= self.limits.max_paths {
+                    break;
+                }
+            }
+        }
+
+        self.visited.remove(&current);
+        self.depth -= 1;
+    }
+}
+
 /// Get paths from cache or enumerate them
 ///
 /// This bridge function connects the caching layer to path enumeration.
@@ -1160,6 +1862,7 @@ fn dfs_enumerate(
 /// )?;
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
+#[tracing::instrument(level = "debug", skip(cfg, function_hash, limits, db_conn))]
 pub fn get_or_enumerate_paths(
     cfg: &Cfg,
     function_id: i64,
@@ -1167,7 +1870,27 @@ pub fn get_or_enumerate_paths(
     limits: &PathLimits,
     db_conn: &mut rusqlite::Connection,
 ) -> Result<Vec<Path>, String> {
-    use crate::storage::paths::{get_cached_paths, invalidate_function_paths, store_paths};
+    if let Some(paths) = lookup_cached_paths(function_id, function_hash, db_conn)? {
+        return Ok(paths);
+    }
+
+    // Cache miss or hash changed - enumerate and store paths
+    let paths = enumerate_paths(cfg, limits);
+    store_enumerated_paths(function_id, &paths, db_conn)?;
+    Ok(paths)
+}
+
+/// The lookup half of [`get_or_enumerate_paths`]
+///
+/// Returns the cached paths if the cache is valid for `function_hash`, or
+/// `Ok(None)` on a miss. Callers that enumerate elsewhere (such as a parallel
+/// batch) store the result with [`store_enumerated_paths`].
+pub fn lookup_cached_paths(
+    function_id: i64,
+    function_hash: &str,
+    db_conn: &mut rusqlite::Connection,
+) -> Result<Option<Vec<Path>>, String> {
+    use crate::storage::paths::get_cached_paths;
 
     // Check current hash in cfg_blocks
     let current_hash: Option<String> = db_conn.query_row(
@@ -1177,29 +1900,29 @@ pub fn get_or_enumerate_paths(
     ).unwrap_or(None);
 
     // If hash matches, return cached paths
-    if let Some(ref hash) = current_hash {
-        if hash == function_hash {
-            // Cache hit - retrieve stored paths
-            let paths = get_cached_paths(db_conn, function_id)
-                .map_err(|e| format!("Failed to retrieve cached paths: {}", e))?;
-            return Ok(paths);
-        }
+    if current_hash.as_deref() == Some(function_hash) {
+        let paths = get_cached_paths(db_conn, function_id)
+            .map_err(|e| format!("Failed to retrieve cached paths: {}", e))?;
+        return Ok(Some(paths));
     }
+    Ok(None)
+}
 
-    // Cache miss or hash changed - enumerate and store paths
-    let paths = enumerate_paths(cfg, limits);
+/// The store half of [`get_or_enumerate_paths`]: replace a function's cached paths
+pub fn store_enumerated_paths(
+    function_id: i64,
+    paths: &[Path],
+    db_conn: &mut rusqlite::Connection,
+) -> Result<(), String> {
+    use crate::storage::paths::{invalidate_function_paths, store_paths};
 
     // Invalidate old paths if any
     let _ = invalidate_function_paths(db_conn, function_id);
 
-    // Store the enumerated paths
-    store_paths(db_conn, function_id, &paths)
-        .map_err(|e| format!("Failed to store enumerated paths: {}", e))?;
-
     // Note: function_hash tracking removed - not available in Magellan's cfg_blocks schema
     // Magellan manages its own caching and re-indexing when source files change
-
-    Ok(paths)
+    store_paths(db_conn, function_id, paths)
+        .map_err(|e| format!("Failed to store enumerated paths: {}", e))
 }
 
 /// Enumerate paths with integrated caching and pre-computed context
@@ -1455,6 +2178,205 @@ pub fn check_path_explosion(cfg: &Cfg, limits: &PathLimits) -> Option<usize> {
     }
 }
 
+/// Compute BLAKE3 hash of the set of blocks a path visits
+///
+/// Order and repetition are ignored: `[0, 1, 2, 1, 3]` and `[0, 1, 2, 3]`
+/// hash the same. Used as the grouping key for `dedup_by_blockset`.
+pub fn hash_block_set(blocks: &[BlockId]) -> String {
+    let mut set: Vec<BlockId> = blocks.to_vec();
+    set.sort_unstable();
+    set.dedup();
+    hash_path(&set)
+}
+
+/// Collapse paths that visit an identical set of blocks
+///
+/// Paths through a loop that differ only in iteration count get distinct
+/// path_ids but cover the same blocks. This pass keeps one representative
+/// per block set (the shortest, earliest on ties) in first-seen order.
+///
+/// This is lossy with respect to block ordering and repetition, so it is
+/// opt-in (`paths --dedup-by-blockset`) rather than part of enumeration.
+///
+/// # Returns
+///
+/// The kept paths and the number of paths collapsed into them.
+pub fn dedup_by_blockset(paths: Vec<Path>) -> (Vec<Path>, usize) {
+    let total = paths.len();
+    let mut index_by_set: HashMap<String, usize> = HashMap::new();
+    let mut kept: Vec<Path> = Vec::new();
+
+    for path in paths {
+        let key = hash_block_set(&path.blocks);
+        match index_by_set.get(&key) {
+            Some(&idx) => {
+                if path.len() < kept[idx].len() {
+                    kept[idx] = path;
+                }
+            }
+            None => {
+                index_by_set.insert(key, kept.len());
+                kept.push(path);
+            }
+        }
+    }
+
+    let collapsed = total - kept.len();
+    (kept, collapsed)
+}
+
+/// Order for [`sort_paths`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSort {
+    /// Number of blocks
+    Length,
+    /// Path kind: normal paths first, then error, degenerate and unreachable
+    Kind,
+    /// Block sequence, compared lexicographically
+    Blocks,
+}
+
+/// Sort paths by `by`, breaking ties by block sequence
+///
+/// Enumeration order depends on successor order in the graph; sorting gives
+/// output that is stable across runs and indexing passes. `descending`
+/// reverses the primary key only; ties stay in ascending block order.
+pub fn sort_paths(paths: &mut [Path], by: PathSort, descending: bool) {
+    fn kind_rank(kind: PathKind) -> u8 {
+        match kind {
+            PathKind::Normal => 0,
+            PathKind::Error => 1,
+            PathKind::Degenerate => 2,
+            PathKind::Unreachable => 3,
+        }
+    }
+
+    paths.sort_by(|a, b| {
+        let primary = match by {
+            PathSort::Length => a.len().cmp(&b.len()),
+            PathSort::Kind => kind_rank(a.kind).cmp(&kind_rank(b.kind)),
+            PathSort::Blocks => a.blocks.cmp(&b.blocks),
+        };
+        let primary = if descending { primary.reverse() } else { primary };
+        primary.then_with(|| a.blocks.cmp(&b.blocks))
+    });
+}
+
+/// 1-based rank of each path in ascending block-sequence order
+///
+/// Ranks back the readable path IDs from [`readable_path_id`]. They depend
+/// only on the set of paths, not on the order they were enumerated or loaded
+/// in, so a function's aliases are the same on every run. The result is
+/// parallel to `paths`.
+pub fn path_ordinals(paths: &[Path]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..paths.len()).collect();
+    order.sort_by(|&a, &b| paths[a].blocks.cmp(&paths[b].blocks));
+
+    let mut ordinals = vec![0; paths.len()];
+    for (rank, index) in order.into_iter().enumerate() {
+        ordinals[index] = rank + 1;
+    }
+    ordinals
+}
+
+/// Readable alias for a function's path with the given ordinal: `function#pN`
+///
+/// The BLAKE3 `path_id` stays the canonical key; the alias only exists to be
+/// quoted in reviews and issues.
+pub fn readable_path_id(function: &str, ordinal: usize) -> String {
+    format!("{}#p{}", function, ordinal)
+}
+
+/// Split a readable path ID into its function and ordinal
+///
+/// Returns `None` for anything else, including BLAKE3 path IDs.
+pub fn parse_readable_path_id(id: &str) -> Option<(&str, usize)> {
+    let (function, ordinal) = id.trim().rsplit_once("#p")?;
+    if function.is_empty() || ordinal.is_empty() || !ordinal.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    ordinal.parse().ok().filter(|&n| n > 0).map(|n| (function, n))
+}
+
+/// A call made from a function body, located by byte span
+///
+/// Built from Magellan's `Call` nodes (see `analysis::function_call_sites`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallSite {
+    /// Name of the called function
+    pub callee: String,
+    /// Byte offset of the start of the call expression
+    pub byte_start: usize,
+    /// Byte offset of the end of the call expression
+    pub byte_end: usize,
+}
+
+/// Functions called along a path, in call order without repeats
+///
+/// A call site belongs to a `Terminator::Call` block on the path when it
+/// starts inside that block's byte range. Blocks without a source location
+/// contribute nothing.
+pub fn path_callees(cfg: &Cfg, path: &Path, call_sites: &[CallSite]) -> Vec<String> {
+    let mut callees: Vec<String> = Vec::new();
+    for &block_id in &path.blocks {
+        let Some(block) = cfg.node_weights().find(|b| b.id == block_id) else {
+            continue;
+        };
+        if !matches!(block.terminator, Terminator::Call { .. }) {
+            continue;
+        }
+        let Some(loc) = &block.source_location else {
+            continue;
+        };
+        let mut in_block: Vec<&CallSite> = call_sites.iter()
+            .filter(|c| c.byte_start >= loc.byte_start && c.byte_start < loc.byte_end)
+            .collect();
+        in_block.sort_by_key(|c| c.byte_start);
+        for call in in_block {
+            if !callees.contains(&call.callee) {
+                callees.push(call.callee.clone());
+            }
+        }
+    }
+    callees
+}
+
+/// Back edges of a CFG as (tail, header) block ID pairs
+///
+/// Includes edges typed `EdgeType::LoopBack` and the back edge of every
+/// natural loop, since CFGs loaded from the database do not label loop
+/// edges. Pass the result to `path_loop_headers`.
+pub fn back_edge_block_ids(cfg: &Cfg) -> HashSet<(BlockId, BlockId)> {
+    let mut back_edges: HashSet<(BlockId, BlockId)> = crate::cfg::loops::detect_natural_loops(cfg)
+        .into_iter()
+        .map(|l| (cfg[l.back_edge.0].id, cfg[l.back_edge.1].id))
+        .collect();
+
+    for edge in cfg.edge_indices() {
+        if cfg[edge] == EdgeType::LoopBack {
+            if let Some((from, to)) = cfg.edge_endpoints(edge) {
+                back_edges.insert((cfg[from].id, cfg[to].id));
+            }
+        }
+    }
+
+    back_edges
+}
+
+/// Loop headers a path re-enters through a back edge, sorted and deduplicated
+///
+/// Empty for straight-line paths, including paths that pass through a loop
+/// header but exit without iterating.
+pub fn path_loop_headers(path: &Path, back_edges: &HashSet<(BlockId, BlockId)>) -> Vec<BlockId> {
+    let mut headers: Vec<BlockId> = path.blocks.windows(2)
+        .filter(|pair| back_edges.contains(&(pair[0], pair[1])))
+        .map(|pair| pair[1])
+        .collect();
+    headers.sort_unstable();
+    headers.dedup();
+    headers
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1589,6 +2511,302 @@ mod tests {
         g
     }
 
+    #[test]
+    fn test_path_loop_headers_flags_iterating_paths() {
+        let cfg = create_loop_cfg();
+        let back_edges = back_edge_block_ids(&cfg);
+        assert_eq!(back_edges, HashSet::from([(2, 1)]));
+
+        let paths = enumerate_paths(&cfg, &PathLimits::default().with_max_loop_iterations(1));
+        let skipping = paths.iter().find(|p| p.blocks == vec![0, 1, 3]).unwrap();
+        let iterating = paths.iter().find(|p| p.blocks == vec![0, 1, 2, 1, 3]).unwrap();
+
+        assert!(path_loop_headers(skipping, &back_edges).is_empty());
+        assert_eq!(path_loop_headers(iterating, &back_edges), vec![1]);
+    }
+
+    #[test]
+    fn test_sort_paths() {
+        let path = |blocks: Vec<BlockId>, kind| Path::new(blocks, kind);
+        let mut paths = vec![
+            path(vec![0, 2, 3], PathKind::Normal),
+            path(vec![0, 1, 4, 5], PathKind::Error),
+            path(vec![0, 1, 3], PathKind::Normal),
+            path(vec![0, 4], PathKind::Error),
+        ];
+        let order = |paths: &[Path]| paths.iter().map(|p| p.blocks.clone()).collect::<Vec<_>>();
+
+        sort_paths(&mut paths, PathSort::Length, false);
+        assert_eq!(order(&paths), vec![vec![0, 4], vec![0, 1, 3], vec![0, 2, 3], vec![0, 1, 4, 5]]);
+
+        // Descending reverses the length, not the tie-break
+        sort_paths(&mut paths, PathSort::Length, true);
+        assert_eq!(order(&paths), vec![vec![0, 1, 4, 5], vec![0, 1, 3], vec![0, 2, 3], vec![0, 4]]);
+
+        sort_paths(&mut paths, PathSort::Kind, false);
+        assert_eq!(order(&paths), vec![vec![0, 1, 3], vec![0, 2, 3], vec![0, 1, 4, 5], vec![0, 4]]);
+
+        sort_paths(&mut paths, PathSort::Blocks, false);
+        assert_eq!(order(&paths), vec![vec![0, 1, 3], vec![0, 1, 4, 5], vec![0, 2, 3], vec![0, 4]]);
+    }
+
+    #[test]
+    fn test_sort_paths_diamond() {
+        let cfg = create_diamond_cfg();
+        let mut paths = enumerate_paths(&cfg, &PathLimits::default());
+
+        // Both diamond paths have 3 blocks; length ties fall back to block order
+        sort_paths(&mut paths, PathSort::Length, false);
+        assert!(paths.iter().all(|p| p.len() == 3));
+        assert!(paths[0].blocks < paths[1].blocks);
+
+        // Block order does not depend on enumeration order
+        let mut reversed: Vec<Path> = paths.iter().rev().cloned().collect();
+        sort_paths(&mut reversed, PathSort::Blocks, false);
+        sort_paths(&mut paths, PathSort::Blocks, false);
+        assert_eq!(reversed, paths);
+    }
+
+    #[test]
+    fn test_path_ordinals_stable_across_runs() {
+        let cfg = create_diamond_cfg();
+        let paths = enumerate_paths(&cfg, &PathLimits::default());
+        let ordinals = path_ordinals(&paths);
+
+        // The lower block sequence is p1, whatever order paths come in
+        let first = if paths[0].blocks < paths[1].blocks { 0 } else { 1 };
+        assert_eq!(ordinals[first], 1);
+        assert_eq!(ordinals[1 - first], 2);
+
+        let reversed: Vec<Path> = paths.iter().rev().cloned().collect();
+        let aliases = |paths: &[Path]| {
+            let mut aliases: Vec<(String, String)> = paths.iter()
+                .zip(path_ordinals(paths))
+                .map(|(p, n)| (p.path_id.clone(), readable_path_id("diamond", n)))
+                .collect();
+            aliases.sort();
+            aliases
+        };
+        assert_eq!(aliases(&paths), aliases(&reversed));
+        assert_eq!(aliases(&paths), aliases(&enumerate_paths(&cfg, &PathLimits::default())));
+    }
+
+    #[test]
+    fn test_parse_readable_path_id() {
+        assert_eq!(parse_readable_path_id("foo#p1"), Some(("foo", 1)));
+        assert_eq!(parse_readable_path_id(" my_crate::foo#p12 "), Some(("my_crate::foo", 12)));
+        assert_eq!(parse_readable_path_id(&readable_path_id("bar", 3)), Some(("bar", 3)));
+
+        assert_eq!(parse_readable_path_id(&hash_path(&[0, 1, 2])), None);
+        assert_eq!(parse_readable_path_id("foo#p0"), None);
+        assert_eq!(parse_readable_path_id("foo#p"), None);
+        assert_eq!(parse_readable_path_id("foo#p+1"), None);
+        assert_eq!(parse_readable_path_id("#p1"), None);
+    }
+
+    #[test]
+    fn test_path_callees_from_call_blocks() {
+        use crate::cfg::{BasicBlock, SourceLocation};
+
+        let mut g = DiGraph::new();
+        let block = |id, kind, terminator, bytes: (usize, usize)| BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: Some(SourceLocation::new("lib.rs", bytes.0, bytes.1, 1, 1, 1, 1)),
+        };
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Call { target: Some(1), unwind: None }, (0, 40)));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::Goto { target: 2 }, (40, 60)));
+        let b2 = g.add_node(block(2, BlockKind::Exit, Terminator::Return, (60, 80)));
+        g.add_edge(b0, b1, EdgeType::Call);
+        g.add_edge(b1, b2, EdgeType::Fallthrough);
+
+        let call_sites = vec![
+            CallSite { callee: "unsafe_helper".to_string(), byte_start: 20, byte_end: 35 },
+            CallSite { callee: "setup".to_string(), byte_start: 5, byte_end: 12 },
+            // Not a call-terminated block
+            CallSite { callee: "log".to_string(), byte_start: 45, byte_end: 50 },
+        ];
+        let path = Path::new(vec![0, 1, 2], PathKind::Normal);
+
+        assert_eq!(path_callees(&g, &path, &call_sites), vec!["setup", "unsafe_helper"]);
+        assert!(path_callees(&g, &path, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_dedup_by_blockset_collapses_loop_iterations() {
+        // Two loop iterations visit the same blocks as one iteration
+        let cfg = create_loop_cfg();
+        let paths = enumerate_paths(&cfg, &PathLimits::default().with_max_loop_iterations(2));
+        assert_eq!(paths.len(), 3);
+
+        let (deduped, collapsed) = dedup_by_blockset(paths);
+
+        assert_eq!(collapsed, 1);
+        let blocks: Vec<Vec<BlockId>> = deduped.iter().map(|p| p.blocks.clone()).collect();
+        assert_eq!(blocks, vec![vec![0, 1, 2, 1, 3], vec![0, 1, 3]], "Shortest representative kept");
+    }
+
+    #[test]
+    fn test_max_loop_iterations_counts_paths() {
+        let cfg = create_loop_cfg();
+
+        for (n, expected) in [(0, 1), (1, 2), (2, 3)] {
+            let paths = enumerate_paths(&cfg, &PathLimits::default().with_max_loop_iterations(n));
+            assert_eq!(paths.len(), expected, "n = {}: {:?}", n, paths);
+        }
+
+        let paths = enumerate_paths(&cfg, &PathLimits::default().with_max_loop_iterations(2));
+        let blocks: Vec<Vec<BlockId>> = paths.iter().map(|p| p.blocks.clone()).collect();
+        assert_eq!(blocks, vec![
+            vec![0, 1, 2, 1, 2, 1, 3],
+            vec![0, 1, 2, 1, 3],
+            vec![0, 1, 3],
+        ]);
+    }
+
+    #[test]
+    fn test_max_loop_iterations_nested_loops_terminate() {
+        let cfg = create_nested_loop_cfg();
+        let paths = enumerate_paths(&cfg, &PathLimits::default().with_max_loop_iterations(2));
+
+        assert!(!paths.is_empty());
+        assert!(paths.iter().any(|p| p.blocks == vec![0, 1, 2, 3, 2, 3, 2, 1, 4]),
+                "Inner loop should iterate twice: {:?}", paths);
+    }
+
+    #[test]
+    fn test_enumerate_paths_from_interior_block() {
+        let cfg = create_diamond_cfg();
+
+        // From the merge block only the tail remains
+        let paths = enumerate_paths_from(&cfg, NodeIndex::new(3), &PathLimits::default());
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].blocks, vec![3]);
+
+        // From one arm: a single tail path, starting at the chosen block
+        let paths = enumerate_paths_from(&cfg, NodeIndex::new(1), &PathLimits::default());
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].blocks, vec![1, 3]);
+        assert_eq!(paths[0].entry, 1);
+        assert_eq!(paths[0].kind, PathKind::Normal, "Not degenerate just for skipping the entry");
+        assert_eq!(paths[0].path_id, hash_path(&[1, 3]));
+
+        // From the entry it matches enumerate_paths
+        assert_eq!(
+            enumerate_paths_from(&cfg, NodeIndex::new(0), &PathLimits::default()),
+            enumerate_paths(&cfg, &PathLimits::default())
+        );
+    }
+
+    #[test]
+    fn test_summarize_paths_diamond() {
+        let cfg = create_diamond_cfg();
+        let paths = enumerate_paths(&cfg, &PathLimits::default());
+        let stats = summarize_paths(&cfg, &paths, false);
+
+        assert_eq!(stats.total, 2);
+        assert_eq!(stats.normal, 2);
+        assert_eq!(stats.error, 0);
+        assert_eq!(stats.panic, 0);
+        assert_eq!((stats.min_len, stats.max_len), (3, 3));
+        assert_eq!(stats.avg_len, 3.0);
+        assert!(!stats.truncated);
+
+        // Kind counts agree with count_paths
+        let counts = count_paths(&cfg, &PathLimits::default());
+        assert_eq!((stats.total, stats.normal, stats.error), (counts.total, counts.normal, counts.error));
+
+        assert_eq!(summarize_paths(&cfg, &[], false), PathSummaryStats::default());
+    }
+
+    #[test]
+    fn test_diff_paths_gained_branch() {
+        // 0 -> 1 -> 3 gains a second arm through block 2
+        let before = vec![Path::new(vec![0, 1, 3], PathKind::Normal)];
+        let after = enumerate_paths(&create_diamond_cfg(), &PathLimits::default());
+
+        let diff = diff_paths(&before, &after);
+        assert_eq!(diff.added, vec![vec![0, 2, 3]]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.unchanged, vec![vec![0, 1, 3]]);
+
+        let reverse = diff_paths(&after, &before);
+        assert_eq!(reverse.removed, vec![vec![0, 2, 3]]);
+        assert!(reverse.added.is_empty());
+    }
+
+    #[test]
+    fn test_count_paths_matches_enumerate_paths() {
+        let mut panic_diamond = create_diamond_cfg();
+        let panic_node = NodeIndex::new(2);
+        panic_diamond[panic_node].kind = crate::cfg::BlockKind::Panic;
+
+        let fixtures = [
+            ("linear", create_linear_cfg()),
+            ("diamond", create_diamond_cfg()),
+            ("panic diamond", panic_diamond),
+            ("loop", create_loop_cfg()),
+            ("error", create_error_cfg()),
+            ("unreachable terminator", create_unreachable_term_cfg()),
+            ("dead code", create_dead_code_cfg()),
+            ("call unwind", create_call_unwind_cfg()),
+            ("self loop", create_self_loop_cfg()),
+            ("nested loop", create_nested_loop_cfg()),
+            ("large diamond", create_large_diamond_cfg()),
+        ];
+        let all_limits = [
+            PathLimits::default(),
+            PathLimits::default().with_max_loop_iterations(2),
+            PathLimits::default().with_loop_unroll_limit(3),
+            PathLimits::default().with_max_length(3),
+            PathLimits::default().with_max_paths(2),
+            PathLimits::default().with_min_length(4),
+            PathLimits::default().with_min_length(4).with_max_paths(1),
+        ];
+
+        for (name, cfg) in &fixtures {
+            let panic_blocks = crate::cfg::panic_block_ids(cfg);
+            for limits in &all_limits {
+                let paths = enumerate_paths(cfg, limits);
+                let counts = count_paths(cfg, limits);
+                let context = format!("{} with {:?}", name, limits);
+
+                assert_eq!(counts.total, paths.len(), "{}", context);
+                assert_eq!(counts.normal, paths.iter().filter(|p| p.kind == PathKind::Normal).count(), "{}", context);
+                assert_eq!(counts.error, paths.iter().filter(|p| p.kind == PathKind::Error).count(), "{}", context);
+                assert_eq!(
+                    counts.panic,
+                    paths.iter().filter(|p| p.blocks.iter().any(|b| panic_blocks.contains(b))).count(),
+                    "{}", context
+                );
+                assert_eq!(counts.truncated, paths.len() >= limits.max_paths, "{}", context);
+                assert_eq!(count_path_summary(cfg, limits), summarize_paths(cfg, &paths, counts.truncated), "{}", context);
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_paths_caps_fan_out() {
+        let cfg = create_large_diamond_cfg();
+        let all = enumerate_paths(&cfg, &PathLimits::default());
+        assert!(all.len() > 100, "Fixture should fan out past the cap");
+        assert!(!PathLimits::default().is_truncated(all.len()));
+
+        let limits = PathLimits::default().with_max_paths(100);
+        let capped = enumerate_paths(&cfg, &limits);
+        assert_eq!(capped.len(), 100);
+        assert!(limits.is_truncated(capped.len()));
+        assert!(capped.iter().all(|p| all.contains(p)), "Capped paths are a subset");
+    }
+
+    #[test]
+    fn test_hash_block_set_ignores_order_and_repetition() {
+        assert_eq!(hash_block_set(&[0, 1, 2, 1, 3]), hash_block_set(&[3, 2, 1, 0]));
+        assert_ne!(hash_block_set(&[0, 1, 3]), hash_block_set(&[0, 1, 2, 3]));
+    }
+
     #[test]
     fn test_hash_path_deterministic() {
         let blocks = vec![0, 1, 2];
@@ -1662,6 +2880,7 @@ mod tests {
         assert_eq!(limits.max_length, 100);
         assert_eq!(limits.max_paths, 500);
         assert_eq!(limits.loop_unroll_limit, 5);
+        assert_eq!(limits.max_loop_iterations, None);
     }
 
     #[test]
@@ -1853,6 +3072,76 @@ mod tests {
         g
     }
 
+    /// 0 switches to 1 (early return), 2 (abort) and 3; 3 switches to 4
+    /// (unreachable), 5 (natural return) and 6 (a call back into 3)
+    fn create_end_kinds_cfg() -> Cfg {
+        let block = |id, kind, terminator| BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        };
+
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1, 2], otherwise: 3 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, Terminator::Return));
+        let b2 = g.add_node(block(2, BlockKind::Exit, Terminator::Abort("overflow".to_string())));
+        let b3 = g.add_node(block(3, BlockKind::Normal, Terminator::SwitchInt { targets: vec![4, 5], otherwise: 6 }));
+        let b4 = g.add_node(block(4, BlockKind::Exit, Terminator::Unreachable));
+        let b5 = g.add_node(block(5, BlockKind::Exit, Terminator::Return));
+        let b6 = g.add_node(block(6, BlockKind::Normal, Terminator::Call { target: Some(3), unwind: None }));
+
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::TrueBranch);
+        g.add_edge(b0, b3, EdgeType::FalseBranch);
+        g.add_edge(b3, b4, EdgeType::TrueBranch);
+        g.add_edge(b3, b5, EdgeType::TrueBranch);
+        g.add_edge(b3, b6, EdgeType::FalseBranch);
+        g.add_edge(b6, b3, EdgeType::LoopBack);
+        g
+    }
+
+    #[test]
+    fn test_classify_end() {
+        let cfg = create_end_kinds_cfg();
+        let natural = natural_exit(&cfg);
+        assert_eq!(natural, Some(5));
+
+        let end = |blocks: Vec<BlockId>| classify_end(&cfg, &Path::new(blocks, PathKind::Normal), natural);
+        assert_eq!(end(vec![0, 3, 5]), EndKind::NormalReturn);
+        assert_eq!(end(vec![0, 3, 6, 3, 5]), EndKind::NormalReturn);
+        assert_eq!(end(vec![0, 1]), EndKind::EarlyReturn);
+        assert_eq!(end(vec![0, 2]), EndKind::Panic);
+        assert_eq!(end(vec![0, 3, 4]), EndKind::Diverge);
+        // Cut off inside the loop
+        assert_eq!(end(vec![0, 3, 6]), EndKind::Loop);
+
+        // Every enumerated path gets the end kind of its exit block
+        for path in enumerate_paths(&cfg, &PathLimits::default()) {
+            let expected = match path.exit {
+                1 => EndKind::EarlyReturn,
+                2 => EndKind::Panic,
+                4 => EndKind::Diverge,
+                5 => EndKind::NormalReturn,
+                other => panic!("unexpected exit block {}", other),
+            };
+            assert_eq!(classify_end(&cfg, &path, natural), expected, "path {:?}", path.blocks);
+        }
+    }
+
+    #[test]
+    fn test_classify_end_panic_block() {
+        let mut cfg = create_linear_cfg();
+        let last = cfg.node_indices().next_back().unwrap();
+        cfg[last].kind = BlockKind::Panic;
+
+        // A return inside a panic block is not the natural exit
+        assert_eq!(natural_exit(&cfg), None);
+        let path = Path::new(vec![0, 1, 2], PathKind::Error);
+        assert_eq!(classify_end(&cfg, &path, None), EndKind::Panic);
+    }
+
     #[test]
     fn test_classify_path_normal_return() {
         let cfg = create_linear_cfg();
@@ -2394,6 +3683,61 @@ mod tests {
         assert_eq!(paths.len(), 0, "Path exceeds max_length, should return 0 paths");
     }
 
+    #[test]
+    fn test_path_limits_min_length_band() {
+        let cfg = create_diamond_cfg();
+
+        // Both diamond paths have 3 blocks
+        let limits = PathLimits::default().with_min_length(3);
+        let mut paths = enumerate_paths(&cfg, &limits);
+        assert_eq!(limits.retain_length_band(&mut paths), 0);
+        assert_eq!(paths.len(), 2);
+
+        let limits = PathLimits::default().with_min_length(4);
+        assert!(enumerate_paths(&cfg, &limits).is_empty());
+        assert_eq!(count_paths(&cfg, &limits).total, 0);
+        let mut paths = enumerate_paths(&cfg, &PathLimits::default());
+        assert_eq!(limits.retain_length_band(&mut paths), 2);
+        assert!(paths.is_empty());
+
+        // A band that excludes the diamond from above
+        let limits = PathLimits::default().with_min_length(2).with_max_length(2);
+        let mut paths = enumerate_paths(&cfg, &limits);
+        limits.retain_length_band(&mut paths);
+        assert!(paths.is_empty());
+    }
+
+    #[test]
+    fn test_min_length_applies_before_max_paths() {
+        // 0 -> 1 (exit) is found before the longer 0 -> 2 -> 3
+        let mut g = DiGraph::new();
+        let block = |id, kind, terminator| BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        };
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, Terminator::Return));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+
+        // The skipped short path does not use up the cap
+        for limits in [
+            PathLimits::default().with_min_length(3).with_max_paths(1),
+            PathLimits::new(1000, 1, 3).with_min_length(3),
+        ] {
+            let paths = enumerate_paths(&g, &limits);
+            assert_eq!(paths.len(), 1, "{:?}", limits);
+            assert_eq!(paths[0].blocks, vec![0, 2, 3]);
+            assert_eq!(count_paths(&g, &limits).total, 1);
+        }
+    }
+
     #[test]
     fn test_path_limits_max_paths_exact() {
         let cfg = create_diamond_cfg();
@@ -3598,6 +4942,24 @@ mod tests {
         assert!(paths.len() <= 4); // Entry + up to 3 loop iterations
     }
 
+    #[test]
+    fn test_enumerate_paths_with_context_honors_max_loop_iterations() {
+        use super::super::{enumerate_paths, enumerate_paths_with_context, EnumerationContext};
+
+        let cfg = create_loop_cfg();
+        let ctx = EnumerationContext::new(&cfg);
+
+        for n in 0..=3 {
+            let limits = PathLimits::default().with_max_loop_iterations(n);
+            let mut basic: Vec<_> = enumerate_paths(&cfg, &limits).into_iter().map(|p| p.blocks).collect();
+            let mut context: Vec<_> = enumerate_paths_with_context(&cfg, &limits, &ctx).into_iter().map(|p| p.blocks).collect();
+            basic.sort();
+            context.sort();
+            assert_eq!(context, basic, "max_loop_iterations = {}", n);
+            assert_eq!(context.len(), n + 1);
+        }
+    }
+
     #[test]
     fn test_enumerate_paths_with_context_performance() {
         use super::super::{enumerate_paths, enumerate_paths_with_context, EnumerationContext};
@@ -3975,8 +5337,10 @@ mod tests {
         let cfg = create_diamond_cfg();
         let limits = PathLimits {
             max_length: 100,
+            min_length: 0,
             max_paths: 1, // Very low limit
             loop_unroll_limit: 3,
+            max_loop_iterations: Some(1),
         };
 
         // Diamond might exceed very low limit
@@ -1,9 +1,10 @@
 //! Branching pattern recovery from CFG shapes
 
 use crate::cfg::{BlockId, Cfg, Terminator};
-use crate::cfg::analysis::is_branch_point;
+use crate::cfg::analysis::{is_branch_point, is_exit_block, is_merge_point};
 use crate::cfg::EdgeType;
 use petgraph::graph::NodeIndex;
+use serde::Serialize;
 use std::collections::HashSet;
 
 /// Classification of a node's branching structure
@@ -19,6 +20,32 @@ pub enum BranchType {
     Unknown,
 }
 
+/// How the two arms of an if/else relate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IfShape {
+    /// Both arms do work, then merge
+    Diamond,
+    /// No else: one arm runs, then falls into the other, which is the merge
+    IfOnly,
+    /// One arm leaves the function early; see [`detect_guard_clauses`]
+    GuardReturn,
+    /// The arms never merge, and neither is an early exit guarding the other
+    Divergent,
+}
+
+impl std::fmt::Display for IfShape {
+    /// The same snake_case name as in JSON output
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Diamond => write!(f, "diamond"),
+            Self::IfOnly => write!(f, "if_only"),
+            Self::GuardReturn => write!(f, "guard_return"),
+            Self::Divergent => write!(f, "divergent"),
+        }
+    }
+}
+
 /// Represents an if/else structure detected in the CFG
 #[derive(Debug, Clone)]
 pub struct IfElsePattern {
@@ -31,6 +58,8 @@ pub struct IfElsePattern {
     /// Merge point (where branches reconverge)
     /// None if branches don't merge (e.g., early return)
     pub merge_point: Option<NodeIndex>,
+    /// Diamond, if-without-else, or guard
+    pub shape: IfShape,
 }
 
 impl IfElsePattern {
@@ -45,6 +74,46 @@ impl IfElsePattern {
     }
 }
 
+/// An early exit (`if cond { return }`) guarding the rest of a function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardClause {
+    /// Condition node (branch point)
+    pub condition: NodeIndex,
+    /// Arm that leaves the function
+    pub guard: NodeIndex,
+    /// Arm that continues the main flow
+    pub continuation: NodeIndex,
+    /// Whether the guard is the true branch
+    pub guard_on_true: bool,
+    /// Exit node the guard arm ends in
+    pub exit: NodeIndex,
+    /// Blocks on the guard arm, from its first block to the exit inclusive
+    pub exit_distance: usize,
+}
+
+/// Longest guard arm, in blocks, that `detect_guard_clauses` accepts
+pub const GUARD_MAX_BLOCKS: usize = 3;
+
+/// An if/else whose arms differ sharply in size
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnbalancedBranch {
+    /// Condition node (branch point)
+    pub condition: NodeIndex,
+    /// True branch target
+    pub true_branch: NodeIndex,
+    /// False branch target
+    pub false_branch: NodeIndex,
+    /// Blocks only reachable through the true arm
+    pub true_blocks: usize,
+    /// Blocks only reachable through the false arm
+    pub false_blocks: usize,
+    /// Larger arm over smaller arm (an empty arm counts as one block)
+    pub ratio: f64,
+}
+
+/// Arm-size ratio above which `patterns --unbalanced` flags a branch by default
+pub const UNBALANCED_DEFAULT_THRESHOLD: f64 = 4.0;
+
 /// Represents a match/switch structure detected in the CFG
 #[derive(Debug, Clone)]
 pub struct MatchPattern {
@@ -54,6 +123,13 @@ pub struct MatchPattern {
     pub targets: Vec<NodeIndex>,
     /// Default/otherwise branch
     pub otherwise: NodeIndex,
+    /// `(arm index, target)` for every non-default switch arm, in order
+    ///
+    /// The index is the arm's position in the `SwitchInt` targets, not the
+    /// discriminant value it matches: stored CFGs do not record those values.
+    /// Arms sharing a target stay distinct here while `targets` only lists
+    /// the blocks.
+    pub arm_index: Vec<(usize, NodeIndex)>,
 }
 
 impl MatchPattern {
@@ -231,11 +307,14 @@ pub fn detect_if_else_patterns(cfg: &Cfg) -> Vec<IfElsePattern> {
             // Determine which branch is true/false based on edge type
             let (true_branch, false_branch) = order_branches_by_edge_type(cfg, branch, successors[0], successors[1]);
 
+            let shape = if_shape(cfg, branch, true_branch, false_branch, merge_point);
+
             patterns.push(IfElsePattern {
                 condition: branch,
                 true_branch,
                 false_branch,
                 merge_point,
+                shape,
             });
         }
     }
@@ -243,6 +322,170 @@ pub fn detect_if_else_patterns(cfg: &Cfg) -> Vec<IfElsePattern> {
     patterns
 }
 
+/// Detect guard clauses: two-way branches where one arm exits early
+///
+/// An arm exits early when it runs straight (one successor per block) into
+/// a `Return`, `Abort` or `Unreachable` block within `GUARD_MAX_BLOCKS`
+/// blocks, without passing through the other arm. The branch is a guard when
+/// exactly one arm does so, or both do and the guard's run is strictly
+/// shorter; arms that exit at the same distance are a balanced if/else.
+/// Arms may share the function's return block. An `IfOnly` branch is never
+/// a guard, since its short arm is where the other one rejoins.
+///
+/// Returns guards ordered by condition node.
+///
+/// These are exactly the if/else patterns of shape `GuardReturn`.
+pub fn detect_guard_clauses(cfg: &Cfg) -> Vec<GuardClause> {
+    let mut guards: Vec<GuardClause> = detect_if_else_patterns(cfg)
+        .into_iter()
+        .filter(|p| p.shape == IfShape::GuardReturn)
+        .filter_map(|p| {
+            let (guard_on_true, exit, exit_distance) = guard_arm(cfg, p.true_branch, p.false_branch)?;
+            let (guard, continuation) = if guard_on_true {
+                (p.true_branch, p.false_branch)
+            } else {
+                (p.false_branch, p.true_branch)
+            };
+            Some(GuardClause {
+                condition: p.condition,
+                guard,
+                continuation,
+                guard_on_true,
+                exit,
+                exit_distance,
+            })
+        })
+        .collect();
+    guards.sort_by_key(|g| g.condition);
+    guards
+}
+
+/// Which arm of a two-way branch is a guard, with the exit it reaches
+///
+/// Returns `(guard_on_true, exit, exit_distance)`. The single predicate behind
+/// both `IfShape::GuardReturn` and [`detect_guard_clauses`].
+fn guard_arm(cfg: &Cfg, true_branch: NodeIndex, false_branch: NodeIndex) -> Option<(bool, NodeIndex, usize)> {
+    let true_exit = straight_exit(cfg, true_branch, false_branch);
+    let false_exit = straight_exit(cfg, false_branch, true_branch);
+    match (true_exit, false_exit) {
+        (Some((exit, t)), None) => Some((true, exit, t)),
+        (None, Some((exit, f))) => Some((false, exit, f)),
+        (Some((exit, t)), Some((_, f))) if t < f => Some((true, exit, t)),
+        (Some((_, t)), Some((exit, f))) if f < t => Some((false, exit, f)),
+        _ => None,
+    }
+}
+
+/// Exit reached from `start` by straight-line flow, and the blocks walked
+///
+/// The walk must end in a block terminated by `Return`, `Abort` or
+/// `Unreachable`. None if it branches, loops, passes through `other` (the
+/// opposite arm), ends anywhere else, or runs past `GUARD_MAX_BLOCKS`.
+fn straight_exit(cfg: &Cfg, start: NodeIndex, other: NodeIndex) -> Option<(NodeIndex, usize)> {
+    let mut node = start;
+    for blocks in 1..=GUARD_MAX_BLOCKS {
+        let mut successors = cfg.neighbors(node);
+        match (successors.next(), successors.next()) {
+            (None, _) => return is_exit_block(cfg, node).then_some((node, blocks)),
+            (Some(next), None) if next != start && next != other => node = next,
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Detect if/else branches whose arms are lopsided
+///
+/// An arm's size is the number of blocks its head dominates: the blocks
+/// that can only be reached through that arm. A head that is also reached
+/// some other way (the join block of a short-circuit, say) owns no blocks.
+/// A branch is flagged when the larger arm over the smaller one exceeds
+/// `threshold`. `IfOnly` branches are skipped, since their "else" arm is
+/// the rest of the function.
+///
+/// Returns branches ordered by condition node.
+pub fn detect_unbalanced_branches(cfg: &Cfg, threshold: f64) -> Vec<UnbalancedBranch> {
+    let Some(dom_tree) = crate::cfg::DominatorTree::new(cfg) else {
+        return Vec::new();
+    };
+    let arm_blocks = |condition: NodeIndex, head: NodeIndex| {
+        if cfg.neighbors_directed(head, petgraph::Direction::Incoming).any(|pred| pred != condition) {
+            return 0;
+        }
+        let mut count = 0;
+        let mut stack = vec![head];
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend_from_slice(dom_tree.children(node));
+        }
+        count
+    };
+
+    let mut branches: Vec<UnbalancedBranch> = detect_if_else_patterns(cfg)
+        .into_iter()
+        .filter(|p| p.shape != IfShape::IfOnly)
+        .filter_map(|p| {
+            let true_blocks = arm_blocks(p.condition, p.true_branch);
+            let false_blocks = arm_blocks(p.condition, p.false_branch);
+            let ratio = true_blocks.max(false_blocks) as f64 / true_blocks.min(false_blocks).max(1) as f64;
+            (ratio > threshold).then_some(UnbalancedBranch {
+                condition: p.condition,
+                true_branch: p.true_branch,
+                false_branch: p.false_branch,
+                true_blocks,
+                false_blocks,
+                ratio,
+            })
+        })
+        .collect();
+    branches.sort_by_key(|b| b.condition);
+    branches
+}
+
+/// Classify an if/else from how its arms reach each other
+///
+/// An arm that leads into the other arm without going back through the
+/// condition makes an `IfOnly`: the other arm is where the `if` body rejoins.
+/// Otherwise a guard arm (see [`detect_guard_clauses`]) makes a `GuardReturn`,
+/// even when it jumps to a return block the other arm also reaches. The rest
+/// form a `Diamond` if the arms merge, and are `Divergent` if they never do.
+fn if_shape(
+    cfg: &Cfg,
+    condition: NodeIndex,
+    true_branch: NodeIndex,
+    false_branch: NodeIndex,
+    merge_point: Option<NodeIndex>,
+) -> IfShape {
+    if reaches_avoiding(cfg, true_branch, false_branch, condition)
+        || reaches_avoiding(cfg, false_branch, true_branch, condition)
+    {
+        IfShape::IfOnly
+    } else if guard_arm(cfg, true_branch, false_branch).is_some() {
+        IfShape::GuardReturn
+    } else if merge_point.is_some() {
+        IfShape::Diamond
+    } else {
+        IfShape::Divergent
+    }
+}
+
+/// Whether `to` is reachable from `from` without passing through `avoid`
+fn reaches_avoiding(cfg: &Cfg, from: NodeIndex, to: NodeIndex, avoid: NodeIndex) -> bool {
+    let mut visited = HashSet::from([from, avoid]);
+    let mut worklist = vec![from];
+    while let Some(node) = worklist.pop() {
+        for succ in cfg.neighbors(node) {
+            if succ == to {
+                return true;
+            }
+            if visited.insert(succ) {
+                worklist.push(succ);
+            }
+        }
+    }
+    false
+}
+
 /// Order branches as (true, false) based on edge type
 ///
 /// Uses EdgeType to determine which successor is the true branch
@@ -295,17 +538,20 @@ pub fn detect_match_patterns(cfg: &Cfg) -> Vec<MatchPattern> {
                     continue;
                 }
 
-                // Convert BlockIds to NodeIndices
-                let target_indices: Vec<_> = targets
+                // Convert BlockIds to NodeIndices, keeping each arm's position
+                let arm_index: Vec<_> = targets
                     .iter()
-                    .filter_map(|&id| find_node_by_id(cfg, id))
+                    .enumerate()
+                    .filter_map(|(arm, &id)| find_node_by_id(cfg, id).map(|n| (arm, n)))
                     .collect();
+                let target_indices: Vec<_> = arm_index.iter().map(|&(_, n)| n).collect();
 
                 if let Some(otherwise_idx) = find_node_by_id(cfg, *otherwise) {
                     patterns.push(MatchPattern {
                         switch_node: node,
                         targets: target_indices,
                         otherwise: otherwise_idx,
+                        arm_index,
                     });
                 }
             }
@@ -332,12 +578,45 @@ pub fn detect_all_patterns(cfg: &Cfg) -> (Vec<IfElsePattern>, Vec<MatchPattern>)
     )
 }
 
+/// Find all control-flow join points in the CFG
+///
+/// A join point is a block with two or more predecessors. These are the
+/// blocks where SSA construction would place phi nodes, whether they merge
+/// an if/else, the arms of a match, or a loop back edge.
+///
+/// Returns nodes in node index order.
+///
+/// # Example
+/// ```rust,no_run
+/// # use mirage_analyzer::cfg::patterns::find_merge_points;
+/// # use mirage_analyzer::cfg::Cfg;
+/// # let graph: Cfg = unimplemented!();
+/// for join in find_merge_points(&graph) {
+///     println!("join at {:?}", join);
+/// }
+/// ```
+pub fn find_merge_points(cfg: &Cfg) -> Vec<NodeIndex> {
+    cfg.node_indices()
+        .filter(|&n| is_merge_point(cfg, n))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
     use petgraph::graph::DiGraph;
 
+    fn block(id: BlockId, kind: BlockKind, terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        }
+    }
+
     /// Create a diamond pattern CFG (if/else)
     fn create_diamond_cfg() -> Cfg {
         let mut g = DiGraph::new();
@@ -409,6 +688,190 @@ mod tests {
         assert_eq!(pattern.false_branch.index(), 3);
         assert_eq!(pattern.merge_point, Some(NodeIndex::new(4)));
         assert!(pattern.has_else());
+        assert_eq!(pattern.shape, IfShape::Diamond);
+    }
+
+    #[test]
+    fn test_if_shape_if_only() {
+        // 0 -> 1 -> 2 -> 3 (if body), 1 -> 3 (no else), 3 -> 4
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::SwitchInt { targets: vec![2], otherwise: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b3 = g.add_node(block(3, BlockKind::Normal, Terminator::Goto { target: 4 }));
+        let b4 = g.add_node(block(4, BlockKind::Exit, Terminator::Return));
+
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+        g.add_edge(b1, b2, EdgeType::TrueBranch);
+        g.add_edge(b1, b3, EdgeType::FalseBranch);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+        g.add_edge(b3, b4, EdgeType::Fallthrough);
+
+        let patterns = detect_if_else_patterns(&g);
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].shape, IfShape::IfOnly);
+    }
+
+    #[test]
+    fn test_if_shape_guard_return() {
+        // 0 -> 1 -> 2 (early return), 1 -> 3 -> 4 (return)
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::SwitchInt { targets: vec![2], otherwise: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Exit, Terminator::Return));
+        let b3 = g.add_node(block(3, BlockKind::Normal, Terminator::Goto { target: 4 }));
+        let b4 = g.add_node(block(4, BlockKind::Exit, Terminator::Return));
+
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+        g.add_edge(b1, b2, EdgeType::TrueBranch);
+        g.add_edge(b1, b3, EdgeType::FalseBranch);
+        g.add_edge(b3, b4, EdgeType::Fallthrough);
+
+        let patterns = detect_if_else_patterns(&g);
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].merge_point, None);
+        assert_eq!(patterns[0].shape, IfShape::GuardReturn);
+    }
+
+    #[test]
+    fn test_detect_guard_clauses_early_return() {
+        // 0 -> 1 -(true)-> 2 (early return), 1 -(false)-> 3 -> 4 -> 5 (main flow)
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::SwitchInt { targets: vec![2], otherwise: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Exit, Terminator::Return));
+        let b3 = g.add_node(block(3, BlockKind::Normal, Terminator::SwitchInt { targets: vec![4], otherwise: 5 }));
+        let b4 = g.add_node(block(4, BlockKind::Normal, Terminator::Goto { target: 5 }));
+        let b5 = g.add_node(block(5, BlockKind::Exit, Terminator::Return));
+
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+        g.add_edge(b1, b2, EdgeType::TrueBranch);
+        g.add_edge(b1, b3, EdgeType::FalseBranch);
+        g.add_edge(b3, b4, EdgeType::TrueBranch);
+        g.add_edge(b3, b5, EdgeType::FalseBranch);
+        g.add_edge(b4, b5, EdgeType::Fallthrough);
+
+        let guards = detect_guard_clauses(&g);
+        assert_eq!(guards.len(), 1, "The inner if-only is not a guard");
+        let guard = &guards[0];
+        assert_eq!(guard.condition, b1);
+        assert_eq!(guard.guard, b2);
+        assert_eq!(guard.continuation, b3);
+        assert!(guard.guard_on_true);
+        assert_eq!((guard.exit, guard.exit_distance), (b2, 1));
+    }
+
+    #[test]
+    fn test_detect_unbalanced_branches() {
+        // 0 -(true)-> 1 (return), 0 -(false)-> 2 -> 3 -> 4 -> 5 -> 6 (return)
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, Terminator::Return));
+        let long_arm: Vec<NodeIndex> = (2..7)
+            .map(|id| {
+                let terminator = if id == 6 { Terminator::Return } else { Terminator::Goto { target: id + 1 } };
+                g.add_node(block(id, if id == 6 { BlockKind::Exit } else { BlockKind::Normal }, terminator))
+            })
+            .collect();
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, long_arm[0], EdgeType::FalseBranch);
+        for pair in long_arm.windows(2) {
+            g.add_edge(pair[0], pair[1], EdgeType::Fallthrough);
+        }
+
+        let branches = detect_unbalanced_branches(&g, UNBALANCED_DEFAULT_THRESHOLD);
+        assert_eq!(branches.len(), 1);
+        let branch = &branches[0];
+        assert_eq!((branch.condition, branch.true_branch, branch.false_branch), (b0, b1, long_arm[0]));
+        assert_eq!((branch.true_blocks, branch.false_blocks), (1, 5));
+        assert_eq!(branch.ratio, 5.0);
+
+        // The ratio must exceed the threshold, not just reach it
+        assert!(detect_unbalanced_branches(&g, 5.0).is_empty());
+
+        // A diamond with one block per arm is balanced
+        assert!(detect_unbalanced_branches(&create_diamond_cfg(), 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_detect_guard_clauses_skips_balanced_if_else() {
+        // Diamond arms merge into the same return at the same distance
+        assert!(detect_guard_clauses(&create_diamond_cfg()).is_empty());
+    }
+
+    #[test]
+    fn test_guard_shape_agrees_with_guard_clauses() {
+        // 0 -(true)-> 1 -> 4 (shared return), 0 -(false)-> 2 -> {3, 4}, 3 -> 4
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::Goto { target: 4 }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::SwitchInt { targets: vec![3], otherwise: 4 }));
+        let b3 = g.add_node(block(3, BlockKind::Normal, Terminator::Goto { target: 4 }));
+        let b4 = g.add_node(block(4, BlockKind::Exit, Terminator::Return));
+
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b4, EdgeType::Fallthrough);
+        g.add_edge(b2, b3, EdgeType::TrueBranch);
+        g.add_edge(b2, b4, EdgeType::FalseBranch);
+        g.add_edge(b3, b4, EdgeType::Fallthrough);
+
+        // The arms merge at the return block, yet the short one is a guard
+        let patterns = detect_if_else_patterns(&g);
+        let outer = patterns.iter().find(|p| p.condition == b0).unwrap();
+        assert_eq!(outer.merge_point, Some(b4));
+        assert_eq!(outer.shape, IfShape::GuardReturn);
+
+        let guards = detect_guard_clauses(&g);
+        assert_eq!(guards.len(), 1);
+        assert_eq!(guards[0].condition, b0);
+        assert_eq!(guards[0].guard, b1);
+    }
+
+    #[test]
+    fn test_guard_arm_must_end_in_exit_terminator() {
+        // 0 -(true)-> 1 (diverging call, no successors), 0 -(false)-> 2 -> 3 -> 4 (return)
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::Call { target: None, unwind: None }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b3 = g.add_node(block(3, BlockKind::Normal, Terminator::Goto { target: 4 }));
+        let b4 = g.add_node(block(4, BlockKind::Exit, Terminator::Return));
+
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+        g.add_edge(b3, b4, EdgeType::Fallthrough);
+
+        // Only the false arm reaches a Return, and it is the longer one
+        let guards = detect_guard_clauses(&g);
+        assert_eq!(guards.len(), 1);
+        assert_eq!(guards[0].guard, b2);
+        assert_eq!((guards[0].exit, guards[0].exit_distance), (b4, 3));
+    }
+
+    #[test]
+    fn test_if_shape_display_matches_json() {
+        for shape in [IfShape::Diamond, IfShape::IfOnly, IfShape::GuardReturn, IfShape::Divergent] {
+            assert_eq!(serde_json::to_value(shape).unwrap(), shape.to_string());
+        }
+    }
+
+    #[test]
+    fn test_if_shape_divergent() {
+        // 0 -(true)-> 1 (panic), 0 -(false)-> 2 (panic): no merge, no guard
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, Terminator::Abort("panic".to_string())));
+        let b2 = g.add_node(block(2, BlockKind::Exit, Terminator::Abort("panic".to_string())));
+
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+
+        let patterns = detect_if_else_patterns(&g);
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].shape, IfShape::Divergent);
+        assert!(detect_guard_clauses(&g).is_empty());
     }
 
     #[test]
@@ -472,6 +935,25 @@ mod tests {
         assert_eq!(pattern.branch_count(), 3);
     }
 
+    #[test]
+    fn test_match_arm_index_keeps_shared_arms() {
+        // Arms 0 and 1 go to block 1, arm 2 to block 2, the default to block 3
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1, 1, 2], otherwise: 3 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, Terminator::Return));
+        let b2 = g.add_node(block(2, BlockKind::Exit, Terminator::Return));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::TrueBranch);
+        g.add_edge(b0, b3, EdgeType::FalseBranch);
+
+        let patterns = detect_match_patterns(&g);
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].arm_index, vec![(0, b1), (1, b1), (2, b2)]);
+        assert_eq!(patterns[0].targets, vec![b1, b1, b2]);
+        assert_eq!(patterns[0].otherwise, b3);
+    }
+
     #[test]
     fn test_classify_multiway() {
         let mut g = DiGraph::new();
@@ -644,4 +1126,27 @@ mod tests {
         assert!(detect_if_else_patterns(&g).is_empty());
         assert!(detect_match_patterns(&g).is_empty());
     }
+
+    #[test]
+    fn test_find_merge_points_diamond() {
+        let cfg = create_diamond_cfg();
+        assert_eq!(find_merge_points(&cfg), vec![NodeIndex::new(4)]);
+    }
+
+    #[test]
+    fn test_find_merge_points_loop_header() {
+        // 0 -> 1 -> 2 -> 1 (back edge), 1 -> 3
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::SwitchInt { targets: vec![2], otherwise: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 1 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+        g.add_edge(b1, b2, EdgeType::TrueBranch);
+        g.add_edge(b1, b3, EdgeType::FalseBranch);
+        g.add_edge(b2, b1, EdgeType::LoopBack);
+
+        assert_eq!(find_merge_points(&g), vec![b1]);
+    }
 }
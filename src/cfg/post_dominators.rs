@@ -234,6 +234,19 @@ pub fn immediate_post_dominator_id(tree: &PostDominatorTree, block_id: BlockId,
     Some(cfg[ipdom_node].id)
 }
 
+/// Blocks guaranteed to run after a block, in execution order
+///
+/// These are the block's strict post-dominators: once `block_id` executes,
+/// every path to the exit passes through each of them, nearest first. It is
+/// the dual of must-pass-through and answers where Drop or finally-style
+/// cleanup is certain to happen. Returns None if the block is not in the CFG.
+///
+/// Like the tree itself, this only considers paths to the primary exit.
+pub fn guaranteed_after(tree: &PostDominatorTree, cfg: &Cfg, block_id: BlockId) -> Option<Vec<BlockId>> {
+    let node = node_from_id(cfg, block_id)?;
+    Some(tree.post_dominators(node).skip(1).map(|n| cfg[n].id).collect())
+}
+
 /// Helper: find NodeIndex from BlockId
 fn node_from_id(cfg: &Cfg, block_id: BlockId) -> Option<NodeIndex> {
     cfg.node_indices()
@@ -479,4 +492,42 @@ mod tests {
         assert_eq!(reversed.node_count(), cfg.node_count());
         assert_eq!(reversed.edge_count(), cfg.edge_count());
     }
+
+    #[test]
+    fn test_guaranteed_after_diamond() {
+        let cfg = create_diamond_cfg();
+        let post_dom_tree = PostDominatorTree::new(&cfg).expect("CFG has exit");
+
+        // Either branch only guarantees the join; so does the entry
+        assert_eq!(guaranteed_after(&post_dom_tree, &cfg, 0), Some(vec![3]));
+        assert_eq!(guaranteed_after(&post_dom_tree, &cfg, 1), Some(vec![3]));
+        assert_eq!(guaranteed_after(&post_dom_tree, &cfg, 2), Some(vec![3]));
+        assert_eq!(guaranteed_after(&post_dom_tree, &cfg, 3), Some(vec![]));
+        assert_eq!(guaranteed_after(&post_dom_tree, &cfg, 9), None);
+
+        // Every block listed post-dominates the target
+        for block in guaranteed_after(&post_dom_tree, &cfg, 1).unwrap() {
+            assert!(post_dom_tree.strictly_post_dominates(NodeIndex::new(block), NodeIndex::new(1)));
+        }
+    }
+
+    #[test]
+    fn test_guaranteed_after_linear_in_execution_order() {
+        // 0 -> 1 -> 2 -> 3
+        let mut g = DiGraph::new();
+        let nodes: Vec<NodeIndex> = (0..4).map(|id| g.add_node(BasicBlock {
+            id,
+            kind: if id == 3 { BlockKind::Exit } else { BlockKind::Normal },
+            statements: vec![],
+            terminator: if id == 3 { Terminator::Return } else { Terminator::Goto { target: id + 1 } },
+            source_location: None,
+        })).collect();
+        for pair in nodes.windows(2) {
+            g.add_edge(pair[0], pair[1], EdgeType::Fallthrough);
+        }
+
+        let post_dom_tree = PostDominatorTree::new(&g).expect("CFG has exit");
+        assert_eq!(guaranteed_after(&post_dom_tree, &g, 0), Some(vec![1, 2, 3]));
+        assert_eq!(guaranteed_after(&post_dom_tree, &g, 2), Some(vec![3]));
+    }
 }
@@ -82,6 +82,38 @@ pub fn unreachable_block_ids(cfg: &Cfg) -> Vec<BlockId> {
         .collect()
 }
 
+/// Split the CFG into weakly connected components
+///
+/// Edge direction is ignored, so a component is a set of blocks linked by
+/// any chain of edges. A well-formed CFG is a single component; more than
+/// one means blocks were indexed that nothing links to the function body.
+/// Each component is sorted, and components are ordered by their first node,
+/// so the one holding the entry comes first.
+pub fn components(cfg: &Cfg) -> Vec<Vec<NodeIndex>> {
+    let mut seen = HashSet::new();
+    let mut components = Vec::new();
+
+    for start in cfg.node_indices() {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut component = vec![start];
+        let mut worklist = vec![start];
+        while let Some(node) = worklist.pop() {
+            for neighbor in cfg.neighbors_undirected(node) {
+                if seen.insert(neighbor) {
+                    component.push(neighbor);
+                    worklist.push(neighbor);
+                }
+            }
+        }
+        component.sort_unstable();
+        components.push(component);
+    }
+
+    components
+}
+
 /// Check if node `from` can reach node `to`
 ///
 /// Returns true if there exists any path from `from` to `to`.
@@ -194,6 +226,16 @@ pub struct BlockImpact {
     pub has_cycles: bool,
 }
 
+/// Direction of a block impact traversal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImpactDirection {
+    /// Blocks the source can reach (successors)
+    Forward,
+    /// Blocks that can reach the source (predecessors)
+    Backward,
+}
+
 /// Find all blocks reachable from a specific starting block
 ///
 /// Unlike `find_reachable` which starts from entry, this starts from any block.
@@ -222,8 +264,62 @@ pub fn find_reachable_from_block(
     start_block_id: BlockId,
     max_depth: Option<usize>,
 ) -> BlockImpact {
+    block_impact(cfg, start_block_id, max_depth, ImpactDirection::Forward, None)
+}
+
+/// Find the blocks a branch affects before control flow reconverges
+///
+/// Walks forward like [`find_reachable_from_block`] but never enters the
+/// source's immediate post-dominator, so `reachable_blocks` holds only the
+/// blocks strictly between the two. Also returns the post-dominator's
+/// BlockId. When the source has none (it is the exit, or no exit is
+/// reachable) the walk is not bounded.
+pub fn find_reachable_until_convergence(
+    cfg: &Cfg,
+    start_block_id: BlockId,
+    max_depth: Option<usize>,
+) -> (BlockImpact, Option<BlockId>) {
+    use crate::cfg::post_dominators::PostDominatorTree;
+
+    let convergence = cfg
+        .node_indices()
+        .find(|&n| cfg[n].id == start_block_id)
+        .and_then(|start| PostDominatorTree::new(cfg)?.immediate_post_dominator(start));
+    let impact = block_impact(cfg, start_block_id, max_depth, ImpactDirection::Forward, convergence);
+    (impact, convergence.map(|n| cfg[n].id))
+}
+
+/// Find all blocks that can reach a specific block
+///
+/// The reverse of `find_reachable_from_block`: walks predecessor edges to
+/// answer "what leads to this block?" rather than "what does it affect?".
+/// `reachable_blocks` holds the predecessors found.
+pub fn find_blocks_reaching(
+    cfg: &Cfg,
+    target_block_id: BlockId,
+    max_depth: Option<usize>,
+) -> BlockImpact {
+    block_impact(cfg, target_block_id, max_depth, ImpactDirection::Backward, None)
+}
+
+/// BFS from a block along successor or predecessor edges
+///
+/// `boundary`, if set, is never entered.
+fn block_impact(
+    cfg: &Cfg,
+    start_block_id: BlockId,
+    max_depth: Option<usize>,
+    direction: ImpactDirection,
+    boundary: Option<NodeIndex>,
+) -> BlockImpact {
+    use petgraph::Direction;
     use std::collections::{HashSet, VecDeque};
 
+    let edge_direction = match direction {
+        ImpactDirection::Forward => Direction::Outgoing,
+        ImpactDirection::Backward => Direction::Incoming,
+    };
+
     // Find the NodeIndex for the start BlockId
     let start_node = match cfg.node_indices().find(|&n| cfg[n].id == start_block_id) {
         Some(n) => n,
@@ -264,7 +360,10 @@ pub fn find_reachable_from_block(
         }
 
         // Explore neighbors
-        for neighbor in cfg.neighbors(node) {
+        for neighbor in cfg.neighbors_directed(node, edge_direction) {
+            if Some(neighbor) == boundary {
+                continue;
+            }
             if visited.contains(&neighbor) {
                 // We've seen this node before - indicates a cycle
                 has_cycles = true;
@@ -438,6 +537,24 @@ mod tests {
         assert!(!is_reachable_from_entry(&cfg, b3));
     }
 
+    #[test]
+    fn test_components_disconnected() {
+        let mut cfg = create_test_cfg_with_unreachable();
+        assert_eq!(
+            components(&cfg),
+            vec![
+                vec![NodeIndex::new(0), NodeIndex::new(1), NodeIndex::new(2)],
+                vec![NodeIndex::new(3)],
+            ]
+        );
+
+        // An edge into the entry's component joins them, whatever its direction
+        cfg.add_edge(NodeIndex::new(3), NodeIndex::new(2), EdgeType::Fallthrough);
+        assert_eq!(components(&cfg).len(), 1);
+
+        assert!(components(&Cfg::new()).is_empty());
+    }
+
     #[test]
     fn test_empty_cfg() {
         let cfg: Cfg = DiGraph::new();
@@ -730,6 +847,117 @@ mod tests {
         assert!(impact.reachable_blocks.contains(&3));
     }
 
+    #[test]
+    fn test_find_reachable_until_convergence_diamond() {
+        let mut g = DiGraph::new();
+
+        // Diamond: 0 -> 1, 0 -> 2, 1 -> 3, 2 -> 3
+        let b0 = g.add_node(BasicBlock {
+            id: 0,
+            kind: BlockKind::Entry,
+            statements: vec![],
+            terminator: Terminator::SwitchInt { targets: vec![1], otherwise: 2 },
+            source_location: None,
+        });
+
+        let b1 = g.add_node(BasicBlock {
+            id: 1,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Goto { target: 3 },
+            source_location: None,
+        });
+
+        let b2 = g.add_node(BasicBlock {
+            id: 2,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Goto { target: 3 },
+            source_location: None,
+        });
+
+        let b3 = g.add_node(BasicBlock {
+            id: 3,
+            kind: BlockKind::Exit,
+            statements: vec![],
+            terminator: Terminator::Return,
+            source_location: None,
+        });
+
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b3, EdgeType::Fallthrough);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+
+        // The branch's influence ends where the arms merge
+        let (impact, convergence) = find_reachable_until_convergence(&g, 0, None);
+        assert_eq!(convergence, Some(3));
+        let mut blocks = impact.reachable_blocks.clone();
+        blocks.sort_unstable();
+        assert_eq!(blocks, vec![1, 2]);
+        assert_eq!(impact.reachable_count, 2);
+
+        // The exit has no post-dominator, so nothing bounds the walk
+        let (impact, convergence) = find_reachable_until_convergence(&g, 3, None);
+        assert_eq!(convergence, None);
+        assert_eq!(impact.reachable_count, 0);
+    }
+
+    #[test]
+    fn test_find_blocks_reaching_diamond() {
+        let mut g = DiGraph::new();
+
+        // Diamond: 0 -> 1, 0 -> 2, 1 -> 3, 2 -> 3
+        let b0 = g.add_node(BasicBlock {
+            id: 0,
+            kind: BlockKind::Entry,
+            statements: vec![],
+            terminator: Terminator::SwitchInt { targets: vec![1], otherwise: 2 },
+            source_location: None,
+        });
+
+        let b1 = g.add_node(BasicBlock {
+            id: 1,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Goto { target: 3 },
+            source_location: None,
+        });
+
+        let b2 = g.add_node(BasicBlock {
+            id: 2,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Goto { target: 3 },
+            source_location: None,
+        });
+
+        let b3 = g.add_node(BasicBlock {
+            id: 3,
+            kind: BlockKind::Exit,
+            statements: vec![],
+            terminator: Terminator::Return,
+            source_location: None,
+        });
+
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b3, EdgeType::Fallthrough);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+
+        // Reverse from the merge block reaches both branches and the entry
+        let impact = find_blocks_reaching(&g, 3, None);
+        assert_eq!(impact.source_block_id, 3);
+        assert_eq!(impact.reachable_count, 3);
+        assert!(impact.reachable_blocks.contains(&0));
+        assert!(impact.reachable_blocks.contains(&1));
+        assert!(impact.reachable_blocks.contains(&2));
+
+        // Nothing reaches the entry
+        let impact = find_blocks_reaching(&g, 0, None);
+        assert_eq!(impact.reachable_count, 0);
+    }
+
     #[test]
     fn test_find_reachable_from_block_max_depth() {
         let mut g = DiGraph::new();
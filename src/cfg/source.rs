@@ -1,7 +1,10 @@
 //! Source location mapping for CFG blocks
 
+use super::{BlockId, Cfg};
+use petgraph::graph::NodeIndex;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Source location for a CFG block or statement
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -127,6 +130,13 @@ impl SourceLocation {
         }
     }
 
+    /// Check if this location spans the given 1-indexed line
+    ///
+    /// Always false when line information is unavailable (start_line == 0).
+    pub fn covers_line(&self, line: usize) -> bool {
+        self.start_line > 0 && self.start_line <= line && line <= self.end_line.max(self.start_line)
+    }
+
     /// Get a human-readable description (fallback to byte ranges if line/column unavailable)
     pub fn display_or_bytes(&self) -> String {
         if self.start_line > 0 {
@@ -142,6 +152,191 @@ impl SourceLocation {
     }
 }
 
+/// Byte offset to line/column index over source files
+///
+/// Files are read and indexed the first time a location in them is
+/// resolved, then kept for the life of the map, so one map should be shared
+/// across a command run. Relative paths are resolved against the current
+/// directory. A file that cannot be read is remembered as missing and its
+/// locations are left as they are.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: HashMap<PathBuf, Option<LineIndex>>,
+}
+
+/// One line of a [`SourceMap::snippet`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetLine {
+    /// Line number (1-indexed)
+    pub line: usize,
+    /// Line text, trailing whitespace removed
+    pub text: String,
+    /// False for context lines outside the location
+    pub covered: bool,
+}
+
+/// Line start offsets of one file
+#[derive(Debug)]
+struct LineIndex {
+    source: String,
+    /// Byte offset at which each line starts; `line_starts[0] == 0`
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(source: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { source, line_starts }
+    }
+
+    /// 1-indexed line and column (in UTF-8 characters) of a byte offset
+    ///
+    /// Offsets past the end of the file resolve to the end of the file.
+    fn line_column(&self, byte_offset: usize) -> (usize, usize) {
+        let mut offset = byte_offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line - 1];
+        let column = self.source[line_start..offset].chars().count() + 1;
+        (line, column)
+    }
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Line and column of a byte offset in a file, or None if the file cannot be read
+    pub fn line_column(&mut self, file_path: &Path, byte_offset: usize) -> Option<(usize, usize)> {
+        self.index(file_path).map(|index| index.line_column(byte_offset))
+    }
+
+    /// Fill in the line/column fields of a byte-only location
+    ///
+    /// Locations that already carry line information are left untouched.
+    /// Returns true if the location has line information afterwards.
+    pub fn resolve(&mut self, location: &mut SourceLocation) -> bool {
+        if location.start_line > 0 {
+            return true;
+        }
+        let Some(index) = self.index(&location.file_path) else {
+            return false;
+        };
+
+        let (start_line, start_column) = index.line_column(location.byte_start);
+        let (end_line, end_column) = index.line_column(location.byte_end);
+        location.start_line = start_line;
+        location.start_column = start_column;
+        location.end_line = end_line;
+        location.end_column = end_column;
+        true
+    }
+
+    /// Resolve the source location of every block in a CFG (see [`SourceMap::resolve`])
+    pub fn resolve_cfg(&mut self, cfg: &mut Cfg) {
+        for block in cfg.node_weights_mut() {
+            if let Some(ref mut location) = block.source_location {
+                self.resolve(location);
+            }
+        }
+    }
+
+    /// The lines a location covers, with `context` extra lines on each side
+    ///
+    /// None if the location has no line information or its file cannot be read.
+    pub fn snippet(&mut self, location: &SourceLocation, context: usize) -> Option<Vec<SnippetLine>> {
+        if location.start_line == 0 {
+            return None;
+        }
+        let index = self.index(&location.file_path)?;
+
+        let end_line = location.end_line.max(location.start_line);
+        let first = location.start_line.saturating_sub(context).max(1);
+        // A trailing newline does not start another line
+        let line_count = index.line_starts.len() - usize::from(index.source.ends_with('\n'));
+        let last = (end_line + context).min(line_count);
+        let lines = (first..=last)
+            .map(|line| {
+                let start = index.line_starts[line - 1];
+                let end = index.line_starts.get(line).copied().unwrap_or(index.source.len());
+                SnippetLine {
+                    line,
+                    text: index.source[start..end].trim_end().to_string(),
+                    covered: (location.start_line..=end_line).contains(&line),
+                }
+            })
+            .collect();
+        Some(lines)
+    }
+
+    fn index(&mut self, file_path: &Path) -> Option<&LineIndex> {
+        self.files
+            .entry(file_path.to_path_buf())
+            .or_insert_with(|| std::fs::read_to_string(file_path).ok().map(LineIndex::new))
+            .as_ref()
+    }
+}
+
+/// One location per source file covering a path's blocks
+///
+/// Files are listed in the order the path first enters them; inlined or
+/// inter-procedural blocks can put a path in several. Each span runs from
+/// the earliest start to the latest end of the path's blocks in that file.
+/// Blocks without line information only widen the byte range, so a file
+/// whose blocks could not be resolved gets a span with `start_line == 0`.
+pub fn path_source_spans(cfg: &Cfg, blocks: &[BlockId]) -> Vec<SourceLocation> {
+    let mut spans: Vec<SourceLocation> = Vec::new();
+    for &block_id in blocks {
+        let Some(location) = cfg.node_weights()
+            .find(|b| b.id == block_id)
+            .and_then(|b| b.source_location.as_ref())
+        else {
+            continue;
+        };
+
+        let Some(span) = spans.iter_mut().find(|s| s.file_path == location.file_path) else {
+            spans.push(location.clone());
+            continue;
+        };
+        span.byte_start = span.byte_start.min(location.byte_start);
+        span.byte_end = span.byte_end.max(location.byte_end);
+        if location.start_line == 0 {
+            continue;
+        }
+        if span.start_line == 0 || (location.start_line, location.start_column) < (span.start_line, span.start_column) {
+            span.start_line = location.start_line;
+            span.start_column = location.start_column;
+        }
+        if (location.end_line, location.end_column) > (span.end_line, span.end_column) {
+            span.end_line = location.end_line;
+            span.end_column = location.end_column;
+        }
+    }
+    spans
+}
+
+/// Check whether any block in the CFG carries line information
+pub fn has_line_info(cfg: &Cfg) -> bool {
+    cfg.node_weights()
+        .any(|block| block.source_location.as_ref().is_some_and(|loc| loc.start_line > 0))
+}
+
+/// Find the blocks whose source location covers a 1-indexed line
+///
+/// Returns blocks in node order. Empty when no block spans the line or
+/// when the CFG has no line information (see [`has_line_info`]).
+pub fn blocks_at_line(cfg: &Cfg, line: usize) -> Vec<NodeIndex> {
+    cfg.node_indices()
+        .filter(|&n| cfg[n].source_location.as_ref().is_some_and(|loc| loc.covers_line(line)))
+        .collect()
+}
+
 /// Convert byte offset to line and column (1-indexed)
 fn byte_to_line_column(source: &str, byte_offset: usize) -> (usize, usize) {
     let mut line = 1;
@@ -404,4 +599,147 @@ mod tests {
 
         assert_eq!(loc.display_or_bytes(), "test.rs:bytes100-200");
     }
+
+    #[test]
+    fn test_blocks_at_line() {
+        use crate::cfg::{BasicBlock, BlockKind, Terminator};
+        use petgraph::graph::DiGraph;
+
+        let block = |id, location| BasicBlock {
+            id,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Return,
+            source_location: location,
+        };
+
+        let mut cfg: Cfg = DiGraph::new();
+        let b0 = cfg.add_node(block(0, Some(SourceLocation::new("lib.rs", 0, 40, 1, 1, 3, 2))));
+        let b1 = cfg.add_node(block(1, Some(SourceLocation::new("lib.rs", 20, 30, 2, 5, 2, 15))));
+        cfg.add_node(block(2, None));
+
+        assert!(has_line_info(&cfg));
+        assert_eq!(blocks_at_line(&cfg, 1), vec![b0]);
+        assert_eq!(blocks_at_line(&cfg, 2), vec![b0, b1]);
+        assert!(blocks_at_line(&cfg, 4).is_empty());
+
+        // Byte-only locations carry no line information
+        let mut bytes_only: Cfg = DiGraph::new();
+        bytes_only.add_node(block(0, Some(SourceLocation::from_bytes_with_source("lib.rs", None, 0, 10))));
+        assert!(!has_line_info(&bytes_only));
+        assert!(blocks_at_line(&bytes_only, 0).is_empty());
+    }
+
+    #[test]
+    fn test_source_map_resolves_byte_ranges() {
+        // Line 1 starts at byte 0, line 2 at byte 12, line 3 at byte 32
+        let source = "fn main() {\n    let s = \"é\"; s\n}\n";
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.rs");
+        std::fs::write(&file, source).unwrap();
+
+        let mut map = SourceMap::new();
+        assert_eq!(map.line_column(&file, 0), Some((1, 1)));
+        assert_eq!(map.line_column(&file, 3), Some((1, 4)));
+        assert_eq!(map.line_column(&file, 12), Some((2, 1)));
+        // "é" is two bytes: the `;` after it at byte 28 is column 16
+        assert_eq!(map.line_column(&file, 28), Some((2, 16)));
+        assert_eq!(map.line_column(&file, 32), Some((3, 1)));
+        assert_eq!(map.line_column(&file, 1000), Some((4, 1)), "Past the end clamps to EOF");
+
+        // Agrees with the unindexed conversion
+        for offset in [0, 3, 12, 28, 32] {
+            assert_eq!(map.line_column(&file, offset), Some(byte_to_line_column(source, offset)));
+        }
+
+        let mut location = SourceLocation::from_bytes_with_source(&file, None, 16, 33);
+        assert!(map.resolve(&mut location));
+        assert_eq!((location.start_line, location.start_column), (2, 5));
+        assert_eq!((location.end_line, location.end_column), (3, 2));
+
+        // Existing line information is kept as is
+        let mut indexed = SourceLocation::new(&file, 0, 5, 7, 7, 7, 12);
+        assert!(map.resolve(&mut indexed));
+        assert_eq!(indexed.start_line, 7);
+
+        // The file is read once and then served from the cache
+        std::fs::remove_file(&file).unwrap();
+        assert_eq!(map.line_column(&file, 12), Some((2, 1)));
+
+        let mut missing = SourceLocation::from_bytes_with_source(dir.path().join("gone.rs"), None, 0, 4);
+        assert!(!map.resolve(&mut missing));
+        assert_eq!(missing.start_line, 0);
+    }
+
+    #[test]
+    fn test_source_map_snippet() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "fn f() {\n    return;  \n    dead();\n}\n").unwrap();
+
+        let mut map = SourceMap::new();
+        let location = SourceLocation::new(&file, 0, 0, 3, 5, 3, 12);
+        let snippet = map.snippet(&location, 1).unwrap();
+        assert_eq!(
+            snippet,
+            vec![
+                SnippetLine { line: 2, text: "    return;".to_string(), covered: false },
+                SnippetLine { line: 3, text: "    dead();".to_string(), covered: true },
+                SnippetLine { line: 4, text: "}".to_string(), covered: false },
+            ]
+        );
+
+        // Context is clipped to the file
+        let snippet = map.snippet(&SourceLocation::new(&file, 0, 0, 1, 1, 1, 8), 5).unwrap();
+        assert_eq!(snippet.first().map(|l| l.line), Some(1));
+        assert_eq!(snippet.last().map(|l| l.line), Some(4));
+
+        let gone = SourceLocation::new(dir.path().join("gone.rs"), 0, 0, 1, 1, 1, 8);
+        assert!(map.snippet(&gone, 1).is_none());
+        assert!(map.snippet(&SourceLocation::from_bytes_with_source(&file, None, 0, 4), 1).is_none());
+    }
+
+    #[test]
+    fn test_path_source_spans_with_context() {
+        use crate::cfg::{BasicBlock, BlockKind, Terminator};
+        use petgraph::graph::DiGraph;
+
+        let dir = tempfile::tempdir().unwrap();
+        let lib = dir.path().join("lib.rs");
+        std::fs::write(&lib, "// header\nfn f(x: bool) {\n    if x {\n        a();\n    }\n    b();\n}\n// footer\n").unwrap();
+        let helper = dir.path().join("helper.rs");
+
+        let block = |id, location| BasicBlock {
+            id,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Return,
+            source_location: location,
+        };
+        let mut cfg: Cfg = DiGraph::new();
+        cfg.add_node(block(0, Some(SourceLocation::new(&lib, 0, 0, 3, 5, 3, 10))));
+        cfg.add_node(block(1, Some(SourceLocation::new(&helper, 0, 0, 10, 1, 12, 2))));
+        cfg.add_node(block(2, Some(SourceLocation::new(&lib, 0, 0, 6, 5, 6, 9))));
+        cfg.add_node(block(3, None));
+
+        // Path 0 -> 1 -> 2 -> 3 covers lib.rs lines 3-6 and an inlined helper
+        let spans = path_source_spans(&cfg, &[0, 1, 2, 3]);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].file_path, lib);
+        assert_eq!((spans[0].start_line, spans[0].end_line), (3, 6));
+        assert_eq!(spans[1].file_path, helper);
+        assert_eq!((spans[1].start_line, spans[1].end_line), (10, 12));
+
+        // One line of context on each side: lines 2-7, with 3-6 covered
+        let mut map = SourceMap::new();
+        let snippet = map.snippet(&spans[0], 1).unwrap();
+        assert_eq!(snippet.iter().map(|l| l.line).collect::<Vec<_>>(), vec![2, 3, 4, 5, 6, 7]);
+        assert_eq!(snippet[0].text, "fn f(x: bool) {");
+        assert!(!snippet[0].covered && !snippet[5].covered);
+        assert!(snippet[1..5].iter().all(|l| l.covered));
+
+        // The helper file does not exist, so it has no snippet
+        assert!(map.snippet(&spans[1], 1).is_none());
+        assert!(path_source_spans(&cfg, &[3]).is_empty());
+    }
 }
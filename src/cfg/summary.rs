@@ -51,6 +51,7 @@ pub fn describe_block(cfg: &Cfg, block_id: BlockId) -> String {
     let kind_desc = match block.kind {
         BlockKind::Entry => "entry",
         BlockKind::Exit => "exit",
+        BlockKind::Panic => "panic",
         BlockKind::Normal => "",
     };
 
@@ -270,4 +271,18 @@ mod tests {
 
         assert!(summary.contains("Unreachable"));
     }
+
+    #[test]
+    fn test_describe_block_panic() {
+        let mut cfg: Cfg = DiGraph::new();
+        cfg.add_node(BasicBlock {
+            id: 0,
+            kind: BlockKind::Panic,
+            statements: vec![],
+            terminator: Terminator::Unreachable,
+            source_location: None,
+        });
+
+        assert_eq!(describe_block(&cfg, 0), "panic(unreachable)");
+    }
 }
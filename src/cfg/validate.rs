@@ -0,0 +1,141 @@
+//! Structural sanity checks for stored CFGs
+//!
+//! MIR lowering always produces one entry block from which every other block
+//! hangs. A CFG that breaks that shape almost always comes from an indexing
+//! problem (blocks written for the wrong function, edges dropped on the way
+//! into the database), and every analysis built on top of it is then wrong
+//! in quiet ways. These checks flag such functions so the index can be
+//! rebuilt before anyone trusts their paths.
+
+use crate::cfg::analysis::{find_entry, find_entry_blocks, find_exits};
+use crate::cfg::reachability::{components, find_reachable};
+use crate::cfg::{BlockId, Cfg};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Which structural check a CFG failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    /// More than one block has no predecessors
+    MultipleEntries,
+    /// An exit block cannot be reached from the entry
+    UnreachableExit,
+    /// A group of blocks shares no edge with the entry's component
+    DisconnectedComponent,
+}
+
+/// One failed check, with the blocks it concerns
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    /// Entry-like blocks, the unreachable exit, or the detached component
+    pub blocks: Vec<BlockId>,
+}
+
+/// Run every structural check on one CFG
+///
+/// Returns violations in check order: entries, exits, then components.
+/// An empty CFG has nothing to check and yields no violations.
+pub fn validate_cfg(cfg: &Cfg) -> Vec<Violation> {
+    let Some(entry) = find_entry(cfg) else {
+        return Vec::new();
+    };
+    let mut violations = Vec::new();
+
+    let entries = find_entry_blocks(cfg);
+    if entries.len() > 1 {
+        violations.push(Violation {
+            kind: ViolationKind::MultipleEntries,
+            blocks: entries.iter().map(|&n| cfg[n].id).collect(),
+        });
+    }
+
+    let reachable: HashSet<_> = find_reachable(cfg).into_iter().collect();
+    for exit in find_exits(cfg) {
+        if !reachable.contains(&exit) {
+            violations.push(Violation {
+                kind: ViolationKind::UnreachableExit,
+                blocks: vec![cfg[exit].id],
+            });
+        }
+    }
+
+    for component in components(cfg) {
+        if !component.contains(&entry) {
+            violations.push(Violation {
+                kind: ViolationKind::DisconnectedComponent,
+                blocks: component.iter().map(|&n| cfg[n].id).collect(),
+            });
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
+    use petgraph::graph::DiGraph;
+
+    fn block(id: BlockId, kind: BlockKind, terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        }
+    }
+
+    #[test]
+    fn test_well_formed_cfg_is_valid() {
+        let mut g: Cfg = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+
+        assert!(validate_cfg(&g).is_empty());
+        assert!(validate_cfg(&Cfg::new()).is_empty());
+    }
+
+    #[test]
+    fn test_disconnected_cfg() {
+        // 0 -> 1 (return), plus a detached 2 -> 3 (return)
+        let mut g: Cfg = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, Terminator::Return));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+
+        assert_eq!(
+            validate_cfg(&g),
+            vec![
+                Violation { kind: ViolationKind::MultipleEntries, blocks: vec![0, 2] },
+                Violation { kind: ViolationKind::UnreachableExit, blocks: vec![3] },
+                Violation { kind: ViolationKind::DisconnectedComponent, blocks: vec![2, 3] },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_orphan_block_joined_to_entry_component() {
+        // 0 -> 1 -> 3; 2 only feeds into 1, so it is connected but a second entry
+        let mut g: Cfg = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 1 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::Fallthrough);
+        g.add_edge(b2, b1, EdgeType::Fallthrough);
+        g.add_edge(b1, b3, EdgeType::Fallthrough);
+
+        let violations = validate_cfg(&g);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, ViolationKind::MultipleEntries);
+        assert_eq!(violations[0].blocks, vec![0, 2]);
+    }
+}
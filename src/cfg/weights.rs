@@ -0,0 +1,195 @@
+//! Edge weights for branch probability estimation
+//!
+//! Assigns each CFG edge a probability based on how many discriminant values
+//! of a `SwitchInt` map to its target. A switch with three values routed to
+//! block 1 and only the `otherwise` arm routed to block 2 gives the edge to
+//! block 1 weight 0.75 and the edge to block 2 weight 0.25.
+//!
+//! Blocks without switch metadata (or with a terminator that has no value
+//! mapping, such as `Call`) fall back to uniform weights across their
+//! distinct successors, so absent weights default to uniform.
+
+use crate::cfg::{BlockId, Cfg, Path, Terminator};
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet};
+
+/// Probability of each edge, keyed by `(from_block, to_block)`
+pub type EdgeWeights = HashMap<(BlockId, BlockId), f64>;
+
+/// Compute a probability for every edge in the CFG
+///
+/// For `SwitchInt` terminators each entry in `targets` counts as one
+/// discriminant value, and `otherwise` counts as one more. All other blocks
+/// split probability uniformly among their distinct successors.
+pub fn compute_edge_weights(cfg: &Cfg) -> EdgeWeights {
+    let mut weights = EdgeWeights::new();
+
+    for node in cfg.node_indices() {
+        let block = &cfg[node];
+        let successors: HashSet<BlockId> = cfg
+            .neighbors_directed(node, Direction::Outgoing)
+            .map(|n| cfg[n].id)
+            .collect();
+
+        if successors.is_empty() {
+            continue;
+        }
+
+        if let Terminator::SwitchInt { targets, otherwise } = &block.terminator {
+            let mut counts: HashMap<BlockId, usize> = HashMap::new();
+            for &target in targets.iter().chain(std::iter::once(otherwise)) {
+                *counts.entry(target).or_insert(0) += 1;
+            }
+            let total = targets.len() + 1;
+
+            // Only trust the switch metadata if it describes the actual edges
+            if counts.keys().all(|t| successors.contains(t)) && counts.len() == successors.len() {
+                for (target, count) in counts {
+                    weights.insert((block.id, target), count as f64 / total as f64);
+                }
+                continue;
+            }
+        }
+
+        let uniform = 1.0 / successors.len() as f64;
+        for target in successors {
+            weights.insert((block.id, target), uniform);
+        }
+    }
+
+    weights
+}
+
+/// Split each block's probability evenly among its distinct successors
+///
+/// The weights of a CFG without switch metadata; used where a stored weight
+/// is missing.
+pub fn uniform_edge_weights(cfg: &Cfg) -> EdgeWeights {
+    let mut weights = EdgeWeights::new();
+
+    for node in cfg.node_indices() {
+        let successors: HashSet<BlockId> = cfg
+            .neighbors_directed(node, Direction::Outgoing)
+            .map(|n| cfg[n].id)
+            .collect();
+        let uniform = 1.0 / successors.len() as f64;
+        for target in successors {
+            weights.insert((cfg[node].id, target), uniform);
+        }
+    }
+
+    weights
+}
+
+/// Probability of following a path, as the product of its edge weights
+///
+/// Edges missing from `weights` contribute a factor of 1.0, so a path
+/// through a CFG with no recorded weights has probability 1.0.
+pub fn path_probability(path: &Path, weights: &EdgeWeights) -> f64 {
+    path.blocks
+        .windows(2)
+        .map(|pair| weights.get(&(pair[0], pair[1])).copied().unwrap_or(1.0))
+        .product()
+}
+
+/// Sort paths so the most likely path comes first
+///
+/// The sort is stable: paths with equal probability keep their
+/// enumeration order.
+pub fn sort_by_likelihood(paths: &mut [Path], weights: &EdgeWeights) {
+    paths.sort_by(|a, b| {
+        path_probability(b, weights)
+            .partial_cmp(&path_probability(a, weights))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{BasicBlock, BlockKind, EdgeType, PathKind};
+    use petgraph::graph::DiGraph;
+
+    fn block(id: BlockId, kind: BlockKind, terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        }
+    }
+
+    /// Switch with three values going to block 1 and one (otherwise) to block 2
+    fn create_skewed_switch_cfg() -> Cfg {
+        let mut g = DiGraph::new();
+
+        let b0 = g.add_node(block(
+            0,
+            BlockKind::Entry,
+            Terminator::SwitchInt { targets: vec![1, 1, 1], otherwise: 2 },
+        ));
+        let b1 = g.add_node(block(1, BlockKind::Exit, Terminator::Return));
+        let b2 = g.add_node(block(2, BlockKind::Exit, Terminator::Return));
+
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+
+        g
+    }
+
+    #[test]
+    fn test_switch_weights_from_value_counts() {
+        let cfg = create_skewed_switch_cfg();
+        let weights = compute_edge_weights(&cfg);
+
+        assert_eq!(weights.get(&(0, 1)), Some(&0.75));
+        assert_eq!(weights.get(&(0, 2)), Some(&0.25));
+    }
+
+    #[test]
+    fn test_uniform_weights_without_switch_metadata() {
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::Goto { target: 1 }));
+        let b1 = g.add_node(block(1, BlockKind::Exit, Terminator::Return));
+        let b2 = g.add_node(block(2, BlockKind::Exit, Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+
+        let weights = compute_edge_weights(&g);
+
+        assert_eq!(weights.get(&(0, 1)), Some(&0.5));
+        assert_eq!(weights.get(&(0, 2)), Some(&0.5));
+    }
+
+    #[test]
+    fn test_uniform_edge_weights_ignore_switch_values() {
+        let weights = uniform_edge_weights(&create_skewed_switch_cfg());
+
+        assert_eq!(weights.get(&(0, 1)), Some(&0.5));
+        assert_eq!(weights.get(&(0, 2)), Some(&0.5));
+        assert_eq!(weights.len(), 2);
+    }
+
+    #[test]
+    fn test_sort_by_likelihood_puts_dominant_path_first() {
+        let cfg = create_skewed_switch_cfg();
+        let weights = compute_edge_weights(&cfg);
+
+        let mut paths = vec![
+            Path::new(vec![0, 2], PathKind::Normal),
+            Path::new(vec![0, 1], PathKind::Normal),
+        ];
+        sort_by_likelihood(&mut paths, &weights);
+
+        assert_eq!(paths[0].blocks, vec![0, 1]);
+        assert_eq!(path_probability(&paths[0], &weights), 0.75);
+        assert_eq!(path_probability(&paths[1], &weights), 0.25);
+    }
+
+    #[test]
+    fn test_missing_weights_default_to_one() {
+        let path = Path::new(vec![0, 1, 2], PathKind::Normal);
+        assert_eq!(path_probability(&path, &EdgeWeights::new()), 1.0);
+    }
+}
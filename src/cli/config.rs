@@ -0,0 +1,129 @@
+//! Project configuration from `mirage.toml`
+//!
+//! The config file is discovered by walking up from the current directory,
+//! the same way git finds `.git`. Only the `[database]` table is read today:
+//!
+//! ```toml
+//! [database]
+//! path = ".codemcp/project.v3"
+//! ```
+//!
+//! A relative `path` is resolved against the directory containing `mirage.toml`,
+//! so running mirage from a subdirectory still finds the project database.
+
+use anyhow::Context;
+use std::path::{Path, PathBuf};
+
+/// File name searched for by [`MirageConfig::discover`]
+pub const CONFIG_FILE_NAME: &str = "mirage.toml";
+
+/// Project configuration loaded from `mirage.toml`
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct MirageConfig {
+    /// `[database]` table
+    #[serde(default)]
+    pub database: DatabaseConfig,
+
+    /// Directory containing the config file (relative paths resolve against it)
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+/// `[database]` table of `mirage.toml`
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct DatabaseConfig {
+    /// Database path, relative to the config file's directory
+    pub path: Option<String>,
+}
+
+impl MirageConfig {
+    /// Load a config file from an explicit path
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut config: MirageConfig = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        config.root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(config)
+    }
+
+    /// Find and load the nearest `mirage.toml` at or above `start`
+    ///
+    /// Returns Ok(None) if no config file exists up to the filesystem root.
+    /// A config file that exists but fails to parse is an error.
+    pub fn discover(start: &Path) -> anyhow::Result<Option<Self>> {
+        for dir in start.ancestors() {
+            let candidate = dir.join(CONFIG_FILE_NAME);
+            if candidate.is_file() {
+                return Self::load(&candidate).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Database path from `[database] path`, resolved against the config directory
+    pub fn database_path(&self) -> Option<String> {
+        let path = Path::new(self.database.path.as_ref()?);
+        let resolved = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.root.join(path)
+        };
+        Some(resolved.to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_walks_up_to_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE_NAME),
+            "[database]\npath = \"data/graph.db\"\n",
+        ).unwrap();
+        let nested = dir.path().join("crates").join("inner");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let config = MirageConfig::discover(&nested).unwrap().expect("config found");
+
+        assert_eq!(config.root, dir.path());
+        assert_eq!(
+            config.database_path(),
+            Some(dir.path().join("data/graph.db").to_string_lossy().into_owned())
+        );
+    }
+
+    #[test]
+    fn test_discover_prefers_nearest_config() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE_NAME), "[database]\npath = \"/outer.db\"\n").unwrap();
+        let nested = dir.path().join("inner");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join(CONFIG_FILE_NAME), "[database]\npath = \"/inner.db\"\n").unwrap();
+
+        let config = MirageConfig::discover(&nested).unwrap().unwrap();
+
+        assert_eq!(config.database_path(), Some("/inner.db".to_string()));
+    }
+
+    #[test]
+    fn test_config_without_database_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE_NAME), "").unwrap();
+
+        let config = MirageConfig::discover(dir.path()).unwrap().unwrap();
+
+        assert_eq!(config.database_path(), None);
+    }
+
+    #[test]
+    fn test_invalid_config_is_error() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(CONFIG_FILE_NAME), "[database\npath = ").unwrap();
+
+        assert!(MirageConfig::discover(dir.path()).is_err());
+    }
+}
@@ -2,6 +2,14 @@
 
 use clap::{Parser, Subcommand, ValueEnum};
 
+pub mod config;
+pub mod repl;
+pub mod session;
+#[cfg(feature = "tui")]
+pub mod tui;
+
+use config::MirageConfig;
+
 // Re-export for CLI use
 pub use crate::analysis::DeadSymbolJson;
 
@@ -40,10 +48,106 @@ pub struct Cli {
     #[arg(long, global = true, default_value = "false")]
     pub detect_backend: bool,
 
+    /// Only analyze functions whose hash differs from this baseline database
+    #[arg(long, global = true, value_name = "BASELINE_DB")]
+    pub only_changed: Option<String>,
+
+    /// Analyze a named function even if --only-changed reports it unchanged
+    #[arg(long, global = true)]
+    pub force: bool,
+
+    /// Print every error code with its exit code and description, as JSON
+    #[arg(long)]
+    pub list_error_codes: bool,
+
+    /// Query the index as of this backend snapshot ID (0 = current state)
+    #[arg(long, global = true, value_name = "ID")]
+    pub snapshot: Option<u64>,
+
+    /// Truncate each emitted block statement to N characters
+    #[arg(long, global = true, value_name = "N")]
+    pub max_statement_len: Option<usize>,
+
+    /// Emit at most N statements per block, then a "... (k more)" marker
+    #[arg(long, global = true, value_name = "N")]
+    pub max_statements_per_block: Option<usize>,
+
+    /// Write the command's result to this file instead of stdout
+    #[arg(long, global = true, value_name = "PATH")]
+    pub output_file: Option<String>,
+
+    /// Never color human output (also off when stdout is not a terminal or NO_COLOR is set)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Suppress informational output ([INFO], [OK], headers and hints); results, warnings and errors still print
+    #[arg(long, global = true)]
+    pub quiet: bool,
+
+    /// Log timings for each phase (database open, CFG load, path enumeration, cache writes) to stderr
+    #[arg(long, global = true)]
+    pub trace: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+impl Cli {
+    /// Statement truncation requested by --max-statement-len / --max-statements-per-block
+    pub fn statement_limits(&self) -> crate::output::StatementLimits {
+        crate::output::StatementLimits {
+            max_len: self.max_statement_len,
+            max_per_block: self.max_statements_per_block,
+        }
+    }
+}
+
+/// Run one subcommand
+pub fn dispatch(command: &Commands, cli: &Cli) -> anyhow::Result<()> {
+    match command {
+        Commands::Status(args) => cmds::status(args, cli),
+        Commands::Functions(args) => cmds::functions(args, cli),
+        Commands::Validate(args) => cmds::validate(args, cli),
+        Commands::Paths(args) => cmds::paths(args, cli),
+        Commands::Cfg(args) => cmds::cfg(args, cli),
+        Commands::Dominators(args) => cmds::dominators(args, cli),
+        Commands::Loops(args) => cmds::loops(args, cli),
+        Commands::Unreachable(args) => cmds::unreachable(args, cli),
+        Commands::Patterns(args) => cmds::patterns(args, cli),
+        Commands::Frontiers(args) => cmds::frontiers(args, cli),
+        Commands::ControlDeps(args) => cmds::control_deps(args, cli),
+        Commands::Cleanup(args) => cmds::cleanup(args, cli),
+        Commands::Explain(args) => cmds::explain(args, cli),
+        Commands::Blocks(args) => cmds::blocks(args, cli),
+        Commands::BlockAt(args) => cmds::block_at(args, cli),
+        Commands::Terminators(args) => cmds::terminators(args, cli),
+        Commands::EdgeTypes(args) => cmds::edge_types(args, cli),
+        Commands::TerminatorKinds(args) => cmds::terminator_kinds(args, cli),
+        Commands::BlockKinds(args) => cmds::block_kinds(args, cli),
+        Commands::Exits(args) => cmds::exits(args, cli),
+        Commands::LongestPath(args) => cmds::longest_path(args, cli),
+        Commands::Redundant(args) => cmds::redundant(args, cli),
+        Commands::Coverage(args) => cmds::coverage(args, cli),
+        Commands::Verify(args) => cmds::verify(args, cli),
+        Commands::BlastZone(args) => cmds::blast_zone(args, cli),
+        Commands::Cycles(args) => cmds::cycles(args, cli),
+        Commands::Slice(args) => cmds::slice(args, cli),
+        Commands::Hotspots(args) => cmds::hotspots(args, cli),
+        Commands::Hotpaths(args) => cmds::hotpaths(args, cli),
+        Commands::Diff(args) => cmds::diff(args, cli),
+        Commands::DiffCfg(args) => cmds::diff_cfg(args, cli),
+        Commands::Icfg(args) => cmds::icfg(args, cli),
+        Commands::Migrate(args) => cmds::migrate(args, cli),
+        Commands::Repl(args) => repl::run(args, cli),
+        Commands::Prune(args) => cmds::prune(args, cli),
+        Commands::InitSchema(args) => cmds::init_schema(args, cli),
+        Commands::Export(args) => cmds::export(args, cli),
+        Commands::Import(args) => cmds::import(args, cli),
+        #[cfg(feature = "tui")]
+        Commands::Tui(args) => tui::run(args, cli),
+    }
+}
+
 /// Output format options
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OutputFormat {
@@ -60,6 +164,12 @@ pub enum Commands {
     /// Show database statistics
     Status(StatusArgs),
 
+    /// List indexed functions with their CFG sizes
+    Functions(FunctionsArgs),
+
+    /// Check every function's CFG for structural problems left by indexing
+    Validate(ValidateArgs),
+
     /// Show all execution paths through a function
     Paths(PathsArgs),
 
@@ -81,6 +191,45 @@ pub enum Commands {
     /// Show dominance frontiers in CFG
     Frontiers(FrontiersArgs),
 
+    /// Show the branch blocks that decide whether a block executes
+    ControlDeps(ControlDepsArgs),
+
+    /// Show the blocks guaranteed to run after a block (its post-dominators)
+    Cleanup(CleanupArgs),
+
+    /// Describe a function's control flow in plain English
+    Explain(ExplainArgs),
+
+    /// Dump the raw stored block data for a function
+    Blocks(BlocksArgs),
+
+    /// Find the blocks whose source span covers a line
+    BlockAt(BlockAtArgs),
+
+    /// Count terminator kinds (Goto, SwitchInt, Return, Call, ...) per function
+    Terminators(TerminatorsArgs),
+
+    /// List every edge type that can appear in output, with its meaning
+    EdgeTypes(EdgeTypesArgs),
+
+    /// List every terminator kind that can appear in output, with its meaning
+    TerminatorKinds(TerminatorKindsArgs),
+
+    /// List every block kind that can appear in output, with its meaning
+    BlockKinds(BlockKindsArgs),
+
+    /// List a function's exit blocks and which of them are reachable
+    Exits(ExitsArgs),
+
+    /// Show the longest path from the entry to an exit (loops count once)
+    LongestPath(LongestPathArgs),
+
+    /// Find branches whose condition cannot change control flow
+    Redundant(RedundantArgs),
+
+    /// Show which CFG edges the enumerated paths traverse
+    Coverage(CoverageArgs),
+
     /// Verify a path is still valid
     Verify(VerifyArgs),
 
@@ -102,11 +251,33 @@ pub enum Commands {
     /// Show CFG differences between two snapshots
     Diff(DiffArgs),
 
+    /// Compare the control-flow structure of two functions
+    DiffCfg(DiffCfgArgs),
+
     /// Show inter-procedural CFG (combined function CFGs with call/return edges)
     Icfg(IcfgArgs),
 
     /// Migrate database between storage backends
     Migrate(MigrateArgs),
+
+    /// Read commands from stdin and run them against one open database
+    Repl(ReplArgs),
+
+    /// Delete CFG rows left behind by deleted functions
+    Prune(PruneArgs),
+
+    /// Add Mirage's tables to an existing Magellan database without indexing
+    InitSchema(InitSchemaArgs),
+
+    /// Dump every function's CFG data as JSON Lines for backup or transfer
+    Export(ExportArgs),
+
+    /// Build a new database from a JSON Lines dump written by export
+    Import(ImportArgs),
+
+    /// Browse functions and their CFGs interactively (requires the `tui` feature)
+    #[cfg(feature = "tui")]
+    Tui(TuiArgs),
 }
 
 // ============================================================================
@@ -116,12 +287,62 @@ pub enum Commands {
 #[derive(Parser, Debug, Clone, Copy)]
 pub struct StatusArgs {}
 
+#[derive(Parser, Debug, Clone)]
+pub struct FunctionsArgs {
+    /// Only list functions whose name or FQN contains this substring
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Order of the listing
+    #[arg(long, value_enum, default_value_t = FunctionSort::Name)]
+    pub sort: FunctionSort,
+
+    /// List at most this many functions
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Only list functions whose paths were cached since this time
+    /// (RFC3339 timestamp, or a duration ago such as 30m, 1h, 2d)
+    #[arg(long, value_name = "TIME")]
+    pub since: Option<String>,
+
+    /// Write the listing as CSV instead of following --output
+    #[arg(long, value_enum)]
+    pub format: Option<TableFormat>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ValidateArgs {}
+
+/// Sort order for `functions --sort`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionSort {
+    /// Alphabetically by name
+    Name,
+    /// Most blocks first
+    Blocks,
+    /// Most edges first
+    Edges,
+}
+
 #[derive(Parser, Debug, Clone)]
 pub struct PathsArgs {
     /// Function symbol ID or name
-    #[arg(long)]
+    #[arg(long, required_unless_present = "functions_from_file", default_value = "", hide_default_value = true)]
     pub function: String,
 
+    /// Run once per function listed in this file (one name or ID per line)
+    #[arg(long, value_name = "PATH", conflicts_with = "function")]
+    pub functions_from_file: Option<String>,
+
+    /// With --functions-from-file, fill the path cache on up to this many threads
+    #[arg(long, value_name = "N", requires = "functions_from_file", conflicts_with = "function")]
+    pub jobs: Option<std::num::NonZeroUsize>,
+
+    /// With --functions-from-file, report wall-clock and per-function time
+    #[arg(long, requires = "functions_from_file", conflicts_with = "function")]
+    pub timings: bool,
+
     /// Show only error paths
     #[arg(long)]
     pub show_errors: bool,
@@ -130,6 +351,10 @@ pub struct PathsArgs {
     #[arg(long)]
     pub max_length: Option<usize>,
 
+    /// Minimum path length; shorter paths are filtered out
+    #[arg(long)]
+    pub min_length: Option<usize>,
+
     /// Show block details for each path
     #[arg(long)]
     pub with_blocks: bool,
@@ -141,17 +366,141 @@ pub struct PathsArgs {
     /// Git revision for incremental analysis (e.g., "HEAD~1")
     #[arg(long)]
     pub since: Option<String>,
+
+    /// Order paths by branch probability, most likely first
+    #[arg(long)]
+    pub most_likely: bool,
+
+    /// Drop paths that pass through panic/abort landing blocks
+    #[arg(long)]
+    pub skip_panic_blocks: bool,
+
+    /// Collapse paths visiting the same set of blocks (lossy: ignores order and loop counts)
+    #[arg(long)]
+    pub dedup_by_blockset: bool,
+
+    /// Only show paths that iterate a loop (take a back edge)
+    #[arg(long, conflicts_with = "no_loops")]
+    pub loops_only: bool,
+
+    /// Only show straight-line paths that take no back edge
+    #[arg(long)]
+    pub no_loops: bool,
+
+    /// Maximum times each loop back edge is taken per path (default: 1)
+    #[arg(long)]
+    pub max_loop_iterations: Option<usize>,
+
+    /// Stop after enumerating this many paths (default 10000)
+    #[arg(long, value_name = "N", conflicts_with = "incremental")]
+    pub max_paths: Option<std::num::NonZeroUsize>,
+
+    /// Only count paths by kind; skips building paths and the path cache
+    #[arg(long, conflicts_with_all = ["show_errors", "skip_panic_blocks", "dedup_by_blockset", "loops_only", "no_loops"])]
+    pub count_only: bool,
+
+    /// Only report headline statistics (counts by kind, min/max/average length), not the paths
+    #[arg(long, conflicts_with_all = ["count_only", "incremental", "tree", "with_blocks", "include_statements", "readable_ids"])]
+    pub summary_only: bool,
+
+    /// Include each block's statements in path output
+    #[arg(long)]
+    pub include_statements: bool,
+
+    /// Start enumeration at this block ID instead of the function entry (bypasses the path cache)
+    #[arg(long, conflicts_with = "count_only")]
+    pub entry: Option<usize>,
+
+    /// Show only paths that end this way
+    #[arg(long, value_enum, conflicts_with = "count_only")]
+    pub ends_in: Option<EndKindArg>,
+
+    /// Order paths by length, kind or block sequence
+    #[arg(long, value_enum, default_value_t = PathSortArg::Blocks)]
+    pub sort: PathSortArg,
+
+    /// Reverse the --sort order
+    #[arg(long)]
+    pub desc: bool,
+
+    /// Merge paths into a prefix tree that branches where they diverge
+    #[arg(long, conflicts_with_all = ["count_only", "incremental", "with_blocks", "include_statements"])]
+    pub tree: bool,
+
+    /// Show a readable alias (`function#pN`) next to each BLAKE3 path ID
+    #[arg(long, conflicts_with_all = ["count_only", "incremental", "tree", "entry"])]
+    pub readable_ids: bool,
+
+    /// With --with-blocks, include each path's source text plus N lines of context
+    #[arg(long, value_name = "N", requires = "with_blocks", conflicts_with = "incremental")]
+    pub context_lines: Option<usize>,
+
+    /// Only show paths that call this function (callee name as indexed by Magellan)
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["count_only", "incremental"])]
+    pub calls: Option<String>,
+
+    /// Compare the function's paths with the same-named function in this database (added/removed/unchanged)
+    #[arg(long, value_name = "PATH", conflicts_with_all = [
+        "functions_from_file", "incremental", "count_only", "summary_only", "tree", "entry", "readable_ids",
+        "with_blocks", "include_statements", "show_errors", "skip_panic_blocks", "dedup_by_blockset",
+        "loops_only", "no_loops", "ends_in", "calls", "most_likely",
+    ])]
+    pub db2: Option<String>,
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct CfgArgs {
     /// Function symbol ID or name
-    #[arg(long)]
-    pub function: String,
+    #[arg(long, required_unless_present_any = ["all", "functions_from_file"])]
+    pub function: Option<String>,
+
+    /// Run once per function listed in this file (one name or ID per line)
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["function", "all"])]
+    pub functions_from_file: Option<String>,
+
+    /// With --functions-from-file, report wall-clock and per-function time
+    #[arg(long, requires = "functions_from_file", conflicts_with_all = ["function", "all"])]
+    pub timings: bool,
+
+    /// Export the CFG of every indexed function into a single output
+    #[arg(long, conflicts_with = "function")]
+    pub all: bool,
+
+    /// With --all, only export functions whose name contains this substring
+    #[arg(long, requires = "all", conflicts_with = "function")]
+    pub filter: Option<String>,
 
     /// Output format
     #[arg(long, value_enum)]
     pub format: Option<CfgFormat>,
+
+    /// Annotate each block with the number of loops enclosing it
+    #[arg(long)]
+    pub with_loop_depth: bool,
+
+    /// Group each natural loop's blocks in a DOT cluster (nested loops nest)
+    #[arg(long)]
+    pub cluster_loops: bool,
+
+    /// Add successor and predecessor maps to JSON output
+    #[arg(long)]
+    pub with_adjacency: bool,
+
+    /// Add each block's in_degree and out_degree (edge counts) to JSON output
+    #[arg(long)]
+    pub with_degrees: bool,
+
+    /// DOT graph direction
+    #[arg(long, value_enum, default_value_t = RankDirArg::TB)]
+    pub rankdir: RankDirArg,
+
+    /// DOT edge routing
+    #[arg(long, value_enum, default_value_t = SplinesArg::Curved)]
+    pub splines: SplinesArg,
+
+    /// Only export edges of these types (comma-separated, e.g. TrueBranch,FalseBranch)
+    #[arg(long, value_name = "TYPES", value_delimiter = ',')]
+    pub edge_filter: Vec<crate::cfg::EdgeType>,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -164,6 +513,14 @@ pub struct DominatorsArgs {
     #[arg(long)]
     pub must_pass_through: Option<String>,
 
+    /// Like --must-pass-through, for every block whose source span covers this line
+    #[arg(long, conflicts_with = "must_pass_through")]
+    pub must_pass_through_line: Option<usize>,
+
+    /// Leave the queried block out of its own must-pass set (strict dominance)
+    #[arg(long)]
+    pub strict: bool,
+
     /// Show post-dominators instead of dominators
     #[arg(long)]
     pub post: bool,
@@ -171,6 +528,24 @@ pub struct DominatorsArgs {
     /// Use inter-procedural (call graph) dominance instead of intra-procedural (CFG)
     #[arg(long)]
     pub inter_procedural: bool,
+
+
+    /// Output format
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = DominatorsFormat::Tree,
+        conflicts_with_all = ["must_pass_through", "must_pass_through_line", "inter_procedural"],
+    )]
+    pub format: DominatorsFormat,
+
+    /// With --format dot, overlay the CFG's own edges on the tree
+    #[arg(long)]
+    pub with_cfg: bool,
+
+    /// Print the human tree only this many levels below the root (JSON is never truncated)
+    #[arg(long)]
+    pub max_depth: Option<usize>,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -197,6 +572,22 @@ pub struct UnreachableArgs {
     /// Include uncalled functions (requires Magellan call graph)
     #[arg(long)]
     pub include_uncalled: bool,
+
+    /// Emit a SARIF 2.1.0 log of unreachable blocks (for CI code scanning)
+    #[arg(long)]
+    pub sarif: bool,
+
+    /// Group JSON output by function instead of one flat block list
+    #[arg(long)]
+    pub group_by_function: bool,
+
+    /// Include each block's source lines (with a little context) in the report
+    #[arg(long)]
+    pub with_source: bool,
+
+    /// Also report blocks reachable only through a branch on a literal constant (e.g. `if false`)
+    #[arg(long)]
+    pub conditional: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -212,6 +603,36 @@ pub struct PatternsArgs {
     /// Show only match patterns
     #[arg(long)]
     pub r#match: bool,
+
+    /// Show control-flow join points (blocks with 2+ predecessors)
+    #[arg(long, conflicts_with_all = ["if_else", "match"])]
+    pub merge_points: bool,
+
+    /// Show only if/else patterns of this shape
+    #[arg(long, value_enum, conflicts_with_all = ["match", "merge_points"])]
+    pub shape: Option<IfShapeArg>,
+
+    /// Show guard clauses (branches with one arm that returns early)
+    #[arg(long, conflicts_with_all = ["if_else", "match", "merge_points", "shape"])]
+    pub guards: bool,
+
+    /// Show if/else branches whose arms differ sharply in size
+    #[arg(long, conflicts_with_all = ["if_else", "match", "merge_points", "shape", "guards"])]
+    pub unbalanced: bool,
+
+    /// With --unbalanced, flag branches whose larger arm exceeds the smaller by more than this ratio (default 4)
+    #[arg(long, value_name = "RATIO", requires = "unbalanced", value_parser = parse_ratio)]
+    pub threshold: Option<f64>,
+}
+
+/// Parse an arm-size ratio: a finite number of at least 1
+fn parse_ratio(value: &str) -> std::result::Result<f64, String> {
+    let ratio: f64 = value.parse().map_err(|e| format!("{}", e))?;
+    if ratio.is_finite() && ratio >= 1.0 {
+        Ok(ratio)
+    } else {
+        Err(format!("{} is not a ratio; expected a finite number >= 1", value))
+    }
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -227,13 +648,72 @@ pub struct FrontiersArgs {
     /// Show frontiers for specific node only
     #[arg(long)]
     pub node: Option<usize>,
+
+    /// Show post-dominance frontiers (control dependences) instead
+    #[arg(long)]
+    pub post: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ControlDepsArgs {
+    /// Function containing the block
+    #[arg(long)]
+    pub function: String,
+
+    /// Block whose control dependences to show
+    #[arg(long)]
+    pub block: usize,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct CleanupArgs {
+    /// Function containing the block
+    #[arg(long)]
+    pub function: String,
+
+    /// Block to show guaranteed successors of
+    #[arg(long)]
+    pub block: usize,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ExplainArgs {
+    /// Function to explain
+    #[arg(long)]
+    pub function: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct BlocksArgs {
+    /// Function whose blocks to dump
+    #[arg(long)]
+    pub function: String,
+
+    /// Show only the block with this stored ID
+    #[arg(long)]
+    pub block: Option<i64>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct BlockAtArgs {
+    /// Function containing the line
+    #[arg(long)]
+    pub function: String,
+
+    /// Source line (1-indexed)
+    #[arg(long)]
+    pub line: usize,
 }
 
 #[derive(Parser, Debug, Clone)]
 pub struct VerifyArgs {
-    /// Path ID to verify
+    /// Path ID to verify (BLAKE3 hash or readable `function#pN`)
+    #[arg(long, required_unless_present = "function", conflicts_with = "function")]
+    pub path_id: Option<String>,
+
+    /// Re-verify all cached paths of a function in one batch
     #[arg(long)]
-    pub path_id: String,
+    pub function: Option<String>,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -246,7 +726,7 @@ pub struct BlastZoneArgs {
     #[arg(long)]
     pub block_id: Option<usize>,
 
-    /// Path ID to analyze impact for
+    /// Path ID to analyze impact for (BLAKE3 hash or readable `function#pN`)
     #[arg(long)]
     pub path_id: Option<String>,
 
@@ -261,6 +741,22 @@ pub struct BlastZoneArgs {
     /// Use call graph for inter-procedural impact analysis
     #[arg(long)]
     pub use_call_graph: bool,
+
+    /// List functions that (in)directly call this function
+    #[arg(long, conflicts_with = "callees")]
+    pub callers: Option<String>,
+
+    /// List functions this function (in)directly calls
+    #[arg(long)]
+    pub callees: Option<String>,
+
+    /// Find blocks that can reach --block-id instead of blocks it reaches
+    #[arg(long, conflicts_with = "path_id")]
+    pub reverse: bool,
+
+    /// Stop at --block-id's immediate post-dominator: only blocks before control flow reconverges
+    #[arg(long, conflicts_with_all = ["path_id", "reverse"])]
+    pub until_convergence: bool,
 }
 
 #[derive(Parser, Debug, Clone)]
@@ -318,6 +814,10 @@ pub struct HotspotsArgs {
     /// Use inter-procedural analysis (requires Magellan DB)
     #[arg(long)]
     pub inter_procedural: bool,
+
+    /// Write the hotspots as CSV instead of following --output
+    #[arg(long, value_enum)]
+    pub format: Option<TableFormat>,
 }
 
 /// Hot path detection arguments
@@ -419,6 +919,111 @@ pub struct DiffArgs {
     pub verbose: bool,
 }
 
+#[derive(Parser, Debug, Clone)]
+pub struct DiffCfgArgs {
+    /// First function symbol ID or name
+    #[arg(long)]
+    pub function: String,
+
+    /// Second function symbol ID or name
+    #[arg(long)]
+    pub function2: String,
+
+    /// Database to load the second function from (default: --db)
+    #[arg(long)]
+    pub db2: Option<String>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ReplArgs {}
+
+#[derive(Parser, Debug, Clone)]
+pub struct PruneArgs {
+    /// Report what would be deleted without deleting anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Also drop cached paths that no longer match their function's blocks
+    #[arg(long)]
+    pub stale_paths: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct InitSchemaArgs {}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ExportArgs {
+    /// JSON Lines file to write, one function per line
+    #[arg(long, value_name = "FILE")]
+    pub function_data: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ImportArgs {
+    /// JSON Lines file written by `export --function-data`
+    pub file: String,
+}
+
+#[cfg(feature = "tui")]
+#[derive(Parser, Debug, Clone)]
+pub struct TuiArgs {}
+
+#[derive(Parser, Debug, Clone)]
+pub struct TerminatorsArgs {
+    /// Function symbol ID or name
+    #[arg(long, required_unless_present = "all")]
+    pub function: Option<String>,
+
+    /// Count every indexed function and report an aggregate
+    #[arg(long, conflicts_with = "function")]
+    pub all: bool,
+
+    /// Write the counts as CSV instead of following --output
+    #[arg(long, value_enum)]
+    pub format: Option<TableFormat>,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct EdgeTypesArgs {}
+
+#[derive(Parser, Debug, Clone)]
+pub struct TerminatorKindsArgs {}
+
+#[derive(Parser, Debug, Clone)]
+pub struct BlockKindsArgs {}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ExitsArgs {
+    /// Function symbol ID or name
+    #[arg(long)]
+    pub function: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct LongestPathArgs {
+    /// Function symbol ID or name
+    #[arg(long)]
+    pub function: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct RedundantArgs {
+    /// Function symbol ID or name
+    #[arg(long)]
+    pub function: String,
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct CoverageArgs {
+    /// Function symbol ID or name
+    #[arg(long)]
+    pub function: String,
+
+    /// Maximum path length (paths longer than this are not enumerated)
+    #[arg(long)]
+    pub max_length: Option<usize>,
+}
+
 /// Backend format for migration
 #[derive(clap::ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
 pub enum BackendFormat {
@@ -445,31 +1050,203 @@ pub enum SliceDirectionArg {
     Forward,
 }
 
-/// CFG output format
+/// How a path ends (see `cfg::EndKind`)
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
-pub enum CfgFormat {
-    /// Human-readable text
-    Human,
-    /// Graphviz DOT format
-    Dot,
-    /// JSON export
-    Json,
+pub enum EndKindArg {
+    /// Returns through the function's natural exit
+    NormalReturn,
+    /// Returns before the natural exit
+    EarlyReturn,
+    /// Ends in a panic or abort
+    Panic,
+    /// Cut off by enumeration bounds without leaving the function
+    Loop,
+    /// Ends in Unreachable or a call that never returns
+    Diverge,
 }
 
-// ============================================================================
-// Utility Functions
-// ============================================================================
-
+impl From<EndKindArg> for crate::cfg::EndKind {
+    fn from(arg: EndKindArg) -> Self {
+        match arg {
+            EndKindArg::NormalReturn => Self::NormalReturn,
+            EndKindArg::EarlyReturn => Self::EarlyReturn,
+            EndKindArg::Panic => Self::Panic,
+            EndKindArg::Loop => Self::Loop,
+            EndKindArg::Diverge => Self::Diverge,
+        }
+    }
+}
+
+/// Sort order for `paths --sort` (see `cfg::paths::PathSort`)
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSortArg {
+    /// Shortest paths first
+    Length,
+    /// Normal paths first, then error paths grouped together
+    Kind,
+    /// Lexicographic by block sequence (stable for diffing)
+    Blocks,
+}
+
+impl From<PathSortArg> for crate::cfg::paths::PathSort {
+    fn from(arg: PathSortArg) -> Self {
+        match arg {
+            PathSortArg::Length => Self::Length,
+            PathSortArg::Kind => Self::Kind,
+            PathSortArg::Blocks => Self::Blocks,
+        }
+    }
+}
+
+/// Graph direction for `cfg --rankdir` (see `cfg::export::RankDir`)
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankDirArg {
+    /// Top to bottom
+    #[value(name = "TB")]
+    TB,
+    /// Left to right
+    #[value(name = "LR")]
+    LR,
+    /// Bottom to top
+    #[value(name = "BT")]
+    BT,
+    /// Right to left
+    #[value(name = "RL")]
+    RL,
+}
+
+impl From<RankDirArg> for crate::cfg::export::RankDir {
+    fn from(arg: RankDirArg) -> Self {
+        match arg {
+            RankDirArg::TB => Self::TB,
+            RankDirArg::LR => Self::LR,
+            RankDirArg::BT => Self::BT,
+            RankDirArg::RL => Self::RL,
+        }
+    }
+}
+
+/// Edge routing for `cfg --splines` (see `cfg::export::Splines`)
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplinesArg {
+    /// Straight line segments
+    Line,
+    /// Axis-aligned segments
+    Ortho,
+    /// Curved edges
+    Curved,
+}
+
+impl From<SplinesArg> for crate::cfg::export::Splines {
+    fn from(arg: SplinesArg) -> Self {
+        match arg {
+            SplinesArg::Line => Self::Line,
+            SplinesArg::Ortho => Self::Ortho,
+            SplinesArg::Curved => Self::Curved,
+        }
+    }
+}
+
+/// If/else shape for `patterns --shape` (see `cfg::patterns::IfShape`)
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IfShapeArg {
+    /// Both arms do work, then merge
+    Diamond,
+    /// An if without an else
+    IfOnly,
+    /// One arm returns early (a guard clause)
+    GuardReturn,
+    /// Arms that never merge and are not a guard
+    Divergent,
+}
+
+impl From<IfShapeArg> for crate::cfg::patterns::IfShape {
+    fn from(arg: IfShapeArg) -> Self {
+        match arg {
+            IfShapeArg::Diamond => Self::Diamond,
+            IfShapeArg::IfOnly => Self::IfOnly,
+            IfShapeArg::GuardReturn => Self::GuardReturn,
+            IfShapeArg::Divergent => Self::Divergent,
+        }
+    }
+}
+
+/// Output format for `dominators`
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DominatorsFormat {
+    /// Text tree or JSON, following --output
+    Tree,
+    /// Graphviz DOT of the dominator tree
+    Dot,
+}
+
+/// Alternative output format for tabular commands
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    /// Comma-separated values: a header row, then one row per item
+    Csv,
+}
+
+/// CFG output format
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfgFormat {
+    /// Human-readable text
+    Human,
+    /// Graphviz DOT format
+    Dot,
+    /// JSON export
+    Json,
+}
+
+// ============================================================================
+// Utility Functions
+// ============================================================================
+
+/// Built-in database path used when nothing else is configured
+pub const DEFAULT_DB_PATH: &str = ".codemcp/codegraph.db";
+
 /// Resolve the database path from multiple sources
 ///
-/// Priority: CLI arg > MIRAGE_DB env var > default ".codemcp/codegraph.db"
+/// Priority: CLI arg > MIRAGE_DB env var > mirage.toml > default ".codemcp/codegraph.db"
 /// This follows Magellan/llmgrep's pattern for database path resolution.
+/// mirage.toml is discovered by walking up from the current directory.
 pub fn resolve_db_path(cli_db: Option<String>) -> anyhow::Result<String> {
-    match cli_db {
-        Some(path) => Ok(path),
-        None => std::env::var("MIRAGE_DB")
-            .or_else(|_| Ok(".codemcp/codegraph.db".to_string())),
+    resolve_db_path_from(cli_db, &std::env::current_dir()?)
+}
+
+/// `resolve_db_path`, discovering mirage.toml from `dir`
+///
+/// The config is only read when neither `--db` nor MIRAGE_DB decides the
+/// path, so a broken mirage.toml cannot fail a command that does not need it.
+fn resolve_db_path_from(cli_db: Option<String>, dir: &std::path::Path) -> anyhow::Result<String> {
+    if let Some(path) = cli_db {
+        return Ok(path);
+    }
+    if let Ok(path) = std::env::var("MIRAGE_DB") {
+        return Ok(path);
+    }
+
+    let config = MirageConfig::discover(dir)?;
+    resolve_db_path_with_config(None, config.as_ref())
+}
+
+/// Resolve the database path using an already discovered mirage.toml
+///
+/// Same priority as `resolve_db_path`, with `config` standing in for discovery.
+pub fn resolve_db_path_with_config(
+    cli_db: Option<String>,
+    config: Option<&MirageConfig>,
+) -> anyhow::Result<String> {
+    if let Some(path) = cli_db {
+        return Ok(path);
     }
+    if let Ok(path) = std::env::var("MIRAGE_DB") {
+        return Ok(path);
+    }
+    if let Some(path) = config.and_then(MirageConfig::database_path) {
+        return Ok(path);
+    }
+    Ok(DEFAULT_DB_PATH.to_string())
 }
 
 /// Detect the git repository path from the database path
@@ -512,6 +1289,95 @@ struct PathsResponse {
     total_paths: usize,
     error_paths: usize,
     paths: Vec<PathSummary>,
+    /// Enumeration stopped at the --max-paths cap, so paths may be missing
+    truncated: bool,
+    /// Paths enumerated before filtering (only when truncated)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    truncated_at: Option<usize>,
+    /// Paths collapsed by --dedup-by-blockset (omitted when not requested)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collapsed_count: Option<usize>,
+    /// Paths outside the --min-length/--max-length band (omitted without --min-length)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    length_filtered: Option<usize>,
+}
+
+/// Response for paths --tree
+#[derive(serde::Serialize)]
+struct PathTreeResponse {
+    function: String,
+    total_paths: usize,
+    error_paths: usize,
+    /// Enumeration stopped at the --max-paths cap, so branches may be missing
+    truncated: bool,
+    node_count: usize,
+    tree: crate::cfg::path_tree::PathTree,
+}
+
+/// Response for --functions-from-file: one entry per listed function
+#[derive(serde::Serialize)]
+struct FunctionBatchResponse {
+    requested: usize,
+    results: Vec<FunctionBatchResult>,
+    /// Functions that did not resolve or whose run failed
+    errors: Vec<FunctionBatchError>,
+    /// Batch wall-clock time against per-function time (only with --timings)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timings: Option<BatchTimings>,
+}
+
+/// How long a --functions-from-file batch took, for judging --jobs
+#[derive(serde::Serialize)]
+struct BatchTimings {
+    jobs: usize,
+    wall_ms: u64,
+    /// Sum of each function's own run time
+    function_ms: u64,
+}
+
+/// One function's result in a --functions-from-file batch
+#[derive(serde::Serialize)]
+struct FunctionBatchResult {
+    function: String,
+    /// The `data` the command reports for this function on its own
+    data: serde_json::Value,
+}
+
+/// A function skipped in a --functions-from-file batch
+#[derive(serde::Serialize)]
+struct FunctionBatchError {
+    function: String,
+    error: String,
+}
+
+/// Response for paths --count-only
+#[derive(serde::Serialize)]
+struct PathCountsResponse {
+    function: String,
+    counts: crate::cfg::paths::PathCounts,
+}
+
+/// Response for paths --db2
+#[derive(serde::Serialize)]
+struct PathsComparisonResponse {
+    function: String,
+    db: String,
+    db2: String,
+    added_count: usize,
+    removed_count: usize,
+    unchanged_count: usize,
+    /// Whether either side stopped at the path limit
+    truncated: bool,
+    #[serde(flatten)]
+    diff: crate::cfg::paths::PathSetDiff,
+}
+
+/// Response for paths --summary-only
+#[derive(serde::Serialize)]
+struct PathSummaryStatsResponse {
+    function: String,
+    #[serde(flatten)]
+    stats: crate::cfg::paths::PathSummaryStats,
 }
 
 /// LLM-optimized block representation with metadata
@@ -519,6 +1385,9 @@ struct PathsResponse {
 struct PathBlock {
     block_id: usize,
     terminator: String,
+    /// Block statements (only with --include-statements)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    statements: Option<Vec<String>>,
 }
 
 /// Source location range for a path (to be populated in plan 07-02)
@@ -529,10 +1398,25 @@ struct SourceRange {
     end_line: usize,
 }
 
+/// Source text of a path within one file (with --context-lines)
+#[derive(serde::Serialize, Clone)]
+struct PathSnippet {
+    file_path: String,
+    /// Lines the path covers, plus context; covered lines have `covered: true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lines: Option<Vec<crate::cfg::SnippetLine>>,
+    /// Why `lines` is missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+}
+
 /// Summary of a single path for JSON output (LLM-optimized)
 #[derive(serde::Serialize)]
 struct PathSummary {
     path_id: String,
+    /// Readable alias such as `foo#p3` (only with --readable-ids)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    readable_id: Option<String>,
     kind: String,
     length: usize,
     blocks: Vec<PathBlock>,
@@ -540,6 +1424,23 @@ struct PathSummary {
     summary: Option<String>,
     /// Source range for the entire path (to be populated in plan 07-02)
     source_range: Option<SourceRange>,
+    /// Product of edge weights along the path (only with --most-likely)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    probability: Option<f64>,
+    /// How the path ends (see `cfg::classify_end`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ends_in: Option<crate::cfg::EndKind>,
+    /// Whether the path takes a loop back edge
+    contains_loop: bool,
+    /// Loop headers the path re-enters, sorted
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    loop_headers: Vec<usize>,
+    /// Functions called along the path, in call order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    callees: Vec<String>,
+    /// Source text per file (only with --context-lines)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_snippets: Option<Vec<PathSnippet>>,
 }
 
 impl From<crate::cfg::Path> for PathSummary {
@@ -552,16 +1453,24 @@ impl From<crate::cfg::Path> for PathSummary {
             .map(|block_id| PathBlock {
                 block_id,
                 terminator: "Unknown".to_string(),
+                statements: None,
             })
             .collect();
 
         Self {
             path_id: path.path_id,
+            readable_id: None,
             kind: format!("{:?}", path.kind),
             length,
             blocks,
             summary: None,  // To be populated in plan 07-04
             source_range: None,  // To be populated in plan 07-02
+            probability: None,
+            ends_in: None,
+            contains_loop: false,
+            loop_headers: vec![],
+            callees: vec![],
+            source_snippets: None,
         }
     }
 }
@@ -589,6 +1498,7 @@ impl PathSummary {
             PathBlock {
                 block_id,
                 terminator,
+                statements: None,
             }
         }).collect();
 
@@ -596,15 +1506,43 @@ impl PathSummary {
         let source_range = Self::calculate_source_range(&path, cfg);
 
         let length = path.len();
+        let ends_in = crate::cfg::paths::classify_end(cfg, &path, crate::cfg::paths::natural_exit(cfg));
 
         Self {
             path_id: path.path_id,
+            readable_id: None,
             kind: format!("{:?}", path.kind),
             length,
             summary,
             source_range,
             blocks,
+            probability: None,
+            ends_in: Some(ends_in),
+            contains_loop: false,
+            loop_headers: vec![],
+            callees: vec![],
+            source_snippets: None,
+        }
+    }
+
+    /// Record which loops the path iterates (see `back_edge_block_ids`)
+    pub fn with_loops(mut self, path: &crate::cfg::Path, back_edges: &std::collections::HashSet<(usize, usize)>) -> Self {
+        self.loop_headers = crate::cfg::paths::path_loop_headers(path, back_edges);
+        self.contains_loop = !self.loop_headers.is_empty();
+        self
+    }
+
+    /// Attach each block's statements from the CFG (for --include-statements)
+    pub fn with_statements(mut self, cfg: &crate::cfg::Cfg) -> Self {
+        for block in &mut self.blocks {
+            block.statements = Some(
+                cfg.node_weights()
+                    .find(|b| b.id == block.block_id)
+                    .map(|b| b.statements.clone())
+                    .unwrap_or_default(),
+            );
         }
+        self
     }
 
     /// Calculate overall source range for a path
@@ -618,6 +1556,8 @@ impl PathSummary {
             .and_then(|idx| cfg[idx].source_location.clone());
 
         match (first_loc, last_loc) {
+            // Byte-only locations whose source file could not be read carry no lines
+            (Some(first), Some(last)) if first.start_line == 0 || last.start_line == 0 => None,
             (Some(first), Some(last)) => {
                 // Use first file_path, combine line ranges
                 Some(SourceRange {
@@ -639,6 +1579,11 @@ struct DominanceResponse {
     root: Option<usize>,
     dominance_tree: Vec<DominatorEntry>,
     must_pass_through: Option<MustPassThroughResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    must_pass_through_line: Option<MustPassThroughLineResult>,
+    /// Every entry-like block, when the CFG has more than one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry_blocks: Option<Vec<usize>>,
 }
 
 /// Entry in dominance tree for JSON output
@@ -656,6 +1601,13 @@ struct MustPassThroughResult {
     must_pass: Vec<usize>,
 }
 
+/// Result of must-pass-through query by source line (one entry per block on the line)
+#[derive(serde::Serialize)]
+struct MustPassThroughLineResult {
+    line: usize,
+    results: Vec<MustPassThroughResult>,
+}
+
 /// Response for inter-procedural dominators command
 #[derive(serde::Serialize)]
 struct InterProceduralDominanceResponse {
@@ -680,24 +1632,221 @@ struct UnreachableResponse {
     /// Uncalled functions (only populated when --include-uncalled is set)
     #[serde(skip_serializing_if = "Option::is_none")]
     uncalled_functions: Option<Vec<DeadSymbolJson>>,
+    /// Number of changed functions considered (only populated when --only-changed is set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changed_functions: Option<usize>,
+}
+
+/// Response for redundant command
+#[derive(serde::Serialize)]
+struct RedundantResponse {
+    function: String,
+    redundant_count: usize,
+    branches: Vec<crate::analysis::RedundantBranch>,
+}
+
+/// Response for functions command
+#[derive(serde::Serialize)]
+struct FunctionsResponse {
+    count: usize,
+    functions: Vec<FunctionInfo>,
+}
+
+/// One indexed function
+#[derive(serde::Serialize)]
+struct FunctionInfo {
+    id: i64,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fqn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_path: Option<String>,
+    block_count: usize,
+    edge_count: usize,
+    paths_cached: bool,
+    /// When the function's paths were last cached (RFC3339)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+}
+
+/// Response for validate command
+#[derive(serde::Serialize)]
+struct ValidateResponse {
+    functions_checked: usize,
+    /// Functions with no stored CFG blocks
+    functions_skipped: usize,
+    invalid_count: usize,
+    invalid_functions: Vec<InvalidFunction>,
+}
+
+/// A function whose CFG failed one or more structural checks
+#[derive(serde::Serialize)]
+struct InvalidFunction {
+    id: i64,
+    name: String,
+    violations: Vec<crate::cfg::validate::Violation>,
+}
+
+/// Response for exits command
+#[derive(serde::Serialize)]
+struct ExitsResponse {
+    function: String,
+    exit_count: usize,
+    reachable_exit_count: usize,
+    /// Whether any normal path from the entry reaches a return
+    returns_normally: bool,
+    /// Path enumeration hit its limit; per-exit path counts are lower bounds
+    paths_truncated: bool,
+    exits: Vec<crate::cfg::exits::ExitInfo>,
+}
+
+/// Response for longest-path command
+#[derive(serde::Serialize)]
+struct LongestPathResponse {
+    function: String,
+    #[serde(flatten)]
+    path: crate::cfg::longest_path::LongestPath,
+}
+
+/// Response for coverage command
+#[derive(serde::Serialize)]
+struct CoverageResponse {
+    function: String,
+    path_count: usize,
+    /// Path enumeration hit its limit; some uncovered edges may be reachable
+    paths_truncated: bool,
+    edge_count: usize,
+    covered_count: usize,
+    /// Share of edges covered, 0-100 (100 for a CFG without edges)
+    coverage_percent: f64,
+    uncovered: Vec<UncoveredEdge>,
+}
+
+/// A CFG edge no enumerated path traverses
+#[derive(serde::Serialize)]
+struct UncoveredEdge {
+    from_block: usize,
+    to_block: usize,
+    edge_type: crate::cfg::EdgeType,
+}
+
+/// Response for `terminators --all`
+#[derive(serde::Serialize)]
+struct AllTerminatorsResponse {
+    function_count: usize,
+    /// Terminator kind counts summed over all functions
+    totals: std::collections::BTreeMap<String, usize>,
+    functions: Vec<crate::cfg::analysis::TerminatorStats>,
+}
+
+/// One enum value listed by `edge-types`, `terminator-kinds` or `block-kinds`
+#[derive(serde::Serialize)]
+struct KindInfo {
+    /// The value exactly as it appears in JSON output
+    name: String,
+    description: &'static str,
+}
+
+/// Response for edge-types, terminator-kinds and block-kinds commands
+#[derive(serde::Serialize)]
+struct KindsResponse {
+    kind: &'static str,
+    values: Vec<KindInfo>,
+}
+
+/// Response for prune command
+#[derive(serde::Serialize)]
+struct PruneResponse {
+    dry_run: bool,
+    /// Total rows removed (or that would be removed)
+    total: usize,
+    removed: crate::storage::prune::PruneReport,
+}
+
+/// Response for init-schema command
+#[derive(serde::Serialize)]
+struct InitSchemaResponse {
+    database: String,
+    /// False if the database already had the Mirage tables
+    created: bool,
+    mirage_schema_version: i32,
+}
+
+/// Response for export command
+#[derive(serde::Serialize)]
+struct ExportResponse {
+    database: String,
+    file: String,
+    functions: usize,
+}
+
+/// Response for import command
+#[derive(serde::Serialize)]
+struct ImportResponse {
+    database: String,
+    file: String,
+    functions: usize,
+}
+
+/// Response for unreachable --group-by-function
+#[derive(serde::Serialize)]
+struct GroupedUnreachableResponse {
+    total_functions: usize,
+    functions_with_unreachable: usize,
+    unreachable_count: usize,
+    functions: Vec<UnreachableFunction>,
+    /// Uncalled functions (only populated when --include-uncalled is set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uncalled_functions: Option<Vec<DeadSymbolJson>>,
+    /// Number of changed functions considered (only populated when --only-changed is set)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changed_functions: Option<usize>,
+}
+
+/// Unreachable blocks of a single function
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+struct UnreachableFunction {
+    function: String,
+    function_id: i64,
+    blocks: Vec<UnreachableBlock>,
 }
 
 /// Incoming edge information for unreachable blocks
-#[derive(serde::Serialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 struct IncomingEdge {
     from_block: usize,
     edge_type: String,
 }
 
 /// Unreachable block details for JSON output
-#[derive(serde::Serialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 struct UnreachableBlock {
     block_id: usize,
     kind: String,
     statements: Vec<String>,
     terminator: String,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     incoming_edges: Vec<IncomingEdge>,
+    /// Source lines of the block (only populated with --with-source)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<UnreachableSource>,
+    /// Why `source` is missing under --with-source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_note: Option<String>,
+
+    /// Set for blocks found by --conditional: the constant branch that hides them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    conditionally_dead: Option<String>,
+}
+
+/// Lines of context shown around an unreachable block with --with-source
+const UNREACHABLE_SOURCE_CONTEXT: usize = 2;
+
+/// Source snippet of an unreachable block
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+struct UnreachableSource {
+    file_path: String,
+    lines: Vec<crate::cfg::SnippetLine>,
 }
 
 /// Response for verify command
@@ -711,12 +1860,46 @@ struct VerifyResult {
     current_paths: usize,
 }
 
+/// Response for verify --function (batch mode)
+#[derive(serde::Serialize)]
+struct VerifyBatchResult {
+    function: String,
+    total: usize,
+    still_valid: usize,
+    invalidated: Vec<String>,
+    current_paths: usize,
+    /// Every cached path with the reason it is (in)valid
+    paths: Vec<PathVerdict>,
+}
+
+/// Why a cached path is or is not still valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+enum VerifyReason {
+    /// The path is still produced by the current enumeration
+    StillValid,
+    /// The path no longer starts at the function's entry block
+    EntryMissing,
+    /// The path starts at the entry but diverges from every current path
+    BranchChanged,
+    /// The function no longer has any CFG blocks
+    FunctionGone,
+}
+
+/// One cached path in a verify --function batch
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct PathVerdict {
+    path_id: String,
+    reason: VerifyReason,
+}
+
 /// Response for loops command
 #[derive(serde::Serialize)]
 struct LoopsResponse {
     function: String,
     loop_count: usize,
     loops: Vec<LoopInfo>,
+    /// Blocks that branch to themselves, kept out of `loops`
+    self_loops: Vec<LoopInfo>,
 }
 
 /// Information about a single natural loop
@@ -727,6 +1910,12 @@ struct LoopInfo {
     body_size: usize,
     nesting_level: usize,
     body_blocks: Vec<usize>,
+    /// Edges from a body block to a block outside the loop, as (from, to)
+    exit_edges: Vec<(usize, usize)>,
+    /// Blocks outside the loop reached by the exit edges
+    exit_blocks: Vec<usize>,
+    /// The header's unique predecessor outside the loop, if any
+    preheader: Option<usize>,
 }
 
 /// Response for patterns command
@@ -747,6 +1936,7 @@ struct IfElseInfo {
     false_branch: usize,
     merge_point: Option<usize>,
     has_else: bool,
+    shape: crate::cfg::patterns::IfShape,
 }
 
 /// Information about a single match pattern
@@ -756,56 +1946,200 @@ struct MatchInfo {
     branch_count: usize,
     targets: Vec<usize>,
     otherwise: usize,
+    /// `(arm index, target block)` for every non-default arm; positional, not the matched value
+    arm_index: Vec<(usize, usize)>,
 }
 
-/// Response for frontiers command
+/// Response for patterns --merge-points
 #[derive(serde::Serialize)]
-struct FrontiersResponse {
+struct MergePointsResponse {
     function: String,
-    nodes_with_frontiers: usize,
-    frontiers: Vec<NodeFrontier>,
+    merge_point_count: usize,
+    merge_points: Vec<MergePointInfo>,
 }
 
-/// Information about a single node's dominance frontier
+/// Information about a single control-flow join point
 #[derive(serde::Serialize)]
-struct NodeFrontier {
-    node: usize,
-    frontier_set: Vec<usize>,
+struct MergePointInfo {
+    block: usize,
+    predecessor_count: usize,
+    predecessors: Vec<usize>,
+    incoming_edge_types: Vec<crate::cfg::EdgeType>,
+    /// Nearest block dominating every predecessor (None if a predecessor is unreachable)
+    common_dominator: Option<usize>,
 }
 
-/// Response for iterated frontier command
+/// Response for patterns --guards
 #[derive(serde::Serialize)]
-struct IteratedFrontierResponse {
+struct GuardsResponse {
     function: String,
-    iterated_frontier: Vec<usize>,
+    guard_count: usize,
+    guards: Vec<GuardInfo>,
 }
 
-/// Response for block impact analysis (blast zone)
+/// Information about a single guard clause
 #[derive(serde::Serialize)]
-struct BlockImpactResponse {
-    function: String,
-    block_id: usize,
-    reachable_blocks: Vec<usize>,
-    reachable_count: usize,
-    max_depth: usize,
-    has_cycles: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    forward_impact: Option<Vec<CallGraphSymbol>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    backward_impact: Option<Vec<CallGraphSymbol>>,
+struct GuardInfo {
+    condition_block: usize,
+    /// First block of the arm that exits early
+    guard_branch: usize,
+    /// First block of the arm that continues the main flow
+    continuation: usize,
+    /// Which arm is the guard: "true" or "false"
+    guard_arm: &'static str,
+    exit_block: usize,
+    exit_distance: usize,
 }
 
-/// Response for path impact analysis (blast zone)
+/// Response for patterns --unbalanced
 #[derive(serde::Serialize)]
-struct PathImpactResponse {
-    path_id: String,
-    path_length: usize,
-    unique_blocks_affected: Vec<usize>,
-    impact_count: usize,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    forward_impact: Option<Vec<CallGraphSymbol>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    backward_impact: Option<Vec<CallGraphSymbol>>,
+struct UnbalancedResponse {
+    function: String,
+    threshold: f64,
+    unbalanced_count: usize,
+    branches: Vec<UnbalancedInfo>,
+}
+
+/// Information about a single lopsided if/else
+#[derive(serde::Serialize)]
+struct UnbalancedInfo {
+    condition_block: usize,
+    true_branch: usize,
+    false_branch: usize,
+    /// Blocks only reachable through each arm
+    true_blocks: usize,
+    false_blocks: usize,
+    ratio: f64,
+}
+
+/// Response for frontiers command
+#[derive(serde::Serialize)]
+struct FrontiersResponse {
+    function: String,
+    nodes_with_frontiers: usize,
+    frontiers: Vec<NodeFrontier>,
+    /// Every entry-like block, when the CFG has more than one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry_blocks: Option<Vec<usize>>,
+}
+
+/// Response for control-deps command
+#[derive(serde::Serialize)]
+struct ControlDepsResponse {
+    function: String,
+    block: usize,
+    /// Branch blocks the block is control dependent on (its post-dominance frontier)
+    control_dependences: Vec<usize>,
+}
+
+/// Response for cleanup command
+#[derive(serde::Serialize)]
+struct CleanupResponse {
+    function: String,
+    block: usize,
+    /// Blocks that run on every path from the block to the exit, in execution order
+    guaranteed_after: Vec<usize>,
+}
+
+/// Information about a single node's dominance frontier
+#[derive(serde::Serialize)]
+struct NodeFrontier {
+    node: usize,
+    frontier_set: Vec<usize>,
+}
+
+/// Response for iterated frontier command
+#[derive(serde::Serialize)]
+struct IteratedFrontierResponse {
+    function: String,
+    iterated_frontier: Vec<usize>,
+}
+
+/// Response for explain command
+#[derive(serde::Serialize)]
+struct ExplanationResponse {
+    function: String,
+    summary: String,
+    branches: Vec<crate::cfg::explain::BranchExplanation>,
+    loops: Vec<crate::cfg::explain::LoopExplanation>,
+    exits: Vec<crate::cfg::explain::ExitExplanation>,
+}
+
+/// Response for blocks command
+#[derive(serde::Serialize)]
+struct BlocksResponse {
+    function_id: i64,
+    blocks: Vec<crate::storage::CfgBlockData>,
+}
+
+/// Response for block-at command
+#[derive(serde::Serialize)]
+struct BlockAtResponse {
+    function: String,
+    line: usize,
+    /// Blocks covering the line, ordered by byte offset
+    blocks: Vec<BlockAtEntry>,
+}
+
+/// A block covering the queried line
+#[derive(serde::Serialize)]
+struct BlockAtEntry {
+    block_id: usize,
+    kind: String,
+    terminator: String,
+    statements: Vec<String>,
+    byte_start: usize,
+    byte_end: usize,
+    source_location: String,
+}
+
+/// Response for block impact analysis (blast zone)
+#[derive(serde::Serialize)]
+struct BlockImpactResponse {
+    function: String,
+    block_id: usize,
+    direction: crate::cfg::ImpactDirection,
+    reachable_blocks: Vec<usize>,
+    reachable_count: usize,
+    max_depth: usize,
+    has_cycles: bool,
+    /// Immediate post-dominator the walk stopped at (only with --until-convergence)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    convergence_block: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    forward_impact: Option<Vec<CallGraphSymbol>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backward_impact: Option<Vec<CallGraphSymbol>>,
+}
+
+/// Response for path impact analysis (blast zone)
+#[derive(serde::Serialize)]
+struct PathImpactResponse {
+    path_id: String,
+    path_length: usize,
+    unique_blocks_affected: Vec<usize>,
+    impact_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    forward_impact: Option<Vec<CallGraphSymbol>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backward_impact: Option<Vec<CallGraphSymbol>>,
+}
+
+/// Response for blast-zone --callers / --callees
+#[derive(serde::Serialize)]
+struct CallImpactResponse {
+    target: String,
+    direction: crate::analysis::CallDirection,
+    functions: Vec<CallerEntry>,
+}
+
+/// A function in the call-graph impact set
+#[derive(serde::Serialize)]
+struct CallerEntry {
+    function_id: i64,
+    name: String,
+    distance: usize,
+    has_cfg: bool,
 }
 
 /// Call graph symbol for impact analysis
@@ -855,30 +2189,29 @@ struct HotspotEntry {
 
 pub mod cmds {
     use super::*;
-    use crate::output;
+    use crate::output::{self, out, outln};
     use anyhow::Result;
 
 
     pub fn status(_args: &StatusArgs, cli: &Cli) -> Result<()> {
-        use crate::storage::MirageDb;
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
         // Open database
-        let db = match MirageDb::open(&db_path) {
+        let db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 // JSON-aware error handling with remediation
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -892,36 +2225,373 @@ pub mod cmds {
         match cli.output {
             OutputFormat::Human => {
                 // Human-readable text format
-                println!("Mirage Database Status:");
-                println!("  Schema version: {} (Magellan: {})", status.mirage_schema_version, status.magellan_schema_version);
-                println!("  cfg_blocks: {}", status.cfg_blocks);
+                outln!("Mirage Database Status:");
+                outln!("  Schema version: {} (Magellan: {})", status.mirage_schema_version, status.magellan_schema_version);
+                outln!("  cfg_blocks: {}", status.cfg_blocks);
                 // cfg_edges are computed in memory, not stored
-                println!("  cfg_paths: {}", status.cfg_paths);
-                println!("  cfg_dominators: {}", status.cfg_dominators);
+                outln!("  cfg_paths: {}", status.cfg_paths);
+                outln!("  cfg_dominators: {}", status.cfg_dominators);
+                match status.cached_path_id_version {
+                    Some(cached) if cached != status.path_id_version => outln!(
+                        "  Path ID scheme: v{} (cached paths use v{} and will be re-enumerated)",
+                        status.path_id_version, cached
+                    ),
+                    _ => outln!("  Path ID scheme: v{}", status.path_id_version),
+                }
             }
             OutputFormat::Json => {
                 // Compact JSON
                 let response = output::JsonResponse::new(status);
-                println!("{}", response.to_json());
+                outln!("{}", response.to_json());
             }
             OutputFormat::Pretty => {
                 // Formatted JSON with indentation
                 let response = output::JsonResponse::new(status);
-                println!("{}", response.to_pretty_json());
+                outln!("{}", response.to_pretty_json());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn functions(args: &FunctionsArgs, cli: &Cli) -> Result<()> {
+        use crate::storage::paths::{cached_path_counts, paths_cached_since};
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        let since = args.since.as_deref().map(|value| {
+            match parse_since(value, chrono::Utc::now().timestamp()) {
+                Ok(since) => since,
+                Err(e) => {
+                    if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                        let error = output::JsonError::new("InvalidInput", &e.to_string(), output::E_INVALID_INPUT);
+                        let wrapper = output::JsonResponse::new(error);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_USAGE);
+                    } else {
+                        output::error(&e.to_string());
+                        output::exit(output::EXIT_USAGE);
+                    }
+                }
+            }
+        });
+
+        let db = match super::session::open_readonly(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let function_ids = match db.storage().function_ids() {
+            Ok(functions) => functions,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "QueryError",
+                        &format!("Failed to query functions: {}", e),
+                        output::E_DATABASE_NOT_FOUND,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to query functions: {}", e));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Path caching is SQLite-only; other backends report nothing cached
+        let cached_paths = db.conn().and_then(cached_path_counts).unwrap_or_default();
+        let cached_at = db.conn().and_then(|conn| paths_cached_since(conn, since)).unwrap_or_default();
+
+        let mut functions: Vec<FunctionInfo> = function_ids
+            .into_iter()
+            .filter_map(|(id, name)| {
+                let entity = db.storage().get_entity(id);
+                let fqn = entity.as_ref()
+                    .and_then(|e| e.data.get("fqn").and_then(|v| v.as_str()).map(str::to_string));
+                if let Some(ref filter) = args.filter {
+                    if !name.contains(filter.as_str()) && !fqn.as_deref().is_some_and(|f| f.contains(filter.as_str())) {
+                        return None;
+                    }
+                }
+                let created_at = cached_at.get(&id).copied();
+                if since.is_some() && created_at.is_none() {
+                    return None;
+                }
+                // A function without CFG blocks fails to load; list it as empty
+                let (block_count, edge_count) = db.load_cfg(id)
+                    .map(|cfg| (cfg.node_count(), cfg.edge_count()))
+                    .unwrap_or((0, 0));
+                Some(FunctionInfo {
+                    id,
+                    name,
+                    fqn,
+                    file_path: entity.and_then(|e| e.file_path),
+                    block_count,
+                    edge_count,
+                    paths_cached: cached_paths.contains_key(&id),
+                    created_at: created_at
+                        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+                        .map(|t| t.to_rfc3339()),
+                })
+            })
+            .collect();
+
+        // function_ids() is already in name order; the other sorts are stable on it
+        match args.sort {
+            FunctionSort::Name => {}
+            FunctionSort::Blocks => functions.sort_by_key(|f| std::cmp::Reverse(f.block_count)),
+            FunctionSort::Edges => functions.sort_by_key(|f| std::cmp::Reverse(f.edge_count)),
+        }
+        if let Some(limit) = args.limit {
+            functions.truncate(limit);
+        }
+
+        if args.format == Some(TableFormat::Csv) {
+            outln!("{}", output::csv_record(&["id", "name", "fqn", "file_path", "block_count", "edge_count", "paths_cached", "created_at"]));
+            for f in &functions {
+                outln!("{}", output::csv_record(&[
+                    f.id.to_string(),
+                    f.name.clone(),
+                    f.fqn.clone().unwrap_or_default(),
+                    f.file_path.clone().unwrap_or_default(),
+                    f.block_count.to_string(),
+                    f.edge_count.to_string(),
+                    f.paths_cached.to_string(),
+                    f.created_at.clone().unwrap_or_default(),
+                ]));
+            }
+            return Ok(());
+        }
+
+        let response = FunctionsResponse {
+            count: functions.len(),
+            functions,
+        };
+
+        match cli.output {
+            OutputFormat::Human => {
+                if response.functions.is_empty() {
+                    output::info("No functions found");
+                    return Ok(());
+                }
+                outln!("Functions: {}", response.count);
+                outln!();
+                for function in &response.functions {
+                    let cached = match (function.paths_cached, &function.created_at) {
+                        (true, Some(created_at)) => format!(", paths cached {}", created_at),
+                        (true, None) => ", paths cached".to_string(),
+                        (false, _) => String::new(),
+                    };
+                    outln!(
+                        "  [{}] {} ({} blocks, {} edges{})",
+                        function.id,
+                        function.fqn.as_deref().unwrap_or(&function.name),
+                        function.block_count,
+                        function.edge_count,
+                        cached
+                    );
+                    if let Some(ref file_path) = function.file_path {
+                        outln!("      {}", file_path);
+                    }
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    /// Resolve a `--since` value to Unix seconds
+    ///
+    /// Accepts an RFC3339 timestamp, or a duration before `now` written as a
+    /// number and a unit: `s`, `m`, `h`, `d` or `w`.
+    pub(crate) fn parse_since(value: &str, now: i64) -> Result<i64> {
+        let value = value.trim();
+        if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+            return Ok(time.timestamp());
+        }
+
+        let invalid = || anyhow::anyhow!(
+            "Invalid --since '{}': expected an RFC3339 timestamp or a duration such as 30m, 1h or 2d",
+            value
+        );
+        let unit_start = value.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let (amount, unit) = value.split_at(unit_start);
+        let amount: i64 = amount.parse().map_err(|_| invalid())?;
+        let unit_seconds = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return Err(invalid()),
+        };
+        amount.checked_mul(unit_seconds)
+            .map(|ago| now - ago)
+            .ok_or_else(invalid)
+    }
+
+    pub fn validate(_args: &ValidateArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::validate::{validate_cfg, ViolationKind};
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        let db = match super::session::open_readonly(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let function_ids = match db.storage().function_ids() {
+            Ok(functions) => functions,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "QueryError",
+                        &format!("Failed to query functions: {}", e),
+                        output::E_DATABASE_NOT_FOUND,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to query functions: {}", e));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let mut functions_checked = 0;
+        let mut functions_skipped = 0;
+        let mut invalid_functions = Vec::new();
+        for (id, name) in function_ids {
+            // Functions without CFG blocks (declarations, unindexed bodies) fail to load
+            let Ok(cfg) = db.load_cfg(id) else {
+                functions_skipped += 1;
+                continue;
+            };
+            functions_checked += 1;
+            let violations = validate_cfg(&cfg);
+            if !violations.is_empty() {
+                invalid_functions.push(InvalidFunction { id, name, violations });
+            }
+        }
+
+        let response = ValidateResponse {
+            functions_checked,
+            functions_skipped,
+            invalid_count: invalid_functions.len(),
+            invalid_functions,
+        };
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Functions checked: {}", response.functions_checked);
+                if response.functions_skipped > 0 {
+                    outln!("Functions without CFG: {}", response.functions_skipped);
+                }
+                if response.invalid_functions.is_empty() {
+                    output::success("All CFGs are well-formed");
+                } else {
+                    outln!("Invalid functions: {}", response.invalid_count);
+                    outln!();
+                    for function in &response.invalid_functions {
+                        outln!("  [{}] {}", function.id, function.name);
+                        for violation in &function.violations {
+                            match violation.kind {
+                                ViolationKind::MultipleEntries => outln!(
+                                    "    Multiple entries: blocks {:?} have no predecessors", violation.blocks),
+                                ViolationKind::UnreachableExit => outln!(
+                                    "    Unreachable exit: Block {} cannot be reached from the entry", violation.blocks[0]),
+                                ViolationKind::DisconnectedComponent => outln!(
+                                    "    Disconnected component: blocks {:?} share no edge with the entry", violation.blocks),
+                            }
+                        }
+                    }
+                    outln!();
+                    output::info("Hint: Re-index the affected files with 'magellan watch'");
+                }
             }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(&response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(&response).to_pretty_json()),
         }
 
+        if response.invalid_count > 0 {
+            output::exit(output::EXIT_VALIDATION);
+        }
         Ok(())
     }
 
     pub fn paths(args: &PathsArgs, cli: &Cli) -> Result<()> {
-        use crate::cfg::{PathKind, PathLimits, get_or_enumerate_paths, enumerate_paths_incremental};
+        use crate::cfg::{PathKind, get_or_enumerate_paths, enumerate_paths_incremental};
+        use crate::cfg::{compute_edge_weights, panic_block_ids};
+        use crate::cfg::paths::{count_paths, dedup_by_blockset, enumerate_paths_from, path_callees, path_ordinals, readable_path_id, summarize_paths};
+        use crate::cfg::weights::{path_probability, sort_by_likelihood};
         use crate::cfg::{resolve_function_name, load_cfg_from_db};
-        use crate::storage::{MirageDb, get_function_hash_db};
+        use crate::storage::{get_function_hash_db, get_function_name_db};
+
+        if let Some(ref list_path) = args.functions_from_file {
+            let prepare = |db: &mut crate::storage::MirageDb, functions: &[&str], jobs: usize| {
+                warm_path_cache(args, cli, db, functions, jobs)
+            };
+            return functions_from_file(list_path, args.jobs, args.timings, cli, Some(&prepare), |function| {
+                let run = PathsArgs {
+                    function: function.to_string(),
+                    functions_from_file: None,
+                    ..args.clone()
+                };
+                paths(&run, cli)
+            });
+        }
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
+        // Reject an empty length band before doing any work
+        if let (Some(min_length), Some(max_length)) = (args.min_length, args.max_length) {
+            if min_length > max_length {
+                let msg = format!("--min-length {} exceeds --max-length {}", min_length, max_length);
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new("InvalidInput", &msg, output::E_INVALID_INPUT);
+                    let wrapper = output::JsonResponse::new(error);
+                    output::print_json(&wrapper, false);
+                    output::exit(output::EXIT_USAGE);
+                } else {
+                    output::error(&msg);
+                    output::exit(output::EXIT_USAGE);
+                }
+            }
+        }
+
+        // Compare against a second database if requested
+        if let Some(ref db2_path) = args.db2 {
+            return paths_across_databases(args, &db_path, db2_path, cli);
+        }
+
         // Detect repository path for incremental mode
         let repo_path = detect_repo_path(&db_path);
 
@@ -931,18 +2601,18 @@ pub mod cmds {
                 .ok_or_else(|| anyhow::anyhow!("--since required with --incremental"))?;
 
             // Open database for incremental mode
-            let db = match MirageDb::open(&db_path) {
+            let db = match super::session::open(&db_path) {
                 Ok(db) => db,
-                Err(_e) => {
+                Err(e) => {
                     if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                        let error = output::JsonError::database_not_found(&db_path);
+                        let error = output::JsonError::database_open_failed(&db_path, &e);
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::print_json(&wrapper, false);
+                        output::exit(output::EXIT_DATABASE);
                     } else {
                         output::error(&format!("Failed to open database: {}", db_path));
-                        output::info("Hint: Run 'magellan watch' to create the database");
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                        output::exit(output::EXIT_DATABASE);
                     }
                 }
             };
@@ -964,11 +2634,11 @@ pub mod cmds {
                             output::E_CFG_ERROR,
                         );
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::print_json(&wrapper, false);
+                        output::exit(output::EXIT_DATABASE);
                     } else {
                         output::error(&format!("Incremental analysis failed: {}", e));
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::exit(output::EXIT_DATABASE);
                     }
                 }
             };
@@ -976,22 +2646,22 @@ pub mod cmds {
             // Output results
             match cli.output {
                 OutputFormat::Human => {
-                    println!("Incremental path enumeration (since {}):", since);
-                    println!("  Analyzed functions: {}", result.analyzed_functions);
-                    println!("  Total paths: {}", result.paths.len());
+                    outln!("Incremental path enumeration (since {}):", since);
+                    outln!("  Analyzed functions: {}", result.analyzed_functions);
+                    outln!("  Total paths: {}", result.paths.len());
 
                     if args.show_errors {
                         let error_count = result.paths.iter()
                             .filter(|p| matches!(p.kind, PathKind::Error))
                             .count();
-                        println!("  Error paths: {}", error_count);
+                        outln!("  Error paths: {}", error_count);
                     }
 
                     if !result.paths.is_empty() {
-                        println!("\nPaths:");
+                        outln!("\nPaths:");
                         for path in &result.paths {
                             if args.show_errors || !matches!(path.kind, PathKind::Error) {
-                                println!("  {}", path);
+                                outln!("  {}", path);
                             }
                         }
                     }
@@ -1005,7 +2675,7 @@ pub mod cmds {
                         "total_paths": result.paths.len(),
                         "paths": result.paths,
                     });
-                    println!("{}", serde_json::to_string(&response)?);
+                    outln!("{}", serde_json::to_string(&response)?);
                 }
                 OutputFormat::Pretty => {
                     let response = serde_json::json!({
@@ -1016,7 +2686,7 @@ pub mod cmds {
                         "total_paths": result.paths.len(),
                         "paths": result.paths,
                     });
-                    println!("{}", serde_json::to_string_pretty(&response)?);
+                    outln!("{}", serde_json::to_string_pretty(&response)?);
                 }
             }
 
@@ -1024,20 +2694,25 @@ pub mod cmds {
         }
 
         // Standard path enumeration (non-incremental)
-        // Open database
-        let mut db = match MirageDb::open(&db_path) {
+        // Open database (counting and summaries never touch the path cache)
+        let db_result = if args.count_only || args.summary_only {
+            super::session::open_readonly(&db_path)
+        } else {
+            super::session::open(&db_path)
+        };
+        let mut db = match db_result {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 // JSON-aware error handling with remediation
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::print_json(&wrapper, false);
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -1049,18 +2724,21 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::function_not_found(&args.function);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::print_json(&wrapper, false);
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Function '{}' not found in database", args.function));
                     output::info("Hint: Run 'magellan watch' to index your code");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, &args.function, cli);
+
         // Load CFG from database
-        let cfg = match load_cfg_from_db(&db, function_id) {
+        let mut cfg = match load_cfg_from_db(&db, function_id) {
             Ok(cfg) => cfg,
             Err(_e) => {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
@@ -1070,28 +2748,97 @@ pub mod cmds {
                         output::E_CFG_ERROR,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::print_json(&wrapper, false);
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to load CFG for function '{}'", args.function));
                     output::info("The function may be corrupted. Try re-running 'magellan watch'");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Resolve byte-only block locations to lines from the source files
+        crate::cfg::SourceMap::new().resolve_cfg(&mut cfg);
+
+        // Calls made from the function body; without a call graph, paths list no callees
+        let call_sites = if args.count_only || (args.summary_only && args.calls.is_none()) {
+            Vec::new()
+        } else {
+            crate::analysis::function_call_sites(db.backend(), function_id).unwrap_or_default()
+        };
+
         // Build path limits based on args
-        let mut limits = PathLimits::default();
-        if let Some(max_length) = args.max_length {
-            limits = limits.with_max_length(max_length);
-        }
+        let limits = path_limits(args);
 
-        // Enumerate paths (backend-agnostic)
-        // For SQLite backend: use get_or_enumerate_paths for caching
-        // For native-v2 backend: use enumerate_paths directly (no caching)
-        let mut paths = if db.is_sqlite() {
-            // SQLite backend: use caching layer
-            let function_hash = match get_function_hash_db(&db, function_id) {
+        // Count without materializing paths if requested
+        if args.count_only {
+            let counts = count_paths(&cfg, &limits);
+            match cli.output {
+                OutputFormat::Human => {
+                    outln!("Function: {}", args.function);
+                    outln!("Total paths: {}", counts.total);
+                    outln!("  Normal: {}", counts.normal);
+                    outln!("  Error: {}", counts.error);
+                    outln!("  Through panic blocks: {}", counts.panic);
+                    if counts.truncated {
+                        output::warn(&format!("Stopped at the {} path limit; counts are incomplete", limits.max_paths));
+                    }
+                }
+                OutputFormat::Json | OutputFormat::Pretty => {
+                    let response = PathCountsResponse {
+                        function: args.function.clone(),
+                        counts,
+                    };
+                    let wrapper = output::JsonResponse::new(response);
+                    match cli.output {
+                        OutputFormat::Json => output::print_json(&wrapper, false),
+                        OutputFormat::Pretty => output::print_json(&wrapper, true),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        // Without path-level filters, summary statistics come from counting alone
+        if args.summary_only && !has_path_filters(args) {
+            let stats = crate::cfg::paths::count_path_summary(&cfg, &limits);
+            if stats.truncated && matches!(cli.output, OutputFormat::Human) {
+                output::warn(&format!("Stopped at the {} path limit; statistics are incomplete", limits.max_paths));
+            }
+            print_path_summary_stats(&args.function, stats, cli);
+            return Ok(());
+        }
+
+        // Resolve --entry to a CFG node
+        let start_node = args.entry.map(|block_id| {
+            match cfg.node_indices().find(|&n| cfg[n].id == block_id) {
+                Some(node) => node,
+                None => {
+                    if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                        let error = output::JsonError::block_not_found(block_id);
+                        let wrapper = output::JsonResponse::new(error);
+                        output::print_json(&wrapper, false);
+                        output::exit(1);
+                    } else {
+                        output::error(&format!("Block {} not found in CFG", block_id));
+                        output::exit(1);
+                    }
+                }
+            }
+        });
+
+        // Enumerate paths (backend-agnostic)
+        // With --entry: enumerate from that block directly (the cache holds whole-function paths)
+        // For SQLite backend: use get_or_enumerate_paths for caching, unless the limits
+        // differ from those the cache was filled with
+        // For native-v2 backend: use enumerate_paths directly (no caching)
+        let mut paths = if let Some(start) = start_node {
+            enumerate_paths_from(&cfg, start, &limits)
+        } else if db.is_sqlite() && uses_path_cache(args) {
+            // SQLite backend: use caching layer
+            let function_hash = match get_function_hash_db(&db, function_id) {
                 Some(hash) => hash,
                 None => {
                     if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
@@ -1101,12 +2848,12 @@ pub mod cmds {
                             output::E_CFG_ERROR,
                         );
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::print_json(&wrapper, false);
+                        output::exit(output::EXIT_DATABASE);
                     } else {
                         output::error(&format!("Function hash not found for '{}'", args.function));
                         output::info("The function data may be incomplete. Try re-running 'magellan watch'");
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::exit(output::EXIT_DATABASE);
                     }
                 }
             };
@@ -1115,35 +2862,180 @@ pub mod cmds {
                 &cfg,
                 function_id,
                 &function_hash,
-                &limits,
+                &cache_fill_limits(args),
                 db.conn_mut()?,
             ).map_err(|e| anyhow::anyhow!("Path enumeration failed: {}", e))?
         } else {
-            // Native-v2 backend: enumerate directly without caching
-            // Magellan manages its own caching
+            // Native-v2 backend (Magellan manages its own caching) or non-default
+            // limits: enumerate directly without caching
             crate::cfg::enumerate_paths(&cfg, &limits)
         };
 
+        // Hitting the cap leaves a usable partial result, reported as truncated
+        let truncated_at = limits.is_truncated(paths.len()).then_some(paths.len());
+        if let (Some(reached), OutputFormat::Human) = (truncated_at, cli.output) {
+            output::warn(&format!(
+                "Stopped at the {} path limit after {} paths; results are incomplete (raise with --max-paths)",
+                limits.max_paths, reached
+            ));
+        }
+
+        // Number readable aliases over every enumerated path, as the cache does,
+        // so filters below never shift them
+        let readable_ids: Option<std::collections::HashMap<String, String>> = args.readable_ids.then(|| {
+            let function_name = get_function_name_db(&db, function_id).unwrap_or_else(|| args.function.clone());
+            paths.iter()
+                .zip(path_ordinals(&paths))
+                .map(|(p, ordinal)| (p.path_id.clone(), readable_path_id(&function_name, ordinal)))
+                .collect()
+        });
+
+        // Apply the length band to cached paths; a fresh enumeration already skipped them
+        let length_filtered = args.min_length.map(|_| limits.retain_length_band(&mut paths));
+
         // Filter to error paths if requested
         if args.show_errors {
             paths.retain(|p| p.kind == PathKind::Error);
         }
 
+        // Drop panic scaffolding paths if requested
+        if args.skip_panic_blocks {
+            let panic_blocks = panic_block_ids(&cfg);
+            paths.retain(|p| !p.blocks.iter().any(|b| panic_blocks.contains(b)));
+        }
+
+        // Filter by how paths end if requested
+        let natural_exit = crate::cfg::paths::natural_exit(&cfg);
+        if let Some(ends_in) = args.ends_in {
+            let ends_in = crate::cfg::EndKind::from(ends_in);
+            paths.retain(|p| crate::cfg::paths::classify_end(&cfg, p, natural_exit) == ends_in);
+        }
+
+        // Classify paths by loop iteration; filter if requested
+        let back_edges = crate::cfg::paths::back_edge_block_ids(&cfg);
+        if args.loops_only || args.no_loops {
+            paths.retain(|p| crate::cfg::paths::path_loop_headers(p, &back_edges).is_empty() == args.no_loops);
+        }
+
+        // Keep paths that reach a given callee if requested
+        if let Some(ref callee) = args.calls {
+            paths.retain(|p| path_callees(&cfg, p, &call_sites).contains(callee));
+        }
+
+        // Collapse same-blockset paths if requested
+        let collapsed_count = if args.dedup_by_blockset {
+            let (deduped, collapsed) = dedup_by_blockset(paths);
+            paths = deduped;
+            Some(collapsed)
+        } else {
+            None
+        };
+
+        // Report headline statistics instead of the paths if requested
+        if args.summary_only {
+            print_path_summary_stats(&args.function, summarize_paths(&cfg, &paths, truncated_at.is_some()), cli);
+            return Ok(());
+        }
+
+        // Sort for stable output; --most-likely reorders on top of this
+        crate::cfg::paths::sort_paths(&mut paths, args.sort.into(), args.desc);
+
+        // Order by branch probability if requested, using the weights stored
+        // with the CFG's edges where the backend has them
+        let weights = if args.most_likely {
+            let stored = db.conn().ok()
+                .and_then(|conn| crate::storage::load_edge_weights(conn, function_id, &cfg).ok());
+            let weights = stored.unwrap_or_else(|| compute_edge_weights(&cfg));
+            sort_by_likelihood(&mut paths, &weights);
+            Some(weights)
+        } else {
+            None
+        };
+        let statement_limits = cli.statement_limits();
+        // Source text for --context-lines; each file is read once
+        let snippets: Option<std::collections::HashMap<String, Vec<PathSnippet>>> = args.context_lines.map(|context| {
+            let mut source_map = crate::cfg::SourceMap::new();
+            paths.iter()
+                .map(|p| (p.path_id.clone(), path_snippets(p, &cfg, context, &mut source_map)))
+                .collect()
+        });
+        let summarize = |p: &crate::cfg::Path| {
+            let summary = PathSummary {
+                probability: weights.as_ref().map(|w| path_probability(p, w)),
+                readable_id: readable_ids.as_ref().and_then(|ids| ids.get(&p.path_id).cloned()),
+                source_snippets: snippets.as_ref().and_then(|s| s.get(&p.path_id).cloned()),
+                callees: path_callees(&cfg, p, &call_sites),
+                ..PathSummary::from_with_cfg(p.clone(), &cfg)
+            }.with_loops(p, &back_edges);
+            if args.include_statements {
+                let mut summary = summary.with_statements(&cfg);
+                for block in &mut summary.blocks {
+                    block.statements = block.statements.as_deref().map(|s| statement_limits.apply(s));
+                }
+                summary
+            } else {
+                summary
+            }
+        };
+
         // Count error paths for reporting
         let error_count = paths.iter().filter(|p| p.kind == PathKind::Error).count();
 
+        if args.tree {
+            let tree = crate::cfg::path_tree::PathTree::from_paths(&paths);
+            match cli.output {
+                OutputFormat::Human => {
+                    outln!("Function: {}", args.function);
+                    outln!("Total paths: {}", paths.len());
+                    outln!("Error paths: {}", error_count);
+                    outln!();
+                    if paths.is_empty() {
+                        output::info("No paths found");
+                    } else {
+                        out!("{}", tree);
+                    }
+                }
+                OutputFormat::Json | OutputFormat::Pretty => {
+                    let response = PathTreeResponse {
+                        function: args.function.clone(),
+                        total_paths: paths.len(),
+                        error_paths: error_count,
+                        truncated: truncated_at.is_some(),
+                        node_count: tree.node_count(),
+                        tree,
+                    };
+                    let wrapper = output::JsonResponse::new(response);
+                    match cli.output {
+                        OutputFormat::Json => output::print_json(&wrapper, false),
+                        OutputFormat::Pretty => output::print_json(&wrapper, true),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+            return Ok(());
+        }
+
         // Format output based on cli.output
         match cli.output {
             OutputFormat::Human => {
                 // Human-readable text format
-                println!("Function: {}", args.function);
-                println!("Total paths: {}", paths.len());
+                outln!("Function: {}", args.function);
+                outln!("Total paths: {}", paths.len());
                 if args.show_errors {
-                    println!("(Showing error paths only)");
+                    outln!("(Showing error paths only)");
                 } else {
-                    println!("Error paths: {}", error_count);
+                    outln!("Error paths: {}", error_count);
+                }
+                if args.most_likely {
+                    outln!("(Ordered by likelihood, most likely first)");
+                }
+                if let Some(collapsed) = collapsed_count {
+                    outln!("Collapsed paths (same block set): {}", collapsed);
+                }
+                if let Some(filtered) = length_filtered {
+                    outln!("Filtered by length band: {}", filtered);
                 }
-                println!();
+                outln!();
 
                 if paths.is_empty() {
                     output::info("No paths found");
@@ -1151,16 +3043,56 @@ pub mod cmds {
                 }
 
                 for (i, path) in paths.iter().enumerate() {
-                    println!("Path {}: {}", i + 1, path.path_id);
-                    println!("  Kind: {:?}", path.kind);
-                    println!("  Length: {} blocks", path.len());
+                    match readable_ids.as_ref().and_then(|ids| ids.get(&path.path_id)) {
+                        Some(readable_id) => outln!("Path {}: {} ({})", i + 1, readable_id, path.path_id),
+                        None => outln!("Path {}: {}", i + 1, path.path_id),
+                    }
+                    outln!("  Kind: {:?}", path.kind);
+                    outln!("  Ends in: {:?}", crate::cfg::paths::classify_end(&cfg, path, natural_exit));
+                    outln!("  Length: {} blocks", path.len());
+                    if let Some(ref weights) = weights {
+                        outln!("  Probability: {:.4}", path_probability(path, weights));
+                    }
+                    let loop_headers = crate::cfg::paths::path_loop_headers(path, &back_edges);
+                    if !loop_headers.is_empty() {
+                        outln!("  Loops: iterates header(s) {:?}", loop_headers);
+                    }
+                    let callees = path_callees(&cfg, path, &call_sites);
+                    if !callees.is_empty() {
+                        outln!("  Calls: {}", callees.join(", "));
+                    }
                     if args.with_blocks {
-                        println!("  Blocks: {}", path.blocks.iter()
+                        outln!("  Blocks: {}", path.blocks.iter()
                             .map(|id| id.to_string())
                             .collect::<Vec<_>>()
                             .join(" -> "));
+                        for block in path.blocks.iter().filter_map(|&id| cfg.node_weights().find(|b| b.id == id)) {
+                            if let Some(ref location) = block.source_location {
+                                outln!("    b{}: {}", block.id, location.display_or_bytes());
+                            }
+                        }
+                        for snippet in snippets.as_ref().and_then(|s| s.get(&path.path_id)).into_iter().flatten() {
+                            match snippet.lines {
+                                Some(ref lines) => {
+                                    outln!("  Source ({}):", snippet.file_path);
+                                    for line in lines {
+                                        let marker = if line.covered { '>' } else { ' ' };
+                                        outln!("  {} {:>5} | {}", marker, line.line, line.text);
+                                    }
+                                }
+                                None => outln!("  Source: {}", snippet.note.as_deref().unwrap_or_default()),
+                            }
+                        }
+                    }
+                    if args.include_statements {
+                        outln!("  Statements:");
+                        for block in path.blocks.iter().filter_map(|&id| cfg.node_weights().find(|b| b.id == id)) {
+                            for statement in statement_limits.apply(&block.statements) {
+                                outln!("    b{}: {}", block.id, statement);
+                            }
+                        }
                     }
-                    println!();
+                    outln!();
                 }
             }
             OutputFormat::Json => {
@@ -1169,10 +3101,14 @@ pub mod cmds {
                     function: args.function.clone(),
                     total_paths: paths.len(),
                     error_paths: error_count,
-                    paths: paths.iter().map(|p| PathSummary::from_with_cfg(p.clone(), &cfg)).collect(),
+                    paths: paths.iter().map(summarize).collect(),
+                    truncated: truncated_at.is_some(),
+                    truncated_at,
+                    collapsed_count,
+                    length_filtered,
                 };
                 let wrapper = output::JsonResponse::new(response);
-                println!("{}", wrapper.to_json());
+                output::print_json(&wrapper, false);
             }
             OutputFormat::Pretty => {
                 // Formatted JSON with indentation and source locations from CFG
@@ -1180,10 +3116,14 @@ pub mod cmds {
                     function: args.function.clone(),
                     total_paths: paths.len(),
                     error_paths: error_count,
-                    paths: paths.iter().map(|p| PathSummary::from_with_cfg(p.clone(), &cfg)).collect(),
+                    paths: paths.iter().map(summarize).collect(),
+                    truncated: truncated_at.is_some(),
+                    truncated_at,
+                    collapsed_count,
+                    length_filtered,
                 };
                 let wrapper = output::JsonResponse::new(response);
-                println!("{}", wrapper.to_pretty_json());
+                output::print_json(&wrapper, true);
             }
         }
 
@@ -1191,48 +3131,65 @@ pub mod cmds {
     }
 
     pub fn cfg(args: &CfgArgs, cli: &Cli) -> Result<()> {
-        use crate::cfg::{export_dot, export_json, CFGExport};
         use crate::cfg::{resolve_function_name, load_cfg_from_db};
-        use crate::storage::MirageDb;
+
+        if let Some(ref list_path) = args.functions_from_file {
+            return functions_from_file(list_path, None, args.timings, cli, None, |function| {
+                let run = CfgArgs {
+                    function: Some(function.to_string()),
+                    functions_from_file: None,
+                    ..args.clone()
+                };
+                cfg(&run, cli)
+            });
+        }
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
         // Open database (follows status command pattern for error handling)
-        let db = match MirageDb::open(&db_path) {
+        let db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 // JSON-aware error handling with remediation
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::print_json(&wrapper, false);
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Batch mode: every indexed function into a single output
+        let Some(function) = args.function.as_deref() else {
+            return cfg_all(&db, args, cli);
+        };
+
         // Resolve function name/ID to function_id
-        let function_id = match resolve_function_name(&db, &args.function) {
+        let function_id = match resolve_function_name(&db, function) {
             Ok(id) => id,
             Err(_e) => {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::function_not_found(&args.function);
+                    let error = output::JsonError::function_not_found(function);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::print_json(&wrapper, false);
+                    output::exit(output::EXIT_DATABASE);
                 } else {
-                    output::error(&format!("Function '{}' not found in database", args.function));
+                    output::error(&format!("Function '{}' not found in database", function));
                     output::info("Hint: Run 'magellan watch' to index your code");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, function, cli);
+
         // Load CFG from database
         let cfg = match load_cfg_from_db(&db, function_id) {
             Ok(cfg) => cfg,
@@ -1240,49 +3197,212 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::new(
                         "CgfLoadError",
-                        &format!("Failed to load CFG for function '{}'", args.function),
+                        &format!("Failed to load CFG for function '{}'", function),
                         output::E_CFG_ERROR,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::print_json(&wrapper, false);
+                    output::exit(output::EXIT_DATABASE);
                 } else {
-                    output::error(&format!("Failed to load CFG for function '{}'", args.function));
+                    output::error(&format!("Failed to load CFG for function '{}'", function));
                     output::info("The function may be corrupted. Try re-running 'magellan watch'");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
-        // Determine output format (args.format overrides cli.output)
-        let format = args.format.unwrap_or(match cli.output {
-            OutputFormat::Human => CfgFormat::Human,
-            OutputFormat::Json => CfgFormat::Json,
-            OutputFormat::Pretty => CfgFormat::Json,
-        });
-
-        match format {
+        match cfg_format(args, cli) {
             CfgFormat::Human | CfgFormat::Dot => {
                 // Both Human and Dot use DOT format
-                let dot = export_dot(&cfg);
-                println!("{}", dot);
+                output::print_text(&cfg_dot(&cfg, args));
             }
             CfgFormat::Json => {
+                let mut cfg = cfg;
+                crate::cfg::SourceMap::new().resolve_cfg(&mut cfg);
+
                 // Export to JSON and wrap in JsonResponse for consistency
-                let export: CFGExport = export_json(&cfg, &args.function);
-                let response = output::JsonResponse::new(export);
+                let response = output::JsonResponse::new(cfg_export(&cfg, function, args, cli));
 
                 match cli.output {
-                    OutputFormat::Json => println!("{}", response.to_json()),
-                    OutputFormat::Pretty => println!("{}", response.to_pretty_json()),
-                    OutputFormat::Human => println!("{}", response.to_pretty_json()),
+                    OutputFormat::Json => output::print_json(&response, false),
+                    OutputFormat::Pretty => output::print_json(&response, true),
+                    OutputFormat::Human => output::print_json(&response, true),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `cfg --all`: export every indexed function's CFG into one output
+    ///
+    /// CFGs are loaded, rendered and written one function at a time, so memory
+    /// use does not grow with the number of functions. Functions whose CFG fails
+    /// to load are skipped.
+    fn cfg_all(db: &crate::storage::MirageDb, args: &CfgArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::load_cfg_from_db;
+        use std::io::Write;
+
+        let mut functions = match db.storage().function_ids() {
+            Ok(functions) => functions,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "QueryError",
+                        &format!("Failed to query functions: {}", e),
+                        output::E_DATABASE_NOT_FOUND,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to query functions: {}", e));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        if let Some(ref filter) = args.filter {
+            functions.retain(|(_, name)| name.contains(filter.as_str()));
+        }
+
+        // Restrict to functions changed since the --only-changed baseline
+        if let Some(changed) = only_changed_functions(db, cli) {
+            functions.retain(|(id, _)| changed.contains(id));
+        }
+
+        let mut out = std::io::BufWriter::new(output::ResultWriter);
+
+        match cfg_format(args, cli) {
+            CfgFormat::Human | CfgFormat::Dot => {
+                // One digraph per function, each preceded by a comment naming it
+                for (function_id, name) in &functions {
+                    let Ok(cfg) = load_cfg_from_db(db, *function_id) else {
+                        continue;
+                    };
+                    writeln!(out, "// {}", name)?;
+                    writeln!(out, "{}", cfg_dot(&cfg, args))?;
                 }
             }
+            CfgFormat::Json => {
+                // One source map for the whole run: functions share files
+                let mut source_map = crate::cfg::SourceMap::new();
+                let exports = functions.iter().filter_map(|(function_id, name)| {
+                    let mut cfg = load_cfg_from_db(db, *function_id).ok()?;
+                    source_map.resolve_cfg(&mut cfg);
+                    Some(cfg_export(&cfg, name, args, cli))
+                });
+                let response = output::JsonResponse::new(output::JsonSeq::new(exports));
+                response.write_to(&mut out, !matches!(cli.output, OutputFormat::Json))?;
+                writeln!(out)?;
+            }
         }
 
+        out.flush()?;
         Ok(())
     }
 
+    /// Output format for `cfg` (--format overrides --output)
+    fn cfg_format(args: &CfgArgs, cli: &Cli) -> CfgFormat {
+        args.format.unwrap_or(match cli.output {
+            OutputFormat::Human => CfgFormat::Human,
+            OutputFormat::Json => CfgFormat::Json,
+            OutputFormat::Pretty => CfgFormat::Json,
+        })
+    }
+
+    /// Natural loops and per-block loop depths requested by the `cfg` flags
+    fn cfg_loop_annotations(
+        cfg: &crate::cfg::Cfg,
+        args: &CfgArgs,
+    ) -> (Vec<crate::cfg::loops::NaturalLoop>, Option<std::collections::HashMap<petgraph::graph::NodeIndex, usize>>) {
+        use crate::cfg::loops::{compute_loop_depths, detect_natural_loops};
+
+        let natural_loops = if args.with_loop_depth || args.cluster_loops {
+            detect_natural_loops(cfg)
+        } else {
+            Vec::new()
+        };
+        let loop_depths = if args.with_loop_depth {
+            Some(compute_loop_depths(cfg, &natural_loops))
+        } else {
+            None
+        };
+        (natural_loops, loop_depths)
+    }
+
+    /// Apply `--edge-filter`, or `None` when every edge type is exported
+    ///
+    /// Loop annotations are computed on the unfiltered CFG beforehand, so
+    /// dropping back edges from the output does not hide the loops.
+    fn cfg_edge_filter(cfg: &crate::cfg::Cfg, args: &CfgArgs) -> Option<crate::cfg::Cfg> {
+        if args.edge_filter.is_empty() {
+            None
+        } else {
+            Some(crate::cfg::export::retain_edge_types(cfg, &args.edge_filter))
+        }
+    }
+
+    /// Render one CFG as DOT according to the `cfg` flags
+    fn cfg_dot(cfg: &crate::cfg::Cfg, args: &CfgArgs) -> String {
+        use crate::cfg::export::{
+            export_dot_clustered, export_dot_with_loop_depths, export_dot_with_options, DotOptions,
+        };
+
+        let options = DotOptions {
+            rankdir: args.rankdir.into(),
+            splines: args.splines.into(),
+        };
+        let (natural_loops, loop_depths) = cfg_loop_annotations(cfg, args);
+        let filtered;
+        let cfg = match cfg_edge_filter(cfg, args) {
+            Some(f) => {
+                filtered = f;
+                &filtered
+            }
+            None => cfg,
+        };
+        if args.cluster_loops {
+            export_dot_clustered(cfg, &natural_loops, loop_depths.as_ref(), &options)
+        } else {
+            match loop_depths {
+                Some(ref depths) => export_dot_with_loop_depths(cfg, depths, &options),
+                None => export_dot_with_options(cfg, &options),
+            }
+        }
+    }
+
+    /// Build one CFG's JSON export according to the `cfg` flags
+    fn cfg_export(cfg: &crate::cfg::Cfg, function: &str, args: &CfgArgs, cli: &Cli) -> crate::cfg::CFGExport {
+        use crate::cfg::export_json;
+        use crate::cfg::export::export_json_with_loop_depths;
+
+        let (_, loop_depths) = cfg_loop_annotations(cfg, args);
+        let filtered;
+        let cfg = match cfg_edge_filter(cfg, args) {
+            Some(f) => {
+                filtered = f;
+                &filtered
+            }
+            None => cfg,
+        };
+        let mut export = match loop_depths {
+            Some(ref depths) => export_json_with_loop_depths(cfg, function, depths),
+            None => export_json(cfg, function),
+        };
+        let statement_limits = cli.statement_limits();
+        for block in &mut export.blocks {
+            block.statements = statement_limits.apply(&block.statements);
+        }
+        if args.with_adjacency {
+            export = export.with_adjacency(cfg);
+        }
+        if args.with_degrees {
+            export = export.with_degrees(cfg);
+        }
+        export
+    }
+
     /// Helper to create a test CFG for demonstration
     ///
     /// This will be replaced with database loading in future plans
@@ -1335,9 +3455,9 @@ pub mod cmds {
     }
 
     pub fn dominators(args: &DominatorsArgs, cli: &Cli) -> Result<()> {
-        use crate::cfg::{DominatorTree, PostDominatorTree};
+        use crate::cfg::PostDominatorTree;
+        use crate::cfg::export::export_dominator_tree_dot;
         use crate::cfg::{resolve_function_name, load_cfg_from_db};
-        use crate::storage::MirageDb;
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
@@ -1348,19 +3468,19 @@ pub mod cmds {
         }
 
         // Open database (follows status command pattern for error handling)
-        let db = match MirageDb::open(&db_path) {
+        let mut db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 // JSON-aware error handling with remediation
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -1372,16 +3492,19 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::function_not_found(&args.function);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Function '{}' not found in database", args.function));
                     output::info("Hint: Run 'magellan watch' to index your code");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, &args.function, cli);
+
         // Load CFG from database
         let cfg = match load_cfg_from_db(&db, function_id) {
             Ok(cfg) => cfg,
@@ -1393,27 +3516,47 @@ pub mod cmds {
                         output::E_CFG_ERROR,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to load CFG for function '{}'", args.function));
                     output::info("The function may be corrupted. Try re-running 'magellan watch'");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Resolve --must-pass-through-line to the blocks spanning that line
+        let line_targets = args.must_pass_through_line
+            .map(|line| (line, resolve_line_blocks(&cfg, line, &args.function, cli)));
+
         // Compute dominator tree based on args.post flag
         if args.post {
             // Post-dominator analysis
             let post_dom_tree = match PostDominatorTree::new(&cfg) {
                 Some(tree) => tree,
                 None => {
-                    output::error("Could not compute post-dominator tree (CFG may have no exit blocks)");
-                    std::process::exit(1);
+                    if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                        let error = output::JsonError::new(
+                            "DominatorTreeError",
+                            "Could not compute post-dominator tree (CFG may have no exit blocks)",
+                            output::E_CFG_ERROR,
+                        );
+                        let wrapper = output::JsonResponse::new(error);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
+                    } else {
+                        output::error("Could not compute post-dominator tree (CFG may have no exit blocks)");
+                        output::exit(output::EXIT_DATABASE);
+                    }
                 }
             };
 
+            if args.format == DominatorsFormat::Dot {
+                outln!("{}", export_dominator_tree_dot(&cfg, post_dom_tree.as_dominator_tree(), args.with_cfg));
+                return Ok(());
+            }
+
             // Handle must-pass-through query if specified
             if let Some(ref block_id_str) = args.must_pass_through {
                 match block_id_str.parse::<usize>() {
@@ -1428,34 +3571,33 @@ pub mod cmds {
                                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                                     let error = output::JsonError::block_not_found(block_id);
                                     let wrapper = output::JsonResponse::new(error);
-                                    println!("{}", wrapper.to_json());
-                                    std::process::exit(1);
+                                    outln!("{}", wrapper.to_json());
+                                    output::exit(1);
                                 } else {
                                     output::error(&format!("Block {} not found in CFG", block_id));
-                                    std::process::exit(1);
+                                    output::exit(1);
                                 }
                             }
                         };
 
                         // Find all nodes post-dominated by this block
-                        let must_pass: Vec<usize> = cfg.node_indices()
+                        let must_pass = sorted_block_ids(&cfg, cfg.node_indices()
                             .filter(|&n| post_dom_tree.post_dominates(target_node, n))
-                            .map(|n| cfg[n].id)
-                            .collect();
+                            .filter(|&n| !(args.strict && n == target_node)));
 
                         // Output based on format
                         match cli.output {
                             OutputFormat::Human => {
-                                println!("Function: {}", args.function);
-                                println!("Post-Dominator Query: Blocks post-dominated by {}", block_id);
-                                println!("Count: {}", must_pass.len());
-                                println!();
+                                outln!("Function: {}", args.function);
+                                outln!("Post-Dominator Query: Blocks post-dominated by {}", block_id);
+                                outln!("Count: {}", must_pass.len());
+                                outln!();
                                 if must_pass.is_empty() {
                                     output::info("No blocks are post-dominated by this block");
                                 } else {
-                                    println!("Blocks that must pass through {}:", block_id);
+                                    outln!("Blocks that must pass through {}:", block_id);
                                     for id in &must_pass {
-                                        println!("  - Block {}", id);
+                                        outln!("  - Block {}", id);
                                     }
                                 }
                             }
@@ -1469,11 +3611,13 @@ pub mod cmds {
                                         block: block_id,
                                         must_pass,
                                     }),
+                                    must_pass_through_line: None,
+                                    entry_blocks: None,
                                 };
                                 let wrapper = output::JsonResponse::new(response);
                                 match cli.output {
-                                    OutputFormat::Json => println!("{}", wrapper.to_json()),
-                                    OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                                     _ => unreachable!(),
                                 }
                             }
@@ -1481,39 +3625,46 @@ pub mod cmds {
                         return Ok(());
                     }
                     Err(_) => {
-                        output::error(&format!("Invalid block ID: {}", block_id_str));
-                        std::process::exit(1);
+                        let msg = format!("Invalid block ID: {}", block_id_str);
+                        if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                            let error = output::JsonError::new("InvalidInput", &msg, output::E_INVALID_INPUT);
+                            let wrapper = output::JsonResponse::new(error);
+                            outln!("{}", wrapper.to_json());
+                            output::exit(output::EXIT_USAGE);
+                        } else {
+                            output::error(&msg);
+                            output::exit(output::EXIT_USAGE);
+                        }
                     }
                 }
             }
 
+            // Handle must-pass-through-line query if specified
+            if let Some((line, targets)) = line_targets {
+                let results = targets.iter()
+                    .map(|&target| MustPassThroughResult {
+                        block: cfg[target].id,
+                        must_pass: sorted_block_ids(&cfg, cfg.node_indices()
+                            .filter(|&n| post_dom_tree.post_dominates(target, n))
+                            .filter(|&n| !(args.strict && n == target))),
+                    })
+                    .collect();
+                print_must_pass_through_line(args, cli, "post-dominators", cfg[post_dom_tree.root()].id, line, results, None);
+                return Ok(());
+            }
+
             // Build dominance tree for output
-            let dominance_tree: Vec<DominatorEntry> = cfg.node_indices()
-                .map(|node| {
-                    let block = cfg[node].id;
-                    let immediate_dominator = post_dom_tree.immediate_post_dominator(node)
-                        .map(|n| cfg[n].id);
-                    let dominated: Vec<usize> = post_dom_tree.children(node)
-                        .iter()
-                        .map(|&n| cfg[n].id)
-                        .collect();
-                    DominatorEntry {
-                        block,
-                        immediate_dominator,
-                        dominated,
-                    }
-                })
-                .collect();
+            let dominance_tree = dominance_entries(&cfg, post_dom_tree.as_dominator_tree());
 
             // Format output
             match cli.output {
                 OutputFormat::Human => {
-                    println!("Function: {}", args.function);
-                    println!("Post-Dominator Tree (root: {})", cfg[post_dom_tree.root()].id);
-                    println!();
+                    outln!("Function: {}", args.function);
+                    outln!("Post-Dominator Tree (root: {})", cfg[post_dom_tree.root()].id);
+                    outln!();
 
                     // Print tree structure
-                    print_dominator_tree_human(&cfg, post_dom_tree.as_dominator_tree(), post_dom_tree.root(), 0, true);
+                    print_dominator_tree_human(&cfg, post_dom_tree.as_dominator_tree(), post_dom_tree.root(), true, args.max_depth)?;
                 }
                 OutputFormat::Json | OutputFormat::Pretty => {
                     let response = DominanceResponse {
@@ -1522,24 +3673,42 @@ pub mod cmds {
                         root: Some(cfg[post_dom_tree.root()].id),
                         dominance_tree,
                         must_pass_through: None,
+                        must_pass_through_line: None,
+                        entry_blocks: None,
                     };
                     let wrapper = output::JsonResponse::new(response);
                     match cli.output {
-                        OutputFormat::Json => println!("{}", wrapper.to_json()),
-                        OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                        OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                        OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                         _ => unreachable!(),
                     }
                 }
             }
         } else {
             // Regular dominator analysis
-            let dom_tree = match DominatorTree::new(&cfg) {
+            let dom_tree = match load_or_compute_dominator_tree(&mut db, function_id, &cfg) {
                 Some(tree) => tree,
                 None => {
-                    output::error("Could not compute dominator tree (CFG may have no entry block)");
-                    std::process::exit(1);
+                    if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                        let error = output::JsonError::new(
+                            "DominatorTreeError",
+                            "Could not compute dominator tree (CFG may have no entry block)",
+                            output::E_CFG_ERROR,
+                        );
+                        let wrapper = output::JsonResponse::new(error);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
+                    } else {
+                        output::error("Could not compute dominator tree (CFG may have no entry block)");
+                        output::exit(output::EXIT_DATABASE);
+                    }
                 }
             };
+            if args.format == DominatorsFormat::Dot {
+                outln!("{}", export_dominator_tree_dot(&cfg, &dom_tree, args.with_cfg));
+                return Ok(());
+            }
+            let entry_blocks = check_multiple_entry_blocks(&cfg, cli);
 
             // Handle must-pass-through query if specified
             if let Some(ref block_id_str) = args.must_pass_through {
@@ -1555,11 +3724,11 @@ pub mod cmds {
                                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                                     let error = output::JsonError::block_not_found(block_id);
                                     let wrapper = output::JsonResponse::new(error);
-                                    println!("{}", wrapper.to_json());
-                                    std::process::exit(1);
+                                    outln!("{}", wrapper.to_json());
+                                    output::exit(1);
                                 } else {
                                     output::error(&format!("Block {} not found in CFG", block_id));
-                                    std::process::exit(1);
+                                    output::exit(1);
                                 }
                             }
                         };
@@ -1567,22 +3736,23 @@ pub mod cmds {
                         // Find all nodes dominated by this block
                         let must_pass: Vec<usize> = cfg.node_indices()
                             .filter(|&n| dom_tree.dominates(target_node, n))
+                            .filter(|&n| !(args.strict && n == target_node))
                             .map(|n| cfg[n].id)
                             .collect();
 
                         // Output based on format
                         match cli.output {
                             OutputFormat::Human => {
-                                println!("Function: {}", args.function);
-                                println!("Dominator Query: Blocks dominated by {}", block_id);
-                                println!("Count: {}", must_pass.len());
-                                println!();
+                                outln!("Function: {}", args.function);
+                                outln!("Dominator Query: Blocks dominated by {}", block_id);
+                                outln!("Count: {}", must_pass.len());
+                                outln!();
                                 if must_pass.is_empty() {
                                     output::info("No blocks are dominated by this block");
                                 } else {
-                                    println!("Blocks that must pass through {}:", block_id);
+                                    outln!("Blocks that must pass through {}:", block_id);
                                     for id in &must_pass {
-                                        println!("  - Block {}", id);
+                                        outln!("  - Block {}", id);
                                     }
                                 }
                             }
@@ -1596,11 +3766,13 @@ pub mod cmds {
                                         block: block_id,
                                         must_pass,
                                     }),
+                                    must_pass_through_line: None,
+                                    entry_blocks,
                                 };
                                 let wrapper = output::JsonResponse::new(response);
                                 match cli.output {
-                                    OutputFormat::Json => println!("{}", wrapper.to_json()),
-                                    OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                                     _ => unreachable!(),
                                 }
                             }
@@ -1608,39 +3780,46 @@ pub mod cmds {
                         return Ok(());
                     }
                     Err(_) => {
-                        output::error(&format!("Invalid block ID: {}", block_id_str));
-                        std::process::exit(1);
+                        let msg = format!("Invalid block ID: {}", block_id_str);
+                        if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                            let error = output::JsonError::new("InvalidInput", &msg, output::E_INVALID_INPUT);
+                            let wrapper = output::JsonResponse::new(error);
+                            outln!("{}", wrapper.to_json());
+                            output::exit(output::EXIT_USAGE);
+                        } else {
+                            output::error(&msg);
+                            output::exit(output::EXIT_USAGE);
+                        }
                     }
                 }
             }
 
+            // Handle must-pass-through-line query if specified
+            if let Some((line, targets)) = line_targets {
+                let results = targets.iter()
+                    .map(|&target| MustPassThroughResult {
+                        block: cfg[target].id,
+                        must_pass: sorted_block_ids(&cfg, cfg.node_indices()
+                            .filter(|&n| dom_tree.dominates(target, n))
+                            .filter(|&n| !(args.strict && n == target))),
+                    })
+                    .collect();
+                print_must_pass_through_line(args, cli, "dominators", cfg[dom_tree.root()].id, line, results, entry_blocks);
+                return Ok(());
+            }
+
             // Build dominance tree for output
-            let dominance_tree: Vec<DominatorEntry> = cfg.node_indices()
-                .map(|node| {
-                    let block = cfg[node].id;
-                    let immediate_dominator = dom_tree.immediate_dominator(node)
-                        .map(|n| cfg[n].id);
-                    let dominated: Vec<usize> = dom_tree.children(node)
-                        .iter()
-                        .map(|&n| cfg[n].id)
-                        .collect();
-                    DominatorEntry {
-                        block,
-                        immediate_dominator,
-                        dominated,
-                    }
-                })
-                .collect();
+            let dominance_tree = dominance_entries(&cfg, &dom_tree);
 
             // Format output
             match cli.output {
                 OutputFormat::Human => {
-                    println!("Function: {}", args.function);
-                    println!("Dominator Tree (root: {})", cfg[dom_tree.root()].id);
-                    println!();
+                    outln!("Function: {}", args.function);
+                    outln!("Dominator Tree (root: {})", cfg[dom_tree.root()].id);
+                    outln!();
 
                     // Print tree structure
-                    print_dominator_tree_human(&cfg, &dom_tree, dom_tree.root(), 0, false);
+                    print_dominator_tree_human(&cfg, &dom_tree, dom_tree.root(), false, args.max_depth)?;
                 }
                 OutputFormat::Json | OutputFormat::Pretty => {
                     let response = DominanceResponse {
@@ -1649,11 +3828,13 @@ pub mod cmds {
                         root: Some(cfg[dom_tree.root()].id),
                         dominance_tree,
                         must_pass_through: None,
+                        must_pass_through_line: None,
+                        entry_blocks,
                     };
                     let wrapper = output::JsonResponse::new(response);
                     match cli.output {
-                        OutputFormat::Json => println!("{}", wrapper.to_json()),
-                        OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                        OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                        OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                         _ => unreachable!(),
                     }
                 }
@@ -1663,40 +3844,636 @@ pub mod cmds {
         Ok(())
     }
 
-    /// Helper to print dominator tree in human-readable format
-    fn print_dominator_tree_human(
+    /// Resolve a source line to the CFG blocks spanning it, exiting with an error if none do
+    fn resolve_line_blocks(
         cfg: &crate::cfg::Cfg,
-        dom_tree: &crate::cfg::DominatorTree,
-        node: petgraph::graph::NodeIndex,
-        depth: usize,
-        is_post: bool,
-    ) {
-        let indent = "  ".repeat(depth);
-        let block_id = cfg[node].id;
-        let kind_label = if is_post { "post-dominator" } else { "dominator" };
-
-        println!("{}Block {} ({})", indent, block_id, kind_label);
+        line: usize,
+        function: &str,
+        cli: &Cli,
+    ) -> Vec<petgraph::graph::NodeIndex> {
+        use crate::cfg::source::{blocks_at_line, has_line_info};
+
+        if !has_line_info(cfg) {
+            let message = format!("Function '{}' has no line information; cannot resolve line {}", function, line);
+            if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                let error = output::JsonError::new("NoSourceLocations", &message, output::E_INVALID_INPUT)
+                    .with_remediation("Re-index so blocks carry source spans, or use --must-pass-through <BLOCK_ID>");
+                let wrapper = output::JsonResponse::new(error);
+                outln!("{}", wrapper.to_json());
+                output::exit(output::EXIT_USAGE);
+            } else {
+                output::error(&message);
+                output::info("Hint: Re-index so blocks carry source spans, or use --must-pass-through <BLOCK_ID>");
+                output::exit(output::EXIT_USAGE);
+            }
+        }
 
-        for &child in dom_tree.children(node) {
-            print_dominator_tree_human(cfg, dom_tree, child, depth + 1, is_post);
+        let targets = blocks_at_line(cfg, line);
+        if targets.is_empty() {
+            let message = format!("No block in '{}' covers line {}", function, line);
+            if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                let error = output::JsonError::new("BlockNotFound", &message, output::E_BLOCK_NOT_FOUND);
+                let wrapper = output::JsonResponse::new(error);
+                outln!("{}", wrapper.to_json());
+                output::exit(1);
+            } else {
+                output::error(&message);
+                output::exit(1);
+            }
         }
+
+        targets
     }
 
-    /// Helper to print post-dominator tree in human-readable format
-    fn print_post_dominator_tree_human(
-        cfg: &crate::cfg::Cfg,
-        post_dom_tree: &crate::cfg::PostDominatorTree,
-        node: petgraph::graph::NodeIndex,
-        depth: usize,
+    /// Print must-pass-through results for every block on a source line
+    fn print_must_pass_through_line(
+        args: &DominatorsArgs,
+        cli: &Cli,
+        kind: &str,
+        root: usize,
+        line: usize,
+        results: Vec<MustPassThroughResult>,
+        entry_blocks: Option<Vec<usize>>,
     ) {
-        let indent = "  ".repeat(depth);
-        let block_id = cfg[node].id;
-
-        println!("{}Block {} (post-dominator)", indent, block_id);
+        let relation = if kind == "post-dominators" { "post-dominated" } else { "dominated" };
 
-        for &child in post_dom_tree.children(node) {
-            print_post_dominator_tree_human(cfg, post_dom_tree, child, depth + 1);
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", args.function);
+                outln!("Line {} maps to {} block(s)", line, results.len());
+                for result in &results {
+                    outln!();
+                    outln!("Blocks {} by {} (count: {}):", relation, result.block, result.must_pass.len());
+                    for id in &result.must_pass {
+                        outln!("  - Block {}", id);
+                    }
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = DominanceResponse {
+                    function: args.function.clone(),
+                    kind: kind.to_string(),
+                    root: Some(root),
+                    dominance_tree: vec![],
+                    must_pass_through: None,
+                    must_pass_through_line: Some(MustPassThroughLineResult { line, results }),
+                    entry_blocks,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    /// Block IDs of `nodes`, sorted so JSON output does not depend on graph insertion order
+    pub(super) fn sorted_block_ids(
+        cfg: &crate::cfg::Cfg,
+        nodes: impl IntoIterator<Item = petgraph::graph::NodeIndex>,
+    ) -> Vec<usize> {
+        let mut ids: Vec<usize> = nodes.into_iter().map(|n| cfg[n].id).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Dominance tree entries for JSON output, ordered by block ID
+    pub(super) fn dominance_entries(
+        cfg: &crate::cfg::Cfg,
+        dom_tree: &crate::cfg::DominatorTree,
+    ) -> Vec<DominatorEntry> {
+        let mut entries: Vec<DominatorEntry> = cfg.node_indices()
+            .map(|node| DominatorEntry {
+                block: cfg[node].id,
+                immediate_dominator: dom_tree.immediate_dominator(node).map(|n| cfg[n].id),
+                dominated: sorted_block_ids(cfg, dom_tree.children(node).iter().copied()),
+            })
+            .collect();
+        entries.sort_by_key(|entry| entry.block);
+        entries
+    }
+
+    /// Helper to print dominator tree in human-readable format
+    fn print_dominator_tree_human(
+        cfg: &crate::cfg::Cfg,
+        dom_tree: &crate::cfg::DominatorTree,
+        root: petgraph::graph::NodeIndex,
+        is_post: bool,
+        max_depth: Option<usize>,
+    ) -> Result<()> {
+        let mut out = std::io::BufWriter::new(output::ResultWriter);
+        write_dominator_tree(&mut out, cfg, dom_tree, root, is_post, max_depth)?;
+        std::io::Write::flush(&mut out)?;
+        Ok(())
+    }
+
+    /// Write a dominator tree as indented lines, one per block, in pre-order
+    ///
+    /// Walks the tree with an explicit stack, so arbitrarily deep trees (long
+    /// straight-line functions) cannot overflow the call stack, and writes each
+    /// line as it goes instead of collecting them. Blocks deeper than
+    /// `max_depth` (the root is depth 0) are replaced by one `...` line per
+    /// cut subtree giving how many blocks it hides.
+    pub(super) fn write_dominator_tree(
+        out: &mut impl std::io::Write,
+        cfg: &crate::cfg::Cfg,
+        dom_tree: &crate::cfg::DominatorTree,
+        root: petgraph::graph::NodeIndex,
+        is_post: bool,
+        max_depth: Option<usize>,
+    ) -> std::io::Result<()> {
+        let kind_label = if is_post { "post-dominator" } else { "dominator" };
+        let mut stack = vec![(root, 0)];
+
+        while let Some((node, depth)) = stack.pop() {
+            let indent = "  ".repeat(depth);
+            if max_depth.is_some_and(|max| depth > max) {
+                let hidden = dominator_subtree_size(dom_tree, node);
+                writeln!(out, "{}... ({} more block{})", indent, hidden, if hidden == 1 { "" } else { "s" })?;
+                continue;
+            }
+            writeln!(out, "{}Block {} ({})", indent, cfg[node].id, kind_label)?;
+            // Reversed so children pop, and print, in tree order
+            stack.extend(dom_tree.children(node).iter().rev().map(|&child| (child, depth + 1)));
+        }
+        Ok(())
+    }
+
+    /// Number of blocks in the dominator subtree rooted at `node`, itself included
+    fn dominator_subtree_size(dom_tree: &crate::cfg::DominatorTree, node: petgraph::graph::NodeIndex) -> usize {
+        let mut size = 0;
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            size += 1;
+            stack.extend_from_slice(dom_tree.children(node));
+        }
+        size
+    }
+
+    /// Function IDs changed relative to the --only-changed baseline
+    ///
+    /// Returns None when --only-changed is not set. Exits if either database
+    /// cannot be read.
+    fn only_changed_functions(db: &crate::storage::MirageDb, cli: &Cli) -> Option<std::collections::HashSet<i64>> {
+        use crate::storage::changed::{find_changed_functions, open_baseline};
+
+        let baseline_path = cli.only_changed.as_ref()?;
+
+        let changed = open_baseline(baseline_path)
+            .and_then(|baseline| find_changed_functions(db.conn()?, &baseline));
+
+        match changed {
+            Ok(ids) => Some(ids.into_iter().collect()),
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "BaselineError",
+                        &format!("Failed to compare against baseline '{}': {}", baseline_path, e),
+                        output::E_DATABASE_NOT_FOUND,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to compare against baseline '{}': {}", baseline_path, e));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        }
+    }
+
+    /// `--functions-from-file`: run the current command for each listed function
+    ///
+    /// `run` is the single-function handler, called in-process with the
+    /// function name in place of the list, so every result is exactly what
+    /// the single-function command reports, failures included. The database
+    /// is opened once and shared by every run. JSON results are collected
+    /// from the handler's output rather than printed; human output is printed
+    /// under a `== NAME ==` header. Names that do not resolve, and runs that
+    /// fail, are reported under `errors` instead of stopping the batch. Blank
+    /// lines and lines starting with `#` are ignored.
+    ///
+    /// `prepare`, if given, runs first on a read-write database with the
+    /// resolved names and `--jobs`, to do the parallelizable work up front;
+    /// it returns the summed per-function time it spent.
+    fn functions_from_file(
+        list_path: &str,
+        jobs: Option<std::num::NonZeroUsize>,
+        timings: bool,
+        cli: &Cli,
+        prepare: Option<BatchPrepare<'_>>,
+        run: impl Fn(&str) -> Result<()>,
+    ) -> Result<()> {
+        let list = match std::fs::read_to_string(list_path) {
+            Ok(list) => list,
+            Err(e) => {
+                let msg = format!("Failed to read function list '{}': {}", list_path, e);
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new("InvalidInput", &msg, output::E_INVALID_INPUT);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_USAGE);
+                } else {
+                    output::error(&msg);
+                    output::exit(output::EXIT_USAGE);
+                }
+            }
+        };
+        let functions: Vec<&str> = list
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+
+        // Keep the database open across runs, unless a REPL session already does
+        let own_session = !super::session::is_enabled();
+        if own_session {
+            super::session::enable();
+        }
+        let result = run_function_batch(&functions, jobs, timings, cli, prepare, run);
+        if own_session {
+            super::session::disable();
+        }
+        result
+    }
+
+    /// Up-front work for a `--functions-from-file` batch: database, names, jobs
+    type BatchPrepare<'a> = &'a dyn Fn(&mut crate::storage::MirageDb, &[&str], usize) -> std::time::Duration;
+
+    /// Body of [`functions_from_file`], run with the session cache enabled
+    fn run_function_batch(
+        functions: &[&str],
+        jobs: Option<std::num::NonZeroUsize>,
+        timings: bool,
+        cli: &Cli,
+        prepare: Option<BatchPrepare<'_>>,
+        run: impl Fn(&str) -> Result<()>,
+    ) -> Result<()> {
+        use crate::cfg::resolve_function_name;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::time::Instant;
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+        let db_result = if prepare.is_some() {
+            super::session::open(&db_path)
+        } else {
+            super::session::open_readonly(&db_path)
+        };
+        let mut db = match db_result {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let mut errors = Vec::new();
+        let resolved: Vec<&str> = functions
+            .iter()
+            .copied()
+            .filter(|&function| {
+                let found = resolve_function_name(&db, function).is_ok();
+                if !found {
+                    errors.push(FunctionBatchError {
+                        function: function.to_string(),
+                        error: format!("Function '{}' not found in database", function),
+                    });
+                }
+                found
+            })
+            .collect();
+
+        let jobs = jobs.map_or(1, std::num::NonZeroUsize::get);
+        let started = Instant::now();
+        let mut function_time = prepare.map_or(std::time::Duration::ZERO, |prepare| prepare(&mut db, &resolved, jobs));
+        // Back to the session cache for the runs to reuse
+        drop(db);
+
+        // A failing run ends only that run
+        let scoped_exit = output::command_scoped_exit();
+        output::set_command_scoped_exit(true);
+
+        let mut results = Vec::new();
+        for &function in &resolved {
+            if cli.output == OutputFormat::Human {
+                outln!("== {} ==", function);
+            }
+            let run_started = Instant::now();
+            let (outcome, mut emitted) = if cli.output == OutputFormat::Human {
+                (catch_unwind(AssertUnwindSafe(|| run(function))), Vec::new())
+            } else {
+                output::capture(|| catch_unwind(AssertUnwindSafe(|| run(function))))
+            };
+            function_time += run_started.elapsed();
+
+            // The handler's own response, or the error response it reported before exiting
+            let data = emitted.pop();
+            let error = match outcome {
+                Ok(Ok(())) => {
+                    if cli.output != OutputFormat::Human {
+                        results.push(FunctionBatchResult {
+                            function: function.to_string(),
+                            data: data.unwrap_or_default(),
+                        });
+                    }
+                    continue;
+                }
+                Ok(Err(e)) => format!("{:#}", e),
+                Err(payload) => {
+                    let reported = data.as_ref().and_then(|data| data["message"].as_str()).map(str::to_string);
+                    match payload.downcast_ref::<output::CommandExit>() {
+                        Some(output::CommandExit(code)) => {
+                            reported.unwrap_or_else(|| format!("exited with status {}", code))
+                        }
+                        None => "panicked".to_string(),
+                    }
+                }
+            };
+            errors.push(FunctionBatchError { function: function.to_string(), error });
+        }
+        let wall = started.elapsed();
+        output::set_command_scoped_exit(scoped_exit);
+
+        let timings = timings.then(|| {
+            let wall_ms = wall.as_millis() as u64;
+            let function_ms = function_time.as_millis() as u64;
+            BatchTimings {
+                jobs,
+                wall_ms,
+                function_ms,
+            }
+        });
+
+        match cli.output {
+            OutputFormat::Human => {
+                if !errors.is_empty() {
+                    outln!("Skipped {} of {} function(s):", errors.len(), functions.len());
+                    for error in &errors {
+                        outln!("  - {}: {}", error.function, error.error);
+                    }
+                }
+                if let Some(ref timings) = timings {
+                    outln!(
+                        "Ran {} function(s) in {} ms with {} job(s): {} ms of function time",
+                        resolved.len(),
+                        timings.wall_ms,
+                        timings.jobs,
+                        timings.function_ms
+                    );
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = FunctionBatchResponse {
+                    requested: functions.len(),
+                    results,
+                    errors,
+                    timings,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a `paths` run reads and fills the path cache
+    ///
+    /// The cache is keyed by function hash alone, so it only holds enumerations
+    /// under the default cap, length and loop bound; a different `--max-paths`,
+    /// `--max-length` or `--max-loop-iterations` always enumerates afresh.
+    /// `--count-only` and `--summary-only` report numbers alone and never read or fill it.
+    fn uses_path_cache(args: &PathsArgs) -> bool {
+        let (limits, cached_limits) = (path_limits(args), crate::cfg::PathLimits::default());
+        !args.count_only && !args.summary_only && !args.incremental && args.db2.is_none() && args.entry.is_none()
+            && limits.max_paths == cached_limits.max_paths
+            && limits.max_length == cached_limits.max_length
+            && limits.max_loop_iterations == cached_limits.max_loop_iterations
+    }
+
+    /// Whether a `paths` run drops or reorders enumerated paths before reporting them
+    ///
+    /// Without such filters, `--summary-only` statistics equal those of
+    /// `count_path_summary`, so the paths need not be built.
+    fn has_path_filters(args: &PathsArgs) -> bool {
+        args.show_errors || args.skip_panic_blocks || args.dedup_by_blockset || args.loops_only || args.no_loops
+            || args.ends_in.is_some() || args.calls.is_some() || args.entry.is_some()
+    }
+
+    /// Print `paths --summary-only` statistics
+    fn print_path_summary_stats(function: &str, stats: crate::cfg::paths::PathSummaryStats, cli: &Cli) {
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", function);
+                outln!("Total paths: {}", stats.total);
+                outln!("  Normal: {}", stats.normal);
+                outln!("  Error: {}", stats.error);
+                outln!("  Through panic blocks: {}", stats.panic);
+                outln!("Length: min {}, max {}, average {:.2}", stats.min_len, stats.max_len, stats.avg_len);
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = PathSummaryStatsResponse {
+                    function: function.to_string(),
+                    stats,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                output::print_json(&wrapper, matches!(cli.output, OutputFormat::Pretty));
+            }
+        }
+    }
+
+    /// Limits a `paths` run fills the path cache under
+    ///
+    /// The cache holds every path, so `--min-length` is left out here and
+    /// applied to the cached paths afterwards.
+    fn cache_fill_limits(args: &PathsArgs) -> crate::cfg::PathLimits {
+        path_limits(args).with_min_length(0)
+    }
+
+    /// `prepare` step of a `paths` batch: fill the path cache in parallel
+    ///
+    /// CFGs are loaded, and the cache read and written, on the calling thread,
+    /// which owns the database connection; only enumeration runs on the `jobs`
+    /// worker threads. Functions with a valid cache, without a function hash,
+    /// or whose CFG fails to load are left to the per-function run, which
+    /// then reports them as usual. Returns the summed enumeration time.
+    fn warm_path_cache(
+        args: &PathsArgs,
+        cli: &Cli,
+        db: &mut crate::storage::MirageDb,
+        functions: &[&str],
+        jobs: usize,
+    ) -> std::time::Duration {
+        use crate::cfg::paths::{lookup_cached_paths, store_enumerated_paths};
+        use crate::cfg::{enumerate_paths, load_cfg_from_db, resolve_function_name};
+        use rayon::prelude::*;
+        use std::time::{Duration, Instant};
+
+        if !db.is_sqlite() || !uses_path_cache(args) {
+            return Duration::ZERO;
+        }
+        let changed = only_changed_functions(db, cli);
+
+        let mut misses = Vec::new();
+        for &function in functions {
+            let Ok(function_id) = resolve_function_name(db, function) else {
+                continue;
+            };
+            if changed.as_ref().is_some_and(|changed| !changed.contains(&function_id) && !cli.force) {
+                continue;
+            }
+            let Some(function_hash) = crate::storage::get_function_hash_db(db, function_id) else {
+                continue;
+            };
+            let Ok(cfg) = load_cfg_from_db(db, function_id) else {
+                continue;
+            };
+            let Ok(conn) = db.conn_mut() else {
+                return Duration::ZERO;
+            };
+            if let Ok(None) = lookup_cached_paths(function_id, &function_hash, conn) {
+                misses.push((function_id, cfg));
+            }
+        }
+
+        let limits = cache_fill_limits(args);
+        let Ok(pool) = rayon::ThreadPoolBuilder::new().num_threads(jobs).build() else {
+            return Duration::ZERO;
+        };
+        let enumerated: Vec<(Vec<crate::cfg::Path>, Duration)> = pool.install(|| {
+            misses
+                .par_iter()
+                .map(|(_, cfg)| {
+                    let started = Instant::now();
+                    (enumerate_paths(cfg, &limits), started.elapsed())
+                })
+                .collect()
+        });
+
+        // A failed write only means the run enumerates the function again
+        let mut elapsed = Duration::ZERO;
+        for ((function_id, _), (paths, time)) in misses.iter().zip(enumerated) {
+            elapsed += time;
+            if let Ok(conn) = db.conn_mut() {
+                let _ = store_enumerated_paths(*function_id, &paths, conn);
+            }
+        }
+        elapsed
+    }
+
+    /// Map a readable path ID (`function#pN`) to its cached BLAKE3 path ID
+    ///
+    /// Anything that is not a readable ID, or that names no cached path, is
+    /// returned unchanged so the caller's own not-found handling applies.
+    pub(crate) fn resolve_path_id(db: &crate::storage::MirageDb, path_id: &str) -> String {
+        use crate::cfg::paths::parse_readable_path_id;
+        use crate::storage::paths::find_path_by_ordinal;
+
+        let Some((function, ordinal)) = parse_readable_path_id(path_id) else {
+            return path_id.to_string();
+        };
+        crate::cfg::resolve_function_name(db, function)
+            .ok()
+            .and_then(|function_id| find_path_by_ordinal(db.conn().ok()?, function_id, ordinal).ok().flatten())
+            .unwrap_or_else(|| path_id.to_string())
+    }
+
+    /// Exit if --only-changed is set and the function is unchanged (unless --force)
+    fn ensure_function_changed(db: &crate::storage::MirageDb, function_id: i64, function: &str, cli: &Cli) {
+        if cli.force {
+            return;
+        }
+        let Some(changed) = only_changed_functions(db, cli) else {
+            return;
+        };
+        if changed.contains(&function_id) {
+            return;
+        }
+
+        let msg = format!(
+            "Function '{}' is unchanged relative to the baseline ({} changed functions)",
+            function,
+            changed.len()
+        );
+        if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+            let error = output::JsonError::new("FunctionUnchanged", &msg, output::E_FUNCTION_UNCHANGED);
+            let wrapper = output::JsonResponse::new(error);
+            output::print_json(&wrapper, false);
+            output::exit(output::EXIT_VALIDATION);
+        } else {
+            output::error(&msg);
+            output::info("Hint: Pass --force to analyze it anyway");
+            output::exit(output::EXIT_VALIDATION);
+        }
+    }
+
+    /// Helper to get the dominator tree, using the cfg_dominators cache when available
+    ///
+    /// SQLite databases with a function hash go through get_or_compute_dominators.
+    /// A read-only database only reads the cache (filled by `store_cfg`) and
+    /// computes the tree in memory on a miss, so query commands never write.
+    /// Any cache failure falls back to computing the tree in memory.
+    pub(super) fn load_or_compute_dominator_tree(
+        db: &mut crate::storage::MirageDb,
+        function_id: i64,
+        cfg: &crate::cfg::Cfg,
+    ) -> Option<crate::cfg::DominatorTree> {
+        use crate::cfg::dominators::get_or_compute_dominators;
+        use crate::storage::dominators::load_dominators_from_db;
+        use crate::storage::get_function_hash_db;
+
+        if db.is_sqlite() {
+            if let Some(function_hash) = get_function_hash_db(db, function_id) {
+                if db.is_read_only() {
+                    let Ok(conn) = db.conn() else {
+                        return crate::cfg::DominatorTree::new(cfg);
+                    };
+                    if let Ok(Some(tree)) = load_dominators_from_db(conn, function_id, &function_hash, cfg) {
+                        return Some(tree);
+                    }
+                } else if let Ok(conn) = db.conn_mut() {
+                    if let Ok(tree) = get_or_compute_dominators(cfg, function_id, &function_hash, conn) {
+                        return tree;
+                    }
+                }
+            }
+        }
+
+        crate::cfg::DominatorTree::new(cfg)
+    }
+
+    /// Block IDs of every entry-like block, if the CFG has more than one
+    ///
+    /// Dominance is computed from the first entry only, so blocks reachable
+    /// only from the others are left out of the tree. Human output gets a
+    /// warning; JSON output reports the IDs in `entry_blocks`.
+    pub(super) fn check_multiple_entry_blocks(cfg: &crate::cfg::Cfg, cli: &Cli) -> Option<Vec<usize>> {
+        let entries = crate::cfg::find_entry_blocks(cfg);
+        if entries.len() < 2 {
+            return None;
+        }
+        let ids: Vec<usize> = entries.iter().map(|&n| cfg[n].id).collect();
+        if matches!(cli.output, OutputFormat::Human) {
+            output::warn(&format!(
+                "Multiple entry blocks detected: {:?}. Computed from block {}; blocks reachable only from the others have no dominator",
+                ids, ids[0]
+            ));
         }
+        Some(ids)
     }
 
     /// Inter-procedural dominance analysis using call graph condensation
@@ -1718,12 +4495,12 @@ pub mod cmds {
                         "Run 'magellan watch' to build the call graph",
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Magellan database not available: {}", e));
                     output::info("Hint: Run 'magellan watch' to build the call graph");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -1739,12 +4516,12 @@ pub mod cmds {
                         "Ensure the call graph is properly built",
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to condense call graph: {}", e));
                     output::info("Hint: Ensure the call graph is properly built");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -1796,17 +4573,17 @@ pub mod cmds {
             OutputFormat::Human => {
                 output::header(&format!("Inter-procedural Dominators: {}", args.function));
                 output::info("Functions that must execute before this function can be reached");
-                println!();
+                outln!();
 
                 if dominating_functions.is_empty() {
-                    println!("No dominators found (this may be an entry point or not in call graph)");
+                    outln!("No dominators found (this may be an entry point or not in call graph)");
                 } else {
-                    println!("Found {} dominating function(s):", dominating_functions.len());
-                    println!();
+                    outln!("Found {} dominating function(s):", dominating_functions.len());
+                    outln!();
                     for (i, dominator) in dominating_functions.iter().enumerate() {
-                        println!("{}. {}", i + 1, dominator);
+                        outln!("{}. {}", i + 1, dominator);
                     }
-                    println!();
+                    outln!();
                     output::info("These functions are on all call paths to the target");
                 }
             }
@@ -1818,7 +4595,7 @@ pub mod cmds {
                     dominators: dominating_functions.clone(),
                 };
                 let wrapper = output::JsonResponse::new(response);
-                println!("{}", wrapper.to_json());
+                outln!("{}", wrapper.to_json());
             }
             OutputFormat::Pretty => {
                 let response = InterProceduralDominanceResponse {
@@ -1828,7 +4605,7 @@ pub mod cmds {
                     dominators: dominating_functions.clone(),
                 };
                 let wrapper = output::JsonResponse::new(response);
-                println!("{}", wrapper.to_pretty_json());
+                outln!("{}", wrapper.to_pretty_json());
             }
         }
 
@@ -1860,28 +4637,81 @@ pub mod cmds {
         false
     }
 
+    /// Loop descriptions for output, ordered by header then back edge
+    pub(super) fn loop_infos(
+        cfg: &crate::cfg::Cfg,
+        natural_loops: &[crate::cfg::loops::NaturalLoop],
+    ) -> Vec<LoopInfo> {
+        let mut infos: Vec<LoopInfo> = natural_loops.iter().map(|loop_| {
+            LoopInfo {
+                header: cfg[loop_.header].id,
+                back_edge_from: cfg[loop_.back_edge.0].id,
+                body_size: loop_.size(),
+                nesting_level: loop_.nesting_level(natural_loops),
+                body_blocks: sorted_block_ids(cfg, loop_.body.iter().copied()),
+                exit_edges: {
+                    let mut edges: Vec<(usize, usize)> = loop_.exit_edges(cfg)
+                        .into_iter()
+                        .map(|(from, to)| (cfg[from].id, cfg[to].id))
+                        .collect();
+                    edges.sort_unstable();
+                    edges
+                },
+                exit_blocks: sorted_block_ids(cfg, loop_.exit_blocks(cfg)),
+                preheader: loop_.preheader(cfg).map(|n| cfg[n].id),
+            }
+        }).collect();
+        infos.sort_by_key(|info| (info.header, info.back_edge_from));
+        infos
+    }
+
+    /// Split loop infos into (multi-block loops, self-loops)
+    ///
+    /// Self-loops come from `find_self_loops`, so blocks unreachable from the
+    /// entry are included, and are never reported among the multi-block loops.
+    pub(super) fn loop_and_self_loop_infos(
+        cfg: &crate::cfg::Cfg,
+        natural_loops: &[crate::cfg::loops::NaturalLoop],
+    ) -> (Vec<LoopInfo>, Vec<LoopInfo>) {
+        use crate::cfg::loops::{find_self_loops, NaturalLoop};
+
+        let mut loops: Vec<NaturalLoop> = natural_loops
+            .iter()
+            .filter(|loop_| !loop_.is_self_loop())
+            .cloned()
+            .collect();
+        loops.extend(find_self_loops(cfg).into_iter().map(|node| NaturalLoop {
+            header: node,
+            back_edge: (node, node),
+            body: std::iter::once(node).collect(),
+        }));
+
+        loop_infos(cfg, &loops)
+            .into_iter()
+            .partition(|info| info.header != info.back_edge_from)
+    }
+
     pub fn loops(args: &LoopsArgs, cli: &Cli) -> Result<()> {
         use crate::cfg::detect_natural_loops;
         use crate::cfg::{resolve_function_name, load_cfg_from_db};
-        use crate::storage::MirageDb;
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
         // Open database (follows status command pattern for error handling)
-        let db = match MirageDb::open(&db_path) {
+        let db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 // JSON-aware error handling with remediation
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -1893,16 +4723,19 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::function_not_found(&args.function);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Function '{}' not found in database", args.function));
                     output::info("Hint: Run 'magellan watch' to index your code");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, &args.function, cli);
+
         // Load CFG from database
         let cfg = match load_cfg_from_db(&db, function_id) {
             Ok(cfg) => cfg,
@@ -1914,12 +4747,12 @@ pub mod cmds {
                         output::E_CFG_ERROR,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to load CFG for function '{}'", args.function));
                     output::info("The function may be corrupted. Try re-running 'magellan watch'");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -1927,55 +4760,59 @@ pub mod cmds {
         // Detect natural loops
         let natural_loops = detect_natural_loops(&cfg);
 
-        // Compute nesting levels for each loop
-        let loop_infos: Vec<LoopInfo> = natural_loops.iter().map(|loop_| {
-            let nesting_level = loop_.nesting_level(&natural_loops);
-            let body_blocks: Vec<usize> = loop_.body.iter()
-                .map(|&node| cfg[node].id)
-                .collect();
-            LoopInfo {
-                header: cfg[loop_.header].id,
-                back_edge_from: cfg[loop_.back_edge.0].id,
-                body_size: loop_.size(),
-                nesting_level,
-                body_blocks,
-            }
-        }).collect();
+        // Compute nesting levels for each loop, with self-loops listed separately
+        let (loop_infos, self_loop_infos) = loop_and_self_loop_infos(&cfg, &natural_loops);
 
         // Output based on format
         match cli.output {
             OutputFormat::Human => {
-                println!("Function: {}", args.function);
-                println!("Natural Loops: {}", natural_loops.len());
-                println!();
+                outln!("Function: {}", args.function);
+                outln!("Natural Loops: {}", loop_infos.len());
+                outln!();
 
-                if natural_loops.is_empty() {
+                if loop_infos.is_empty() {
                     output::info("No natural loops detected in this function");
                 } else {
                     for (i, loop_info) in loop_infos.iter().enumerate() {
-                        println!("Loop {}:", i + 1);
-                        println!("  Header: Block {}", loop_info.header);
-                        println!("  Back edge from: Block {}", loop_info.back_edge_from);
-                        println!("  Body size: {} blocks", loop_info.body_size);
-                        println!("  Nesting level: {}", loop_info.nesting_level);
+                        outln!("Loop {}:", i + 1);
+                        outln!("  Header: Block {}", loop_info.header);
+                        outln!("  Back edge from: Block {}", loop_info.back_edge_from);
+                        outln!("  Body size: {} blocks", loop_info.body_size);
+                        outln!("  Nesting level: {}", loop_info.nesting_level);
+                        match loop_info.preheader {
+                            Some(preheader) => outln!("  Preheader: Block {}", preheader),
+                            None => outln!("  Preheader: none"),
+                        }
+                        let exits: Vec<String> = loop_info.exit_edges.iter()
+                            .map(|(from, to)| format!("{} -> {}", from, to))
+                            .collect();
+                        outln!("  Exit edges: {}", if exits.is_empty() { "none".to_string() } else { exits.join(", ") });
 
                         if args.verbose {
-                            println!("  Body blocks: {:?}", loop_info.body_blocks);
+                            outln!("  Body blocks: {:?}", loop_info.body_blocks);
                         }
-                        println!();
+                        outln!();
+                    }
+                }
+
+                if !self_loop_infos.is_empty() {
+                    outln!("Self-loops: {}", self_loop_infos.len());
+                    for loop_info in &self_loop_infos {
+                        outln!("  Block {} (nesting level {})", loop_info.header, loop_info.nesting_level);
                     }
                 }
             }
             OutputFormat::Json | OutputFormat::Pretty => {
                 let response = LoopsResponse {
                     function: args.function.clone(),
-                    loop_count: natural_loops.len(),
+                    loop_count: loop_infos.len(),
                     loops: loop_infos,
+                    self_loops: self_loop_infos,
                 };
                 let wrapper = output::JsonResponse::new(response);
                 match cli.output {
-                    OutputFormat::Json => println!("{}", wrapper.to_json()),
-                    OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                     _ => unreachable!(),
                 }
             }
@@ -1989,7 +4826,7 @@ pub mod cmds {
         use crate::analysis::DeadSymbolJson;
         use crate::cfg::reachability::find_unreachable;
         use crate::cfg::load_cfg_from_db;
-        use crate::storage::MirageDb;
+        use crate::output::sarif::{unreachable_sarif, UnreachableFinding};
         use petgraph::visit::EdgeRef;
 
         // Resolve database path
@@ -2023,35 +4860,26 @@ pub mod cmds {
         };
 
         // Open database (follows status command pattern for error handling)
-        let db = match MirageDb::open(&db_path) {
+        let mut db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 // JSON-aware error handling with remediation
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
-        // Struct to hold unreachable results per function
-        struct FunctionUnreachable {
-            function_name: String,
-            function_id: i64,
-            blocks: Vec<UnreachableBlock>,
-        }
-
-        // Query all functions from the database
-        // Use prepare and execute to handle multiple rows properly
-        let mut function_rows: Vec<(String, i64)> = Vec::new();
-        let mut stmt = match db.conn()?.prepare("SELECT name, id FROM graph_entities WHERE kind = 'function'") {
-            Ok(stmt) => stmt,
+        // Query all functions from the database (works on every backend)
+        let mut function_rows: Vec<(String, i64)> = match db.storage().function_ids() {
+            Ok(functions) => functions.into_iter().map(|(id, name)| (name, id)).collect(),
             Err(e) => {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::new(
@@ -2060,69 +4888,58 @@ pub mod cmds {
                         output::E_DATABASE_NOT_FOUND,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to query functions: {}", e));
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
-        let rows_result = stmt.query_map([], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        // Restrict to functions changed since the --only-changed baseline
+        let changed_functions = only_changed_functions(&db, cli).map(|changed| {
+            function_rows.retain(|(_, id)| changed.contains(id));
+            function_rows.len()
         });
 
-        match rows_result {
-            Ok(rows) => {
-                for row in rows {
-                    match row {
-                        Ok((name, id)) => function_rows.push((name, id)),
-                        Err(e) => {
-                            if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                                let error = output::JsonError::new(
-                                    "QueryError",
-                                    &format!("Failed to read function row: {}", e),
-                                    output::E_DATABASE_NOT_FOUND,
-                                );
-                                let wrapper = output::JsonResponse::new(error);
-                                println!("{}", wrapper.to_json());
-                                std::process::exit(output::EXIT_DATABASE);
-                            } else {
-                                output::error(&format!("Failed to read function row: {}", e));
-                                std::process::exit(output::EXIT_DATABASE);
+        // Load CFG for each function and find unreachable blocks
+        let statement_limits = cli.statement_limits();
+        let mut all_results = Vec::new();
+        let mut sarif_findings = Vec::new();
+        let mut source_map = crate::cfg::SourceMap::new();
+        for (function_name, function_id) in function_rows {
+            match load_cfg_from_db(&db, function_id) {
+                Ok(mut cfg) => {
+                    if args.with_source {
+                        source_map.resolve_cfg(&mut cfg);
+                    }
+                    // The cached dominator tree already knows which blocks the entry reaches
+                    let unreachable = match load_or_compute_dominator_tree(&mut db, function_id, &cfg) {
+                        Some(tree) => tree.unreachable_nodes(),
+                        None => find_unreachable(&cfg),
+                    };
+                    let mut unreachable_indices: Vec<(petgraph::graph::NodeIndex, Option<String>)> =
+                        unreachable.into_iter().map(|idx| (idx, None)).collect();
+                    if args.conditional {
+                        unreachable_indices.extend(conditionally_dead_blocks(&cfg));
+                    }
+                    if args.sarif {
+                        sarif_findings.extend(unreachable_indices.iter().map(|&(idx, _)| {
+                            let block = &cfg[idx];
+                            UnreachableFinding {
+                                function: function_name.clone(),
+                                block_id: block.id,
+                                statements: statement_limits.apply(&block.statements),
+                                terminator: format!("{:?}", block.terminator),
+                                location: block.source_location.clone(),
                             }
-                        }
+                        }));
                     }
-                }
-            }
-            Err(e) => {
-                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::new(
-                        "QueryError",
-                        &format!("Failed to execute query: {}", e),
-                        output::E_DATABASE_NOT_FOUND,
-                    );
-                    let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
-                } else {
-                    output::error(&format!("Failed to execute query: {}", e));
-                    std::process::exit(output::EXIT_DATABASE);
-                }
-            }
-        }
-
-        // Load CFG for each function and find unreachable blocks
-        let mut all_results = Vec::new();
-        for (function_name, function_id) in function_rows {
-            match load_cfg_from_db(&db, function_id) {
-                Ok(cfg) => {
-                    let unreachable_indices = find_unreachable(&cfg);
                     if !unreachable_indices.is_empty() {
                         let blocks: Vec<UnreachableBlock> = unreachable_indices
-                            .iter()
-                            .map(|&idx| {
+                            .into_iter()
+                            .map(|(idx, conditionally_dead)| {
                                 let block = &cfg[idx];
                                 let kind_str = format!("{:?}", block.kind);
                                 let terminator_str = format!("{:?}", block.terminator);
@@ -2143,18 +4960,27 @@ pub mod cmds {
                                     vec![]
                                 };
 
+                                let (source, source_note) = if args.with_source {
+                                    unreachable_source(block, &mut source_map)
+                                } else {
+                                    (None, None)
+                                };
+
                                 UnreachableBlock {
                                     block_id: block.id,
                                     kind: kind_str,
-                                    statements: block.statements.clone(),
+                                    statements: statement_limits.apply(&block.statements),
                                     terminator: terminator_str,
                                     incoming_edges,
+                                    source,
+                                    source_note,
+                                    conditionally_dead,
                                 }
                             })
                             .collect();
 
-                        all_results.push(FunctionUnreachable {
-                            function_name,
+                        all_results.push(UnreachableFunction {
+                            function: function_name,
                             function_id,
                             blocks,
                         });
@@ -2167,6 +4993,16 @@ pub mod cmds {
             }
         }
 
+        // SARIF replaces the normal output entirely
+        if args.sarif {
+            let log = unreachable_sarif(&sarif_findings);
+            match cli.output {
+                OutputFormat::Pretty | OutputFormat::Human => outln!("{}", log.to_pretty_json()),
+                OutputFormat::Json => outln!("{}", log.to_json()),
+            }
+            return Ok(());
+        }
+
         // Calculate totals
         let total_functions = all_results.len();
         let functions_with_unreachable = all_results.iter().filter(|r| !r.blocks.is_empty()).count();
@@ -2175,16 +5011,21 @@ pub mod cmds {
         // Format output based on cli.output
         match cli.output {
             OutputFormat::Human => {
+                if let Some(count) = changed_functions {
+                    outln!("Changed functions considered: {}", count);
+                    outln!();
+                }
+
                 // Show uncalled functions first if available
                 if let Some(ref uncalled) = uncalled_functions {
-                    println!("Uncalled Functions ({}):", uncalled.len());
+                    outln!("Uncalled Functions ({}):", uncalled.len());
                     for dead in uncalled {
                         let name = dead.fqn.as_deref().unwrap_or("?");
-                        println!("  - {} ({})", name, dead.kind);
-                        println!("    File: {}", dead.file_path);
-                        println!("    Reason: {}", dead.reason);
+                        outln!("  - {} ({})", name, dead.kind);
+                        outln!("    File: {}", dead.file_path);
+                        outln!("    Reason: {}", dead.reason);
                     }
-                    println!();
+                    outln!();
                 }
 
                 // Show unreachable blocks
@@ -2195,45 +5036,75 @@ pub mod cmds {
                     return Ok(());
                 }
 
-                println!("Unreachable Code Blocks:");
-                println!("  Total blocks: {}", total_blocks);
-                println!("  Functions with unreachable: {}/{}", functions_with_unreachable, total_functions);
-                println!();
+                outln!("Unreachable Code Blocks:");
+                outln!("  Total blocks: {}", total_blocks);
+                outln!("  Functions with unreachable: {}/{}", functions_with_unreachable, total_functions);
+                outln!();
 
                 for result in &all_results {
                     if result.blocks.is_empty() {
                         continue;
                     }
 
-                    println!("Function: {}", result.function_name);
+                    outln!("Function: {}", result.function);
 
                     for block in &result.blocks {
-                        println!("  Block {} ({})", block.block_id, block.kind);
+                        outln!("  Block {} ({})", block.block_id, block.kind);
                         if !block.statements.is_empty() {
                             for stmt in &block.statements {
-                                println!("    - {}", stmt);
+                                outln!("    - {}", stmt);
+                            }
+                        }
+                        outln!("    Terminator: {}", block.terminator);
+                        if let Some(ref source) = block.source {
+                            outln!("    Source ({}):", source.file_path);
+                            for line in &source.lines {
+                                let marker = if line.covered { '>' } else { ' ' };
+                                outln!("    {} {:>5} | {}", marker, line.line, line.text);
                             }
                         }
-                        println!("    Terminator: {}", block.terminator);
-                        println!();
+                        if let Some(ref note) = block.source_note {
+                            outln!("    Source: {}", note);
+                        }
+                        if let Some(ref reason) = block.conditionally_dead {
+                            outln!("    Conditionally dead: {}", reason);
+                        }
+                        outln!();
                     }
 
                     if args.show_branches {
-                        println!("  Incoming Edges:");
+                        outln!("  Incoming Edges:");
                         for block in &result.blocks {
                             if block.incoming_edges.is_empty() {
-                                println!("    Block {} has no incoming edges (entry or isolated)", block.block_id);
+                                outln!("    Block {} has no incoming edges (entry or isolated)", block.block_id);
                             } else {
-                                println!("    Block {} incoming edges:", block.block_id);
+                                outln!("    Block {} incoming edges:", block.block_id);
                                 for edge in &block.incoming_edges {
-                                    println!("      from block {} ({})", edge.from_block, edge.edge_type);
+                                    outln!("      from block {} ({})", edge.from_block, edge.edge_type);
                                 }
                             }
                         }
-                        println!();
+                        outln!();
                     }
                 }
             }
+            OutputFormat::Json | OutputFormat::Pretty if args.group_by_function => {
+                let response = GroupedUnreachableResponse {
+                    total_functions,
+                    functions_with_unreachable,
+                    unreachable_count: total_blocks,
+                    functions: all_results,
+                    uncalled_functions,
+                    changed_functions,
+                };
+                let wrapper = output::JsonResponse::new(response);
+
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => {}
+                }
+            }
             OutputFormat::Json | OutputFormat::Pretty => {
                 // For multi-function mode, flatten blocks across all functions
                 let all_blocks: Vec<UnreachableBlock> = all_results.iter().flat_map(|r| r.blocks.clone()).collect();
@@ -2245,12 +5116,13 @@ pub mod cmds {
                     unreachable_count: total_blocks,
                     blocks: all_blocks,
                     uncalled_functions: uncalled_functions,
+                    changed_functions,
                 };
                 let wrapper = output::JsonResponse::new(response);
 
                 match cli.output {
-                    OutputFormat::Json => println!("{}", wrapper.to_json()),
-                    OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                     _ => {}
                 }
             }
@@ -2261,31 +5133,37 @@ pub mod cmds {
 
     pub fn verify(args: &VerifyArgs, cli: &Cli) -> Result<()> {
         use crate::cfg::{PathLimits, enumerate_paths, load_cfg_from_db};
-        use crate::storage::MirageDb;
         use rusqlite::OptionalExtension;
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
         // Open database (follows status command pattern for error handling)
-        let db = match MirageDb::open(&db_path) {
+        let db = match super::session::open(&db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 // JSON-aware error handling with remediation
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
-        let path_id = &args.path_id;
+        if let Some(ref function) = args.function {
+            return verify_function_batch(&db, function, cli);
+        }
+
+        let Some(path_id) = args.path_id.as_ref() else {
+            output::exit_usage("Either --path-id or --function is required");
+        };
+        let path_id = &resolve_path_id(&db, path_id);
 
         // Check if path exists in cache by querying cfg_paths table
         let cached_path_info: Option<(String, i64, String)> = db.conn()?
@@ -2318,14 +5196,14 @@ pub mod cmds {
 
                 match cli.output {
                     OutputFormat::Human => {
-                        println!("Path ID {}: not found in cache", path_id);
-                        println!("  The path may have been invalidated or never existed.");
+                        outln!("Path ID {}: not found in cache", path_id);
+                        outln!("  The path may have been invalidated or never existed.");
                     }
                     OutputFormat::Json | OutputFormat::Pretty => {
                         let wrapper = output::JsonResponse::new(result);
                         match cli.output {
-                            OutputFormat::Json => println!("{}", wrapper.to_json()),
-                            OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                            OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                            OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                             _ => unreachable!(),
                         }
                     }
@@ -2346,12 +5224,12 @@ pub mod cmds {
                         output::E_CFG_ERROR,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to load CFG for function_id {}", function_id));
                     output::info("The function data may be corrupted. Try re-running 'magellan watch'");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -2382,12 +5260,12 @@ pub mod cmds {
 
         match cli.output {
             OutputFormat::Human => {
-                println!("Path ID {}: {}", path_id, if result.valid { "valid" } else { "invalid" });
-                println!("  Found in cache: {}", if found_in_cache { "yes" } else { "no" });
-                println!("  Status: {}", result.reason);
-                println!("  Current total paths: {}", current_path_count);
+                outln!("Path ID {}: {}", path_id, if result.valid { "valid" } else { "invalid" });
+                outln!("  Found in cache: {}", if found_in_cache { "yes" } else { "no" });
+                outln!("  Status: {}", result.reason);
+                outln!("  Current total paths: {}", current_path_count);
                 if !path_still_valid {
-                    println!();
+                    outln!();
                     output::info("The path may have been invalidated by code changes.");
                     output::info("Consider re-running path enumeration to update the cache.");
                 }
@@ -2395,8 +5273,117 @@ pub mod cmds {
             OutputFormat::Json | OutputFormat::Pretty => {
                 let wrapper = output::JsonResponse::new(result);
                 match cli.output {
-                    OutputFormat::Json => println!("{}", wrapper.to_json()),
-                    OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Re-verify every cached path of a function against a single enumeration
+    fn verify_function_batch(db: &crate::storage::MirageDb, function: &str, cli: &Cli) -> Result<()> {
+        use crate::cfg::{PathLimits, enumerate_paths, load_cfg_from_db, resolve_function_name};
+
+        // Resolve function name/ID to function_id
+        let function_id = match resolve_function_name(db, function) {
+            Ok(id) => id,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::function_not_found(function);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Function '{}' not found in database", function));
+                    output::info("Hint: Run 'magellan watch' to index your code");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Load all cached paths (ID and block sequence) for this function
+        let cached: Vec<(String, Vec<crate::cfg::BlockId>)> = {
+            let conn = db.conn()?;
+            let mut stmt = conn.prepare(
+                "SELECT p.path_id, pe.block_id
+                 FROM cfg_paths p
+                 LEFT JOIN cfg_path_elements pe ON pe.path_id = p.path_id
+                 WHERE p.function_id = ?1
+                 ORDER BY p.path_id, pe.sequence_order",
+            )?;
+            let rows = stmt.query_map(rusqlite::params![function_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
+            })?;
+            let mut cached: Vec<(String, Vec<crate::cfg::BlockId>)> = Vec::new();
+            for row in rows {
+                let (path_id, block_id) = row?;
+                if cached.last().map(|(id, _)| id != &path_id).unwrap_or(true) {
+                    cached.push((path_id, Vec::new()));
+                }
+                if let (Some(block_id), Some((_, blocks))) = (block_id, cached.last_mut()) {
+                    blocks.push(block_id as crate::cfg::BlockId);
+                }
+            }
+            cached
+        };
+
+        // A function whose blocks are gone invalidates every cached path
+        let function_gone = db.storage().get_cfg_blocks(function_id).map(|b| b.is_empty()).unwrap_or(false);
+        let cfg = match load_cfg_from_db(db, function_id) {
+            Ok(cfg) => Some(cfg),
+            Err(_) if function_gone => None,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "CgfLoadError",
+                        &format!("Failed to load CFG for function '{}'", function),
+                        output::E_CFG_ERROR,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to load CFG for function '{}'", function));
+                    output::info("The function data may be corrupted. Try re-running 'magellan watch'");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Enumerate once and check every cached path against it
+        let current_paths = cfg.as_ref()
+            .map(|cfg| enumerate_paths(cfg, &PathLimits::default()))
+            .unwrap_or_default();
+        let result = summarize_verify_batch(function, cached, cfg.as_ref(), &current_paths);
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", result.function);
+                outln!("  Cached paths: {}", result.total);
+                outln!("  Still valid: {}", result.still_valid);
+                outln!("  Invalidated: {}", result.invalidated.len());
+                outln!("  Current total paths: {}", result.current_paths);
+                if result.total == 0 {
+                    outln!();
+                    output::info("No cached paths found for this function.");
+                } else if !result.invalidated.is_empty() {
+                    outln!();
+                    outln!("Invalidated path IDs:");
+                    for verdict in result.paths.iter().filter(|v| v.reason != VerifyReason::StillValid) {
+                        outln!("  {} ({:?})", verdict.path_id, verdict.reason);
+                    }
+                    outln!();
+                    output::info("Consider re-running path enumeration to update the cache.");
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let wrapper = output::JsonResponse::new(result);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                     _ => unreachable!(),
                 }
             }
@@ -2405,34 +5392,91 @@ pub mod cmds {
         Ok(())
     }
 
+    /// Classify cached paths (ID and block sequence) against one enumeration
+    ///
+    /// `cfg` is None when the function has no blocks any more. A path that is
+    /// no longer enumerated is `EntryMissing` if it does not start at the
+    /// current entry block, and `BranchChanged` otherwise.
+    pub(super) fn summarize_verify_batch(
+        function: &str,
+        cached: Vec<(String, Vec<crate::cfg::BlockId>)>,
+        cfg: Option<&crate::cfg::Cfg>,
+        current_paths: &[crate::cfg::Path],
+    ) -> VerifyBatchResult {
+        let current_ids: std::collections::HashSet<&str> = current_paths
+            .iter()
+            .map(|p| p.path_id.as_str())
+            .collect();
+        let entry = cfg.and_then(|cfg| crate::cfg::find_entry(cfg).map(|node| cfg[node].id));
+
+        let paths: Vec<PathVerdict> = cached
+            .into_iter()
+            .map(|(path_id, blocks)| {
+                let reason = if cfg.map_or(true, |cfg| cfg.node_count() == 0) {
+                    VerifyReason::FunctionGone
+                } else if current_ids.contains(path_id.as_str()) {
+                    VerifyReason::StillValid
+                } else if blocks.first().copied() != entry {
+                    VerifyReason::EntryMissing
+                } else {
+                    VerifyReason::BranchChanged
+                };
+                PathVerdict { path_id, reason }
+            })
+            .collect();
+        let invalidated: Vec<String> = paths
+            .iter()
+            .filter(|v| v.reason != VerifyReason::StillValid)
+            .map(|v| v.path_id.clone())
+            .collect();
+
+        VerifyBatchResult {
+            function: function.to_string(),
+            total: paths.len(),
+            still_valid: paths.len() - invalidated.len(),
+            invalidated,
+            current_paths: current_paths.len(),
+            paths,
+        }
+    }
+
     pub fn blast_zone(args: &BlastZoneArgs, cli: &Cli) -> Result<()> {
-        use crate::cfg::{find_reachable_from_block, load_cfg_from_db, resolve_function_name};
-        use crate::storage::{compute_path_impact_from_db, get_function_name_db, MirageDb};
+        use crate::cfg::{find_blocks_reaching, find_reachable_from_block, find_reachable_until_convergence, load_cfg_from_db, resolve_function_name, ImpactDirection};
+        use crate::storage::{compute_path_impact_from_db, get_function_name_db};
         use rusqlite::OptionalExtension;
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
         // Open database (follows status command pattern for error handling)
-        let db = match MirageDb::open(&db_path) {
+        let db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Inter-function impact via the call graph
+        if let Some(ref target) = args.callers {
+            return blast_zone_calls(&db, target, crate::analysis::CallDirection::Callers, args.max_depth, cli);
+        }
+        if let Some(ref target) = args.callees {
+            return blast_zone_calls(&db, target, crate::analysis::CallDirection::Callees, args.max_depth, cli);
+        }
+
         // Determine query type: path-based or block-based
         if let Some(ref path_id) = args.path_id {
             // Path-based impact analysis
+            let path_id = resolve_path_id(&db, path_id);
             let path_id_trimmed = path_id.trim();
 
             // Validate path_id format (basic BLAKE3 hex check)
@@ -2441,12 +5485,12 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::new("InvalidInput", &msg, output::E_INVALID_INPUT);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_USAGE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_USAGE);
                 } else {
                     output::error(&msg);
-                    output::info("Path ID should be a BLAKE3 hash (64 hex characters)");
-                    std::process::exit(output::EXIT_USAGE);
+                    output::info("Path ID should be a BLAKE3 hash (64 hex characters) or a readable ID like foo#p1");
+                    output::exit(output::EXIT_USAGE);
                 }
             }
 
@@ -2462,12 +5506,12 @@ pub mod cmds {
                     if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                         let error = output::JsonError::new("PathNotFound", &msg, output::E_PATH_NOT_FOUND);
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_FILE_NOT_FOUND);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_FILE_NOT_FOUND);
                     } else {
                         output::error(&msg);
                         output::info("Hint: Run 'mirage paths' to enumerate paths first");
-                        std::process::exit(output::EXIT_FILE_NOT_FOUND);
+                        output::exit(output::EXIT_FILE_NOT_FOUND);
                     }
                 }
                 Err(e) => {
@@ -2475,11 +5519,11 @@ pub mod cmds {
                     if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                         let error = output::JsonError::new("DatabaseError", &msg, output::E_DATABASE_NOT_FOUND);
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_DATABASE);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
                     } else {
                         output::error(&msg);
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::exit(output::EXIT_DATABASE);
                     }
                 }
             };
@@ -2490,12 +5534,12 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::new("ErrorPathExcluded", &msg, output::E_INVALID_INPUT);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_USAGE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_USAGE);
                 } else {
                     output::error(&msg);
                     output::info("Use --include-errors to include error paths in analysis");
-                    std::process::exit(output::EXIT_USAGE);
+                    output::exit(output::EXIT_USAGE);
                 }
             }
 
@@ -2507,12 +5551,12 @@ pub mod cmds {
                     if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                         let error = output::JsonError::new("CgfLoadError", &msg, output::E_CFG_ERROR);
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_DATABASE);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
                     } else {
                         output::error(&msg);
                         output::info("The function may be corrupted. Try re-running 'magellan watch'");
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::exit(output::EXIT_DATABASE);
                     }
                 }
             };
@@ -2530,11 +5574,11 @@ pub mod cmds {
                     if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                         let error = output::JsonError::new("ImpactError", &msg, output::E_CFG_ERROR);
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_ERROR);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
                     } else {
                         output::error(&msg);
-                        std::process::exit(output::EXIT_ERROR);
+                        output::exit(output::EXIT_DATABASE);
                     }
                 }
             };
@@ -2577,43 +5621,43 @@ pub mod cmds {
             // Output
             match cli.output {
                 OutputFormat::Human => {
-                    println!("Path Impact Analysis");
-                    println!();
-                    println!("Path ID: {}", impact.path_id);
-                    println!("Function: {}", function_name);
-                    println!("Path kind: {}", path_kind);
-                    println!("Path length: {} blocks", impact.path_length);
-                    println!();
+                    outln!("Path Impact Analysis");
+                    outln!();
+                    outln!("Path ID: {}", impact.path_id);
+                    outln!("Function: {}", function_name);
+                    outln!("Path kind: {}", path_kind);
+                    outln!("Path length: {} blocks", impact.path_length);
+                    outln!();
 
                     // Show call graph impact if available
                     if let Some(ref forward) = forward_impact {
-                        println!("Inter-Procedural Impact (Call Graph):");
-                        println!("  Forward Impact: {} functions reached", forward.len());
+                        outln!("Inter-Procedural Impact (Call Graph):");
+                        outln!("  Forward Impact: {} functions reached", forward.len());
                         for sym in forward {
-                            println!("    - {}", sym.fqn.as_deref().unwrap_or(&sym.file_path));
+                            outln!("    - {}", sym.fqn.as_deref().unwrap_or(&sym.file_path));
                         }
                     }
                     if let Some(ref backward) = backward_impact {
                         if !backward.is_empty() {
-                            println!("  Backward Impact: {} functions can reach this", backward.len());
+                            outln!("  Backward Impact: {} functions can reach this", backward.len());
                             for sym in backward {
-                                println!("    - {}", sym.fqn.as_deref().unwrap_or(&sym.file_path));
+                                outln!("    - {}", sym.fqn.as_deref().unwrap_or(&sym.file_path));
                             }
                         }
                     }
-                    println!();
+                    outln!();
 
-                    println!("Intra-Procedural Impact (CFG):");
-                    println!("  Unique blocks affected: {}", impact.impact_count);
+                    outln!("Intra-Procedural Impact (CFG):");
+                    outln!("  Unique blocks affected: {}", impact.impact_count);
                     if impact.impact_count > 0 {
-                        println!("  Affected blocks: {:?}", impact.unique_blocks_affected);
+                        outln!("  Affected blocks: {:?}", impact.unique_blocks_affected);
                     } else {
-                        println!("  Affected blocks: (none - path has no downstream impact)");
+                        outln!("  Affected blocks: (none - path has no downstream impact)");
                     }
                     if let Some(depth) = max_depth {
-                        println!("  Max depth: {}", depth);
+                        outln!("  Max depth: {}", depth);
                     } else {
-                        println!("  Max depth: unlimited");
+                        outln!("  Max depth: unlimited");
                     }
                 }
                 OutputFormat::Json | OutputFormat::Pretty => {
@@ -2627,8 +5671,8 @@ pub mod cmds {
                     };
                     let wrapper = output::JsonResponse::new(response);
                     match cli.output {
-                        OutputFormat::Json => println!("{}", wrapper.to_json()),
-                        OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                        OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                        OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                         _ => unreachable!(),
                     }
                 }
@@ -2646,12 +5690,12 @@ pub mod cmds {
                     if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                         let error = output::JsonError::function_not_found(function_ref);
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_DATABASE);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
                     } else {
                         output::error(&format!("Function '{}' not found in database", function_ref));
                         output::info("Hint: Run 'magellan watch' to index your code");
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::exit(output::EXIT_DATABASE);
                     }
                 }
             };
@@ -2671,12 +5715,12 @@ pub mod cmds {
                             output::E_CFG_ERROR,
                         );
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_DATABASE);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
                     } else {
                         output::error(&format!("Failed to load CFG for function '{}'", function_ref));
                         output::info("The function may be corrupted. Try re-running 'magellan watch'");
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::exit(output::EXIT_DATABASE);
                     }
                 }
             };
@@ -2694,17 +5738,24 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::new("BlockNotFound", &msg, output::E_BLOCK_NOT_FOUND);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_VALIDATION);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_ERROR);
                 } else {
                     output::error(&msg);
-                    std::process::exit(output::EXIT_VALIDATION);
+                    output::exit(output::EXIT_ERROR);
                 }
             }
 
             // Compute block impact
             let max_depth = if args.max_depth == 100 { None } else { Some(args.max_depth) };
-            let impact = find_reachable_from_block(&cfg, block_id, max_depth);
+            let direction = if args.reverse { ImpactDirection::Backward } else { ImpactDirection::Forward };
+            let (impact, convergence_block) = match direction {
+                ImpactDirection::Forward if args.until_convergence => {
+                    find_reachable_until_convergence(&cfg, block_id, max_depth)
+                }
+                ImpactDirection::Forward => (find_reachable_from_block(&cfg, block_id, max_depth), None),
+                ImpactDirection::Backward => (find_blocks_reaching(&cfg, block_id, max_depth), None),
+            };
 
             // Compute call graph impact if requested
             let (forward_impact, backward_impact): (Option<Vec<CallGraphSymbol>>, Option<Vec<CallGraphSymbol>>) = if args.use_call_graph {
@@ -2744,60 +5795,80 @@ pub mod cmds {
             // Output
             match cli.output {
                 OutputFormat::Human => {
-                    println!("Block Impact Analysis (Blast Zone)");
-                    println!();
-                    println!("Function: {}", function_name);
-                    println!("Source block: {}", impact.source_block_id);
-                    println!();
+                    outln!("Block Impact Analysis (Blast Zone)");
+                    outln!();
+                    outln!("Function: {}", function_name);
+                    outln!("Source block: {}", impact.source_block_id);
+                    outln!();
 
                     // Show call graph impact if available
                     if let Some(ref forward) = forward_impact {
-                        println!("Inter-Procedural Impact (Call Graph):");
-                        println!("  Forward Impact: {} functions reached", forward.len());
+                        outln!("Inter-Procedural Impact (Call Graph):");
+                        outln!("  Forward Impact: {} functions reached", forward.len());
                         for sym in forward {
-                            println!("    - {}", sym.fqn.as_deref().unwrap_or(&sym.file_path));
+                            outln!("    - {}", sym.fqn.as_deref().unwrap_or(&sym.file_path));
                         }
                     }
                     if let Some(ref backward) = backward_impact {
                         if !backward.is_empty() {
-                            println!("  Backward Impact: {} functions can reach this", backward.len());
+                            outln!("  Backward Impact: {} functions can reach this", backward.len());
                             for sym in backward {
-                                println!("    - {}", sym.fqn.as_deref().unwrap_or(&sym.file_path));
+                                outln!("    - {}", sym.fqn.as_deref().unwrap_or(&sym.file_path));
                             }
                         }
                     }
-                    println!();
-
-                    println!("Intra-Procedural Impact (CFG):");
-                    println!("  Reachable blocks: {}", impact.reachable_count);
-                    if impact.reachable_count > 0 {
-                        println!("  Affected blocks: {:?}", impact.reachable_blocks);
-                    } else {
-                        println!("  Affected blocks: (none - block has no downstream impact)");
+                    outln!();
+
+                    outln!("Intra-Procedural Impact (CFG):");
+                    match direction {
+                        ImpactDirection::Forward => {
+                            outln!("  Reachable blocks: {}", impact.reachable_count);
+                            if args.until_convergence {
+                                match convergence_block {
+                                    Some(block) => outln!("  Converges at: Block {}", block),
+                                    None => outln!("  Converges at: (none - no post-dominator, walk is unbounded)"),
+                                }
+                            }
+                            if impact.reachable_count > 0 {
+                                outln!("  Affected blocks: {:?}", impact.reachable_blocks);
+                            } else {
+                                outln!("  Affected blocks: (none - block has no downstream impact)");
+                            }
+                        }
+                        ImpactDirection::Backward => {
+                            outln!("  Blocks reaching this one: {}", impact.reachable_count);
+                            if impact.reachable_count > 0 {
+                                outln!("  Predecessor blocks: {:?}", impact.reachable_blocks);
+                            } else {
+                                outln!("  Predecessor blocks: (none - block is not reached from any other block)");
+                            }
+                        }
                     }
-                    println!("  Max depth reached: {}", impact.max_depth_reached);
-                    println!("  Contains cycles: {}", if impact.has_cycles { "yes (loop detected)" } else { "no" });
+                    outln!("  Max depth reached: {}", impact.max_depth_reached);
+                    outln!("  Contains cycles: {}", if impact.has_cycles { "yes (loop detected)" } else { "no" });
                     if let Some(depth) = max_depth {
-                        println!("  Depth limit: {}", depth);
+                        outln!("  Depth limit: {}", depth);
                     } else {
-                        println!("  Depth limit: unlimited");
+                        outln!("  Depth limit: unlimited");
                     }
                 }
                 OutputFormat::Json | OutputFormat::Pretty => {
                     let response = BlockImpactResponse {
                         function: function_name,
                         block_id: impact.source_block_id,
+                        direction,
                         reachable_blocks: impact.reachable_blocks,
                         reachable_count: impact.reachable_count,
                         max_depth: impact.max_depth_reached,
                         has_cycles: impact.has_cycles,
+                        convergence_block,
                         forward_impact: forward_impact.clone(),
                         backward_impact: backward_impact.clone(),
                     };
                     let wrapper = output::JsonResponse::new(response);
                     match cli.output {
-                        OutputFormat::Json => println!("{}", wrapper.to_json()),
-                        OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                        OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                        OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                         _ => unreachable!(),
                     }
                 }
@@ -2807,11 +5878,95 @@ pub mod cmds {
         Ok(())
     }
 
+    /// Report transitive callers or callees of a function (blast-zone --callers/--callees)
+    fn blast_zone_calls(
+        db: &crate::storage::MirageDb,
+        target: &str,
+        direction: crate::analysis::CallDirection,
+        max_depth: usize,
+        cli: &Cli,
+    ) -> Result<()> {
+        use crate::analysis::{call_graph_distances, CallDirection};
+        use crate::cfg::resolve_function_name;
+        use crate::storage::get_function_name_db;
+
+        // Resolve function name/ID to function_id
+        let function_id = match resolve_function_name(db, target) {
+            Ok(id) => id,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::function_not_found(target);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Function '{}' not found in database", target));
+                    output::info("Hint: Run 'magellan watch' to index your code");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let distances = match call_graph_distances(db.backend(), function_id, direction, max_depth) {
+            Ok(distances) => distances,
+            Err(e) => {
+                // Call graph not available - warn but still produce a (empty) report
+                output::warn(&format!("Could not query Magellan call graph: {}", e));
+                if matches!(cli.output, OutputFormat::Human) {
+                    output::info("Note: --callers/--callees requires a Magellan code graph database");
+                }
+                Vec::new()
+            }
+        };
+
+        let functions: Vec<CallerEntry> = distances.into_iter().map(|(id, distance)| CallerEntry {
+            function_id: id,
+            name: get_function_name_db(db, id).unwrap_or_else(|| format!("<function_{}>", id)),
+            distance,
+            has_cfg: db.storage().get_cfg_blocks(id).map(|b| !b.is_empty()).unwrap_or(false),
+        }).collect();
+
+        match cli.output {
+            OutputFormat::Human => {
+                let label = match direction {
+                    CallDirection::Callers => "Callers",
+                    CallDirection::Callees => "Callees",
+                };
+                outln!("Call Graph Impact Analysis");
+                outln!();
+                outln!("Target: {}", target);
+                outln!("{}: {} functions", label, functions.len());
+                for entry in &functions {
+                    outln!(
+                        "  [{}] {}{}",
+                        entry.distance,
+                        entry.name,
+                        if entry.has_cfg { "" } else { " (no CFG)" }
+                    );
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = CallImpactResponse {
+                    target: target.to_string(),
+                    direction,
+                    functions,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn cycles(args: &CyclesArgs, cli: &Cli) -> Result<()> {
         use crate::analysis::{MagellanBridge, CycleInfo, EnhancedCycles, LoopInfo};
         use crate::cfg::detect_natural_loops;
         use crate::cfg::load_cfg_from_db;
-        use crate::storage::MirageDb;
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
@@ -2849,78 +6004,53 @@ pub mod cmds {
 
         if show_function_loops {
             // Open Mirage database
-            let db = match MirageDb::open(&db_path) {
+            let db = match super::session::open_readonly(&db_path) {
                 Ok(db) => db,
-                Err(_e) => {
-                    if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                        let error = output::JsonError::database_not_found(&db_path);
-                        let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_DATABASE);
-                    } else {
-                        output::error(&format!("Failed to open database: {}", db_path));
-                        output::info("Hint: Run 'magellan watch' to create the database");
-                        std::process::exit(output::EXIT_DATABASE);
-                    }
-                }
-            };
-
-            // Query all functions from the database
-            let mut stmt = match db.conn()?.prepare("SELECT name, id FROM graph_entities WHERE kind = 'function'") {
-                Ok(stmt) => stmt,
                 Err(e) => {
                     if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                        let error = output::JsonError::new(
-                            "QueryError",
-                            &format!("Failed to query functions: {}", e),
-                            output::E_DATABASE_NOT_FOUND,
-                        );
+                        let error = output::JsonError::database_open_failed(&db_path, &e);
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(output::EXIT_DATABASE);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
                     } else {
-                        output::error(&format!("Failed to query functions: {}", e));
-                        std::process::exit(output::EXIT_DATABASE);
+                        output::error(&format!("Failed to open database: {}", db_path));
+                        output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                        output::exit(output::EXIT_DATABASE);
                     }
                 }
             };
 
-            let rows_result = stmt.query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
-            });
+            // Enumerate all functions through the storage backend
+            match db.storage().function_ids() {
+                Ok(functions) => {
+                    for (function_id, function_name) in functions {
+                        // Load CFG for this function
+                        if let Ok(cfg) = load_cfg_from_db(&db, function_id) {
+                            // Detect natural loops
+                            let natural_loops = detect_natural_loops(&cfg);
+
+                            if !natural_loops.is_empty() {
+                                let loop_infos: Vec<LoopInfo> = natural_loops.iter().map(|loop_| {
+                                    let nesting_level = loop_.nesting_level(&natural_loops);
+                                    let body_blocks: Vec<usize> = loop_.body.iter()
+                                        .map(|&node| cfg[node].id)
+                                        .collect();
+                                    LoopInfo {
+                                        header: cfg[loop_.header].id,
+                                        back_edge_from: cfg[loop_.back_edge.0].id,
+                                        body_size: loop_.size(),
+                                        nesting_level,
+                                        body_blocks,
+                                    }
+                                }).collect();
 
-            match rows_result {
-                Ok(rows) => {
-                    for row in rows {
-                        if let Ok((function_name, function_id)) = row {
-                            // Load CFG for this function
-                            if let Ok(cfg) = load_cfg_from_db(&db, function_id) {
-                                // Detect natural loops
-                                let natural_loops = detect_natural_loops(&cfg);
-
-                                if !natural_loops.is_empty() {
-                                    let loop_infos: Vec<LoopInfo> = natural_loops.iter().map(|loop_| {
-                                        let nesting_level = loop_.nesting_level(&natural_loops);
-                                        let body_blocks: Vec<usize> = loop_.body.iter()
-                                            .map(|&node| cfg[node].id)
-                                            .collect();
-                                        LoopInfo {
-                                            header: cfg[loop_.header].id,
-                                            back_edge_from: cfg[loop_.back_edge.0].id,
-                                            body_size: loop_.size(),
-                                            nesting_level,
-                                            body_blocks,
-                                        }
-                                    }).collect();
-
-                                    function_loops_map.insert(function_name, loop_infos);
-                                }
+                                function_loops_map.insert(function_name, loop_infos);
                             }
                         }
                     }
                 }
                 Err(e) => {
-                    eprintln!("Warning: Failed to execute query: {}", e);
+                    output::warn(&format!("Failed to enumerate functions: {}", e));
                 }
             }
         }
@@ -2937,59 +6067,59 @@ pub mod cmds {
         // Output based on format
         match cli.output {
             OutputFormat::Human => {
-                println!("Cycle Detection Report");
-                println!();
+                outln!("Cycle Detection Report");
+                outln!();
 
                 if show_call_graph {
-                    println!("Call Graph Cycles (Inter-procedural): {}", enhanced_cycles.call_graph_cycles.len());
+                    outln!("Call Graph Cycles (Inter-procedural): {}", enhanced_cycles.call_graph_cycles.len());
                     if enhanced_cycles.call_graph_cycles.is_empty() {
-                        println!("  No call graph cycles detected");
+                        outln!("  No call graph cycles detected");
                     } else {
                         for (i, cycle) in enhanced_cycles.call_graph_cycles.iter().enumerate() {
-                            println!("  Cycle {}:", i + 1);
-                            println!("    Type: {}", cycle.cycle_type);
-                            println!("    Size: {} symbols", cycle.size);
+                            outln!("  Cycle {}:", i + 1);
+                            outln!("    Type: {}", cycle.cycle_type);
+                            outln!("    Size: {} symbols", cycle.size);
                             if args.verbose {
-                                println!("    Members:");
+                                outln!("    Members:");
                                 for member in &cycle.members {
-                                    println!("      - {}", member);
+                                    outln!("      - {}", member);
                                 }
                             }
                         }
                     }
-                    println!();
+                    outln!();
                 }
 
                 if show_function_loops {
-                    println!("Function Loops (Intra-procedural): {} functions with loops",
+                    outln!("Function Loops (Intra-procedural): {} functions with loops",
                         enhanced_cycles.function_loops.len());
                     if enhanced_cycles.function_loops.is_empty() {
-                        println!("  No natural loops detected in any function");
+                        outln!("  No natural loops detected in any function");
                     } else {
                         for (function_name, loops) in &enhanced_cycles.function_loops {
-                            println!("  Function: {} ({} loops)", function_name, loops.len());
+                            outln!("  Function: {} ({} loops)", function_name, loops.len());
                             if args.verbose {
                                 for (i, loop_info) in loops.iter().enumerate() {
-                                    println!("    Loop {}:", i + 1);
-                                    println!("      Header: Block {}", loop_info.header);
-                                    println!("      Back edge from: Block {}", loop_info.back_edge_from);
-                                    println!("      Body size: {} blocks", loop_info.body_size);
-                                    println!("      Nesting level: {}", loop_info.nesting_level);
-                                    println!("      Body blocks: {:?}", loop_info.body_blocks);
+                                    outln!("    Loop {}:", i + 1);
+                                    outln!("      Header: Block {}", loop_info.header);
+                                    outln!("      Back edge from: Block {}", loop_info.back_edge_from);
+                                    outln!("      Body size: {} blocks", loop_info.body_size);
+                                    outln!("      Nesting level: {}", loop_info.nesting_level);
+                                    outln!("      Body blocks: {:?}", loop_info.body_blocks);
                                 }
                             }
                         }
                     }
-                    println!();
+                    outln!();
                 }
 
-                println!("Total cycles: {}", total_cycles);
+                outln!("Total cycles: {}", total_cycles);
             }
             OutputFormat::Json | OutputFormat::Pretty => {
                 let wrapper = output::JsonResponse::new(enhanced_cycles);
                 match cli.output {
-                    OutputFormat::Json => println!("{}", wrapper.to_json()),
-                    OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                     _ => unreachable!(),
                 }
             }
@@ -3015,12 +6145,12 @@ pub mod cmds {
                         output::E_DATABASE_NOT_FOUND,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open Magellan database: {}", e));
                     output::info("Note: Program slicing requires a Magellan code graph database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -3038,41 +6168,41 @@ pub mod cmds {
         // Output based on format
         match cli.output {
             OutputFormat::Human => {
-                println!("Program Slice: {}", slice_result.direction);
-                println!();
+                outln!("Program Slice: {}", slice_result.direction);
+                outln!();
 
                 // Target symbol
-                println!("Target:");
-                println!("  Symbol: {}", slice_result.target.fqn.as_deref().unwrap_or(&args.symbol));
-                println!("  Kind: {}", slice_result.target.kind);
-                println!("  File: {}", slice_result.target.file_path);
-                println!();
+                outln!("Target:");
+                outln!("  Symbol: {}", slice_result.target.fqn.as_deref().unwrap_or(&args.symbol));
+                outln!("  Kind: {}", slice_result.target.kind);
+                outln!("  File: {}", slice_result.target.file_path);
+                outln!();
 
                 // Statistics
-                println!("Statistics:");
-                println!("  Total symbols in slice: {}", slice_result.symbol_count);
-                println!("  Data dependencies: {}", slice_result.statistics.data_dependencies);
-                println!("  Control dependencies: {}", slice_result.statistics.control_dependencies);
-                println!();
+                outln!("Statistics:");
+                outln!("  Total symbols in slice: {}", slice_result.symbol_count);
+                outln!("  Data dependencies: {}", slice_result.statistics.data_dependencies);
+                outln!("  Control dependencies: {}", slice_result.statistics.control_dependencies);
+                outln!();
 
                 // Included symbols (verbose only)
                 if args.verbose {
-                    println!("Included symbols ({}):", slice_result.included_symbols.len());
+                    outln!("Included symbols ({}):", slice_result.included_symbols.len());
                     for (i, symbol) in slice_result.included_symbols.iter().enumerate() {
-                        println!("  {}. {}", i + 1, symbol.fqn.as_deref().unwrap_or("<unknown>"));
-                        println!("     Kind: {}, File: {}",
+                        outln!("  {}. {}", i + 1, symbol.fqn.as_deref().unwrap_or("<unknown>"));
+                        outln!("     Kind: {}, File: {}",
                             symbol.kind,
                             symbol.file_path);
                     }
                 } else {
-                    println!("Use --verbose to see all included symbols");
+                    outln!("Use --verbose to see all included symbols");
                 }
             }
             OutputFormat::Json | OutputFormat::Pretty => {
                 let wrapper = output::JsonResponse::new(slice_result);
                 match cli.output {
-                    OutputFormat::Json => println!("{}", wrapper.to_json()),
-                    OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                     _ => unreachable!(),
                 }
             }
@@ -3084,14 +6214,13 @@ pub mod cmds {
     pub fn hotspots(args: &HotspotsArgs, cli: &Cli) -> Result<()> {
         use crate::analysis::MagellanBridge;
         #[cfg(feature = "sqlite")]
-        use crate::cfg::{enumerate_paths_with_context, EnumerationContext, PathLimits, load_cfg_from_db_with_conn};
+        use crate::cfg::{enumerate_paths_with_context, EnumerationContext, PathLimits, load_cfg_from_db};
         use std::collections::HashMap;
-        use crate::storage::MirageDb;
 
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
         // Open Mirage database for intra-procedural analysis
-        let mut db = match MirageDb::open(&db_path) {
+        let db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
             Err(e) => {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
@@ -3101,12 +6230,12 @@ pub mod cmds {
                         output::E_DATABASE_NOT_FOUND
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", e));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -3176,52 +6305,36 @@ pub mod cmds {
         // Fallback to intra-procedural if no hotspots found or inter-procedural failed
         #[cfg(feature = "sqlite")]
         if hotspots.is_empty() {
-            // Get all functions from database by joining with graph_entities
-            let conn = db.conn_mut()?;
-
-            let query = "SELECT DISTINCT cb.function_id, ge.name, ge.file_path
-                        FROM cfg_blocks cb
-                        JOIN graph_entities ge ON cb.function_id = ge.id";
-            let mut stmt = conn.prepare(query)?;
-
-            let function_rows = stmt.query_map([], |row: &rusqlite::Row| {
-                Ok((
-                    row.get::<_, i64>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, String>(2)?,
-                ))
-            })?;
-
-            for func_result in function_rows {
-                if let Ok((func_id, func_name, file_path)) = func_result {
-                    function_count += 1;
-
-                    // Load CFG and enumerate paths
-                    if let Ok(cfg) = load_cfg_from_db_with_conn(conn, func_id) {
-                        let ctx = EnumerationContext::new(&cfg);
-                        let limits = PathLimits::quick_analysis();
-                        let paths = enumerate_paths_with_context(&cfg, &limits, &ctx);
+            for (func_id, func_name) in db.storage().function_ids()? {
+                // Functions without CFG blocks (declarations, unindexed bodies) fail to load
+                let Ok(cfg) = load_cfg_from_db(&db, func_id) else {
+                    continue;
+                };
+                function_count += 1;
 
-                        let path_count = paths.len();
-                        if path_count < args.min_paths.unwrap_or(1) {
-                            continue;
-                        }
+                // Enumerate paths
+                let ctx = EnumerationContext::new(&cfg);
+                let limits = PathLimits::quick_analysis();
+                let paths = enumerate_paths_with_context(&cfg, &limits, &ctx);
 
-                        // Complexity = block count
-                        let complexity = cfg.node_count();
-                        let dominance = 1.0;  // Intra-procedural doesn't have call dominance
-                        let risk_score = path_count as f64 * 0.5 + complexity as f64 * 0.1;
-
-                        hotspots.push(HotspotEntry {
-                            function: func_name.clone(),
-                            risk_score,
-                            path_count,
-                            dominance_factor: dominance,
-                            complexity,
-                            file_path,
-                        });
-                    }
+                let path_count = paths.len();
+                if path_count < args.min_paths.unwrap_or(1) {
+                    continue;
                 }
+
+                // Complexity = block count
+                let complexity = cfg.node_count();
+                let dominance = 1.0;  // Intra-procedural doesn't have call dominance
+                let risk_score = path_count as f64 * 0.5 + complexity as f64 * 0.1;
+
+                hotspots.push(HotspotEntry {
+                    function: func_name,
+                    risk_score,
+                    path_count,
+                    dominance_factor: dominance,
+                    complexity,
+                    file_path: crate::storage::get_function_file_db(&db, func_id).unwrap_or_default(),
+                });
             }
         }
 
@@ -3236,6 +6349,21 @@ pub mod cmds {
         #[cfg(not(feature = "sqlite"))]
         let function_count = 0;
 
+        if args.format == Some(TableFormat::Csv) {
+            outln!("{}", output::csv_record(&["function", "risk_score", "path_count", "dominance_factor", "complexity", "file_path"]));
+            for h in &hotspots {
+                outln!("{}", output::csv_record(&[
+                    h.function.clone(),
+                    h.risk_score.to_string(),
+                    h.path_count.to_string(),
+                    h.dominance_factor.to_string(),
+                    h.complexity.to_string(),
+                    h.file_path.clone(),
+                ]));
+            }
+            return Ok(());
+        }
+
         let response = HotspotsResponse {
             entry_point: args.entry.clone(),
             total_functions: function_count,
@@ -3247,24 +6375,24 @@ pub mod cmds {
             OutputFormat::Human => {
                 output::header(&format!("Hotspots Analysis (entry: {})", response.entry_point));
                 output::info(&format!("Found {} hotspots out of {} functions", hotspots.len(), response.total_functions));
-                println!();
+                outln!();
 
                 for (i, hotspot) in hotspots.iter().enumerate() {
-                    println!("{}. {} (risk: {:.1})", i + 1, hotspot.function, hotspot.risk_score);
+                    outln!("{}. {} (risk: {:.1})", i + 1, hotspot.function, hotspot.risk_score);
                     if args.verbose {
-                        println!("   Paths: {}", hotspot.path_count);
-                        println!("   Dominance: {:.1}", hotspot.dominance_factor);
-                        println!("   Complexity: {}", hotspot.complexity);
+                        outln!("   Paths: {}", hotspot.path_count);
+                        outln!("   Dominance: {:.1}", hotspot.dominance_factor);
+                        outln!("   Complexity: {}", hotspot.complexity);
                     }
                 }
             }
             OutputFormat::Json => {
                 let wrapper = output::JsonResponse::new(response);
-                println!("{}", wrapper.to_json());
+                outln!("{}", wrapper.to_json());
             }
             OutputFormat::Pretty => {
                 let wrapper = output::JsonResponse::new(response);
-                println!("{}", wrapper.to_pretty_json());
+                outln!("{}", wrapper.to_pretty_json());
             }
         }
 
@@ -3276,24 +6404,23 @@ pub mod cmds {
             hotpaths::{compute_hot_paths, HotpathsOptions},
             detect_natural_loops, enumerate_paths, find_entry, PathLimits,
         };
-        use crate::storage::MirageDb;
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
         // Open database (follows status command pattern for error handling)
-        let db = match MirageDb::open(&db_path) {
+        let db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -3305,12 +6432,12 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::function_not_found(&args.function);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Function '{}' not found in database", args.function));
                     output::info("Hint: Run 'magellan watch' to index your code");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -3326,12 +6453,12 @@ pub mod cmds {
                         output::E_CFG_ERROR,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to load CFG for function '{}'", args.function));
                     output::info("The function may be corrupted. Try re-running 'magellan watch'");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -3340,8 +6467,19 @@ pub mod cmds {
         let entry = match find_entry(&cfg) {
             Some(entry) => entry,
             None => {
-                output::error(&format!("No entry block found for function '{}'", args.function));
-                std::process::exit(output::EXIT_DATABASE);
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "NoEntryBlock",
+                        &format!("No entry block found for function '{}'", args.function),
+                        output::E_CFG_ERROR,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("No entry block found for function '{}'", args.function));
+                    output::exit(output::EXIT_DATABASE);
+                }
             }
         };
 
@@ -3367,8 +6505,19 @@ pub mod cmds {
         let mut hot_paths = match compute_hot_paths(&cfg, &paths, entry, &natural_loops, options) {
             Ok(hp) => hp,
             Err(e) => {
-                output::error(&format!("Failed to compute hot paths: {}", e));
-                std::process::exit(output::EXIT_DATABASE);
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "HotPathsError",
+                        &format!("Failed to compute hot paths: {}", e),
+                        output::E_CFG_ERROR,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to compute hot paths: {}", e));
+                    output::exit(output::EXIT_DATABASE);
+                }
             }
         };
 
@@ -3383,10 +6532,10 @@ pub mod cmds {
                 print_hotpaths_human(&hot_paths, args.rationale);
             }
             OutputFormat::Json => {
-                println!("{}", serde_json::to_string(&hot_paths)?);
+                outln!("{}", serde_json::to_string(&hot_paths)?);
             }
             OutputFormat::Pretty => {
-                println!("{}", serde_json::to_string_pretty(&hot_paths)?);
+                outln!("{}", serde_json::to_string_pretty(&hot_paths)?);
             }
         }
 
@@ -3396,25 +6545,24 @@ pub mod cmds {
     pub fn patterns(args: &PatternsArgs, cli: &Cli) -> Result<()> {
         use crate::cfg::{detect_if_else_patterns, detect_match_patterns};
         use crate::cfg::{resolve_function_name, load_cfg_from_db};
-        use crate::storage::MirageDb;
 
         // Resolve database path
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
         // Open database (follows status command pattern for error handling)
-        let db = match MirageDb::open(&db_path) {
+        let db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 // JSON-aware error handling with remediation
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
@@ -3426,16 +6574,19 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::function_not_found(&args.function);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Function '{}' not found in database", args.function));
                     output::info("Hint: Run 'magellan watch' to index your code");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, &args.function, cli);
+
         // Load CFG from database
         let cfg = match load_cfg_from_db(&db, function_id) {
             Ok(cfg) => cfg,
@@ -3447,25 +6598,40 @@ pub mod cmds {
                         output::E_CFG_ERROR,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to load CFG for function '{}'", args.function));
                     output::info("The function may be corrupted. Try re-running 'magellan watch'");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        if args.merge_points {
+            return patterns_merge_points(&args.function, &cfg, cli);
+        }
+        if args.guards {
+            return patterns_guards(&args.function, &cfg, cli);
+        }
+        if args.unbalanced {
+            let threshold = args.threshold.unwrap_or(crate::cfg::patterns::UNBALANCED_DEFAULT_THRESHOLD);
+            return patterns_unbalanced(&args.function, &cfg, threshold, cli);
+        }
+
         // Detect patterns based on filter flags
         let show_if_else = !args.r#match;  // Show if/else unless --match only
-        let show_match = !args.if_else;    // Show match unless --if-else only
+        let show_match = !args.if_else && args.shape.is_none();
 
-        let if_else_patterns = if show_if_else {
+        let mut if_else_patterns = if show_if_else {
             detect_if_else_patterns(&cfg)
         } else {
             vec![]
         };
+        if let Some(shape) = args.shape {
+            let shape = crate::cfg::patterns::IfShape::from(shape);
+            if_else_patterns.retain(|p| p.shape == shape);
+        }
 
         let match_patterns = if show_match {
             detect_match_patterns(&cfg)
@@ -3474,65 +6640,52 @@ pub mod cmds {
         };
 
         // Convert to response format
-        let if_else_infos: Vec<IfElseInfo> = if_else_patterns.iter().map(|p| {
-            IfElseInfo {
-                condition_block: cfg[p.condition].id,
-                true_branch: cfg[p.true_branch].id,
-                false_branch: cfg[p.false_branch].id,
-                merge_point: p.merge_point.map(|n| cfg[n].id),
-                has_else: p.has_else(),
-            }
-        }).collect();
-
-        let match_infos: Vec<MatchInfo> = match_patterns.iter().map(|p| {
-            MatchInfo {
-                switch_block: cfg[p.switch_node].id,
-                branch_count: p.branch_count(),
-                targets: p.targets.iter().map(|n| cfg[*n].id).collect(),
-                otherwise: cfg[p.otherwise].id,
-            }
-        }).collect();
+        let (if_else_infos, match_infos) = pattern_infos(&cfg, &if_else_patterns, &match_patterns);
 
         // Output based on format
         match cli.output {
             OutputFormat::Human => {
-                println!("Function: {}", args.function);
-                println!();
+                outln!("Function: {}", args.function);
+                outln!();
 
                 if show_if_else {
-                    println!("If/Else Patterns: {}", if_else_patterns.len());
+                    outln!("If/Else Patterns: {}", if_else_patterns.len());
                     if if_else_patterns.is_empty() {
                         output::info("No if/else patterns detected");
                     } else {
                         for (i, info) in if_else_infos.iter().enumerate() {
-                            println!("  Pattern {}:", i + 1);
-                            println!("    Condition: Block {}", info.condition_block);
-                            println!("    True branch: Block {}", info.true_branch);
-                            println!("    False branch: Block {}", info.false_branch);
+                            outln!("  Pattern {}:", i + 1);
+                            outln!("    Condition: Block {}", info.condition_block);
+                            outln!("    True branch: Block {}", info.true_branch);
+                            outln!("    False branch: Block {}", info.false_branch);
+                            outln!("    Shape: {}", info.shape);
                             if let Some(merge) = info.merge_point {
-                                println!("    Merge point: Block {}", merge);
-                                println!("    Has else: {}", info.has_else);
+                                outln!("    Merge point: Block {}", merge);
+                                outln!("    Has else: {}", info.has_else);
                             } else {
-                                println!("    Merge point: None (no else)");
+                                outln!("    Merge point: None (no else)");
                             }
-                            println!();
+                            outln!();
                         }
                     }
-                    println!();
+                    outln!();
                 }
 
                 if show_match {
-                    println!("Match Patterns: {}", match_patterns.len());
+                    outln!("Match Patterns: {}", match_patterns.len());
                     if match_patterns.is_empty() {
                         output::info("No match patterns detected");
                     } else {
                         for (i, info) in match_infos.iter().enumerate() {
-                            println!("  Pattern {}:", i + 1);
-                            println!("    Switch: Block {}", info.switch_block);
-                            println!("    Branch count: {}", info.branch_count);
-                            println!("    Targets: {:?}", info.targets);
-                            println!("    Otherwise: Block {}", info.otherwise);
-                            println!();
+                            outln!("  Pattern {}:", i + 1);
+                            outln!("    Switch: Block {}", info.switch_block);
+                            outln!("    Branch count: {}", info.branch_count);
+                            outln!("    Targets: {:?}", info.targets);
+                            for (arm, target) in &info.arm_index {
+                                outln!("    Arm {} -> Block {}", arm, target);
+                            }
+                            outln!("    Otherwise: Block {}", info.otherwise);
+                            outln!();
                         }
                     }
                 }
@@ -3547,8 +6700,8 @@ pub mod cmds {
                 };
                 let wrapper = output::JsonResponse::new(response);
                 match cli.output {
-                    OutputFormat::Json => println!("{}", wrapper.to_json()),
-                    OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                     _ => unreachable!(),
                 }
             }
@@ -3557,32 +6710,247 @@ pub mod cmds {
         Ok(())
     }
 
-    pub fn frontiers(args: &FrontiersArgs, cli: &Cli) -> Result<()> {
-        use crate::cfg::{compute_dominance_frontiers, DominatorTree};
-        use crate::cfg::{resolve_function_name, load_cfg_from_db};
-        use crate::storage::MirageDb;
-
-        // Resolve database path
-        let db_path = super::resolve_db_path(cli.db.clone())?;
+    /// Report join points for `patterns --merge-points`
+    fn patterns_merge_points(function: &str, cfg: &crate::cfg::Cfg, cli: &Cli) -> Result<()> {
+        let infos = merge_point_infos(cfg);
 
-        // Open database (follows status command pattern for error handling)
-        let db = match MirageDb::open(&db_path) {
-            Ok(db) => db,
-            Err(_e) => {
-                // JSON-aware error handling with remediation
-                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
-                    let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", function);
+                outln!();
+                outln!("Merge Points: {}", infos.len());
+                if infos.is_empty() {
+                    output::info("No merge points detected");
                 } else {
-                    output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    for info in &infos {
+                        outln!("  Block {}:", info.block);
+                        outln!("    Predecessors ({}): {:?}", info.predecessor_count, info.predecessors);
+                        outln!("    Incoming edges: {:?}", info.incoming_edge_types);
+                        match info.common_dominator {
+                            Some(dom) => outln!("    Common dominator: Block {}", dom),
+                            None => outln!("    Common dominator: None"),
+                        }
+                        outln!();
+                    }
                 }
             }
-        };
-
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = MergePointsResponse {
+                    function: function.to_string(),
+                    merge_point_count: infos.len(),
+                    merge_points: infos,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Report guard clauses for `patterns --guards`
+    fn patterns_guards(function: &str, cfg: &crate::cfg::Cfg, cli: &Cli) -> Result<()> {
+        use crate::cfg::patterns::detect_guard_clauses;
+
+        let guards: Vec<GuardInfo> = detect_guard_clauses(cfg).into_iter().map(|g| GuardInfo {
+            condition_block: cfg[g.condition].id,
+            guard_branch: cfg[g.guard].id,
+            continuation: cfg[g.continuation].id,
+            guard_arm: if g.guard_on_true { "true" } else { "false" },
+            exit_block: cfg[g.exit].id,
+            exit_distance: g.exit_distance,
+        }).collect();
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", function);
+                outln!();
+                outln!("Guard Clauses: {}", guards.len());
+                if guards.is_empty() {
+                    output::info("No guard clauses detected");
+                } else {
+                    for guard in &guards {
+                        outln!("  Block {}:", guard.condition_block);
+                        outln!("    Guard ({} branch): Block {} exits at Block {} ({} block(s))",
+                            guard.guard_arm, guard.guard_branch, guard.exit_block, guard.exit_distance);
+                        outln!("    Continues: Block {}", guard.continuation);
+                        outln!();
+                    }
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = GuardsResponse {
+                    function: function.to_string(),
+                    guard_count: guards.len(),
+                    guards,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Output for patterns --unbalanced
+    fn patterns_unbalanced(function: &str, cfg: &crate::cfg::Cfg, threshold: f64, cli: &Cli) -> Result<()> {
+        use crate::cfg::patterns::detect_unbalanced_branches;
+
+        let branches: Vec<UnbalancedInfo> = detect_unbalanced_branches(cfg, threshold).into_iter().map(|b| UnbalancedInfo {
+            condition_block: cfg[b.condition].id,
+            true_branch: cfg[b.true_branch].id,
+            false_branch: cfg[b.false_branch].id,
+            true_blocks: b.true_blocks,
+            false_blocks: b.false_blocks,
+            ratio: b.ratio,
+        }).collect();
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", function);
+                outln!();
+                outln!("Unbalanced Branches (ratio > {}): {}", threshold, branches.len());
+                if branches.is_empty() {
+                    output::info("No unbalanced branches detected");
+                } else {
+                    for branch in &branches {
+                        outln!("  Block {}: ratio {:.1}", branch.condition_block, branch.ratio);
+                        outln!("    True branch: Block {} ({} block(s))", branch.true_branch, branch.true_blocks);
+                        outln!("    False branch: Block {} ({} block(s))", branch.false_branch, branch.false_blocks);
+                        outln!();
+                    }
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = UnbalancedResponse {
+                    function: function.to_string(),
+                    threshold,
+                    unbalanced_count: branches.len(),
+                    branches,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// If/else and match patterns for output, ordered by condition/switch block ID
+    ///
+    /// Match targets keep their switch order; only the pattern lists are sorted.
+    pub(super) fn pattern_infos(
+        cfg: &crate::cfg::Cfg,
+        if_else_patterns: &[crate::cfg::patterns::IfElsePattern],
+        match_patterns: &[crate::cfg::patterns::MatchPattern],
+    ) -> (Vec<IfElseInfo>, Vec<MatchInfo>) {
+        let mut if_else_infos: Vec<IfElseInfo> = if_else_patterns.iter().map(|p| {
+            IfElseInfo {
+                condition_block: cfg[p.condition].id,
+                true_branch: cfg[p.true_branch].id,
+                false_branch: cfg[p.false_branch].id,
+                merge_point: p.merge_point.map(|n| cfg[n].id),
+                has_else: p.has_else(),
+                shape: p.shape,
+            }
+        }).collect();
+        if_else_infos.sort_by_key(|info| (info.condition_block, info.true_branch, info.false_branch));
+
+        let mut match_infos: Vec<MatchInfo> = match_patterns.iter().map(|p| {
+            MatchInfo {
+                switch_block: cfg[p.switch_node].id,
+                branch_count: p.branch_count(),
+                targets: p.targets.iter().map(|n| cfg[*n].id).collect(),
+                otherwise: cfg[p.otherwise].id,
+                arm_index: p.arm_index.iter().map(|&(arm, n)| (arm, cfg[n].id)).collect(),
+            }
+        }).collect();
+        match_infos.sort_by_key(|info| info.switch_block);
+
+        (if_else_infos, match_infos)
+    }
+
+    /// Describe every join point: predecessors, incoming edge types, and their common dominator
+    pub(super) fn merge_point_infos(cfg: &crate::cfg::Cfg) -> Vec<MergePointInfo> {
+        use crate::cfg::{find_merge_points, DominatorTree};
+        use petgraph::visit::EdgeRef;
+
+        let dom_tree = DominatorTree::new(cfg);
+
+        find_merge_points(cfg).into_iter().map(|node| {
+            let mut incoming: Vec<_> = cfg.edges_directed(node, petgraph::Direction::Incoming)
+                .map(|e| (e.source(), *e.weight()))
+                .collect();
+            incoming.sort_by_key(|(pred, _)| cfg[*pred].id);
+
+            let common_dominator = dom_tree.as_ref().and_then(|tree| {
+                let mut preds = incoming.iter().map(|(pred, _)| *pred);
+                let first = preds.next()?;
+                preds.try_fold(first, |acc, pred| tree.common_dominator(acc, pred))
+            });
+
+            MergePointInfo {
+                block: cfg[node].id,
+                predecessor_count: incoming.len(),
+                predecessors: incoming.iter().map(|(pred, _)| cfg[*pred].id).collect(),
+                incoming_edge_types: incoming.iter().map(|(_, edge_type)| *edge_type).collect(),
+                common_dominator: common_dominator.map(|n| cfg[n].id),
+            }
+        }).collect()
+    }
+
+    /// Every node with a non-empty dominance frontier, ordered by block ID
+    pub(super) fn node_frontiers(
+        cfg: &crate::cfg::Cfg,
+        frontiers: &crate::cfg::dominance_frontiers::DominanceFrontiers,
+    ) -> Vec<NodeFrontier> {
+        let mut nodes: Vec<NodeFrontier> = frontiers.nodes_with_frontiers()
+            .map(|n| NodeFrontier {
+                node: cfg[n].id,
+                frontier_set: sorted_block_ids(cfg, frontiers.frontier(n)),
+            })
+            .collect();
+        nodes.sort_by_key(|node| node.node);
+        nodes
+    }
+
+    pub fn frontiers(args: &FrontiersArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::{compute_dominance_frontiers, compute_post_dominance_frontiers, PostDominatorTree};
+        use crate::cfg::{resolve_function_name, load_cfg_from_db};
+
+        // Resolve database path
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        // Open database (follows status command pattern for error handling)
+        let mut db = match super::session::open_readonly(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                // JSON-aware error handling with remediation
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
         // Resolve function name/ID to function_id
         let function_id = match resolve_function_name(&db, &args.function) {
             Ok(id) => id,
@@ -3590,16 +6958,19 @@ pub mod cmds {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                     let error = output::JsonError::function_not_found(&args.function);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Function '{}' not found in database", args.function));
                     output::info("Hint: Run 'magellan watch' to index your code");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, &args.function, cli);
+
         // Load CFG from database
         let cfg = match load_cfg_from_db(&db, function_id) {
             Ok(cfg) => cfg,
@@ -3611,49 +6982,80 @@ pub mod cmds {
                         output::E_CFG_ERROR,
                     );
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to load CFG for function '{}'", args.function));
                     output::info("The function may be corrupted. Try re-running 'magellan watch'");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
-        // Compute dominator tree
-        let dom_tree = match DominatorTree::new(&cfg) {
-            Some(tree) => tree,
-            None => {
-                output::error("Could not compute dominator tree (CFG may have no entry blocks)");
-                std::process::exit(1);
-            }
+        // Compute (post-)dominance frontiers
+        let (frontiers, entry_blocks) = if args.post {
+            let post_dom_tree = match PostDominatorTree::new(&cfg) {
+                Some(tree) => tree,
+                None => {
+                    if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                        let error = output::JsonError::new(
+                            "DominatorTreeError",
+                            "Could not compute post-dominator tree (CFG may have no exit blocks)",
+                            output::E_CFG_ERROR,
+                        );
+                        let wrapper = output::JsonResponse::new(error);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
+                    } else {
+                        output::error("Could not compute post-dominator tree (CFG may have no exit blocks)");
+                        output::exit(output::EXIT_DATABASE);
+                    }
+                }
+            };
+            (compute_post_dominance_frontiers(&cfg, post_dom_tree), None)
+        } else {
+            let dom_tree = match load_or_compute_dominator_tree(&mut db, function_id, &cfg) {
+                Some(tree) => tree,
+                None => {
+                    if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                        let error = output::JsonError::new(
+                            "DominatorTreeError",
+                            "Could not compute dominator tree (CFG may have no entry blocks)",
+                            output::E_CFG_ERROR,
+                        );
+                        let wrapper = output::JsonResponse::new(error);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(output::EXIT_DATABASE);
+                    } else {
+                        output::error("Could not compute dominator tree (CFG may have no entry blocks)");
+                        output::exit(output::EXIT_DATABASE);
+                    }
+                }
+            };
+            let entry_blocks = check_multiple_entry_blocks(&cfg, cli);
+            (compute_dominance_frontiers(&cfg, dom_tree), entry_blocks)
         };
-
-        // Compute dominance frontiers
-        let frontiers = compute_dominance_frontiers(&cfg, dom_tree);
+        let label = if args.post { "Post-Dominance Frontier" } else { "Dominance Frontier" };
 
         // Handle query modes based on args
         if args.iterated {
             // Show iterated dominance frontier
             let all_nodes: Vec<petgraph::graph::NodeIndex> = cfg.node_indices().collect();
             let iterated_frontier = frontiers.iterated_frontier(&all_nodes);
-            let iterated_blocks: Vec<usize> = iterated_frontier.iter()
-                .map(|&n| cfg[n].id)
-                .collect();
+            let iterated_blocks = sorted_block_ids(&cfg, iterated_frontier);
 
             match cli.output {
                 OutputFormat::Human => {
-                    println!("Function: {}", args.function);
-                    println!("Iterated Dominance Frontier:");
-                    println!("Count: {}", iterated_blocks.len());
-                    println!();
+                    outln!("Function: {}", args.function);
+                    outln!("Iterated {}:", label);
+                    outln!("Count: {}", iterated_blocks.len());
+                    outln!();
                     if iterated_blocks.is_empty() {
-                        output::info("No iterated dominance frontier (linear CFG)");
+                        output::info(&format!("No iterated {} (linear CFG)", label.to_lowercase()));
                     } else {
-                        println!("Blocks in iterated frontier:");
+                        outln!("Blocks in iterated frontier:");
                         for id in &iterated_blocks {
-                            println!("  - Block {}", id);
+                            outln!("  - Block {}", id);
                         }
                     }
                 }
@@ -3664,8 +7066,8 @@ pub mod cmds {
                     };
                     let wrapper = output::JsonResponse::new(response);
                     match cli.output {
-                        OutputFormat::Json => println!("{}", wrapper.to_json()),
-                        OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                        OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                        OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                         _ => unreachable!(),
                     }
                 }
@@ -3681,32 +7083,29 @@ pub mod cmds {
                     if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
                         let error = output::JsonError::block_not_found(node_id);
                         let wrapper = output::JsonResponse::new(error);
-                        println!("{}", wrapper.to_json());
-                        std::process::exit(1);
+                        outln!("{}", wrapper.to_json());
+                        output::exit(1);
                     } else {
                         output::error(&format!("Block {} not found in CFG", node_id));
-                        std::process::exit(1);
+                        output::exit(1);
                     }
                 }
             };
 
-            let frontier = frontiers.frontier(target_node);
-            let frontier_blocks: Vec<usize> = frontier.iter()
-                .map(|&n| cfg[n].id)
-                .collect();
+            let frontier_blocks = sorted_block_ids(&cfg, frontiers.frontier(target_node));
 
             match cli.output {
                 OutputFormat::Human => {
-                    println!("Function: {}", args.function);
-                    println!("Dominance Frontier for Block {}:", node_id);
-                    println!("Count: {}", frontier_blocks.len());
-                    println!();
+                    outln!("Function: {}", args.function);
+                    outln!("{} for Block {}:", label, node_id);
+                    outln!("Count: {}", frontier_blocks.len());
+                    outln!();
                     if frontier_blocks.is_empty() {
-                        output::info(&format!("Block {} has empty dominance frontier", node_id));
+                        output::info(&format!("Block {} has empty {}", node_id, label.to_lowercase()));
                     } else {
-                        println!("Frontier blocks:");
+                        outln!("Frontier blocks:");
                         for id in &frontier_blocks {
-                            println!("  - Block {}", id);
+                            outln!("  - Block {}", id);
                         }
                     }
                 }
@@ -3718,55 +7117,1543 @@ pub mod cmds {
                             node: node_id,
                             frontier_set: frontier_blocks,
                         }],
+                        entry_blocks,
                     };
                     let wrapper = output::JsonResponse::new(response);
                     match cli.output {
-                        OutputFormat::Json => println!("{}", wrapper.to_json()),
-                        OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
+                        OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                        OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
                         _ => unreachable!(),
                     }
                 }
             }
         } else {
             // Show all nodes with non-empty frontiers
-            let nodes_with_frontiers: Vec<NodeFrontier> = frontiers.nodes_with_frontiers()
-                .map(|n| {
-                    let frontier = frontiers.frontier(n);
-                    NodeFrontier {
-                        node: cfg[n].id,
-                        frontier_set: frontier.iter().map(|&f| cfg[f].id).collect(),
+            let nodes_with_frontiers = node_frontiers(&cfg, &frontiers);
+
+            match cli.output {
+                OutputFormat::Human => {
+                    outln!("Function: {}", args.function);
+                    outln!("Nodes with non-empty {}s: {}", label.to_lowercase(), nodes_with_frontiers.len());
+                    outln!();
+
+                    if nodes_with_frontiers.is_empty() {
+                        output::info(&format!("No {}s (linear CFG)", label.to_lowercase()));
+                    } else {
+                        for node_info in &nodes_with_frontiers {
+                            outln!("Block {}:", node_info.node);
+                            outln!("  Frontier: {:?}", node_info.frontier_set);
+                            outln!();
+                        }
                     }
-                })
-                .collect();
+                }
+                OutputFormat::Json | OutputFormat::Pretty => {
+                    let response = FrontiersResponse {
+                        function: args.function.clone(),
+                        nodes_with_frontiers: nodes_with_frontiers.len(),
+                        frontiers: nodes_with_frontiers,
+                        entry_blocks,
+                    };
+                    let wrapper = output::JsonResponse::new(response);
+                    match cli.output {
+                        OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                        OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn control_deps(args: &ControlDepsArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::{compute_post_dominance_frontiers, PostDominatorTree};
+        use crate::cfg::{resolve_function_name, load_cfg_from_db};
+
+        // Resolve database path
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        // Open database
+        let db = match super::session::open_readonly(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Resolve function name/ID to function_id
+        let function_id = match resolve_function_name(&db, &args.function) {
+            Ok(id) => id,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::function_not_found(&args.function);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Function '{}' not found in database", args.function));
+                    output::info("Hint: Run 'magellan watch' to index your code");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, &args.function, cli);
+
+        let cfg = match load_cfg_from_db(&db, function_id) {
+            Ok(cfg) => cfg,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "CgfLoadError",
+                        &format!("Failed to load CFG for function '{}'", args.function),
+                        output::E_CFG_ERROR,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to load CFG for function '{}'", args.function));
+                    output::info("The function may be corrupted. Try re-running 'magellan watch'");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let target_node = match cfg.node_indices().find(|&n| cfg[n].id == args.block) {
+            Some(node) => node,
+            None => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::block_not_found(args.block);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(1);
+                } else {
+                    output::error(&format!("Block {} not found in CFG", args.block));
+                    output::exit(1);
+                }
+            }
+        };
+
+        let post_dom_tree = match PostDominatorTree::new(&cfg) {
+            Some(tree) => tree,
+            None => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "DominatorTreeError",
+                        "Could not compute post-dominator tree (CFG may have no exit blocks)",
+                        output::E_CFG_ERROR,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error("Could not compute post-dominator tree (CFG may have no exit blocks)");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Control dependences are the post-dominance frontier of the block
+        let frontiers = compute_post_dominance_frontiers(&cfg, post_dom_tree);
+        let control_dependences = sorted_block_ids(&cfg, frontiers.frontier(target_node));
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", args.function);
+                outln!("Control dependences of Block {}: {}", args.block, control_dependences.len());
+                outln!();
+                if control_dependences.is_empty() {
+                    output::info(&format!("Block {} is not control dependent on any branch", args.block));
+                } else {
+                    outln!("Branch blocks deciding whether Block {} executes:", args.block);
+                    for id in &control_dependences {
+                        outln!("  - Block {}", id);
+                    }
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = ControlDepsResponse {
+                    function: args.function.clone(),
+                    block: args.block,
+                    control_dependences,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn cleanup(args: &CleanupArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::post_dominators::guaranteed_after;
+        use crate::cfg::PostDominatorTree;
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+        let cfg = load_function_cfg(&db_path, &args.function, cli);
+
+        let post_dom_tree = match PostDominatorTree::new(&cfg) {
+            Some(tree) => tree,
+            None => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "DominatorTreeError",
+                        "Could not compute post-dominator tree (CFG may have no exit blocks)",
+                        output::E_CFG_ERROR,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error("Could not compute post-dominator tree (CFG may have no exit blocks)");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let Some(guaranteed) = guaranteed_after(&post_dom_tree, &cfg, args.block) else {
+            if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                let error = output::JsonError::block_not_found(args.block);
+                let wrapper = output::JsonResponse::new(error);
+                outln!("{}", wrapper.to_json());
+                output::exit(1);
+            } else {
+                output::error(&format!("Block {} not found in CFG", args.block));
+                output::exit(1);
+            }
+        };
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", args.function);
+                outln!("Blocks guaranteed to run after Block {}: {}", args.block, guaranteed.len());
+                outln!();
+                if guaranteed.is_empty() {
+                    output::info(&format!("No block is certain to run after Block {}", args.block));
+                } else {
+                    outln!("In execution order:");
+                    for id in &guaranteed {
+                        outln!("  - Block {}", id);
+                    }
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = CleanupResponse {
+                    function: args.function.clone(),
+                    block: args.block,
+                    guaranteed_after: guaranteed,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn explain(args: &ExplainArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::explain_function;
+        use crate::cfg::{resolve_function_name, load_cfg_from_db};
+
+        // Resolve database path
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        // Open database (follows status command pattern for error handling)
+        let db = match super::session::open_readonly(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Resolve function name/ID to function_id
+        let function_id = match resolve_function_name(&db, &args.function) {
+            Ok(id) => id,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::function_not_found(&args.function);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Function '{}' not found in database", args.function));
+                    output::info("Hint: Run 'magellan watch' to index your code");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, &args.function, cli);
+
+        // Load CFG from database
+        let cfg = match load_cfg_from_db(&db, function_id) {
+            Ok(cfg) => cfg,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "CgfLoadError",
+                        &format!("Failed to load CFG for function '{}'", args.function),
+                        output::E_CFG_ERROR,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to load CFG for function '{}'", args.function));
+                    output::info("The function may be corrupted. Try re-running 'magellan watch'");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let explanation = explain_function(&args.function, &cfg);
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("{}", explanation.paragraph());
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = ExplanationResponse {
+                    function: args.function.clone(),
+                    summary: explanation.summary,
+                    branches: explanation.branches,
+                    loops: explanation.loops,
+                    exits: explanation.exits,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn blocks(args: &BlocksArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::resolve_function_name;
+
+        // Resolve database path
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        // Open database
+        let db = match super::session::open_readonly(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Resolve function name/ID to function_id
+        let function_id = match resolve_function_name(&db, &args.function) {
+            Ok(id) => id,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::function_not_found(&args.function);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Function '{}' not found in database", args.function));
+                    output::info("Hint: Run 'magellan watch' to index your code");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, &args.function, cli);
+
+        // Go straight through the backend trait so the output reflects
+        // exactly what is stored, with no CFG reconstruction
+        let mut blocks = match db.storage().get_cfg_blocks(function_id) {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "CfgLoadError",
+                        &format!("Failed to load blocks for function '{}': {}", args.function, e),
+                        output::E_CFG_ERROR,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to load blocks for function '{}': {}", args.function, e));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        if let Some(block_id) = args.block {
+            blocks.retain(|b| b.id == block_id);
+            if blocks.is_empty() {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "BlockNotFound",
+                        &format!("Block {} not found in function '{}'", block_id, args.function),
+                        output::E_BLOCK_NOT_FOUND,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(1);
+                } else {
+                    output::error(&format!("Block {} not found in function '{}'", block_id, args.function));
+                    output::exit(1);
+                }
+            }
+        }
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {} (id {})", args.function, function_id);
+                outln!("Blocks: {}", blocks.len());
+                for block in &blocks {
+                    outln!();
+                    outln!("Block {}:", block.id);
+                    outln!("  kind:       {}", block.kind);
+                    outln!("  terminator: {}", block.terminator);
+                    outln!("  bytes:      {}..{}", block.byte_start, block.byte_end);
+                    outln!(
+                        "  span:       {}:{} - {}:{}",
+                        block.start_line, block.start_col, block.end_line, block.end_col
+                    );
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = BlocksResponse { function_id, blocks };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn block_at(args: &BlockAtArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::source::has_line_info;
+        use crate::cfg::{resolve_function_name, load_cfg_from_db};
+
+        // Resolve database path
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        // Open database
+        let db = match super::session::open_readonly(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Resolve function name/ID to function_id
+        let function_id = match resolve_function_name(&db, &args.function) {
+            Ok(id) => id,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::function_not_found(&args.function);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Function '{}' not found in database", args.function));
+                    output::info("Hint: Run 'magellan watch' to index your code");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Restrict to functions changed since the --only-changed baseline
+        ensure_function_changed(&db, function_id, &args.function, cli);
+
+        let mut cfg = match load_cfg_from_db(&db, function_id) {
+            Ok(cfg) => cfg,
+            Err(_e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "CgfLoadError",
+                        &format!("Failed to load CFG for function '{}'", args.function),
+                        output::E_CFG_ERROR,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to load CFG for function '{}'", args.function));
+                    output::info("The function may be corrupted. Try re-running 'magellan watch'");
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+        // Blocks stored with only a byte range get their lines from the source file
+        crate::cfg::SourceMap::new().resolve_cfg(&mut cfg);
+
+        if !has_line_info(&cfg) {
+            let message = format!("Function '{}' has no line information; cannot resolve line {}", args.function, args.line);
+            if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                let error = output::JsonError::new("NoSourceLocations", &message, output::E_INVALID_INPUT)
+                    .with_remediation("Re-index so blocks carry source spans, or run mirage from the crate root so source files can be read");
+                let wrapper = output::JsonResponse::new(error);
+                outln!("{}", wrapper.to_json());
+                output::exit(output::EXIT_USAGE);
+            } else {
+                output::error(&message);
+                output::info("Hint: Re-index so blocks carry source spans, or run mirage from the crate root so source files can be read");
+                output::exit(output::EXIT_USAGE);
+            }
+        }
+
+        let blocks: Vec<BlockAtEntry> = blocks_at_line_by_offset(&cfg, args.line)
+            .into_iter()
+            .map(|node| {
+                let block = &cfg[node];
+                let location = block.source_location.as_ref().expect("block covering a line has a location");
+                BlockAtEntry {
+                    block_id: block.id,
+                    kind: format!("{:?}", block.kind),
+                    terminator: format!("{:?}", block.terminator),
+                    statements: block.statements.clone(),
+                    byte_start: location.byte_start,
+                    byte_end: location.byte_end,
+                    source_location: location.display(),
+                }
+            })
+            .collect();
+
+        if blocks.is_empty() {
+            let message = format!("No block in '{}' covers line {}", args.function, args.line);
+            if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                let error = output::JsonError::new("BlockNotFound", &message, output::E_BLOCK_NOT_FOUND);
+                let wrapper = output::JsonResponse::new(error);
+                outln!("{}", wrapper.to_json());
+                output::exit(1);
+            } else {
+                output::error(&message);
+                output::exit(1);
+            }
+        }
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", args.function);
+                outln!("Line {} maps to {} block(s)", args.line, blocks.len());
+                for block in &blocks {
+                    outln!();
+                    outln!("Block {} ({}):", block.block_id, block.source_location);
+                    outln!("  kind:       {}", block.kind);
+                    outln!("  terminator: {}", block.terminator);
+                    if !block.statements.is_empty() {
+                        outln!("  statements:");
+                        for stmt in &block.statements {
+                            outln!("    {}", stmt);
+                        }
+                    }
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = BlockAtResponse {
+                    function: args.function.clone(),
+                    line: args.line,
+                    blocks,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Blocks whose source span covers `line`, ordered by byte offset then block ID
+    pub(super) fn blocks_at_line_by_offset(
+        cfg: &crate::cfg::Cfg,
+        line: usize,
+    ) -> Vec<petgraph::graph::NodeIndex> {
+        let mut nodes = crate::cfg::source::blocks_at_line(cfg, line);
+        nodes.sort_by_key(|&n| {
+            let byte_start = cfg[n].source_location.as_ref().map_or(0, |loc| loc.byte_start);
+            (byte_start, cfg[n].id)
+        });
+        nodes
+    }
+
+    pub fn diff(args: &DiffArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::diff::compute_cfg_diff;
+
+        // Resolve database path
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        // Open database
+        let db = match super::session::open_readonly(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Resolve function name/ID to function_id
+        let function_id = match db.resolve_function_name(&args.function) {
+            Ok(id) => id,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new("Database", &e.to_string(), "E001");
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to resolve function: {}", e));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Compute diff
+        let diff = match compute_cfg_diff(db.storage(), function_id, &args.before, &args.after) {
+            Ok(diff) => diff,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new("Database", &e.to_string(), "E001");
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    return Err(e);
+                }
+            }
+        };
+
+        // Output based on format
+        match cli.output {
+            OutputFormat::Human => print_diff_human(&diff, args.show_edges, args.verbose),
+            OutputFormat::Json => {
+                let wrapper = output::JsonResponse::new(diff);
+                outln!("{}", wrapper.to_json());
+            }
+            OutputFormat::Pretty => {
+                let wrapper = output::JsonResponse::new(diff);
+                outln!("{}", wrapper.to_pretty_json());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn diff_cfg(args: &DiffCfgArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::diff::compare_cfg_structure;
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+        let db2_path = args.db2.clone().unwrap_or_else(|| db_path.clone());
+
+        let cfg_a = load_function_cfg(&db_path, &args.function, cli);
+        let cfg_b = load_function_cfg(&db2_path, &args.function2, cli);
+
+        let diff = compare_cfg_structure(&cfg_a, &args.function, &cfg_b, &args.function2);
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Comparing: {} -> {}", diff.function_a, diff.function_b);
+                outln!("  Blocks: {} -> {}", diff.block_count_a, diff.block_count_b);
+                outln!("  Edges: {} -> {}", diff.edge_count_a, diff.edge_count_b);
+                for (title, diffs) in [
+                    ("Block kinds", &diff.block_kinds),
+                    ("Terminators", &diff.terminators),
+                    ("Edge types", &diff.edge_types),
+                ] {
+                    if !diffs.is_empty() {
+                        outln!();
+                        outln!("{}:", title);
+                        for d in diffs {
+                            outln!("  {:+} {} ({} -> {})", d.delta, d.kind, d.count_a, d.count_b);
+                        }
+                    }
+                }
+                outln!();
+                outln!("Edit distance (estimate): {}", diff.edit_distance);
+                if diff.isomorphic {
+                    output::success("Structurally identical (isomorphic up to block IDs)");
+                } else {
+                    output::warn("Control flow differs");
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(diff).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(diff).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    pub fn edge_types(_args: &EdgeTypesArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::EdgeType;
+
+        let values = EdgeType::ALL
+            .iter()
+            .map(|edge_type| KindInfo {
+                name: serialized_name(edge_type),
+                description: edge_type.description(),
+            })
+            .collect();
+        print_kinds(KindsResponse { kind: "edge_type", values }, cli);
+        Ok(())
+    }
+
+    pub fn terminator_kinds(_args: &TerminatorKindsArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::analysis::TERMINATOR_KINDS;
+
+        let values = TERMINATOR_KINDS
+            .iter()
+            .map(|&(name, description)| KindInfo {
+                name: name.to_string(),
+                description,
+            })
+            .collect();
+        print_kinds(KindsResponse { kind: "terminator_kind", values }, cli);
+        Ok(())
+    }
+
+    pub fn block_kinds(_args: &BlockKindsArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::BlockKind;
+
+        let values = BlockKind::ALL
+            .iter()
+            .map(|kind| KindInfo {
+                name: serialized_name(kind),
+                description: kind.description(),
+            })
+            .collect();
+        print_kinds(KindsResponse { kind: "block_kind", values }, cli);
+        Ok(())
+    }
+
+    /// An enum value's name as serde writes it in JSON output
+    fn serialized_name(value: &impl serde::Serialize) -> String {
+        match serde_json::to_value(value) {
+            Ok(serde_json::Value::String(name)) => name,
+            other => unreachable!("unit enum variant serialized as {:?}", other),
+        }
+    }
+
+    fn print_kinds(response: KindsResponse, cli: &Cli) {
+        match cli.output {
+            OutputFormat::Human => {
+                let width = response.values.iter().map(|v| v.name.len()).max().unwrap_or(0);
+                for value in &response.values {
+                    outln!("{:width$}  {}", value.name, value.description, width = width);
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+    }
+
+    pub fn terminators(args: &TerminatorsArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::analysis::count_terminators;
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        let Some(function) = args.function.as_deref() else {
+            return terminators_all(&db_path, args, cli);
+        };
+
+        let cfg = load_function_cfg(&db_path, function, cli);
+        let stats = count_terminators(&cfg, function);
+
+        if args.format == Some(TableFormat::Csv) {
+            print_terminators_csv(std::slice::from_ref(&stats));
+            return Ok(());
+        }
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", stats.function);
+                outln!("Blocks: {}", cfg.node_count());
+                outln!();
+                for (kind, count) in &stats.counts {
+                    outln!("  {:<12} {}", kind, count);
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(stats).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(stats).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    /// `terminators --all`: per-function counts plus totals across the database
+    fn terminators_all(db_path: &str, args: &TerminatorsArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::analysis::count_terminators;
+        use crate::cfg::load_cfg_from_db;
+
+        let db = match super::session::open_readonly(db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let mut functions = match db.storage().function_ids() {
+            Ok(functions) => functions,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "QueryError",
+                        &format!("Failed to query functions: {}", e),
+                        output::E_DATABASE_NOT_FOUND,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to query functions: {}", e));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        // Restrict to functions changed since the --only-changed baseline
+        if let Some(changed) = only_changed_functions(&db, cli) {
+            functions.retain(|(id, _)| changed.contains(id));
+        }
+
+        // Skip functions that fail to load
+        let stats: Vec<_> = functions
+            .iter()
+            .filter_map(|(function_id, name)| {
+                load_cfg_from_db(&db, *function_id)
+                    .ok()
+                    .map(|cfg| count_terminators(&cfg, name))
+            })
+            .collect();
+
+        if args.format == Some(TableFormat::Csv) {
+            print_terminators_csv(&stats);
+            return Ok(());
+        }
+
+        let mut totals = std::collections::BTreeMap::new();
+        for (kind, count) in stats.iter().flat_map(|s| &s.counts) {
+            *totals.entry(kind.clone()).or_insert(0) += count;
+        }
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Functions: {}", stats.len());
+                outln!();
+                for s in &stats {
+                    let counts: Vec<String> = s.counts.iter().map(|(kind, count)| format!("{}={}", kind, count)).collect();
+                    outln!("  {}: {}", s.function, counts.join(" "));
+                }
+                outln!();
+                outln!("Totals:");
+                for (kind, count) in &totals {
+                    outln!("  {:<12} {}", kind, count);
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = AllTerminatorsResponse {
+                    function_count: stats.len(),
+                    totals,
+                    functions: stats,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    _ => outln!("{}", wrapper.to_pretty_json()),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// One CSV row per function, with a column for every terminator kind
+    fn print_terminators_csv(stats: &[crate::cfg::analysis::TerminatorStats]) {
+        use crate::cfg::analysis::TERMINATOR_KINDS;
+
+        let header: Vec<&str> = std::iter::once("function").chain(TERMINATOR_KINDS.iter().map(|(kind, _)| *kind)).collect();
+        outln!("{}", output::csv_record(&header));
+        for s in stats {
+            let row: Vec<String> = std::iter::once(s.function.clone())
+                .chain(TERMINATOR_KINDS.iter().map(|(kind, _)| s.counts.get(*kind).copied().unwrap_or(0).to_string()))
+                .collect();
+            outln!("{}", output::csv_record(&row));
+        }
+    }
+
+    pub fn exits(args: &ExitsArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::exits::{analyze_exits, ExitKind};
+        use crate::cfg::{enumerate_paths, PathKind, PathLimits};
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+        let cfg = load_function_cfg(&db_path, &args.function, cli);
+
+        let limits = PathLimits::default();
+        let paths = enumerate_paths(&cfg, &limits);
+        let exits = analyze_exits(&cfg, &paths);
+
+        let response = ExitsResponse {
+            function: args.function.clone(),
+            exit_count: exits.len(),
+            reachable_exit_count: exits.iter().filter(|e| e.reachable).count(),
+            returns_normally: exits.iter().any(|e| {
+                e.exit_kind == ExitKind::Return && e.path_kinds.contains(&PathKind::Normal)
+            }),
+            paths_truncated: paths.len() >= limits.max_paths,
+            exits,
+        };
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", response.function);
+                outln!("Exits: {} ({} reachable)", response.exit_count, response.reachable_exit_count);
+                outln!();
+                for exit in &response.exits {
+                    let kinds: Vec<String> = exit.path_kinds.iter().map(|k| format!("{:?}", k)).collect();
+                    if exit.reachable {
+                        outln!(
+                            "  Block {} ({:?}): {} path(s) [{}]",
+                            exit.block_id, exit.exit_kind, exit.path_count, kinds.join(", ")
+                        );
+                    } else {
+                        outln!("  Block {} ({:?}): unreachable from entry", exit.block_id, exit.exit_kind);
+                    }
+                }
+                outln!();
+                if response.returns_normally {
+                    output::success("Function can return normally");
+                } else {
+                    output::warn("No normal path reaches a return");
+                }
+                if response.paths_truncated {
+                    output::warn(&format!("Path enumeration stopped at {} paths; counts are lower bounds", limits.max_paths));
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    pub fn longest_path(args: &LongestPathArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::longest_path::longest_acyclic_path;
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+        let cfg = load_function_cfg(&db_path, &args.function, cli);
+
+        let path = match longest_acyclic_path(&cfg) {
+            Some(path) => path,
+            None => {
+                let msg = "No path from the entry reaches an exit block";
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new("PathError", msg, output::E_CFG_ERROR);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(msg);
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let response = LongestPathResponse {
+            function: args.function.clone(),
+            path,
+        };
+
+        match cli.output {
+            OutputFormat::Human => {
+                let path = &response.path;
+                outln!("Function: {}", response.function);
+                outln!("Longest path: {} blocks, ending at block {}", path.length, path.exit);
+                let blocks: Vec<String> = path.blocks.iter().map(|id| id.to_string()).collect();
+                outln!("  {}", blocks.join(" -> "));
+                if !path.collapsed_loops.is_empty() {
+                    outln!();
+                    outln!("Loops counted once:");
+                    for body in &path.collapsed_loops {
+                        let ids: Vec<String> = body.iter().map(|id| id.to_string()).collect();
+                        outln!("  {{{}}}", ids.join(", "));
+                    }
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    pub fn redundant(args: &RedundantArgs, cli: &Cli) -> Result<()> {
+        use crate::analysis::detect_redundant_branches;
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+        let cfg = load_function_cfg(&db_path, &args.function, cli);
+
+        let branches = detect_redundant_branches(&cfg);
+        let response = RedundantResponse {
+            function: args.function.clone(),
+            redundant_count: branches.len(),
+            branches,
+        };
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", response.function);
+                if response.branches.is_empty() {
+                    output::info("No redundant branches found");
+                    return Ok(());
+                }
+                outln!("Redundant branches: {}", response.redundant_count);
+                outln!();
+                for branch in &response.branches {
+                    outln!("  Block {}: {}", branch.block_id, branch.reason);
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    pub fn coverage(args: &CoverageArgs, cli: &Cli) -> Result<()> {
+        use crate::cfg::coverage::edge_coverage;
+        use crate::cfg::{enumerate_paths, PathLimits};
+        use petgraph::visit::EdgeRef;
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+        let cfg = load_function_cfg(&db_path, &args.function, cli);
+
+        let mut limits = PathLimits::default();
+        if let Some(max_length) = args.max_length {
+            limits = limits.with_max_length(max_length);
+        }
+        let paths = enumerate_paths(&cfg, &limits);
+        let (covered, _) = edge_coverage(&cfg, &paths);
+
+        let mut uncovered: Vec<UncoveredEdge> = cfg
+            .edge_references()
+            .filter(|edge| !covered.contains(&edge.id()))
+            .map(|edge| UncoveredEdge {
+                from_block: cfg[edge.source()].id,
+                to_block: cfg[edge.target()].id,
+                edge_type: *edge.weight(),
+            })
+            .collect();
+        uncovered.sort_by_key(|edge| (edge.from_block, edge.to_block));
+
+        let edge_count = cfg.edge_count();
+        let response = CoverageResponse {
+            function: args.function.clone(),
+            path_count: paths.len(),
+            paths_truncated: paths.len() >= limits.max_paths,
+            edge_count,
+            covered_count: covered.len(),
+            coverage_percent: if edge_count == 0 {
+                100.0
+            } else {
+                covered.len() as f64 * 100.0 / edge_count as f64
+            },
+            uncovered,
+        };
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", response.function);
+                outln!(
+                    "Edges covered: {}/{} ({:.1}%) by {} path(s)",
+                    response.covered_count, response.edge_count, response.coverage_percent, response.path_count
+                );
+                if !response.uncovered.is_empty() {
+                    outln!();
+                    outln!("Uncovered edges:");
+                    for edge in &response.uncovered {
+                        outln!("  {} -> {} ({:?})", edge.from_block, edge.to_block, edge.edge_type);
+                    }
+                }
+                if response.paths_truncated {
+                    output::warn(&format!("Path enumeration stopped at {} paths; some uncovered edges may be reachable", limits.max_paths));
+                } else if args.max_length.is_some() && !response.uncovered.is_empty() {
+                    output::info("Some edges may be uncovered only because of --max-length");
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    pub fn prune(args: &PruneArgs, cli: &Cli) -> Result<()> {
+        use crate::storage::prune::prune;
+
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        let mut db = match super::session::open(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let removed = match db.conn_mut().and_then(|conn| prune(conn, args.stale_paths, args.dry_run)) {
+            Ok(removed) => removed,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "PruneError",
+                        &format!("Failed to prune database: {}", e),
+                        output::E_DATABASE_NOT_FOUND,
+                    );
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to prune database: {}", e));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let response = PruneResponse {
+            dry_run: args.dry_run,
+            total: removed.total(),
+            removed,
+        };
+
+        match cli.output {
+            OutputFormat::Human => {
+                let removed = &response.removed;
+                if response.dry_run {
+                    outln!("Dry run: rows that would be removed");
+                } else {
+                    outln!("Rows removed");
+                }
+                outln!("  cfg_blocks:        {}", removed.cfg_blocks);
+                outln!("  cfg_edges:         {}", removed.cfg_edges);
+                outln!("  cfg_paths:         {}", removed.cfg_paths);
+                if args.stale_paths {
+                    outln!("  stale cfg_paths:   {}", removed.stale_paths);
+                }
+                outln!("  cfg_path_elements: {}", removed.cfg_path_elements);
+                outln!("  cfg_dominators:    {}", removed.cfg_dominators);
+                outln!();
+                if response.total == 0 {
+                    output::success("Nothing to prune");
+                } else if response.dry_run {
+                    output::info(&format!("{} rows would be removed; run without --dry-run to delete them", response.total));
+                } else {
+                    output::success(&format!("Removed {} rows", response.total));
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    pub fn init_schema(_args: &InitSchemaArgs, cli: &Cli) -> Result<()> {
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        let created = match crate::storage::init_schema(&db_path) {
+            Ok(created) => created,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new(
+                        "InitSchemaError",
+                        &format!("Failed to initialize Mirage schema: {:#}", e),
+                        output::E_DATABASE_NOT_FOUND,
+                    ).with_remediation(output::R_HINT_INDEX);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to initialize Mirage schema: {:#}", e));
+                    output::info(&format!("Hint: {}", output::R_HINT_INDEX));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let response = InitSchemaResponse {
+            database: db_path,
+            created,
+            mirage_schema_version: crate::storage::MIRAGE_SCHEMA_VERSION,
+        };
+
+        match cli.output {
+            OutputFormat::Human => {
+                if response.created {
+                    output::success(&format!(
+                        "Created Mirage schema v{} in {}",
+                        response.mirage_schema_version, response.database
+                    ));
+                    output::info("Run 'magellan watch' to index CFGs");
+                } else {
+                    output::success(&format!(
+                        "Mirage schema already present in {} (v{})",
+                        response.database, response.mirage_schema_version
+                    ));
+                }
+            }
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    pub fn export(args: &ExportArgs, cli: &Cli) -> Result<()> {
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+        let mut db = match super::session::open(&db_path) {
+            Ok(db) => db,
+            Err(e) => {
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::database_open_failed(&db_path, &e);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
+                } else {
+                    output::error(&format!("Failed to open database: {}", db_path));
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
+                }
+            }
+        };
+
+        let written = std::fs::File::create(&args.function_data)
+            .map_err(anyhow::Error::from)
+            .and_then(|file| {
+                let mut out = std::io::BufWriter::new(file);
+                let written = crate::storage::jsonl::export_function_data(&mut db, &mut out)?;
+                std::io::Write::flush(&mut out)?;
+                Ok(written)
+            });
+        let functions = match written {
+            Ok(functions) => functions,
+            Err(e) => {
+                let msg = format!("Failed to export to {}: {:#}", args.function_data, e);
+                // Writing the file and reading the database fail differently
+                let (code, exit_code) = if is_io_failure(&e) {
+                    (output::E_IO_ERROR, output::EXIT_ERROR)
+                } else {
+                    (output::E_CFG_ERROR, output::EXIT_DATABASE)
+                };
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new("ExportError", &msg, code);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(exit_code);
+                } else {
+                    output::error(&msg);
+                    output::exit(exit_code);
+                }
+            }
+        };
+
+        let response = ExportResponse {
+            database: db_path,
+            file: args.function_data.clone(),
+            functions,
+        };
+        match cli.output {
+            OutputFormat::Human => output::success(&format!(
+                "Exported {} function(s) from {} to {}",
+                response.functions, response.database, response.file
+            )),
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    pub fn import(args: &ImportArgs, cli: &Cli) -> Result<()> {
+        let db_path = super::resolve_db_path(cli.db.clone())?;
+
+        let target_exists = std::path::Path::new(&db_path).exists();
+        let imported = if target_exists {
+            Err(anyhow::anyhow!("Database already exists: {}", db_path))
+        } else {
+            std::fs::File::open(&args.file)
+                .map_err(anyhow::Error::from)
+                .and_then(|file| crate::storage::jsonl::import_function_data(
+                    std::path::Path::new(&db_path),
+                    std::io::BufReader::new(file),
+                ))
+        };
+        let functions = match imported {
+            Ok(functions) => functions,
+            Err(e) => {
+                let msg = format!("Failed to import {}: {:#}", args.file, e);
+                // Bad input and an existing target are usage errors; the rest
+                // is reading the file or writing the new database
+                let (code, exit_code) = if target_exists || e.is::<crate::storage::jsonl::InvalidFunctionData>() {
+                    (output::E_INVALID_INPUT, output::EXIT_USAGE)
+                } else if is_io_failure(&e) {
+                    (output::E_IO_ERROR, output::EXIT_ERROR)
+                } else {
+                    (output::E_DATABASE_WRITE, output::EXIT_DATABASE)
+                };
+                if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                    let error = output::JsonError::new("ImportError", &msg, code);
+                    let wrapper = output::JsonResponse::new(error);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(exit_code);
+                } else {
+                    output::error(&msg);
+                    output::exit(exit_code);
+                }
+            }
+        };
+
+        let response = ImportResponse {
+            database: db_path,
+            file: args.file.clone(),
+            functions,
+        };
+        match cli.output {
+            OutputFormat::Human => output::success(&format!(
+                "Imported {} function(s) from {} into {}",
+                response.functions, response.file, response.database
+            )),
+            OutputFormat::Json => outln!("{}", output::JsonResponse::new(response).to_json()),
+            OutputFormat::Pretty => outln!("{}", output::JsonResponse::new(response).to_pretty_json()),
+        }
+
+        Ok(())
+    }
+
+    /// Whether an export/import error came from reading or writing a file
+    fn is_io_failure(error: &anyhow::Error) -> bool {
+        error.chain().any(|cause| {
+            cause.is::<std::io::Error>()
+                || cause.downcast_ref::<serde_json::Error>().is_some_and(|e| e.is_io())
+        })
+    }
+
+    /// Blocks hidden behind a never-taken constant branch, with the reason (for `unreachable --conditional`)
+    ///
+    /// A block hidden by several branches is reported once, for the first.
+    pub(super) fn conditionally_dead_blocks(cfg: &crate::cfg::Cfg) -> Vec<(petgraph::graph::NodeIndex, Option<String>)> {
+        use crate::analysis::detect_dead_branches;
+
+        let mut seen = std::collections::HashSet::new();
+        let mut dead = Vec::new();
+        for branch in detect_dead_branches(cfg) {
+            for &block_id in &branch.dead_blocks {
+                if !seen.insert(block_id) {
+                    continue;
+                }
+                if let Some(idx) = cfg.node_indices().find(|&idx| cfg[idx].id == block_id) {
+                    let reason = format!(
+                        "only reachable through block {}, whose condition is always {}",
+                        branch.block_id, branch.condition
+                    );
+                    dead.push((idx, Some(reason)));
+                }
+            }
+        }
+        dead
+    }
+
+    /// Source text of a path, one snippet per file, with `context` lines on each side
+    fn path_snippets(
+        path: &crate::cfg::Path,
+        cfg: &crate::cfg::Cfg,
+        context: usize,
+        source_map: &mut crate::cfg::SourceMap,
+    ) -> Vec<PathSnippet> {
+        crate::cfg::source::path_source_spans(cfg, &path.blocks)
+            .into_iter()
+            .map(|span| {
+                let file_path = span.file_path.display().to_string();
+                match source_map.snippet(&span, context) {
+                    Some(lines) => PathSnippet { file_path, lines: Some(lines), note: None },
+                    None => PathSnippet {
+                        note: Some(format!("source file not found: {}", file_path)),
+                        file_path,
+                        lines: None,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Source snippet of an unreachable block, or a note saying why there is none
+    fn unreachable_source(
+        block: &crate::cfg::BasicBlock,
+        source_map: &mut crate::cfg::SourceMap,
+    ) -> (Option<UnreachableSource>, Option<String>) {
+        let Some(ref location) = block.source_location else {
+            return (None, Some("no source location recorded".to_string()));
+        };
+        if location.start_line == 0 {
+            return (None, Some(format!("source file not found: {}", location.file_path.display())));
+        }
+        match source_map.snippet(location, UNREACHABLE_SOURCE_CONTEXT) {
+            Some(lines) => (
+                Some(UnreachableSource {
+                    file_path: location.file_path.display().to_string(),
+                    lines,
+                }),
+                None,
+            ),
+            None => (None, Some(format!("source file not found: {}", location.file_path.display()))),
+        }
+    }
+
+    /// Path limits requested by the paths command's length, loop and count flags
+    fn path_limits(args: &PathsArgs) -> crate::cfg::PathLimits {
+        let mut limits = crate::cfg::PathLimits::default();
+        if let Some(max_length) = args.max_length {
+            limits = limits.with_max_length(max_length);
+        }
+        if let Some(min_length) = args.min_length {
+            limits = limits.with_min_length(min_length);
+        }
+        if let Some(max_loop_iterations) = args.max_loop_iterations {
+            limits = limits.with_max_loop_iterations(max_loop_iterations);
+        }
+        if let Some(max_paths) = args.max_paths {
+            limits = limits.with_max_paths(max_paths.get());
+        }
+        limits
+    }
 
-            match cli.output {
-                OutputFormat::Human => {
-                    println!("Function: {}", args.function);
-                    println!("Nodes with non-empty dominance frontiers: {}", nodes_with_frontiers.len());
-                    println!();
+    /// paths --db2: compare one function's paths across two databases by block sequence
+    fn paths_across_databases(args: &PathsArgs, db_path: &str, db2_path: &str, cli: &Cli) -> Result<()> {
+        use crate::cfg::paths::diff_paths;
 
-                    if nodes_with_frontiers.is_empty() {
-                        output::info("No dominance frontiers (linear CFG)");
-                    } else {
-                        for node_info in &nodes_with_frontiers {
-                            println!("Block {}:", node_info.node);
-                            println!("  Frontier: {:?}", node_info.frontier_set);
-                            println!();
-                        }
-                    }
+        let limits = path_limits(args);
+        let enumerate = |path: &str| {
+            let cfg = load_function_cfg(path, &args.function, cli);
+            let mut paths = crate::cfg::enumerate_paths(&cfg, &limits);
+            let truncated = limits.is_truncated(paths.len());
+            limits.retain_length_band(&mut paths);
+            (paths, truncated)
+        };
+        let (before, truncated_before) = enumerate(db_path);
+        let (after, truncated_after) = enumerate(db2_path);
+        let diff = diff_paths(&before, &after);
+        let truncated = truncated_before || truncated_after;
+
+        match cli.output {
+            OutputFormat::Human => {
+                outln!("Function: {}", args.function);
+                outln!("Comparing paths: {} -> {}", db_path, db2_path);
+                outln!("  Added: {}", diff.added.len());
+                outln!("  Removed: {}", diff.removed.len());
+                outln!("  Unchanged: {}", diff.unchanged.len());
+                if truncated {
+                    output::warn(&format!("Stopped at the {} path limit; the comparison is incomplete", limits.max_paths));
                 }
-                OutputFormat::Json | OutputFormat::Pretty => {
-                    let response = FrontiersResponse {
-                        function: args.function.clone(),
-                        nodes_with_frontiers: nodes_with_frontiers.len(),
-                        frontiers: nodes_with_frontiers,
-                    };
-                    let wrapper = output::JsonResponse::new(response);
-                    match cli.output {
-                        OutputFormat::Json => println!("{}", wrapper.to_json()),
-                        OutputFormat::Pretty => println!("{}", wrapper.to_pretty_json()),
-                        _ => unreachable!(),
-                    }
+                for (sign, blocks) in diff.added.iter().map(|b| ('+', b)).chain(diff.removed.iter().map(|b| ('-', b))) {
+                    outln!("  {} {}", sign, blocks.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(" -> "));
+                }
+            }
+            OutputFormat::Json | OutputFormat::Pretty => {
+                let response = PathsComparisonResponse {
+                    function: args.function.clone(),
+                    db: db_path.to_string(),
+                    db2: db2_path.to_string(),
+                    added_count: diff.added.len(),
+                    removed_count: diff.removed.len(),
+                    unchanged_count: diff.unchanged.len(),
+                    truncated,
+                    diff,
+                };
+                let wrapper = output::JsonResponse::new(response);
+                match cli.output {
+                    OutputFormat::Json => outln!("{}", wrapper.to_json()),
+                    OutputFormat::Pretty => outln!("{}", wrapper.to_pretty_json()),
+                    _ => unreachable!(),
                 }
             }
         }
@@ -3774,130 +8661,113 @@ pub mod cmds {
         Ok(())
     }
 
-    pub fn diff(args: &DiffArgs, cli: &Cli) -> Result<()> {
-        use crate::cfg::diff::compute_cfg_diff;
-        use crate::storage::MirageDb;
-
-        // Resolve database path
-        let db_path = super::resolve_db_path(cli.db.clone())?;
+    /// Open `db_path` and load a function's CFG, exiting with an error report on failure
+    fn load_function_cfg(db_path: &str, function: &str, cli: &Cli) -> crate::cfg::Cfg {
+        use crate::cfg::{load_cfg_from_db, resolve_function_name};
 
-        // Open database
-        let db = match MirageDb::open(&db_path) {
+        let db = match super::session::open_readonly(db_path) {
             Ok(db) => db,
-            Err(_e) => {
+            Err(e) => {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::database_not_found(&db_path);
+                    let error = output::JsonError::database_open_failed(db_path, &e);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
                     output::error(&format!("Failed to open database: {}", db_path));
-                    output::info("Hint: Run 'magellan watch' to create the database");
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
-        // Resolve function name/ID to function_id
-        let function_id = match db.resolve_function_name(&args.function) {
+        let function_id = match resolve_function_name(&db, function) {
             Ok(id) => id,
-            Err(e) => {
+            Err(_e) => {
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::new("Database", &e.to_string(), "E001");
+                    let error = output::JsonError::function_not_found(function);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
-                    output::error(&format!("Failed to resolve function: {}", e));
-                    std::process::exit(output::EXIT_DATABASE);
+                    output::error(&format!("Function '{}' not found in database {}", function, db_path));
+                    output::info("Hint: Run 'magellan watch' to index your code");
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
         };
 
-        // Compute diff
-        let diff = match compute_cfg_diff(db.storage(), function_id, &args.before, &args.after) {
-            Ok(diff) => diff,
-            Err(e) => {
+        match load_cfg_from_db(&db, function_id) {
+            Ok(cfg) => cfg,
+            Err(_e) => {
+                let msg = format!("Failed to load CFG for function '{}'", function);
                 if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
-                    let error = output::JsonError::new("Database", &e.to_string(), "E001");
+                    let error = output::JsonError::new("CgfLoadError", &msg, output::E_CFG_ERROR);
                     let wrapper = output::JsonResponse::new(error);
-                    println!("{}", wrapper.to_json());
-                    std::process::exit(output::EXIT_DATABASE);
+                    outln!("{}", wrapper.to_json());
+                    output::exit(output::EXIT_DATABASE);
                 } else {
-                    return Err(e);
+                    output::error(&msg);
+                    output::info("The function may be corrupted. Try re-running 'magellan watch'");
+                    output::exit(output::EXIT_DATABASE);
                 }
             }
-        };
-
-        // Output based on format
-        match cli.output {
-            OutputFormat::Human => print_diff_human(&diff, args.show_edges, args.verbose),
-            OutputFormat::Json => {
-                let wrapper = output::JsonResponse::new(diff);
-                println!("{}", wrapper.to_json());
-            }
-            OutputFormat::Pretty => {
-                let wrapper = output::JsonResponse::new(diff);
-                println!("{}", wrapper.to_pretty_json());
-            }
         }
-
-        Ok(())
     }
 
     fn print_diff_human(diff: &crate::cfg::diff::CfgDiff, show_edges: bool, verbose: bool) {
         use crate::output::{info, warn, success};
 
         info(&format!("CFG Diff: {}", diff.function_name));
-        println!("  Before: {}", diff.before_snapshot);
-        println!("  After: {}", diff.after_snapshot);
+        outln!("  Before: {}", diff.before_snapshot);
+        outln!("  After: {}", diff.after_snapshot);
 
         // Color-code similarity
         let similarity_pct = diff.structural_similarity * 100.0;
         if similarity_pct >= 90.0 {
             success(&format!("  Similarity: {:.1}%", similarity_pct));
         } else if similarity_pct >= 70.0 {
-            println!("  Similarity: {:.1}%", similarity_pct);
+            outln!("  Similarity: {:.1}%", similarity_pct);
         } else {
             warn(&format!("  Similarity: {:.1}%", similarity_pct));
         }
 
         if !diff.added_blocks.is_empty() {
-            println!();
+            outln!();
             info(&format!("Added blocks ({}):", diff.added_blocks.len()));
             for block in &diff.added_blocks {
-                println!("  + Block {}: {} @ {}", block.block_id, block.kind, block.source_location);
+                outln!("  + Block {}: {} @ {}", block.block_id, block.kind, block.source_location);
             }
         }
 
         if !diff.deleted_blocks.is_empty() {
-            println!();
+            outln!();
             info(&format!("Deleted blocks ({}):", diff.deleted_blocks.len()));
             for block in &diff.deleted_blocks {
-                println!("  - Block {}: {} @ {}", block.block_id, block.kind, block.source_location);
+                outln!("  - Block {}: {} @ {}", block.block_id, block.kind, block.source_location);
             }
         }
 
         if !diff.modified_blocks.is_empty() && verbose {
-            println!();
+            outln!();
             info(&format!("Modified blocks ({}):", diff.modified_blocks.len()));
             for change in &diff.modified_blocks {
                 match &change.change_type {
                     crate::cfg::diff::ChangeType::TerminatorChanged { before, after } => {
-                        println!("  ~ Block {}: {} -> {}",
+                        outln!("  ~ Block {}: {} -> {}",
                             change.block_id,
                             before,
                             after
                         );
                     }
                     crate::cfg::diff::ChangeType::SourceLocationChanged => {
-                        println!("  ~ Block {}: location changed", change.block_id);
+                        outln!("  ~ Block {}: location changed", change.block_id);
                     }
                     crate::cfg::diff::ChangeType::BothChanged => {
-                        println!("  ~ Block {}: terminator and location changed", change.block_id);
+                        outln!("  ~ Block {}: terminator and location changed", change.block_id);
                     }
                     crate::cfg::diff::ChangeType::EdgesChanged => {
-                        println!("  ~ Block {}: edges changed", change.block_id);
+                        outln!("  ~ Block {}: edges changed", change.block_id);
                     }
                 }
             }
@@ -3905,17 +8775,17 @@ pub mod cmds {
 
         if show_edges {
             if !diff.added_edges.is_empty() {
-                println!();
+                outln!();
                 info(&format!("Added edges ({}):", diff.added_edges.len()));
                 for edge in &diff.added_edges {
-                    println!("  + {} -> {} ({})", edge.from_block, edge.to_block, edge.edge_type);
+                    outln!("  + {} -> {} ({})", edge.from_block, edge.to_block, edge.edge_type);
                 }
             }
             if !diff.deleted_edges.is_empty() {
-                println!();
+                outln!();
                 info(&format!("Deleted edges ({}):", diff.deleted_edges.len()));
                 for edge in &diff.deleted_edges {
-                    println!("  - {} -> {} ({})", edge.from_block, edge.to_block, edge.edge_type);
+                    outln!("  - {} -> {} ({})", edge.from_block, edge.to_block, edge.edge_type);
                 }
             }
         }
@@ -3927,7 +8797,7 @@ pub mod cmds {
             && diff.added_edges.is_empty()
             && diff.deleted_edges.is_empty()
         {
-            println!();
+            outln!();
             success("No changes detected");
         }
     }
@@ -3936,16 +8806,15 @@ pub mod cmds {
         use crate::cfg::icfg::{build_icfg, to_dot, IcfgJson, IcfgOptions};
         use crate::output::error;
         use crate::output::{EXIT_DATABASE, EXIT_NOT_FOUND};
-        use crate::storage::MirageDb;
 
         let db_path = super::resolve_db_path(cli.db.clone())?;
 
         // Open database
-        let db = match MirageDb::open(&db_path) {
+        let db = match super::session::open_readonly(&db_path) {
             Ok(db) => db,
             Err(e) => {
                 error(&format!("Failed to open database: {}", e));
-                std::process::exit(EXIT_DATABASE);
+                output::exit(EXIT_DATABASE);
             }
         };
 
@@ -3954,7 +8823,7 @@ pub mod cmds {
             Ok(id) => id,
             Err(_) => {
                 error(&format!("Function not found: {}", args.entry));
-                std::process::exit(EXIT_NOT_FOUND);
+                output::exit(EXIT_NOT_FOUND);
             }
         };
 
@@ -3974,7 +8843,7 @@ pub mod cmds {
             Ok(icfg) => icfg,
             Err(e) => {
                 error(&format!("Failed to build ICFG: {}", e));
-                std::process::exit(EXIT_DATABASE);
+                output::exit(EXIT_DATABASE);
             }
         };
 
@@ -3986,11 +8855,11 @@ pub mod cmds {
 
         match format {
             IcfgFormat::Dot => {
-                println!("{}", to_dot(&icfg));
+                outln!("{}", to_dot(&icfg));
             }
             IcfgFormat::Json => {
                 let json_repr = IcfgJson::from_icfg(&icfg);
-                println!("{}", serde_json::to_string_pretty(&json_repr)?);
+                outln!("{}", serde_json::to_string_pretty(&json_repr)?);
             }
             IcfgFormat::Human => {
                 print_icfg_human(&icfg);
@@ -4002,17 +8871,17 @@ pub mod cmds {
 
     fn print_icfg_human(icfg: &crate::cfg::icfg::Icfg) {
         use std::collections::HashSet;
-        println!("Inter-Procedural CFG");
-        println!("  Entry function: {}", icfg.entry_function);
+        outln!("Inter-Procedural CFG");
+        outln!("  Entry function: {}", icfg.entry_function);
 
         // Count unique functions
         let mut functions = HashSet::new();
         for node in icfg.graph.node_indices() {
             functions.insert(icfg.graph[node].function_id);
         }
-        println!("  Functions: {}", functions.len());
-        println!("  Nodes: {}", icfg.graph.node_count());
-        println!("  Edges: {}", icfg.graph.edge_count());
+        outln!("  Functions: {}", functions.len());
+        outln!("  Nodes: {}", icfg.graph.node_count());
+        outln!("  Edges: {}", icfg.graph.edge_count());
 
         // Count edge types
         let mut call_count = 0;
@@ -4027,10 +8896,10 @@ pub mod cmds {
             }
         }
 
-        println!("  Edges by type:");
-        println!("    Call: {}", call_count);
-        println!("    Return: {}", return_count);
-        println!("    Intra-procedural: {}", intra_count);
+        outln!("  Edges by type:");
+        outln!("    Call: {}", call_count);
+        outln!("    Return: {}", return_count);
+        outln!("    Intra-procedural: {}", intra_count);
     }
 
     pub fn migrate(args: &MigrateArgs, cli: &Cli) -> Result<()> {
@@ -4073,8 +8942,8 @@ pub mod cmds {
         if args.dry_run {
             match cli.output {
                 OutputFormat::Human => {
-                    println!("Dry run: would migrate {} -> {}", args.from, args.to);
-                    println!("Database: {}", args.db);
+                    outln!("Dry run: would migrate {} -> {}", args.from, args.to);
+                    outln!("Database: {}", args.db);
                 }
                 OutputFormat::Json | OutputFormat::Pretty => {
                     let output = serde_json::json!({
@@ -4084,8 +8953,8 @@ pub mod cmds {
                         "database": args.db,
                     });
                     match cli.output {
-                        OutputFormat::Json => println!("{}", serde_json::to_string(&output)?),
-                        OutputFormat::Pretty => println!("{}", serde_json::to_string_pretty(&output)?),
+                        OutputFormat::Json => outln!("{}", serde_json::to_string(&output)?),
+                        OutputFormat::Pretty => outln!("{}", serde_json::to_string_pretty(&output)?),
                         _ => unreachable!(),
                     }
                 }
@@ -4121,7 +8990,7 @@ pub mod cmds {
                     // Report migration results
                     match cli.output {
                         OutputFormat::Human => {
-                            println!("{}", result.message);
+                            outln!("{}", result.message);
                         }
                         OutputFormat::Json | OutputFormat::Pretty => {
                             let output = serde_json::json!({
@@ -4133,8 +9002,8 @@ pub mod cmds {
                                 "side_tables_migrated": result.side_tables_migrated,
                             });
                             match cli.output {
-                                OutputFormat::Json => println!("{}", serde_json::to_string(&output)?),
-                                OutputFormat::Pretty => println!("{}", serde_json::to_string_pretty(&output)?),
+                                OutputFormat::Json => outln!("{}", serde_json::to_string(&output)?),
+                                OutputFormat::Pretty => outln!("{}", serde_json::to_string_pretty(&output)?),
                                 _ => unreachable!(),
                             }
                         }
@@ -4171,7 +9040,7 @@ pub mod cmds {
 
 /// Print hot paths in human-readable format
 fn print_hotpaths_human(hot_paths: &[crate::cfg::hotpaths::HotPath], show_rationale: bool) {
-    use crate::output;
+    use crate::output::{self, out, outln};
 
     output::header(&format!("Hot Paths (top {})", hot_paths.len()));
 
@@ -4181,26 +9050,26 @@ fn print_hotpaths_human(hot_paths: &[crate::cfg::hotpaths::HotPath], show_ration
     }
 
     for (i, hp) in hot_paths.iter().enumerate() {
-        println!("\n{}. Path {} - Score: {:.2}",
+        outln!("\n{}. Path {} - Score: {:.2}",
             i + 1, hp.path_id, hp.hotness_score
         );
 
         if show_rationale && !hp.rationale.is_empty() {
-            println!("   Rationale:");
+            outln!("   Rationale:");
             for r in &hp.rationale {
-                println!("     - {}", r);
+                outln!("     - {}", r);
             }
         }
 
-        println!("   Blocks: {} blocks", hp.blocks.len());
+        outln!("   Blocks: {} blocks", hp.blocks.len());
         for (j, block) in hp.blocks.iter().enumerate() {
             if j < 5 || j == hp.blocks.len() - 1 {
-                print!("     {}", block);
+                out!("     {}", block);
                 if j == 4 && hp.blocks.len() > 6 {
-                    println!(" ... (+{} more)", hp.blocks.len() - 6);
+                    outln!(" ... (+{} more)", hp.blocks.len() - 6);
                     break;
                 } else {
-                    println!();
+                    outln!();
                 }
             }
         }
@@ -4215,21 +9084,38 @@ fn print_hotpaths_human(hot_paths: &[crate::cfg::hotpaths::HotPath], show_ration
 mod tests {
     use super::*;
 
+    // Tests that touch MIRAGE_DB hold this lock so they don't race each other
+    static ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+        ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     // Ensure tests don't interfere with each other by clearing env var
     fn clear_env() {
         std::env::remove_var("MIRAGE_DB");
     }
 
+    fn config_with_db(root: &str, path: &str) -> MirageConfig {
+        MirageConfig {
+            database: config::DatabaseConfig { path: Some(path.to_string()) },
+            root: std::path::PathBuf::from(root),
+        }
+    }
+
     #[test]
     fn test_resolve_db_path_default() {
+        let _guard = lock_env();
         clear_env();
         // No arg, no env -> returns default (Magellan pattern)
         let result = resolve_db_path(None).unwrap();
-        assert_eq!(result, ".codemcp/codegraph.db");
+        assert_eq!(result, DEFAULT_DB_PATH);
+        assert_eq!(DEFAULT_DB_PATH, ".codemcp/codegraph.db");
     }
 
     #[test]
     fn test_resolve_db_path_with_cli_arg() {
+        let _guard = lock_env();
         clear_env();
         // CLI arg provided -> returns CLI arg
         let result = resolve_db_path(Some("/custom/path.db".to_string())).unwrap();
@@ -4238,6 +9124,7 @@ mod tests {
 
     #[test]
     fn test_resolve_db_path_with_env_var() {
+        let _guard = lock_env();
         clear_env();
         // Env var set -> returns env var value
         std::env::set_var("MIRAGE_DB", "/env/path.db");
@@ -4248,6 +9135,7 @@ mod tests {
 
     #[test]
     fn test_resolve_db_path_cli_overrides_env() {
+        let _guard = lock_env();
         clear_env();
         // CLI arg should override env var
         std::env::set_var("MIRAGE_DB", "/env/path.db");
@@ -4255,6 +9143,67 @@ mod tests {
         assert_eq!(result, "/cli/path.db");
         std::env::remove_var("MIRAGE_DB");
     }
+
+    #[test]
+    fn test_resolve_db_path_with_config_precedence() {
+        let _guard = lock_env();
+        clear_env();
+        let config = config_with_db("/project", "data/graph.db");
+
+        // Config beats the built-in default, relative to the config directory
+        let result = resolve_db_path_with_config(None, Some(&config)).unwrap();
+        assert_eq!(result, "/project/data/graph.db");
+
+        // Env var beats config
+        std::env::set_var("MIRAGE_DB", "/env/path.db");
+        let result = resolve_db_path_with_config(None, Some(&config)).unwrap();
+        assert_eq!(result, "/env/path.db");
+
+        // CLI arg beats everything
+        let result = resolve_db_path_with_config(Some("/cli/path.db".to_string()), Some(&config)).unwrap();
+        assert_eq!(result, "/cli/path.db");
+        std::env::remove_var("MIRAGE_DB");
+
+        // No config (or a config without a path) falls back to the default
+        assert_eq!(resolve_db_path_with_config(None, None).unwrap(), DEFAULT_DB_PATH);
+        let empty = MirageConfig::default();
+        assert_eq!(resolve_db_path_with_config(None, Some(&empty)).unwrap(), DEFAULT_DB_PATH);
+    }
+
+    #[test]
+    fn test_resolve_db_path_skips_config_when_decided() {
+        let _guard = lock_env();
+        clear_env();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(config::CONFIG_FILE_NAME), "[database\npath = ").unwrap();
+
+        // A malformed mirage.toml only matters when it would be used
+        assert!(resolve_db_path_from(None, dir.path()).is_err());
+        let result = resolve_db_path_from(Some("/cli/path.db".to_string()), dir.path()).unwrap();
+        assert_eq!(result, "/cli/path.db");
+        std::env::set_var("MIRAGE_DB", "/env/path.db");
+        let result = resolve_db_path_from(None, dir.path());
+        std::env::remove_var("MIRAGE_DB");
+        assert_eq!(result.unwrap(), "/env/path.db");
+    }
+
+    #[test]
+    fn test_resolve_db_path_with_discovered_config() {
+        let _guard = lock_env();
+        clear_env();
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(config::CONFIG_FILE_NAME),
+            "[database]\npath = \"graph.db\"\n",
+        ).unwrap();
+        let nested = dir.path().join("src").join("cli");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let discovered = MirageConfig::discover(&nested).unwrap();
+        let result = resolve_db_path_with_config(None, discovered.as_ref()).unwrap();
+
+        assert_eq!(result, dir.path().join("graph.db").to_string_lossy());
+    }
 }
 
 // ============================================================================
@@ -4337,10 +9286,31 @@ mod cfg_tests {
             db: None,
             output: OutputFormat::Human,
             command: Some(Commands::Cfg(CfgArgs {
-                function: "test".to_string(),
+                function: Some("test".to_string()),
+                functions_from_file: None,
+                timings: false,
+                all: false,
+                filter: None,
                 format: None,
+                with_loop_depth: false,
+                cluster_loops: false,
+                with_adjacency: false,
+                with_degrees: false,
+                rankdir: RankDirArg::TB,
+                splines: SplinesArg::Curved,
+                edge_filter: vec![],
             })),
             detect_backend: false,
+            list_error_codes: false,
+            snapshot: None,
+            max_statement_len: None,
+            max_statements_per_block: None,
+            output_file: None,
+            no_color: false,
+            quiet: false,
+            trace: false,
+            only_changed: None,
+            force: false,
         };
 
         let cfg_args = match &cli_human.command {
@@ -4362,10 +9332,31 @@ mod cfg_tests {
             db: None,
             output: OutputFormat::Json,
             command: Some(Commands::Cfg(CfgArgs {
-                function: "test".to_string(),
+                function: Some("test".to_string()),
+                functions_from_file: None,
+                timings: false,
+                all: false,
+                filter: None,
                 format: None,
+                with_loop_depth: false,
+                cluster_loops: false,
+                with_adjacency: false,
+                with_degrees: false,
+                rankdir: RankDirArg::TB,
+                splines: SplinesArg::Curved,
+                edge_filter: vec![],
             })),
             detect_backend: false,
+            list_error_codes: false,
+            snapshot: None,
+            max_statement_len: None,
+            max_statements_per_block: None,
+            output_file: None,
+            no_color: false,
+            quiet: false,
+            trace: false,
+            only_changed: None,
+            force: false,
         };
 
         let cfg_args_json = match &cli_json.command {
@@ -4534,7 +9525,8 @@ mod status_tests {
         assert_eq!(status.cfg_edges, 1, "Should have 1 cfg_edge");
         assert_eq!(status.cfg_paths, 1, "Should have 1 cfg_path");
         assert_eq!(status.cfg_dominators, 1, "Should have 1 cfg_dominator");
-        assert_eq!(status.mirage_schema_version, 1, "Schema version should be 1");
+        assert_eq!(status.mirage_schema_version, 5, "Schema version should be 5");
+        assert_eq!(status.path_id_version, crate::cfg::PATH_ID_VERSION);
         assert_eq!(status.magellan_schema_version, 7, "Magellan version should be 7");
     }
 
@@ -4676,6 +9668,80 @@ mod status_tests {
         assert_eq!(status.cfg_paths, 0, "Empty database should have 0 cfg_paths");
         assert_eq!(status.cfg_dominators, 0, "Empty database should have 0 cfg_dominators");
     }
+
+    /// Test that a read-only open can query but not write
+    #[test]
+    #[cfg(feature = "backend-sqlite")]
+    fn test_open_readonly_rejects_writes() {
+        let (file, _db) = create_test_db().unwrap();
+
+        let db = MirageDb::open_readonly(file.path()).unwrap();
+        assert!(db.is_read_only());
+
+        let status = db.status().unwrap();
+        assert_eq!(status.cfg_blocks, 2, "Read-only open should still query");
+
+        let result = db.conn().unwrap().execute("DELETE FROM cfg_blocks", []);
+        assert!(result.is_err(), "Writes through a read-only connection should fail");
+    }
+
+    /// Test that a read-only open refuses an old schema instead of migrating it
+    #[test]
+    #[cfg(feature = "backend-sqlite")]
+    fn test_open_readonly_rejects_old_schema() {
+        let (file, db) = create_test_db().unwrap();
+        db.conn().unwrap().execute("UPDATE mirage_meta SET mirage_schema_version = 4", []).unwrap();
+        drop(db);
+
+        let err = MirageDb::open_readonly(file.path()).unwrap_err();
+        let outdated = err.downcast_ref::<crate::storage::MirageSchemaOutdated>().expect("schema should be reported as outdated");
+        assert_eq!(outdated.found, 4);
+
+        let version = || rusqlite::Connection::open(file.path()).unwrap()
+            .query_row("SELECT mirage_schema_version FROM mirage_meta WHERE id = 1", [], |row| row.get::<_, i32>(0))
+            .unwrap();
+        assert_eq!(version(), 4, "a read-only open must not migrate");
+
+        MirageDb::open(file.path()).unwrap();
+        assert_eq!(version(), crate::storage::MIRAGE_SCHEMA_VERSION, "a read-write open still migrates");
+    }
+
+    /// Test that only the current snapshot can be opened on SQLite
+    #[test]
+    #[cfg(feature = "backend-sqlite")]
+    fn test_open_at_snapshot() {
+        use sqlitegraph::SnapshotId;
+
+        let (file, _db) = create_test_db().unwrap();
+
+        let db = MirageDb::open_at(file.path(), SnapshotId::current()).unwrap();
+        assert_eq!(db.status().unwrap().cfg_blocks, 2);
+
+        let err = MirageDb::open_at(file.path(), SnapshotId(7)).unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("Snapshot 7"), "{}", message);
+        assert!(message.contains("does not support historical snapshots"), "{}", message);
+    }
+
+    /// Test blocks response passes stored block fields through unchanged
+    #[test]
+    #[cfg(feature = "backend-sqlite")]
+    fn test_blocks_response_passthrough() {
+        let (_file, db) = create_test_db().unwrap();
+        let blocks = db.storage().get_cfg_blocks(1).unwrap();
+
+        let response = super::BlocksResponse { function_id: 1, blocks };
+        let json: serde_json::Value = serde_json::to_value(&response).unwrap();
+
+        assert_eq!(json["function_id"], 1);
+        let blocks = json["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0]["kind"], "entry");
+        assert_eq!(blocks[0]["terminator"], "goto");
+        assert_eq!(blocks[0]["byte_end"], 10);
+        assert_eq!(blocks[1]["kind"], "return");
+        assert_eq!(blocks[1]["start_line"], 2);
+    }
 }
 
 // ============================================================================
@@ -4722,42 +9788,326 @@ mod paths_tests {
         }
     }
 
-    /// Test that max_length limit is applied to path enumeration
+    /// Test that max_length limit is applied to path enumeration
+    #[test]
+    fn test_paths_max_length_limit() {
+        let cfg = cmds::create_test_cfg();
+
+        // Set a very low max_length limit
+        let limits = PathLimits::default().with_max_length(1);
+        let paths = enumerate_paths(&cfg, &limits);
+
+        // All paths should have length <= 1
+        for path in &paths {
+            assert!(path.len() <= 1, "Path length should be <= max_length limit");
+        }
+
+        // With max_length=1, we should get fewer paths than unrestricted
+        let unlimited_paths = enumerate_paths(&cfg, &PathLimits::default());
+        assert!(paths.len() <= unlimited_paths.len(),
+            "Limited enumeration should produce <= paths than unlimited");
+    }
+
+    /// Test that PathsArgs.function is extracted correctly
+    #[test]
+    fn test_paths_args_function_extraction() {
+        let args = PathsArgs {
+            function: "test_function".to_string(),
+            functions_from_file: None,
+            jobs: None,
+            timings: false,
+            show_errors: false,
+            max_length: None,
+            min_length: None,
+            with_blocks: false,
+            incremental: false,
+            since: None,
+            most_likely: false,
+            skip_panic_blocks: false,
+            dedup_by_blockset: false,
+            loops_only: false,
+            no_loops: false,
+            max_loop_iterations: None,
+            max_paths: None,
+            count_only: false,
+            summary_only: false,
+            include_statements: false,
+            entry: None,
+            ends_in: None,
+            sort: PathSortArg::Blocks,
+            desc: false,
+            tree: false,
+            readable_ids: false,
+            context_lines: None,
+            calls: None,
+            db2: None,
+        };
+
+        assert_eq!(args.function, "test_function");
+        assert!(!args.show_errors);
+        assert!(args.max_length.is_none());
+        assert!(!args.with_blocks);
+    }
+
+    /// Test --sort parsing and its default
+    #[test]
+    fn test_paths_args_sort() {
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.sort == PathSortArg::Blocks && !args.desc));
+
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f", "--sort", "length", "--desc"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.sort == PathSortArg::Length && args.desc));
+
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--sort", "random"]).is_err());
+    }
+
+    #[test]
+    fn test_paths_args_tree() {
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f", "--tree", "--show-errors"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.tree && args.show_errors));
+
+        // The tree replaces the per-path listing, so per-path detail flags do not apply
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--tree", "--count-only"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--tree", "--with-blocks"]).is_err());
+    }
+
+    #[test]
+    fn test_paths_args_context_lines() {
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f", "--with-blocks", "--context-lines", "3"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.context_lines == Some(3)));
+
+        // Snippets are part of the block listing
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--context-lines", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_since() {
+        use super::cmds::parse_since;
+
+        let now = 1_700_000_000;
+        assert_eq!(parse_since("90s", now).unwrap(), now - 90);
+        assert_eq!(parse_since("1h", now).unwrap(), now - 3600);
+        assert_eq!(parse_since("2d", now).unwrap(), now - 2 * 86400);
+        assert_eq!(parse_since("2024-01-02T03:04:05Z", now).unwrap(), 1_704_164_645);
+        assert_eq!(parse_since("2024-01-02T05:04:05+02:00", now).unwrap(), 1_704_164_645);
+
+        for bad in ["", "h", "1y", "-1h", "1.5h", "yesterday"] {
+            assert!(parse_since(bad, now).is_err(), "{:?} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn test_export_import_args() {
+        let cli = Cli::parse_from(["mirage", "export", "--function-data", "dump.jsonl"]);
+        assert!(matches!(cli.command, Some(Commands::Export(ref args)) if args.function_data == "dump.jsonl"));
+        assert!(Cli::try_parse_from(["mirage", "export"]).is_err());
+
+        let cli = Cli::parse_from(["mirage", "--db", "new.db", "import", "dump.jsonl"]);
+        assert!(matches!(cli.command, Some(Commands::Import(ref args)) if args.file == "dump.jsonl"));
+        assert_eq!(cli.db.as_deref(), Some("new.db"));
+    }
+
+    #[test]
+    fn test_paths_args_max_paths() {
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f", "--max-paths", "50"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.max_paths.map(|n| n.get()) == Some(50)));
+
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--max-paths", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_paths_args_calls() {
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f", "--calls", "unsafe_helper"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.calls.as_deref() == Some("unsafe_helper")));
+
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--calls", "g", "--count-only"]).is_err());
+    }
+
+    #[test]
+    fn test_paths_args_summary_only() {
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f", "--summary-only", "--show-errors"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.summary_only && args.show_errors));
+
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--summary-only", "--count-only"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--summary-only", "--tree"]).is_err());
+    }
+
+    #[test]
+    fn test_paths_args_count_only_rejects_path_filters() {
+        for filter in ["--show-errors", "--skip-panic-blocks", "--dedup-by-blockset", "--loops-only", "--no-loops"] {
+            assert!(
+                Cli::try_parse_from(["mirage", "paths", "--function", "f", "--count-only", filter]).is_err(),
+                "--count-only ignores {}",
+                filter
+            );
+        }
+
+        // The length band is applied while counting
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f", "--count-only", "--min-length", "3"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.count_only && args.min_length == Some(3)));
+    }
+
+    #[test]
+    fn test_paths_args_db2() {
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f", "--db2", "other.db", "--max-paths", "5"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.db2.as_deref() == Some("other.db")));
+
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--db2", "other.db", "--count-only"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--db2", "other.db", "--show-errors"]).is_err());
+    }
+
+    #[test]
+    fn test_paths_args_readable_ids() {
+        let cli = Cli::parse_from(["mirage", "paths", "--function", "f", "--readable-ids", "--show-errors"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args)) if args.readable_ids && args.show_errors));
+
+        // Aliases number whole-function paths, so partial enumerations cannot carry them
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--readable-ids", "--entry", "2"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--readable-ids", "--tree"]).is_err());
+    }
+
+    #[test]
+    fn test_functions_from_file_args() {
+        let cli = Cli::parse_from(["mirage", "paths", "--functions-from-file", "list.txt"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args))
+            if args.functions_from_file.as_deref() == Some("list.txt")));
+        let cli = Cli::parse_from(["mirage", "cfg", "--functions-from-file", "list.txt"]);
+        assert!(matches!(cli.command, Some(Commands::Cfg(ref args))
+            if args.functions_from_file.as_deref() == Some("list.txt") && args.function.is_none()));
+
+        // A function (or --all) is still required without a list, and the two don't mix
+        assert!(Cli::try_parse_from(["mirage", "paths"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "cfg"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--functions-from-file", "l"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "cfg", "--all", "--functions-from-file", "l"]).is_err());
+    }
+
+    #[test]
+    fn test_functions_from_file_jobs_args() {
+        let cli = Cli::parse_from(["mirage", "paths", "--functions-from-file", "l", "--jobs", "8", "--timings"]);
+        assert!(matches!(cli.command, Some(Commands::Paths(ref args))
+            if args.jobs.map(|j| j.get()) == Some(8) && args.timings));
+
+        // Batch flags mean nothing for a single function, and zero jobs would never run
+        assert!(Cli::try_parse_from(["mirage", "paths", "--function", "f", "--jobs", "2"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "cfg", "--function", "f", "--timings"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "paths", "--functions-from-file", "l", "--jobs", "0"]).is_err());
+        // cfg batches have no parallel step
+        assert!(Cli::try_parse_from(["mirage", "cfg", "--functions-from-file", "l", "--jobs", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_functions_args() {
+        let cli = Cli::parse_from(["mirage", "functions"]);
+        assert!(matches!(cli.command, Some(Commands::Functions(ref args))
+            if args.sort == FunctionSort::Name && args.filter.is_none() && args.limit.is_none()));
+
+        let cli = Cli::parse_from(["mirage", "functions", "--filter", "parse", "--sort", "edges", "--limit", "5"]);
+        assert!(matches!(cli.command, Some(Commands::Functions(ref args))
+            if args.sort == FunctionSort::Edges && args.filter.as_deref() == Some("parse") && args.limit == Some(5)));
+
+        assert!(Cli::try_parse_from(["mirage", "functions", "--sort", "size"]).is_err());
+    }
+
+    #[test]
+    fn test_patterns_args_shape() {
+        let cli = Cli::parse_from(["mirage", "patterns", "--function", "f", "--shape", "if-only"]);
+        assert!(matches!(cli.command, Some(Commands::Patterns(ref args)) if args.shape == Some(IfShapeArg::IfOnly)));
+
+        let cli = Cli::parse_from(["mirage", "patterns", "--function", "f", "--if-else", "--shape", "guard-return"]);
+        assert!(matches!(cli.command, Some(Commands::Patterns(ref args)) if args.shape == Some(IfShapeArg::GuardReturn)));
+
+        // Shapes only describe if/else patterns
+        assert!(Cli::try_parse_from(["mirage", "patterns", "--function", "f", "--match", "--shape", "diamond"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "patterns", "--function", "f", "--merge-points", "--shape", "diamond"]).is_err());
+        let cli = Cli::parse_from(["mirage", "patterns", "--function", "f", "--shape", "divergent"]);
+        assert!(matches!(cli.command, Some(Commands::Patterns(ref args)) if args.shape == Some(IfShapeArg::Divergent)));
+        assert!(Cli::try_parse_from(["mirage", "patterns", "--function", "f", "--shape", "triangle"]).is_err());
+    }
+
+    #[test]
+    fn test_patterns_args_threshold() {
+        let cli = Cli::parse_from(["mirage", "patterns", "--function", "f", "--unbalanced", "--threshold", "2.5"]);
+        assert!(matches!(cli.command, Some(Commands::Patterns(ref args)) if args.threshold == Some(2.5)));
+
+        for bad in ["NaN", "inf", "-1", "0.5", "many"] {
+            assert!(
+                Cli::try_parse_from(["mirage", "patterns", "--function", "f", "--unbalanced", "--threshold", bad]).is_err(),
+                "--threshold {} accepted",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_dominators_args_format() {
+        let cli = Cli::parse_from(["mirage", "dominators", "--function", "f", "--must-pass-through", "1"]);
+        assert!(matches!(cli.command, Some(Commands::Dominators(ref args)) if args.format == DominatorsFormat::Tree));
+
+        let cli = Cli::parse_from(["mirage", "dominators", "--function", "f", "--format", "dot", "--post", "--with-cfg"]);
+        assert!(matches!(cli.command, Some(Commands::Dominators(ref args))
+            if args.format == DominatorsFormat::Dot && args.post && args.with_cfg));
+
+        // A DOT tree has no room for a query result
+        assert!(Cli::try_parse_from(["mirage", "dominators", "--function", "f", "--format", "dot", "--must-pass-through", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_dominator_tree_lines_deep_chain() {
+        use crate::cfg::{BasicBlock, BlockKind, DominatorTree, EdgeType, Terminator};
+
+        // A straight-line chain deep enough to overflow a recursive printer
+        const LEN: usize = 200_000;
+        let mut cfg = crate::cfg::Cfg::new();
+        let nodes: Vec<_> = (0..LEN)
+            .map(|id| cfg.add_node(BasicBlock {
+                id,
+                kind: if id == 0 { BlockKind::Entry } else if id == LEN - 1 { BlockKind::Exit } else { BlockKind::Normal },
+                statements: vec![],
+                terminator: if id == LEN - 1 { Terminator::Return } else { Terminator::Goto { target: id + 1 } },
+                source_location: None,
+            }))
+            .collect();
+        for pair in nodes.windows(2) {
+            cfg.add_edge(pair[0], pair[1], EdgeType::Fallthrough);
+        }
+        let dom_tree = DominatorTree::new(&cfg).unwrap();
+
+        // Counting the hidden blocks walks the whole chain
+        let mut truncated = Vec::new();
+        cmds::write_dominator_tree(&mut truncated, &cfg, &dom_tree, dom_tree.root(), false, Some(2)).unwrap();
+        assert_eq!(String::from_utf8(truncated).unwrap(), format!(
+            "Block 0 (dominator)\n  Block 1 (dominator)\n    Block 2 (dominator)\n      ... ({} more blocks)\n",
+            LEN - 3
+        ));
+    }
+
     #[test]
-    fn test_paths_max_length_limit() {
-        let cfg = cmds::create_test_cfg();
-
-        // Set a very low max_length limit
-        let limits = PathLimits::default().with_max_length(1);
-        let paths = enumerate_paths(&cfg, &limits);
+    fn test_cfg_args_dot_layout() {
+        let cli = Cli::parse_from(["mirage", "cfg", "--function", "f"]);
+        assert!(matches!(cli.command, Some(Commands::Cfg(ref args))
+            if args.rankdir == RankDirArg::TB && args.splines == SplinesArg::Curved));
 
-        // All paths should have length <= 1
-        for path in &paths {
-            assert!(path.len() <= 1, "Path length should be <= max_length limit");
-        }
+        let cli = Cli::parse_from(["mirage", "cfg", "--function", "f", "--rankdir", "LR", "--splines", "ortho"]);
+        assert!(matches!(cli.command, Some(Commands::Cfg(ref args))
+            if args.rankdir == RankDirArg::LR && args.splines == SplinesArg::Ortho));
 
-        // With max_length=1, we should get fewer paths than unrestricted
-        let unlimited_paths = enumerate_paths(&cfg, &PathLimits::default());
-        assert!(paths.len() <= unlimited_paths.len(),
-            "Limited enumeration should produce <= paths than unlimited");
+        assert!(Cli::try_parse_from(["mirage", "cfg", "--function", "f", "--rankdir", "XY"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "cfg", "--function", "f", "--splines", "wavy"]).is_err());
     }
 
-    /// Test that PathsArgs.function is extracted correctly
     #[test]
-    fn test_paths_args_function_extraction() {
-        let args = PathsArgs {
-            function: "test_function".to_string(),
-            show_errors: false,
-            max_length: None,
-            with_blocks: false,
-            incremental: false,
-            since: None,
-        };
+    fn test_cfg_args_edge_filter() {
+        use crate::cfg::EdgeType;
 
-        assert_eq!(args.function, "test_function");
-        assert!(!args.show_errors);
-        assert!(args.max_length.is_none());
-        assert!(!args.with_blocks);
+        let cli = Cli::parse_from(["mirage", "cfg", "--function", "f"]);
+        assert!(matches!(cli.command, Some(Commands::Cfg(ref args)) if args.edge_filter.is_empty()));
+
+        let cli = Cli::parse_from(["mirage", "cfg", "--function", "f", "--edge-filter", "TrueBranch,falsebranch,LoopBack"]);
+        assert!(matches!(cli.command, Some(Commands::Cfg(ref args))
+            if args.edge_filter == vec![EdgeType::TrueBranch, EdgeType::FalseBranch, EdgeType::LoopBack]));
+
+        assert!(Cli::try_parse_from(["mirage", "cfg", "--function", "f", "--edge-filter", "Sideways"]).is_err());
     }
 
     /// Test that PathsArgs with flags set correctly reflects state
@@ -4765,11 +10115,34 @@ mod paths_tests {
     fn test_paths_args_with_flags() {
         let args = PathsArgs {
             function: "my_func".to_string(),
+            functions_from_file: None,
+            jobs: None,
+            timings: false,
             show_errors: true,
             max_length: Some(10),
+            min_length: None,
             with_blocks: true,
             incremental: false,
             since: None,
+            most_likely: false,
+            skip_panic_blocks: false,
+            dedup_by_blockset: false,
+            loops_only: false,
+            no_loops: false,
+            max_loop_iterations: None,
+            max_paths: None,
+            count_only: false,
+            summary_only: false,
+            include_statements: false,
+            entry: None,
+            ends_in: None,
+            sort: PathSortArg::Blocks,
+            desc: false,
+            tree: false,
+            readable_ids: false,
+            context_lines: None,
+            calls: None,
+            db2: None,
         };
 
         assert_eq!(args.function, "my_func");
@@ -4850,6 +10223,10 @@ mod paths_tests {
             total_paths: 5,
             error_paths: 2,
             paths: vec![],
+            truncated: false,
+            truncated_at: None,
+            collapsed_count: None,
+            length_filtered: None,
         };
 
         assert_eq!(response.function, "test_func");
@@ -4886,26 +10263,139 @@ mod paths_tests {
     fn test_paths_args_with_blocks_flag() {
         let args_with = PathsArgs {
             function: "test".to_string(),
+            functions_from_file: None,
+            jobs: None,
+            timings: false,
             show_errors: false,
             max_length: None,
+            min_length: None,
             with_blocks: true,
             incremental: false,
             since: None,
+            most_likely: false,
+            skip_panic_blocks: false,
+            dedup_by_blockset: false,
+            loops_only: false,
+            no_loops: false,
+            max_loop_iterations: None,
+            max_paths: None,
+            count_only: false,
+            summary_only: false,
+            include_statements: false,
+            entry: None,
+            ends_in: None,
+            sort: PathSortArg::Blocks,
+            desc: false,
+            tree: false,
+            readable_ids: false,
+            context_lines: None,
+            calls: None,
+            db2: None,
         };
 
         let args_without = PathsArgs {
             function: "test".to_string(),
+            functions_from_file: None,
+            jobs: None,
+            timings: false,
             show_errors: false,
             max_length: None,
+            min_length: None,
             with_blocks: false,
             incremental: false,
             since: None,
+            most_likely: false,
+            skip_panic_blocks: false,
+            dedup_by_blockset: false,
+            loops_only: false,
+            no_loops: false,
+            max_loop_iterations: None,
+            max_paths: None,
+            count_only: false,
+            summary_only: false,
+            include_statements: false,
+            entry: None,
+            ends_in: None,
+            sort: PathSortArg::Blocks,
+            desc: false,
+            tree: false,
+            readable_ids: false,
+            context_lines: None,
+            calls: None,
+            db2: None,
         };
 
         assert!(args_with.with_blocks, "with_blocks should be true");
         assert!(!args_without.with_blocks, "with_blocks should be false");
     }
 
+    /// Test that probability is only serialized when --most-likely computed it
+    #[test]
+    fn test_path_summary_probability_serialization() {
+        let cfg = cmds::create_test_cfg();
+        let path = enumerate_paths(&cfg, &PathLimits::default()).remove(0);
+
+        let plain = PathSummary::from_with_cfg(path.clone(), &cfg);
+        let json = serde_json::to_string(&plain).unwrap();
+        assert!(!json.contains("probability"));
+
+        let weighted = PathSummary {
+            probability: Some(0.5),
+            ..PathSummary::from_with_cfg(path, &cfg)
+        };
+        let json = serde_json::to_string(&weighted).unwrap();
+        assert!(json.contains("\"probability\":0.5"));
+    }
+
+    /// Test that --include-statements attaches block statements to path JSON
+    #[test]
+    fn test_path_summary_with_statements() {
+        let cfg = cmds::create_test_cfg();
+        let path = enumerate_paths(&cfg, &PathLimits::default()).remove(0);
+
+        let plain = PathSummary::from_with_cfg(path.clone(), &cfg);
+        assert!(!serde_json::to_string(&plain).unwrap().contains("statements"));
+
+        let summary = PathSummary::from_with_cfg(path, &cfg).with_statements(&cfg);
+        assert_eq!(summary.blocks[0].block_id, 0);
+        assert_eq!(summary.blocks[0].statements, Some(vec!["let x = 1".to_string()]));
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["blocks"][0]["statements"][0], "let x = 1");
+    }
+
+    /// Test that path JSON reports how each path ends
+    #[test]
+    fn test_path_summary_ends_in() {
+        // Blocks 2 and 3 both return; 3 is the natural exit
+        let cfg = cmds::create_test_cfg();
+        let ends_in: Vec<(usize, serde_json::Value)> = enumerate_paths(&cfg, &PathLimits::default())
+            .into_iter()
+            .map(|path| (path.exit, serde_json::to_value(PathSummary::from_with_cfg(path, &cfg)).unwrap()["ends_in"].clone()))
+            .collect();
+
+        assert_eq!(ends_in.len(), 2);
+        for (exit, end) in ends_in {
+            let expected = if exit == 3 { "NormalReturn" } else { "EarlyReturn" };
+            assert_eq!(end, expected, "path ending at block {}", exit);
+        }
+    }
+
+    #[test]
+    fn test_path_summary_with_loops() {
+        let back_edges = std::collections::HashSet::from([(2, 1)]);
+
+        let iterating = crate::cfg::Path::new(vec![0, 1, 2, 1, 3], PathKind::Normal);
+        let json = serde_json::to_value(PathSummary::from(iterating.clone()).with_loops(&iterating, &back_edges)).unwrap();
+        assert_eq!(json["contains_loop"], true);
+        assert_eq!(json["loop_headers"], serde_json::json!([1]));
+
+        let straight = crate::cfg::Path::new(vec![0, 1, 3], PathKind::Normal);
+        let json = serde_json::to_value(PathSummary::from(straight.clone()).with_loops(&straight, &back_edges)).unwrap();
+        assert_eq!(json["contains_loop"], false);
+        assert!(json.get("loop_headers").is_none());
+    }
+
     /// Test PathSummary::from_with_cfg with source locations
     #[test]
     fn test_path_summary_from_with_cfg() {
@@ -5387,6 +10877,7 @@ mod unreachable_tests {
 
         let response = UnreachableResponse {
             uncalled_functions: None,
+            changed_functions: None,
             function: "test_func".to_string(),
             total_functions: 1,
             functions_with_unreachable: 1,
@@ -5398,6 +10889,9 @@ mod unreachable_tests {
                     statements: vec!["unreachable code".to_string()],
                     terminator: "Unreachable".to_string(),
                     incoming_edges: vec![],
+                    source: None,
+                    source_note: None,
+                    conditionally_dead: None,
                 }
             ],
         };
@@ -5418,6 +10912,7 @@ mod unreachable_tests {
 
         let response = UnreachableResponse {
             uncalled_functions: None,
+            changed_functions: None,
             function: "test_func".to_string(),
             total_functions: 1,
             functions_with_unreachable: 0,
@@ -5432,6 +10927,66 @@ mod unreachable_tests {
         assert!(json.contains("\"functions_with_unreachable\":0"));
     }
 
+    /// Test that changed_functions is only reported with --only-changed
+    #[test]
+    fn test_unreachable_response_changed_functions() {
+        let mut response = UnreachableResponse {
+            uncalled_functions: None,
+            changed_functions: None,
+            function: "all".to_string(),
+            total_functions: 0,
+            functions_with_unreachable: 0,
+            unreachable_count: 0,
+            blocks: vec![],
+        };
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(!json.contains("changed_functions"));
+
+        response.changed_functions = Some(3);
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"changed_functions\":3"));
+    }
+
+    /// Test that grouped unreachable output keeps per-function attribution
+    #[test]
+    fn test_grouped_unreachable_response_round_trip() {
+        let block = |block_id| UnreachableBlock {
+            block_id,
+            kind: "Normal".to_string(),
+            statements: vec![],
+            terminator: "Return".to_string(),
+            incoming_edges: vec![],
+            source: None,
+            source_note: None,
+            conditionally_dead: None,
+        };
+        let functions = vec![
+            UnreachableFunction { function: "a".to_string(), function_id: 1, blocks: vec![block(3), block(4)] },
+            UnreachableFunction { function: "b".to_string(), function_id: 2, blocks: vec![block(7)] },
+        ];
+
+        let response = GroupedUnreachableResponse {
+            total_functions: 2,
+            functions_with_unreachable: 2,
+            unreachable_count: 3,
+            functions: functions.clone(),
+            uncalled_functions: None,
+            changed_functions: None,
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json.get("blocks").is_none(), "Grouped output has no flat block list");
+
+        let parsed: Vec<UnreachableFunction> = serde_json::from_value(json["functions"].clone()).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].function, "a");
+        assert_eq!(parsed[1].function_id, 2);
+        assert_eq!(parsed[1].blocks[0].block_id, 7);
+
+        let block_sum: usize = parsed.iter().map(|f| f.blocks.len()).sum();
+        assert_eq!(block_sum, json["unreachable_count"].as_u64().unwrap() as usize);
+    }
+
     /// Test that UnreachableBlock struct contains expected fields
     #[test]
     fn test_unreachable_block_fields() {
@@ -5441,6 +10996,9 @@ mod unreachable_tests {
             statements: vec!["stmt1".to_string(), "stmt2".to_string()],
             terminator: "Return".to_string(),
             incoming_edges: vec![],
+            source: None,
+            source_note: None,
+            conditionally_dead: None,
         };
 
         assert_eq!(block.block_id, 5);
@@ -5456,12 +11014,20 @@ mod unreachable_tests {
             include_uncalled: false,
             within_functions: true,
             show_branches: true,
+            sarif: false,
+            group_by_function: false,
+            with_source: false,
+            conditional: false,
         };
 
         let args_without = UnreachableArgs {
             include_uncalled: false,
             within_functions: false,
             show_branches: false,
+            sarif: false,
+            group_by_function: false,
+            with_source: false,
+            conditional: false,
         };
 
         assert!(args_with.within_functions);
@@ -5470,6 +11036,36 @@ mod unreachable_tests {
         assert!(!args_without.show_branches);
     }
 
+    /// Test that --conditional finds blocks hidden behind `if false`
+    #[test]
+    fn test_conditionally_dead_blocks() {
+        use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
+        use petgraph::graph::DiGraph;
+
+        let block = |id, statements: Vec<&str>, terminator| BasicBlock {
+            id,
+            kind: if id == 0 { BlockKind::Entry } else { BlockKind::Normal },
+            statements: statements.into_iter().map(String::from).collect(),
+            terminator,
+            source_location: None,
+        };
+        let mut g = DiGraph::new();
+        let b0 = g.add_node(block(0, vec!["if false"], Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, vec!["debug_dump()"], Terminator::Goto { target: 2 }));
+        let b2 = g.add_node(block(2, vec![], Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b2, EdgeType::Fallthrough);
+
+        // Graph reachability alone sees nothing wrong
+        assert!(find_unreachable(&g).is_empty());
+
+        let dead = cmds::conditionally_dead_blocks(&g);
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].0, b1);
+        assert!(dead[0].1.as_deref().unwrap().contains("block 0, whose condition is always false"));
+    }
+
     /// Test that create_test_cfg has no unreachable blocks
     #[test]
     fn test_test_cfg_fully_reachable() {
@@ -5569,6 +11165,9 @@ mod unreachable_tests {
                     statements: block.statements.clone(),
                     terminator: terminator_str,
                     incoming_edges,
+                    source: None,
+                    source_note: None,
+                    conditionally_dead: None,
                 }
             })
             .collect();
@@ -5667,6 +11266,9 @@ mod unreachable_tests {
                             }
                         })
                         .collect(),
+                    source: None,
+                    source_note: None,
+                    conditionally_dead: None,
                 }
             })
             .collect();
@@ -5678,6 +11280,7 @@ mod unreachable_tests {
             unreachable_count: 2,
             blocks,
             uncalled_functions: None,
+            changed_functions: None,
         };
 
         let wrapper = JsonResponse::new(response);
@@ -5820,6 +11423,45 @@ mod dominators_tests {
         Ok(file)
     }
 
+    /// A read-only run loads the tree stored by store_cfg, and never writes the cache on a miss
+    #[test]
+    #[allow(deprecated)]
+    fn test_dominator_cache_read_only_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dominators.db");
+        crate::storage::create_minimal_database(&path).unwrap();
+        let cfg = cmds::create_test_cfg();
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute(
+            "INSERT INTO graph_entities (kind, name, file_path, data) VALUES ('Symbol', 'f', 'f.rs', '{}')",
+            [],
+        ).unwrap();
+        crate::storage::store_cfg(&conn, 1, "hash", &cfg).unwrap();
+        // Hashes come from Magellan's cfg_blocks.function_hash
+        conn.execute_batch(
+            "ALTER TABLE cfg_blocks ADD COLUMN function_hash TEXT;
+             UPDATE cfg_blocks SET function_hash = 'hash';",
+        ).unwrap();
+        let cached_rows = || -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM cfg_dominators", [], |row| row.get(0)).unwrap()
+        };
+        assert!(cached_rows() > 0, "store_cfg fills the cache");
+
+        // Fresh computation says block 1 dominates block 3; the doctored row says 0
+        conn.execute("UPDATE cfg_dominators SET dominator_id = 0 WHERE block_id = 3 AND is_strict = 1", []).unwrap();
+        let mut db = crate::storage::MirageDb::open_readonly(&path).unwrap();
+        assert!(db.is_read_only());
+        let tree = cmds::load_or_compute_dominator_tree(&mut db, 1, &cfg).unwrap();
+        let node = |id| cfg.node_indices().find(|&n| cfg[n].id == id).unwrap();
+        assert_eq!(tree.immediate_dominator(node(3)), Some(node(0)), "Read-only run reads cfg_dominators");
+
+        // A miss is computed in memory and leaves the table empty
+        conn.execute("DELETE FROM cfg_dominators", []).unwrap();
+        let tree = cmds::load_or_compute_dominator_tree(&mut db, 1, &cfg).unwrap();
+        assert_eq!(tree.immediate_dominator(node(3)), Some(node(1)));
+        assert_eq!(cached_rows(), 0, "Read-only run does not write the cache");
+    }
+
     /// Test that DominatorTree can be computed from test CFG
     #[test]
     fn test_dominator_tree_computation() {
@@ -5914,8 +11556,13 @@ mod dominators_tests {
         let args = DominatorsArgs {
             function: "test_func".to_string(),
             must_pass_through: Some("1".to_string()),
+            must_pass_through_line: None,
+            strict: false,
             post: false,
             inter_procedural: false,
+            format: DominatorsFormat::Tree,
+            with_cfg: false,
+            max_depth: None,
         };
 
         assert_eq!(args.function, "test_func");
@@ -5930,8 +11577,13 @@ mod dominators_tests {
         let args = DominatorsArgs {
             function: "my_function".to_string(),
             must_pass_through: None,
+            must_pass_through_line: None,
+            strict: false,
             post: true,
             inter_procedural: false,
+            format: DominatorsFormat::Tree,
+            with_cfg: false,
+            max_depth: None,
         };
 
         assert_eq!(args.function, "my_function");
@@ -5955,6 +11607,8 @@ mod dominators_tests {
                 },
             ],
             must_pass_through: None,
+            must_pass_through_line: None,
+            entry_blocks: None,
         };
 
         let json = serde_json::to_string(&response);
@@ -6063,6 +11717,8 @@ mod dominators_tests {
             root: Some(0),
             dominance_tree: vec![],
             must_pass_through: None,
+            must_pass_through_line: None,
+            entry_blocks: None,
         };
 
         let wrapper = JsonResponse::new(response);
@@ -6129,6 +11785,8 @@ mod dominators_tests {
                 block: 0,
                 must_pass: vec![0, 1],
             }),
+            must_pass_through_line: None,
+            entry_blocks: None,
         };
 
         let wrapper = JsonResponse::new(response);
@@ -6252,6 +11910,40 @@ mod verify_tests {
         assert!(is_valid, "Cached path should exist in current enumeration");
     }
 
+    /// Test that verify's path lookup resolves readable IDs to the right path
+    #[test]
+    #[cfg(feature = "backend-sqlite")]
+    fn test_verify_resolves_readable_path_id() {
+        use crate::cfg::paths::{path_ordinals, readable_path_id};
+        use crate::storage::paths::{invalidate_function_paths, store_paths};
+
+        // Path IDs are unique across functions, so move the cached path over
+        let (_file, mut db, _) = create_test_db_with_cached_path().unwrap();
+        let conn = db.conn_mut().unwrap();
+        invalidate_function_paths(conn, 1).unwrap();
+        conn.execute(
+            "INSERT INTO graph_entities (kind, name, file_path, data) VALUES ('Symbol', 'readable_func', 'test.rs', '{\"kind\":\"Function\"}')",
+            [],
+        ).unwrap();
+        let function_id = conn.last_insert_rowid();
+        let paths = enumerate_paths(&cmds::create_test_cfg(), &PathLimits::default());
+        assert!(paths.len() > 1);
+        store_paths(conn, function_id, &paths).unwrap();
+
+        for (path, ordinal) in paths.iter().zip(path_ordinals(&paths)) {
+            let readable_id = readable_path_id("readable_func", ordinal);
+            assert_eq!(cmds::resolve_path_id(&db, &readable_id), path.path_id);
+        }
+        // The function part may also be the numeric ID
+        let by_id = readable_path_id(&function_id.to_string(), 1);
+        assert_eq!(cmds::resolve_path_id(&db, &by_id), cmds::resolve_path_id(&db, "readable_func#p1"));
+
+        // Hashes and unknown aliases pass through for the caller to report
+        assert_eq!(cmds::resolve_path_id(&db, &paths[0].path_id), paths[0].path_id);
+        assert_eq!(cmds::resolve_path_id(&db, "readable_func#p99"), "readable_func#p99");
+        assert_eq!(cmds::resolve_path_id(&db, "missing_func#p1"), "missing_func#p1");
+    }
+
     /// Test that VerifyResult serializes correctly
     #[test]
     fn test_verify_result_serialization() {
@@ -6298,10 +11990,85 @@ mod verify_tests {
     #[test]
     fn test_verify_args_fields() {
         let args = VerifyArgs {
-            path_id: "abc123".to_string(),
+            path_id: Some("abc123".to_string()),
+            function: None,
+        };
+
+        assert_eq!(args.path_id.as_deref(), Some("abc123"));
+        assert!(args.function.is_none());
+    }
+
+    /// Test blast-zone --until-convergence parsing and its conflicts
+    #[test]
+    fn test_blast_zone_until_convergence_flag() {
+        let cli = Cli::parse_from(["mirage", "blast-zone", "--function", "f", "--block-id", "1", "--until-convergence"]);
+        assert!(matches!(cli.command, Some(Commands::BlastZone(ref args)) if args.until_convergence));
+
+        assert!(Cli::try_parse_from(["mirage", "blast-zone", "--function", "f", "--until-convergence", "--reverse"]).is_err());
+    }
+
+    /// Test batch verification partitions cached paths against one enumeration
+    #[test]
+    fn test_verify_batch_partitions_cached_paths() {
+        let cfg = cmds::create_test_cfg();
+        let paths = enumerate_paths(&cfg, &PathLimits::default());
+        let cached = vec![
+            (paths[0].path_id.clone(), paths[0].blocks.clone()),
+            ("stale_path".to_string(), vec![0, 2]),
+        ];
+
+        let result = cmds::summarize_verify_batch("test_func", cached, Some(&cfg), &paths);
+
+        assert_eq!(result.function, "test_func");
+        assert_eq!(result.total, 2);
+        assert_eq!(result.still_valid, 1);
+        assert_eq!(result.invalidated, vec!["stale_path".to_string()]);
+        assert_eq!(result.current_paths, paths.len());
+    }
+
+    /// Test each batch verification reason
+    #[test]
+    fn test_verify_batch_reasons() {
+        let cfg = cmds::create_test_cfg();
+        let paths = enumerate_paths(&cfg, &PathLimits::default());
+        let cached = || vec![
+            (paths[0].path_id.clone(), paths[0].blocks.clone()),
+            // Starts at the entry, but 0 -> 2 is no longer an edge
+            ("branch_changed".to_string(), vec![0, 2]),
+            // Starts at a block that is not the entry
+            ("entry_missing".to_string(), vec![1, 2]),
+            ("no_elements".to_string(), vec![]),
+        ];
+        let reasons = |result: &VerifyBatchResult| -> Vec<(String, VerifyReason)> {
+            result.paths.iter().map(|v| (v.path_id.clone(), v.reason)).collect()
         };
 
-        assert_eq!(args.path_id, "abc123");
+        let result = cmds::summarize_verify_batch("f", cached(), Some(&cfg), &paths);
+        assert_eq!(reasons(&result), vec![
+            (paths[0].path_id.clone(), VerifyReason::StillValid),
+            ("branch_changed".to_string(), VerifyReason::BranchChanged),
+            ("entry_missing".to_string(), VerifyReason::EntryMissing),
+            ("no_elements".to_string(), VerifyReason::EntryMissing),
+        ]);
+        assert_eq!(result.invalidated.len(), 3);
+
+        // With the function's blocks gone, every cached path is invalid
+        let result = cmds::summarize_verify_batch("f", cached(), None, &[]);
+        assert!(result.paths.iter().all(|v| v.reason == VerifyReason::FunctionGone));
+        assert_eq!(result.still_valid, 0);
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["paths"][0]["reason"], "FunctionGone");
+    }
+
+    /// Test VerifyBatchResult JSON shape
+    #[test]
+    fn test_verify_batch_result_serialization() {
+        let result = cmds::summarize_verify_batch("f", Vec::new(), None, &[]);
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("\"function\":\"f\""));
+        assert!(json.contains("\"total\":0"));
+        assert!(json.contains("\"invalidated\":[]"));
     }
 
     /// Test that JsonResponse wrapper works with VerifyResult
@@ -6432,7 +12199,160 @@ mod verify_tests {
 #[cfg(test)]
 mod output_format_tests {
     use super::*;
-    use crate::output::JsonResponse;
+    use crate::output::{self, JsonResponse};
+
+    /// Build a looping diamond, adding nodes and edges in the given block order
+    fn build_cfg_in_order(order: &[usize]) -> crate::cfg::Cfg {
+        use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
+
+        // 0 -> 1; 1 -> 2 | 3; 2 -> 4; 3 -> 4; 4 -> 1 (back edge) | 5
+        let edges = [
+            (0, 1, EdgeType::Fallthrough),
+            (1, 2, EdgeType::TrueBranch),
+            (1, 3, EdgeType::FalseBranch),
+            (2, 4, EdgeType::Fallthrough),
+            (3, 4, EdgeType::Fallthrough),
+            (4, 1, EdgeType::LoopBack),
+            (4, 5, EdgeType::FalseBranch),
+        ];
+
+        let mut cfg = crate::cfg::Cfg::new();
+        let mut nodes = std::collections::HashMap::new();
+        for &id in order {
+            let (kind, terminator) = match id {
+                0 => (BlockKind::Entry, Terminator::Goto { target: 1 }),
+                1 => (BlockKind::Normal, Terminator::SwitchInt { targets: vec![2], otherwise: 3 }),
+                2 | 3 => (BlockKind::Normal, Terminator::Goto { target: 4 }),
+                4 => (BlockKind::Normal, Terminator::SwitchInt { targets: vec![1], otherwise: 5 }),
+                _ => (BlockKind::Exit, Terminator::Return),
+            };
+            nodes.insert(id, cfg.add_node(BasicBlock {
+                id,
+                kind,
+                statements: vec![],
+                terminator,
+                source_location: None,
+            }));
+        }
+        for &id in order {
+            for (from, to, edge_type) in edges.iter().filter(|(from, _, _)| *from == id) {
+                cfg.add_edge(nodes[from], nodes[to], *edge_type);
+            }
+        }
+        cfg
+    }
+
+    /// JSON for dominators, post-dominators, loops, patterns and frontiers of `cfg`
+    fn analysis_json(cfg: &crate::cfg::Cfg) -> String {
+        use crate::cfg::{
+            compute_dominance_frontiers, detect_if_else_patterns, detect_match_patterns,
+            detect_natural_loops, DominatorTree, PostDominatorTree,
+        };
+
+        let dom_tree = DominatorTree::new(cfg).unwrap();
+        let post_dom_tree = PostDominatorTree::new(cfg).unwrap();
+        let natural_loops = detect_natural_loops(cfg);
+        let (if_else_patterns, match_patterns) =
+            cmds::pattern_infos(cfg, &detect_if_else_patterns(cfg), &detect_match_patterns(cfg));
+        let frontiers = compute_dominance_frontiers(cfg, dom_tree.clone());
+
+        let json = [
+            serde_json::to_string(&DominanceResponse {
+                function: "f".to_string(),
+                kind: "dominators".to_string(),
+                root: Some(cfg[dom_tree.root()].id),
+                dominance_tree: cmds::dominance_entries(cfg, &dom_tree),
+                must_pass_through: None,
+                must_pass_through_line: None,
+                entry_blocks: None,
+            }),
+            serde_json::to_string(&DominanceResponse {
+                function: "f".to_string(),
+                kind: "post-dominators".to_string(),
+                root: Some(cfg[post_dom_tree.root()].id),
+                dominance_tree: cmds::dominance_entries(cfg, post_dom_tree.as_dominator_tree()),
+                must_pass_through: None,
+                must_pass_through_line: None,
+                entry_blocks: None,
+            }),
+            serde_json::to_string(&{
+                let (loops, self_loops) = cmds::loop_and_self_loop_infos(cfg, &natural_loops);
+                LoopsResponse {
+                    function: "f".to_string(),
+                    loop_count: loops.len(),
+                    loops,
+                    self_loops,
+                }
+            }),
+            serde_json::to_string(&PatternsResponse {
+                function: "f".to_string(),
+                if_else_count: if_else_patterns.len(),
+                match_count: match_patterns.len(),
+                if_else_patterns,
+                match_patterns,
+            }),
+            serde_json::to_string(&FrontiersResponse {
+                function: "f".to_string(),
+                nodes_with_frontiers: frontiers.nodes_with_frontiers().count(),
+                frontiers: cmds::node_frontiers(cfg, &frontiers),
+                entry_blocks: None,
+            }),
+        ];
+        json.into_iter().map(Result::unwrap).collect::<Vec<_>>().join("\n")
+    }
+
+    /// JSON output must not depend on the order blocks were inserted into the graph
+    #[test]
+    fn test_analysis_json_independent_of_insertion_order() {
+        let forward = build_cfg_in_order(&[0, 1, 2, 3, 4, 5]);
+        // The entry stays first: find_entry takes the first node
+        let shuffled = build_cfg_in_order(&[0, 3, 5, 1, 4, 2]);
+        assert_ne!(
+            forward.node_indices().map(|n| forward[n].id).collect::<Vec<_>>(),
+            shuffled.node_indices().map(|n| shuffled[n].id).collect::<Vec<_>>()
+        );
+
+        let json = analysis_json(&forward);
+        assert_eq!(json, analysis_json(&shuffled));
+        assert!(json.contains("\"frontier_set\""));
+        assert!(json.contains("\"body_blocks\":[1,2,3,4]"));
+    }
+
+    /// Every E_* constant the handlers report must be in the exit-code contract
+    #[test]
+    fn test_error_codes_cover_cli_handlers() {
+        assert!(!output::ERROR_CODES.is_empty());
+
+        let source = include_str!("mod.rs");
+        let mut referenced: Vec<&str> = source
+            .match_indices("output::E_")
+            .map(|(start, _)| {
+                let name = &source[start + "output::".len()..];
+                let end = name
+                    .find(|c: char| !(c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'))
+                    .unwrap_or(name.len());
+                &name[..end]
+            })
+            // Skip the bare "E_" matched by the search string in this test
+            .filter(|name| name.len() > "E_".len())
+            .collect();
+        referenced.sort_unstable();
+        referenced.dedup();
+        assert!(!referenced.is_empty());
+
+        for name in referenced {
+            assert!(
+                output::ERROR_CODES.iter().any(|info| info.name == name),
+                "{} is used by a handler but missing from ERROR_CODES",
+                name
+            );
+        }
+
+        // Codes and names are unique
+        for (i, info) in output::ERROR_CODES.iter().enumerate() {
+            assert!(output::ERROR_CODES[i + 1..].iter().all(|other| other.code != info.code && other.name != info.name));
+        }
+    }
 
     /// Test that all response structs serialize correctly to JSON
     #[test]
@@ -6443,6 +12363,10 @@ mod output_format_tests {
             total_paths: 2,
             error_paths: 0,
             paths: vec![],
+            truncated: false,
+            truncated_at: None,
+            collapsed_count: None,
+            length_filtered: None,
         };
         let paths_json = serde_json::to_string(&paths_resp);
         assert!(paths_json.is_ok(), "PathsResponse should serialize");
@@ -6454,6 +12378,8 @@ mod output_format_tests {
             root: Some(0),
             dominance_tree: vec![],
             must_pass_through: None,
+            must_pass_through_line: None,
+            entry_blocks: None,
         };
         let dom_json = serde_json::to_string(&dom_resp);
         assert!(dom_json.is_ok(), "DominanceResponse should serialize");
@@ -6461,6 +12387,7 @@ mod output_format_tests {
         // UnreachableResponse
         let unreach_resp = UnreachableResponse {
             uncalled_functions: None,
+            changed_functions: None,
             function: "test_func".to_string(),
             total_functions: 1,
             functions_with_unreachable: 0,
@@ -6483,6 +12410,28 @@ mod output_format_tests {
         assert!(verify_json.is_ok(), "VerifyResult should serialize");
     }
 
+    /// Test CallImpactResponse JSON shape for blast-zone --callers/--callees
+    #[test]
+    fn test_call_impact_response_serialization() {
+        let response = CallImpactResponse {
+            target: "helper".to_string(),
+            direction: crate::analysis::CallDirection::Callers,
+            functions: vec![CallerEntry {
+                function_id: 7,
+                name: "main".to_string(),
+                distance: 2,
+                has_cfg: true,
+            }],
+        };
+
+        let parsed: serde_json::Value = serde_json::to_value(&response).unwrap();
+        assert_eq!(parsed["target"], "helper");
+        assert_eq!(parsed["direction"], "callers");
+        assert_eq!(parsed["functions"][0]["name"], "main");
+        assert_eq!(parsed["functions"][0]["distance"], 2);
+        assert_eq!(parsed["functions"][0]["has_cfg"], true);
+    }
+
     /// Test that JsonResponse wrapper works for all response types
     #[test]
     fn test_json_response_wrapper_for_all_commands() {
@@ -6492,6 +12441,10 @@ mod output_format_tests {
             total_paths: 2,
             error_paths: 0,
             paths: vec![],
+            truncated: false,
+            truncated_at: None,
+            collapsed_count: None,
+            length_filtered: None,
         };
         let paths_wrapper = JsonResponse::new(paths_resp);
         assert_eq!(paths_wrapper.schema_version, "1.0.1");
@@ -6505,6 +12458,8 @@ mod output_format_tests {
             root: Some(0),
             dominance_tree: vec![],
             must_pass_through: None,
+            must_pass_through_line: None,
+            entry_blocks: None,
         };
         let dom_wrapper = JsonResponse::new(dom_resp);
         assert_eq!(dom_wrapper.schema_version, "1.0.1");
@@ -6513,6 +12468,7 @@ mod output_format_tests {
         // UnreachableResponse wrapped
         let unreach_resp = UnreachableResponse {
             uncalled_functions: None,
+            changed_functions: None,
             function: "test_func".to_string(),
             total_functions: 1,
             functions_with_unreachable: 0,
@@ -6605,7 +12561,7 @@ mod output_format_tests {
     #[test]
     fn test_human_output_no_json_artifacts() {
         // Human format should print readable text, not JSON
-        // This test verifies the pattern: Human output uses println!, not JsonResponse
+        // This test verifies the pattern: Human output uses outln!, not JsonResponse
 
         let function_name = "test_function";
         let path_count = 5;
@@ -6676,6 +12632,16 @@ mod output_format_tests {
                 output: format,
                 command: Some(Commands::Status(StatusArgs {})),
                 detect_backend: false,
+                list_error_codes: false,
+            snapshot: None,
+            max_statement_len: None,
+            max_statements_per_block: None,
+            output_file: None,
+            no_color: false,
+            quiet: false,
+            trace: false,
+                only_changed: None,
+                force: false,
             };
 
             assert_eq!(cli.output, format);
@@ -6711,6 +12677,10 @@ mod output_format_tests {
             total_paths: 1,
             error_paths: 0,
             paths: vec![],
+            truncated: false,
+            truncated_at: None,
+            collapsed_count: None,
+            length_filtered: None,
         };
         let json = serde_json::to_string(&paths_resp).unwrap();
 
@@ -6803,6 +12773,9 @@ mod output_format_tests {
                     body_size: 2,
                     nesting_level: 0,
                     body_blocks: vec![1, 2],
+                    exit_edges: vec![],
+                    exit_blocks: vec![],
+                    preheader: None,
                 },
                 LoopInfo {
                     header: 3,
@@ -6810,8 +12783,12 @@ mod output_format_tests {
                     body_size: 3,
                     nesting_level: 1,
                     body_blocks: vec![1, 2, 3],
+                    exit_edges: vec![],
+                    exit_blocks: vec![],
+                    preheader: None,
                 },
             ],
+            self_loops: vec![],
         };
 
         // Should serialize without errors
@@ -6848,6 +12825,9 @@ mod output_format_tests {
             body_size: 3,
             nesting_level: 2,
             body_blocks: vec![5, 6, 7],
+            exit_edges: vec![],
+            exit_blocks: vec![],
+            preheader: None,
         };
 
         assert_eq!(loop_info.header, 5);
@@ -6871,7 +12851,11 @@ mod output_format_tests {
                 body_size: 2,
                 nesting_level: 0,
                 body_blocks: vec![1, 2],
+                exit_edges: vec![],
+                exit_blocks: vec![],
+                preheader: None,
             }],
+            self_loops: vec![],
         };
 
         let wrapper = JsonResponse::new(response);
@@ -6911,6 +12895,9 @@ mod output_format_tests {
             body_size: 3,
             nesting_level: 0, // Outermost
             body_blocks: vec![1, 2, 3],
+            exit_edges: vec![],
+            exit_blocks: vec![],
+            preheader: None,
         };
 
         let loop_inner = LoopInfo {
@@ -6919,6 +12906,9 @@ mod output_format_tests {
             body_size: 2,
             nesting_level: 1, // Nested inside outer
             body_blocks: vec![2, 4],
+            exit_edges: vec![],
+            exit_blocks: vec![],
+            preheader: None,
         };
 
         assert_eq!(loop_outer.nesting_level, 0);
@@ -6934,6 +12924,7 @@ mod output_format_tests {
             function: "no_loops_func".to_string(),
             loop_count: 0,
             loops: vec![],
+            self_loops: vec![],
         };
 
         let wrapper = JsonResponse::new(response);
@@ -6944,6 +12935,51 @@ mod output_format_tests {
         assert!(json.contains("\"loops\":[]"));
     }
 
+    /// Test that self-loops are reported separately from multi-block loops
+    #[test]
+    fn test_loops_self_loops_reported_separately() {
+        use crate::cfg::{detect_natural_loops, BasicBlock, BlockKind, EdgeType, Terminator};
+        use petgraph::graph::DiGraph;
+
+        // 0 -> 1 -> 2 -> 1 (loop), 2 -> 2 (self-loop inside it), 1 -> 3
+        let mut g = DiGraph::new();
+        let blocks: Vec<_> = (0..4)
+            .map(|id| g.add_node(BasicBlock {
+                id,
+                kind: if id == 0 { BlockKind::Entry } else { BlockKind::Normal },
+                statements: vec![],
+                terminator: Terminator::Return,
+                source_location: None,
+            }))
+            .collect();
+        g.add_edge(blocks[0], blocks[1], EdgeType::Fallthrough);
+        g.add_edge(blocks[1], blocks[2], EdgeType::TrueBranch);
+        g.add_edge(blocks[1], blocks[3], EdgeType::FalseBranch);
+        g.add_edge(blocks[2], blocks[1], EdgeType::LoopBack);
+        g.add_edge(blocks[2], blocks[2], EdgeType::LoopBack);
+
+        let natural_loops = detect_natural_loops(&g);
+        assert_eq!(natural_loops.len(), 2, "Self-loop is also a natural loop");
+
+        let (loops, self_loops) = cmds::loop_and_self_loop_infos(&g, &natural_loops);
+
+        assert_eq!(loops.len(), 1, "Self-loop must not be counted as a multi-block loop");
+        assert_eq!(loops[0].header, 1);
+        assert_eq!(loops[0].body_blocks, vec![1, 2]);
+        assert_eq!(loops[0].exit_edges, vec![(1, 3)]);
+        assert_eq!(loops[0].exit_blocks, vec![3]);
+        assert_eq!(loops[0].preheader, Some(0));
+
+        assert_eq!(self_loops.len(), 1);
+        assert_eq!(self_loops[0].header, 2);
+        assert_eq!(self_loops[0].back_edge_from, 2);
+        assert_eq!(self_loops[0].body_size, 1);
+        assert_eq!(self_loops[0].body_blocks, vec![2]);
+        assert_eq!(self_loops[0].nesting_level, 1, "Self-loop sits inside the loop headed by block 1");
+        assert_eq!(self_loops[0].exit_edges, vec![(2, 1)]);
+        assert_eq!(self_loops[0].preheader, Some(1));
+    }
+
     /// Test patterns command with if/else detection
     #[test]
     fn test_patterns_if_else_detection() {
@@ -6977,6 +13013,11 @@ mod output_format_tests {
             function: "test_func".to_string(),
             if_else: true,
             r#match: false,
+            merge_points: false,
+            shape: None,
+            guards: false,
+            unbalanced: false,
+            threshold: None,
         };
 
         // Verify args are parsed correctly
@@ -6985,6 +13026,26 @@ mod output_format_tests {
         assert_eq!(args.function, "test_func");
     }
 
+    /// Test patterns --guards parsing and its conflicts with the other views
+    #[test]
+    fn test_patterns_guards_flag() {
+        let cli = Cli::parse_from(["mirage", "patterns", "--function", "f", "--guards"]);
+        assert!(matches!(cli.command, Some(Commands::Patterns(ref args)) if args.guards));
+
+        assert!(Cli::try_parse_from(["mirage", "patterns", "--function", "f", "--guards", "--merge-points"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "patterns", "--function", "f", "--guards", "--if-else"]).is_err());
+    }
+
+    /// Test patterns --unbalanced parsing; --threshold only applies to it
+    #[test]
+    fn test_patterns_unbalanced_flag() {
+        let cli = Cli::parse_from(["mirage", "patterns", "--function", "f", "--unbalanced", "--threshold", "2.5"]);
+        assert!(matches!(cli.command, Some(Commands::Patterns(ref args)) if args.unbalanced && args.threshold == Some(2.5)));
+
+        assert!(Cli::try_parse_from(["mirage", "patterns", "--function", "f", "--threshold", "2"]).is_err());
+        assert!(Cli::try_parse_from(["mirage", "patterns", "--function", "f", "--unbalanced", "--guards"]).is_err());
+    }
+
     /// Test patterns command with --match filter
     #[test]
     fn test_patterns_match_filter() {
@@ -6993,6 +13054,11 @@ mod output_format_tests {
             function: "test_func".to_string(),
             if_else: false,
             r#match: true,
+            merge_points: false,
+            shape: None,
+            guards: false,
+            unbalanced: false,
+            threshold: None,
         };
 
         // Verify args are parsed correctly
@@ -7009,6 +13075,11 @@ mod output_format_tests {
             function: "test_func".to_string(),
             if_else: false,
             r#match: false,
+            merge_points: false,
+            shape: None,
+            guards: false,
+            unbalanced: false,
+            threshold: None,
         };
 
         let cli = Cli {
@@ -7016,6 +13087,16 @@ mod output_format_tests {
             output: OutputFormat::Json,
             command: Some(Commands::Patterns(args.clone())),
             detect_backend: false,
+            list_error_codes: false,
+            snapshot: None,
+            max_statement_len: None,
+            max_statements_per_block: None,
+            output_file: None,
+            no_color: false,
+            quiet: false,
+            trace: false,
+            only_changed: None,
+            force: false,
         };
 
         // Verify CLI structure
@@ -7035,6 +13116,7 @@ mod output_format_tests {
                 false_branch: 3,
                 merge_point: Some(4),
                 has_else: true,
+                shape: crate::cfg::patterns::IfShape::Diamond,
             }],
             match_patterns: vec![],
         };
@@ -7049,6 +13131,77 @@ mod output_format_tests {
         assert!(json.contains("\"if_else_patterns\""));
         assert!(json.contains("\"condition_block\""));
         assert!(json.contains("\"merge_point\""));
+        assert!(json.contains("\"shape\":\"diamond\""));
+    }
+
+    /// Test patterns --merge-points reports predecessors and their common dominator
+    #[test]
+    fn test_patterns_merge_points_json() {
+        use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
+        use petgraph::graph::DiGraph;
+
+        // Diamond: 0 -> {1, 2} -> 3
+        let mut g = DiGraph::new();
+        let block = |id, kind, terminator| BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        };
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b3, EdgeType::Fallthrough);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+
+        let infos = cmds::merge_point_infos(&g);
+        assert_eq!(infos.len(), 1);
+
+        let response = MergePointsResponse {
+            function: "test_func".to_string(),
+            merge_point_count: infos.len(),
+            merge_points: infos,
+        };
+        let json: serde_json::Value = serde_json::to_value(&response).unwrap();
+
+        let join = &json["merge_points"][0];
+        assert_eq!(join["block"], 3);
+        assert_eq!(join["predecessor_count"], 2);
+        assert_eq!(join["predecessors"], serde_json::json!([1, 2]));
+        assert_eq!(join["incoming_edge_types"], serde_json::json!(["Fallthrough", "Fallthrough"]));
+        assert_eq!(join["common_dominator"], 0);
+    }
+
+    /// Test explain response exposes branches, loops and exits as JSON
+    #[test]
+    fn test_explain_response_json() {
+        use crate::cfg::explain_function;
+
+        let cfg = cmds::create_test_cfg();
+        let explanation = explain_function("test_func", &cfg);
+
+        let response = ExplanationResponse {
+            function: "test_func".to_string(),
+            summary: explanation.summary,
+            branches: explanation.branches,
+            loops: explanation.loops,
+            exits: explanation.exits,
+        };
+        let json: serde_json::Value = serde_json::to_value(&response).unwrap();
+
+        assert_eq!(json["function"], "test_func");
+        assert!(json["summary"].as_str().unwrap().starts_with("Function 'test_func' starts at b0"));
+        assert_eq!(json["branches"][0]["block"], 1);
+        assert_eq!(json["branches"][0]["kind"], "if");
+        assert_eq!(json["branches"][0]["condition"], "if x > 0");
+        assert!(json["loops"].as_array().unwrap().is_empty());
+        assert_eq!(json["exits"][0]["kind"], "early-return");
+        assert_eq!(json["exits"][1]["kind"], "return");
+        assert_eq!(json["exits"][1]["guarded_by"], 1);
     }
 }
 
@@ -7116,6 +13269,7 @@ mod frontiers_tests {
                     frontier_set: vec![3],
                 },
             ],
+            entry_blocks: None,
         };
 
         let wrapper = JsonResponse::new(response);
@@ -7127,6 +13281,62 @@ mod frontiers_tests {
         assert!(json.contains("\"frontiers\":["));
     }
 
+    /// Test block-at orders blocks by byte offset, not node order
+    #[test]
+    fn test_blocks_at_line_by_offset() {
+        use crate::cfg::{BasicBlock, BlockKind, SourceLocation, Terminator};
+        use petgraph::graph::DiGraph;
+
+        let mut g = DiGraph::new();
+        // (id, byte range, line range); block 0 is inserted first but starts later on line 3
+        for (id, bytes, lines) in [(0, (40, 60), (3, 4)), (1, (20, 45), (2, 3)), (2, (0, 10), (1, 1))] {
+            g.add_node(BasicBlock {
+                id,
+                kind: BlockKind::Normal,
+                statements: vec![],
+                terminator: Terminator::Return,
+                source_location: Some(SourceLocation::new("src/lib.rs", bytes.0, bytes.1, lines.0, 1, lines.1, 1)),
+            });
+        }
+
+        let ids = |line| -> Vec<usize> {
+            cmds::blocks_at_line_by_offset(&g, line).into_iter().map(|n| g[n].id).collect()
+        };
+        assert_eq!(ids(3), vec![1, 0]);
+        assert_eq!(ids(1), vec![2]);
+        assert!(ids(9).is_empty());
+    }
+
+    /// Test multiple entry blocks are reported only when present
+    #[test]
+    fn test_check_multiple_entry_blocks() {
+        use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
+        use clap::Parser;
+
+        let cli = Cli::parse_from(["mirage", "--output", "json", "status"]);
+        let mut cfg = cmds::create_test_cfg();
+        assert_eq!(cmds::check_multiple_entry_blocks(&cfg, &cli), None);
+
+        let b4 = cfg.add_node(BasicBlock {
+            id: 4,
+            kind: BlockKind::Normal,
+            statements: vec![],
+            terminator: Terminator::Goto { target: 3 },
+            source_location: None,
+        });
+        cfg.add_edge(b4, petgraph::graph::NodeIndex::new(3), EdgeType::Fallthrough);
+        let entry_blocks = cmds::check_multiple_entry_blocks(&cfg, &cli);
+        assert_eq!(entry_blocks, Some(vec![0, 4]));
+
+        let json = serde_json::to_value(FrontiersResponse {
+            function: "test_func".to_string(),
+            nodes_with_frontiers: 0,
+            frontiers: vec![],
+            entry_blocks,
+        }).unwrap();
+        assert_eq!(json["entry_blocks"], serde_json::json!([0, 4]));
+    }
+
     /// Test iterated frontier response struct serialization
     #[test]
     fn test_iterated_frontier_response_serialization() {
@@ -7218,6 +13428,7 @@ mod frontiers_tests {
             function: "test_func".to_string(),
             iterated: true,
             node: None,
+            post: false,
         };
 
         assert!(args.iterated);
@@ -7231,6 +13442,7 @@ mod frontiers_tests {
             function: "test_func".to_string(),
             iterated: false,
             node: Some(5),
+            post: false,
         };
 
         assert!(!args.iterated);
@@ -7364,6 +13576,7 @@ mod frontiers_tests {
                     frontier_set: vec![3],
                 },
             ],
+            entry_blocks: None,
         };
 
         let wrapper = JsonResponse::new(response);
@@ -7386,6 +13599,7 @@ mod frontiers_tests {
             function: "linear_func".to_string(),
             nodes_with_frontiers: 0,
             frontiers: vec![],
+            entry_blocks: None,
         };
 
         let wrapper = JsonResponse::new(response);
@@ -7409,6 +13623,7 @@ mod frontiers_tests {
             min_paths: Some(5),
             verbose: true,
             inter_procedural: false,
+            format: None,
         };
 
         assert_eq!(args.entry, "main");
@@ -7427,6 +13642,7 @@ mod frontiers_tests {
             min_paths: None,
             verbose: false,
             inter_procedural: false,
+            format: None,
         };
 
         assert_eq!(args.entry, "main");
@@ -7563,8 +13779,13 @@ mod frontiers_tests {
         let args = DominatorsArgs {
             function: "main".to_string(),
             must_pass_through: Some("block1".to_string()),
+            must_pass_through_line: None,
+            strict: false,
             post: false,
             inter_procedural: true,
+            format: DominatorsFormat::Tree,
+            with_cfg: false,
+            max_depth: None,
         };
 
         assert!(args.inter_procedural);
@@ -7579,8 +13800,13 @@ mod frontiers_tests {
         let args = DominatorsArgs {
             function: "main".to_string(),
             must_pass_through: None,
+            must_pass_through_line: None,
+            strict: false,
             post: false,
             inter_procedural: false,  // default
+            format: DominatorsFormat::Tree,
+            with_cfg: false,
+            max_depth: None,
         };
 
         assert!(!args.inter_procedural);
@@ -7595,8 +13821,13 @@ mod frontiers_tests {
         let args = DominatorsArgs {
             function: "entry".to_string(),
             must_pass_through: None,
+            must_pass_through_line: None,
+            strict: false,
             post: true,
             inter_procedural: true,
+            format: DominatorsFormat::Tree,
+            with_cfg: false,
+            max_depth: None,
         };
 
         // Both flags can be set (inter_procedural takes precedence in handler)
@@ -7611,8 +13842,13 @@ mod frontiers_tests {
         let args = DominatorsArgs {
             function: "main".to_string(),
             must_pass_through: Some("some_block".to_string()),
+            must_pass_through_line: None,
+            strict: false,
             post: false,
             inter_procedural: true,
+            format: DominatorsFormat::Tree,
+            with_cfg: false,
+            max_depth: None,
         };
 
         assert!(args.inter_procedural);
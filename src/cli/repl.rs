@@ -0,0 +1,202 @@
+//! `mirage repl`: run a sequence of query commands against one open database
+//!
+//! Each stdin line is parsed with the normal `mirage` argument parser, e.g.
+//! `paths --function foo --output json`. `--db` and `--output` default to the
+//! values `repl` was started with. The database stays open between commands
+//! (see `session`), and a command that fails or exits early only ends that
+//! command, not the session.
+
+use super::{dispatch, session, Cli, Commands, ReplArgs};
+use crate::output::{self, outln};
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches};
+use std::io::{BufRead, IsTerminal, Write};
+
+pub fn run(_args: &ReplArgs, cli: &Cli) -> anyhow::Result<()> {
+    let db_path = super::resolve_db_path(cli.db.clone())?;
+
+    session::enable();
+
+    // Open up front: fails fast on a bad path and warms the session cache
+    if let Err(e) = session::open_readonly(&db_path) {
+        session::disable();
+        if matches!(cli.output, super::OutputFormat::Json | super::OutputFormat::Pretty) {
+            let error = output::JsonError::database_open_failed(&db_path, &e);
+            let wrapper = output::JsonResponse::new(error);
+            outln!("{}", wrapper.to_json());
+        } else {
+            output::error(&format!("Failed to open database: {}", db_path));
+            output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+        }
+        output::exit(output::EXIT_DATABASE);
+    }
+
+    output::set_command_scoped_exit(true);
+    let result = read_eval_loop(&db_path, cli);
+    output::set_command_scoped_exit(false);
+    session::disable();
+    result
+}
+
+fn read_eval_loop(db_path: &str, cli: &Cli) -> anyhow::Result<()> {
+    let interactive = std::io::stdin().is_terminal();
+    let mut lines = std::io::stdin().lock().lines();
+
+    loop {
+        if interactive {
+            eprint!("mirage> ");
+            std::io::stderr().flush()?;
+        }
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        if !run_line(&line?, db_path, cli) {
+            return Ok(());
+        }
+        std::io::stdout().flush()?;
+    }
+}
+
+/// Parse and run one line; returns false when the session should end
+fn run_line(line: &str, db_path: &str, repl_cli: &Cli) -> bool {
+    let words = match split_line(line) {
+        Ok(words) => words,
+        Err(msg) => {
+            output::error(&msg);
+            return true;
+        }
+    };
+
+    match words.first().map(String::as_str) {
+        None => return true,
+        Some(word) if word.starts_with('#') => return true,
+        Some("exit") | Some("quit") => return false,
+        _ => {}
+    }
+
+    let cli = match parse_line(&words, db_path, repl_cli) {
+        Ok(cli) => cli,
+        Err(e) => {
+            // Also covers `help` and `--help`
+            let _ = e.print();
+            return true;
+        }
+    };
+
+    let Some(ref command) = cli.command else {
+        output::error("No subcommand given. Type 'help' for a list of commands");
+        return true;
+    };
+    if matches!(command, Commands::Repl(_)) {
+        output::error("Already in a REPL");
+        return true;
+    }
+    if cli.output_file.is_some() {
+        output::error("--output-file is not supported in the REPL");
+        return true;
+    }
+
+    // A CommandExit unwind has already reported its error, and a panic has
+    // been printed by the panic hook; either way the session carries on
+    if let Ok(Err(e)) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| dispatch(command, &cli))) {
+        output::error(&format!("{:#}", e));
+    }
+    true
+}
+
+/// Parse one line as `mirage` arguments, defaulting --db and --output to the REPL's
+fn parse_line(words: &[String], db_path: &str, repl_cli: &Cli) -> Result<Cli, clap::Error> {
+    let args = std::iter::once("mirage".to_string()).chain(words.iter().cloned());
+    let matches = Cli::command().try_get_matches_from(args)?;
+    let mut cli = Cli::from_arg_matches(&matches)?;
+
+    if matches.value_source("db") != Some(ValueSource::CommandLine) {
+        cli.db = Some(db_path.to_string());
+    }
+    if matches.value_source("output") != Some(ValueSource::CommandLine) {
+        cli.output = repl_cli.output;
+    }
+    Ok(cli)
+}
+
+/// Split a line into words, honouring single quotes, double quotes and backslash escapes
+fn split_line(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => match chars.next() {
+                Some(escaped) => {
+                    word.push(escaped);
+                    in_word = true;
+                }
+                None => return Err("Trailing backslash".to_string()),
+            },
+            (Some(_), c) => word.push(c),
+            (None, '\'') | (None, '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if let Some(q) = quote {
+        return Err(format!("Unterminated {} quote", q));
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::OutputFormat;
+    use clap::Parser;
+
+    #[test]
+    fn test_split_line() {
+        assert_eq!(split_line("  paths   --function foo ").unwrap(), vec!["paths", "--function", "foo"]);
+        assert_eq!(
+            split_line(r#"cfg --function "my_crate::a b" --filter 'x"y' \"z"#).unwrap(),
+            vec!["cfg", "--function", "my_crate::a b", "--filter", "x\"y", "\"z"]
+        );
+        assert_eq!(split_line(r#"blocks --function """#).unwrap(), vec!["blocks", "--function", ""]);
+        assert!(split_line("").unwrap().is_empty());
+        assert!(split_line("loops --function 'foo").is_err());
+    }
+
+    #[test]
+    fn test_parse_line_defaults_to_repl_options() {
+        let repl_cli = Cli::parse_from(["mirage", "--output", "json", "repl"]);
+        let words = |line: &str| split_line(line).unwrap();
+
+        let cli = parse_line(&words("loops --function foo"), "repl.db", &repl_cli).unwrap();
+        assert_eq!(cli.db.as_deref(), Some("repl.db"));
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(matches!(cli.command, Some(Commands::Loops(ref args)) if args.function == "foo"));
+
+        // Options given on the line win
+        let cli = parse_line(&words("loops --function foo --db other.db --output human"), "repl.db", &repl_cli).unwrap();
+        assert_eq!(cli.db.as_deref(), Some("other.db"));
+        assert_eq!(cli.output, OutputFormat::Human);
+
+        assert!(parse_line(&words("no-such-command"), "repl.db", &repl_cli).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Database reuse across the commands of one session
+//!
+//! Outside `mirage repl` every command opens its own database, exactly as
+//! `MirageDb::open`/`open_readonly` would. Once a session is enabled, the
+//! database a command opened is kept here when the command finishes (or
+//! exits early) and handed to the next command that opens the same path, so
+//! a sequence of queries pays for opening SQLite only once.
+
+use crate::output;
+use crate::storage::MirageDb;
+use std::cell::{Cell, RefCell};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+
+struct CachedDb {
+    path: PathBuf,
+    read_only: bool,
+    db: MirageDb,
+}
+
+thread_local! {
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static CACHED: RefCell<Option<CachedDb>> = const { RefCell::new(None) };
+}
+
+/// Keep opened databases between commands
+pub fn enable() {
+    ENABLED.with(|enabled| enabled.set(true));
+}
+
+/// Whether opened databases are currently kept between commands
+pub fn is_enabled() -> bool {
+    ENABLED.with(|enabled| enabled.get())
+}
+
+/// Stop reusing databases and close the cached one
+pub fn disable() {
+    ENABLED.with(|enabled| enabled.set(false));
+    CACHED.with(|cached| cached.borrow_mut().take());
+}
+
+/// A database handle that goes back to the session cache when dropped
+pub struct SessionDb {
+    inner: Option<CachedDb>,
+}
+
+impl Deref for SessionDb {
+    type Target = MirageDb;
+
+    fn deref(&self) -> &MirageDb {
+        &self.inner.as_ref().expect("session database already released").db
+    }
+}
+
+impl DerefMut for SessionDb {
+    fn deref_mut(&mut self) -> &mut MirageDb {
+        &mut self.inner.as_mut().expect("session database already released").db
+    }
+}
+
+impl Drop for SessionDb {
+    fn drop(&mut self) {
+        if ENABLED.with(|enabled| enabled.get()) {
+            let inner = self.inner.take();
+            CACHED.with(|cached| *cached.borrow_mut() = inner);
+        }
+    }
+}
+
+/// Open a database for reading and writing, reusing the session's if possible
+pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<SessionDb> {
+    open_with_mode(path.as_ref(), false)
+}
+
+/// Open a database read-only, reusing the session's if possible
+///
+/// A cached read-write connection also serves read-only requests.
+pub fn open_readonly<P: AsRef<Path>>(path: P) -> anyhow::Result<SessionDb> {
+    open_with_mode(path.as_ref(), true)
+}
+
+fn open_with_mode(path: &Path, read_only: bool) -> anyhow::Result<SessionDb> {
+    if ENABLED.with(|enabled| enabled.get()) {
+        let reused = CACHED.with(|cached| {
+            let mut slot = cached.borrow_mut();
+            match slot.as_ref() {
+                Some(c) if c.path == path && (read_only || !c.read_only) => slot.take(),
+                _ => None,
+            }
+        });
+        if let Some(inner) = reused {
+            output::set_backend(inner.db.storage().name());
+            return Ok(SessionDb { inner: Some(inner) });
+        }
+    }
+
+    let db = if read_only {
+        MirageDb::open_readonly(path)?
+    } else {
+        MirageDb::open(path)?
+    };
+    output::set_backend(db.storage().name());
+    Ok(SessionDb {
+        inner: Some(CachedDb {
+            path: path.to_path_buf(),
+            read_only,
+            db,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached_mode() -> Option<bool> {
+        CACHED.with(|cached| cached.borrow().as_ref().map(|c| c.read_only))
+    }
+
+    #[test]
+    fn test_session_reuses_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.db");
+        crate::storage::create_minimal_database(&path).unwrap();
+
+        // Disabled: nothing is kept
+        drop(open_readonly(&path).unwrap());
+        assert_eq!(cached_mode(), None);
+
+        enable();
+        drop(open_readonly(&path).unwrap());
+        assert_eq!(cached_mode(), Some(true), "Dropped handle returns to the cache");
+
+        let db = open_readonly(&path).unwrap();
+        assert_eq!(cached_mode(), None, "Open handle is taken out of the cache");
+        drop(db);
+
+        // A read-only connection cannot serve a writer; the writer replaces it
+        drop(open(&path).unwrap());
+        assert_eq!(cached_mode(), Some(false));
+        drop(open_readonly(&path).unwrap());
+        assert_eq!(cached_mode(), Some(false), "Read-write connection serves readers");
+
+        disable();
+        assert_eq!(cached_mode(), None);
+    }
+}
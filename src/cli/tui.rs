@@ -0,0 +1,505 @@
+//! `mirage tui`: browse functions and their CFGs in a terminal UI
+//!
+//! Only built with the `tui` feature. The left pane lists the database's
+//! functions and narrows them as you type after `/`. Opening a function shows
+//! its blocks as a box-drawing graph next to a detail view of the highlighted
+//! block: statements, terminator, edges and source location. Loops and
+//! dominators are computed on demand with the same analyses as the `loops`
+//! and `dominators` commands.
+
+use super::{session, Cli, OutputFormat, TuiArgs};
+use crate::cfg::export::format_terminator;
+use crate::cfg::loops::NaturalLoop;
+use crate::cfg::{detect_natural_loops, load_cfg_from_db, Cfg, DominatorTree};
+use crate::output::{self, outln};
+use crate::storage::MirageDb;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+const HELP: &str = "/ search  Enter open  Tab switch pane  1-9 successor  u predecessor  l loops  d dominators  q quit";
+
+pub fn run(_args: &TuiArgs, cli: &Cli) -> anyhow::Result<()> {
+    let db_path = super::resolve_db_path(cli.db.clone())?;
+
+    if !output::is_terminal() {
+        output::error("tui needs an interactive terminal");
+        output::exit(output::EXIT_USAGE);
+    }
+
+    let db = match session::open_readonly(&db_path) {
+        Ok(db) => db,
+        Err(e) => {
+            if matches!(cli.output, OutputFormat::Json | OutputFormat::Pretty) {
+                let error = output::JsonError::database_open_failed(&db_path, &e);
+                let wrapper = output::JsonResponse::new(error);
+                outln!("{}", wrapper.to_json());
+            } else {
+                output::error(&format!("Failed to open database: {}", db_path));
+                output::info(&format!("Hint: {}", output::open_failure_hint(&e)));
+            }
+            output::exit(output::EXIT_DATABASE);
+        }
+    };
+
+    let mut functions = db.storage().function_ids()?;
+    functions.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+    let mut app = App::new(functions);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, &db);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App, db: &MirageDb) -> anyhow::Result<()> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.handle_key(key, db) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Functions,
+    Blocks,
+}
+
+struct App {
+    /// `(function_id, name)`, sorted by name
+    functions: Vec<(i64, String)>,
+    query: String,
+    searching: bool,
+    /// Indices into `functions` that match `query`
+    matches: Vec<usize>,
+    function_list: ListState,
+    focus: Focus,
+    view: Option<FunctionView>,
+    status: String,
+}
+
+/// The opened function and what has been computed for it so far
+struct FunctionView {
+    name: String,
+    cfg: Cfg,
+    /// Nodes in block ID order, as listed in the graph pane
+    order: Vec<NodeIndex>,
+    block_list: ListState,
+    loops: Option<Vec<NaturalLoop>>,
+    dominators: Option<DominatorTree>,
+}
+
+impl App {
+    fn new(functions: Vec<(i64, String)>) -> Self {
+        let matches = filter_functions(&functions, "");
+        let mut function_list = ListState::default();
+        function_list.select((!matches.is_empty()).then_some(0));
+        Self {
+            functions,
+            query: String::new(),
+            searching: false,
+            matches,
+            function_list,
+            focus: Focus::Functions,
+            view: None,
+            status: HELP.to_string(),
+        }
+    }
+
+    /// Apply one key press; returns false when the UI should close
+    fn handle_key(&mut self, key: KeyEvent, db: &MirageDb) -> bool {
+        if self.searching {
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter => self.searching = false,
+                KeyCode::Backspace => {
+                    self.query.pop();
+                    self.refilter();
+                }
+                KeyCode::Char(c) => {
+                    self.query.push(c);
+                    self.refilter();
+                }
+                _ => {}
+            }
+            return true;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::Functions if self.view.is_some() => Focus::Blocks,
+                    _ => Focus::Functions,
+                };
+            }
+            KeyCode::Char('/') => {
+                self.focus = Focus::Functions;
+                self.searching = true;
+            }
+            _ => match self.focus {
+                Focus::Functions => self.handle_function_key(key.code, db),
+                Focus::Blocks => self.handle_block_key(key.code),
+            },
+        }
+        true
+    }
+
+    fn handle_function_key(&mut self, code: KeyCode, db: &MirageDb) {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.function_list.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.function_list.select_next(),
+            KeyCode::Enter => self.open_selected(db),
+            _ => {}
+        }
+    }
+
+    fn handle_block_key(&mut self, code: KeyCode) {
+        let Some(view) = self.view.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => view.block_list.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => view.block_list.select_next(),
+            KeyCode::Char(c @ '1'..='9') => {
+                let n = c as usize - '1' as usize;
+                match view.selected().and_then(|node| successors(&view.cfg, node).into_iter().nth(n)) {
+                    Some(target) => view.select_node(target),
+                    None => self.status = format!("No successor {}", n + 1),
+                }
+            }
+            KeyCode::Char('u') => {
+                match view.selected().and_then(|node| predecessors(&view.cfg, node).into_iter().next()) {
+                    Some(source) => view.select_node(source),
+                    None => self.status = "Block has no predecessors".to_string(),
+                }
+            }
+            KeyCode::Char('l') => {
+                view.loops = match view.loops {
+                    Some(_) => None,
+                    None => Some(detect_natural_loops(&view.cfg)),
+                };
+                if let Some(ref loops) = view.loops {
+                    self.status = format!("{} natural loop(s)", loops.len());
+                }
+            }
+            KeyCode::Char('d') => {
+                view.dominators = match view.dominators {
+                    Some(_) => None,
+                    None => {
+                        let tree = DominatorTree::new(&view.cfg);
+                        if tree.is_none() {
+                            self.status = "No entry block; dominators unavailable".to_string();
+                        }
+                        tree
+                    }
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn refilter(&mut self) {
+        self.matches = filter_functions(&self.functions, &self.query);
+        self.function_list.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    fn open_selected(&mut self, db: &MirageDb) {
+        let Some(&index) = self.function_list.selected().and_then(|i| self.matches.get(i)) else {
+            return;
+        };
+        let (function_id, ref name) = self.functions[index];
+        match load_cfg_from_db(db, function_id) {
+            Ok(cfg) if cfg.node_count() > 0 => {
+                let mut order: Vec<NodeIndex> = cfg.node_indices().collect();
+                order.sort_by_key(|&node| cfg[node].id);
+                let mut block_list = ListState::default();
+                block_list.select(Some(0));
+                self.status = format!("{}: {} blocks, {} edges", name, cfg.node_count(), cfg.edge_count());
+                self.view = Some(FunctionView {
+                    name: name.clone(),
+                    cfg,
+                    order,
+                    block_list,
+                    loops: None,
+                    dominators: None,
+                });
+                self.focus = Focus::Blocks;
+            }
+            Ok(_) => self.status = format!("{} has no CFG blocks", name),
+            Err(e) => self.status = format!("Failed to load CFG for {}: {:#}", name, e),
+        }
+    }
+}
+
+impl FunctionView {
+    fn selected(&self) -> Option<NodeIndex> {
+        self.block_list.selected().and_then(|i| self.order.get(i).copied())
+    }
+
+    fn select_node(&mut self, node: NodeIndex) {
+        self.block_list.select(self.order.iter().position(|&n| n == node));
+    }
+}
+
+/// Indices of the functions whose name contains `query`, ignoring case
+fn filter_functions(functions: &[(i64, String)], query: &str) -> Vec<usize> {
+    let query = query.to_lowercase();
+    functions
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, name))| name.to_lowercase().contains(&query))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Successors in block ID order, the order the `1`-`9` keys follow
+fn successors(cfg: &Cfg, node: NodeIndex) -> Vec<NodeIndex> {
+    let mut nodes: Vec<NodeIndex> = cfg.neighbors_directed(node, Direction::Outgoing).collect();
+    nodes.sort_by_key(|&n| cfg[n].id);
+    nodes.dedup();
+    nodes
+}
+
+fn predecessors(cfg: &Cfg, node: NodeIndex) -> Vec<NodeIndex> {
+    let mut nodes: Vec<NodeIndex> = cfg.neighbors_directed(node, Direction::Incoming).collect();
+    nodes.sort_by_key(|&n| cfg[n].id);
+    nodes.dedup();
+    nodes
+}
+
+/// One block of the graph pane: a header line, then one line per outgoing edge
+fn block_graph(cfg: &Cfg, node: NodeIndex, loops: Option<&[NaturalLoop]>) -> Vec<String> {
+    let block = &cfg[node];
+    let mut header = format!("┌─ bb{} {:?}", block.id, block.kind);
+    if loops.is_some_and(|loops| loops.iter().any(|l| l.header == node)) {
+        header.push_str("  ⟲ loop header");
+    }
+
+    let mut edges: Vec<_> = cfg.edges(node).collect();
+    edges.sort_by_key(|e| cfg[e.target()].id);
+    if edges.is_empty() {
+        return vec![header, format!("└─ {}", format_terminator(&block.terminator))];
+    }
+
+    let mut lines = vec![header];
+    for (i, edge) in edges.iter().enumerate() {
+        let branch = if i + 1 == edges.len() { "└" } else { "├" };
+        let label = match edge.weight().dot_label() {
+            "" => "─".to_string(),
+            label => label.to_string(),
+        };
+        let back = loops.is_some_and(|loops| loops.iter().any(|l| l.back_edge == (node, edge.target())));
+        lines.push(format!(
+            "{}─{}─▶ bb{}{}",
+            branch,
+            label,
+            cfg[edge.target()].id,
+            if back { "  (back edge)" } else { "" }
+        ));
+    }
+    lines
+}
+
+/// Detail pane text for one block, including any analyses that are switched on
+fn block_detail(
+    cfg: &Cfg,
+    node: NodeIndex,
+    loops: Option<&[NaturalLoop]>,
+    dominators: Option<&DominatorTree>,
+) -> Vec<String> {
+    let block = &cfg[node];
+    let ids = |nodes: &[NodeIndex]| {
+        nodes.iter().map(|&n| format!("bb{}", cfg[n].id)).collect::<Vec<_>>().join(", ")
+    };
+
+    let mut lines = vec![
+        format!("bb{} ({:?})", block.id, block.kind),
+        format!(
+            "Location: {}",
+            block.source_location.as_ref().map(|loc| loc.display()).unwrap_or_else(|| "unknown".to_string())
+        ),
+        format!("Terminator: {}", format_terminator(&block.terminator)),
+    ];
+
+    let mut edges: Vec<_> = cfg.edges(node).collect();
+    edges.sort_by_key(|e| cfg[e.target()].id);
+    if edges.is_empty() {
+        lines.push("Successors: none".to_string());
+    } else {
+        lines.push("Successors:".to_string());
+        let order = successors(cfg, node);
+        for edge in edges {
+            let key = order.iter().position(|&n| n == edge.target()).unwrap_or_default() + 1;
+            lines.push(format!("  {}) bb{} [{:?}]", key, cfg[edge.target()].id, edge.weight()));
+        }
+    }
+    let preds = predecessors(cfg, node);
+    lines.push(format!("Predecessors: {}", if preds.is_empty() { "none".to_string() } else { ids(&preds) }));
+
+    lines.push(String::new());
+    if block.statements.is_empty() {
+        lines.push("No statements".to_string());
+    } else {
+        lines.push(format!("Statements ({}):", block.statements.len()));
+        lines.extend(block.statements.iter().map(|s| format!("  {}", s)));
+    }
+
+    if let Some(loops) = loops {
+        lines.push(String::new());
+        let mut headers: Vec<NodeIndex> = loops.iter().filter(|l| l.contains(node)).map(|l| l.header).collect();
+        headers.sort_by_key(|&n| cfg[n].id);
+        if headers.is_empty() {
+            lines.push("Loops: not in any loop".to_string());
+        } else {
+            lines.push(format!("Loops: in the loops headed by {}", ids(&headers)));
+        }
+    }
+
+    if let Some(tree) = dominators {
+        lines.push(String::new());
+        if node != tree.root() && tree.immediate_dominator(node).is_none() {
+            lines.push("Dominators: unreachable from entry".to_string());
+        } else {
+            let mut chain: Vec<String> = tree.dominators(node).map(|n| format!("bb{}", cfg[n].id)).collect();
+            chain.reverse();
+            lines.push(format!("Dominator chain: {}", chain.join(" → ")));
+            let mut children = tree.children(node).to_vec();
+            children.sort_by_key(|&n| cfg[n].id);
+            lines.push(format!(
+                "Immediately dominates: {}",
+                if children.is_empty() { "none".to_string() } else { ids(&children) }
+            ));
+        }
+    }
+
+    lines
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [main, status] = Layout::vertical([Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+    let [left, right] = Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)]).areas(main);
+    let [graph, detail] = Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(right);
+
+    let focused = |pane: Focus| {
+        if app.focus == pane {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        }
+    };
+    let highlight = Style::default().add_modifier(Modifier::REVERSED);
+
+    let title = if app.searching || !app.query.is_empty() {
+        format!(" Functions /{}{} ", app.query, if app.searching { "_" } else { "" })
+    } else {
+        format!(" Functions ({}) ", app.functions.len())
+    };
+    let items: Vec<ListItem> = app.matches.iter().map(|&i| ListItem::new(app.functions[i].1.as_str())).collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title).border_style(focused(Focus::Functions)))
+        .highlight_style(highlight);
+    frame.render_stateful_widget(list, left, &mut app.function_list);
+
+    match app.view.as_mut() {
+        Some(view) => {
+            let loops = view.loops.as_deref();
+            let items: Vec<ListItem> = view
+                .order
+                .iter()
+                .map(|&node| {
+                    let lines: Vec<Line> = block_graph(&view.cfg, node, loops).into_iter().map(Line::from).collect();
+                    ListItem::new(Text::from(lines))
+                })
+                .collect();
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(format!(" {} ", view.name))
+                        .border_style(focused(Focus::Blocks)),
+                )
+                .highlight_style(highlight);
+            frame.render_stateful_widget(list, graph, &mut view.block_list);
+
+            let text = view
+                .selected()
+                .map(|node| block_detail(&view.cfg, node, view.loops.as_deref(), view.dominators.as_ref()))
+                .unwrap_or_default();
+            let paragraph = Paragraph::new(text.into_iter().map(Line::from).collect::<Vec<_>>())
+                .block(Block::default().borders(Borders::ALL).title(" Block "))
+                .wrap(Wrap { trim: false });
+            frame.render_widget(paragraph, detail);
+        }
+        None => {
+            let paragraph = Paragraph::new("Select a function and press Enter")
+                .block(Block::default().borders(Borders::ALL).title(" CFG "));
+            frame.render_widget(paragraph, right);
+        }
+    }
+
+    frame.render_widget(Paragraph::new(app.status.as_str()), status);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
+
+    fn diamond() -> Cfg {
+        let mut cfg = Cfg::new();
+        let block = |id, kind, terminator| BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        };
+        let b0 = cfg.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = cfg.add_node(block(1, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b2 = cfg.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b3 = cfg.add_node(block(3, BlockKind::Exit, Terminator::Return));
+        cfg.add_edge(b0, b2, EdgeType::FalseBranch);
+        cfg.add_edge(b0, b1, EdgeType::TrueBranch);
+        cfg.add_edge(b1, b3, EdgeType::Fallthrough);
+        cfg.add_edge(b2, b3, EdgeType::Fallthrough);
+        cfg
+    }
+
+    #[test]
+    fn test_filter_functions_ignores_case() {
+        let functions = vec![(1, "my_crate::Parse".to_string()), (2, "my_crate::emit".to_string())];
+        assert_eq!(filter_functions(&functions, ""), vec![0, 1]);
+        assert_eq!(filter_functions(&functions, "parse"), vec![0]);
+        assert_eq!(filter_functions(&functions, "MY_CRATE::E"), vec![1]);
+        assert!(filter_functions(&functions, "lex").is_empty());
+    }
+
+    #[test]
+    fn test_block_graph_and_detail() {
+        let cfg = diamond();
+        let entry = NodeIndex::new(0);
+        assert_eq!(block_graph(&cfg, entry, None), vec!["┌─ bb0 Entry", "├─T─▶ bb1", "└─F─▶ bb2"]);
+        assert_eq!(block_graph(&cfg, NodeIndex::new(3), None), vec!["┌─ bb3 Exit", "└─ return"]);
+
+        let detail = block_detail(&cfg, entry, None, None);
+        assert!(detail.contains(&"  1) bb1 [TrueBranch]".to_string()));
+        assert!(detail.contains(&"  2) bb2 [FalseBranch]".to_string()));
+        assert!(detail.contains(&"Predecessors: none".to_string()));
+
+        let tree = DominatorTree::new(&cfg).unwrap();
+        let detail = block_detail(&cfg, NodeIndex::new(3), Some(&[]), Some(&tree));
+        assert!(detail.contains(&"Predecessors: bb1, bb2".to_string()));
+        assert!(detail.contains(&"Loops: not in any loop".to_string()));
+        assert!(detail.contains(&"Dominator chain: bb0 → bb3".to_string()));
+    }
+}
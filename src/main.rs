@@ -20,6 +20,7 @@ compile_error!(
 
 use clap::Parser;
 use anyhow::Result;
+use tracing_subscriber::fmt::format::FmtSpan;
 
 mod analysis;
 mod cli;
@@ -29,7 +30,8 @@ mod output;
 mod platform;
 mod storage;
 
-use cli::{Cli, Commands};
+use cli::Cli;
+use output::outln;
 
 fn main() -> Result<()> {
     // Check platform and warn about limitations
@@ -37,18 +39,37 @@ fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
-    // Initialize tracing
+    output::set_no_color(cli.no_color);
+    output::set_quiet(cli.quiet);
+
+    // --output-file: collect the result in a temporary file, moved into place
+    // once the command succeeds
+    if let Some(ref path) = cli.output_file {
+        output::start_output_file(std::path::Path::new(path))?;
+    }
+
+    // Initialize tracing (diagnostics go to stderr, results to stdout).
+    // --trace enables the debug-level phase spans and logs each one's timing
+    // when it closes; without it those spans are filtered out at the callsite.
+    let (level, span_events) = if cli.trace {
+        (tracing::Level::DEBUG, FmtSpan::CLOSE)
+    } else {
+        (tracing::Level::WARN, FmtSpan::NONE)
+    };
     tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_ansi(output::use_color())
+        .with_span_events(span_events)
         .with_env_filter(
             tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::WARN.into()),
+                .add_directive(level.into()),
         )
         .init();
 
     // Run the appropriate command
-    run_command(cli)?;
-
-    Ok(())
+    let result = run_command(cli);
+    output::finish_output_file(result.is_ok())?;
+    result
 }
 
 fn run_command(cli: Cli) -> Result<()> {
@@ -72,35 +93,46 @@ fn run_command(cli: Cli) -> Result<()> {
                 "backend": backend_str,
                 "database": db_str,
             });
-            println!("{}", serde_json::to_string(&output)?);
+            outln!("{}", serde_json::to_string(&output)?);
         } else {
-            println!("{}", backend_str);
+            outln!("{}", backend_str);
         }
         return Ok(());
     }
 
+    // Handle --list-error-codes flag before command dispatch
+    if cli.list_error_codes {
+        match cli.output {
+            cli::OutputFormat::Json => outln!("{}", serde_json::to_string(output::ERROR_CODES)?),
+            _ => outln!("{}", serde_json::to_string_pretty(output::ERROR_CODES)?),
+        }
+        return Ok(());
+    }
+
+    // Validate --snapshot before command dispatch. Backends only serve the
+    // current index state today, so any other snapshot fails here without
+    // opening the database; once a backend supports history, handlers must
+    // open with MirageDb::open_at.
+    if let Some(id) = cli.snapshot {
+        if let Err(e) = storage::Backend::check_snapshot(sqlitegraph::SnapshotId(id)) {
+            let msg = format!("{:#}", e);
+            if matches!(cli.output, cli::OutputFormat::Json | cli::OutputFormat::Pretty) {
+                let error = output::JsonError::new("SnapshotUnavailable", &msg, output::E_INVALID_INPUT)
+                    .with_remediation("Omit --snapshot (or pass 0) to query the current index state");
+                let wrapper = output::JsonResponse::new(error);
+                outln!("{}", wrapper.to_json());
+            } else {
+                output::error(&msg);
+                output::info("Hint: Omit --snapshot (or pass 0) to query the current index state");
+            }
+            output::exit(output::EXIT_USAGE);
+        }
+    }
+
     match cli.command {
         None => {
             Err(anyhow::anyhow!("No subcommand provided. Use --help for usage information."))
         }
-        Some(ref cmd) => match cmd {
-            Commands::Status(args) => cli::cmds::status(args, &cli),
-            Commands::Paths(ref args) => cli::cmds::paths(args, &cli),
-            Commands::Cfg(ref args) => cli::cmds::cfg(args, &cli),
-            Commands::Dominators(ref args) => cli::cmds::dominators(args, &cli),
-            Commands::Loops(ref args) => cli::cmds::loops(args, &cli),
-            Commands::Unreachable(ref args) => cli::cmds::unreachable(args, &cli),
-            Commands::Patterns(ref args) => cli::cmds::patterns(args, &cli),
-            Commands::Frontiers(ref args) => cli::cmds::frontiers(args, &cli),
-            Commands::Verify(ref args) => cli::cmds::verify(args, &cli),
-            Commands::BlastZone(ref args) => cli::cmds::blast_zone(args, &cli),
-            Commands::Cycles(ref args) => cli::cmds::cycles(args, &cli),
-            Commands::Slice(ref args) => cli::cmds::slice(args, &cli),
-            Commands::Hotspots(ref args) => cli::cmds::hotspots(args, &cli),
-            Commands::Hotpaths(ref args) => cli::cmds::hotpaths(args, &cli),
-            Commands::Diff(ref args) => cli::cmds::diff(args, &cli),
-            Commands::Icfg(ref args) => cli::cmds::icfg(args, &cli),
-            Commands::Migrate(ref args) => cli::cmds::migrate(args, &cli),
-        },
+        Some(ref cmd) => cli::dispatch(cmd, &cli),
     }
 }
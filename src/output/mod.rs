@@ -1,6 +1,11 @@
 // Output formatting utilities following Magellan's patterns
 
-use std::io::IsTerminal;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+pub mod sarif;
 
 // Colors for terminal output (when supported)
 pub const RED: &str = "\x1b[0;31m";
@@ -12,53 +17,258 @@ pub const MAGENTA: &str = "\x1b[0;35m";
 pub const BOLD: &str = "\x1b[1m";
 pub const NC: &str = "\x1b[0m"; // No Color
 
-/// Check if stdout is a terminal (for color output)
+/// Set by `--no-color`
+static NO_COLOR: AtomicBool = AtomicBool::new(false);
+
+/// Set by `--quiet`
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Temporary file collecting results while `--output-file` is active
+static RESULT_FILE: Mutex<Option<ResultFile>> = Mutex::new(None);
+
+/// An `--output-file` being written: results go to `tmp`, renamed to `target` on success
+struct ResultFile {
+    target: PathBuf,
+    tmp: PathBuf,
+    file: std::io::BufWriter<std::fs::File>,
+}
+
+/// Check if results go to a terminal (for color output)
+///
+/// False while `--output-file` is active, even when stdout is a terminal.
 #[inline]
 pub fn is_terminal() -> bool {
-    std::io::stdout().is_terminal()
+    !has_output_file() && std::io::stdout().is_terminal()
+}
+
+/// Turn colored output off even on a terminal (`--no-color`)
+pub fn set_no_color(disabled: bool) {
+    NO_COLOR.store(disabled, Ordering::Relaxed);
+}
+
+/// Whether human output should use ANSI colors
+///
+/// Off with `--no-color`, when `NO_COLOR` is set to a non-empty value
+/// (<https://no-color.org>), and when stdout is not a terminal.
+pub fn use_color() -> bool {
+    color_enabled(NO_COLOR.load(Ordering::Relaxed), std::env::var_os("NO_COLOR").as_deref(), is_terminal())
+}
+
+fn color_enabled(no_color_flag: bool, no_color_env: Option<&std::ffi::OsStr>, terminal: bool) -> bool {
+    let env_disabled = matches!(no_color_env, Some(value) if !value.is_empty());
+    terminal && !no_color_flag && !env_disabled
+}
+
+/// Suppress informational output (`--quiet`)
+///
+/// Silences [`info`], [`header`], [`cmd`] and [`success`]; results,
+/// [`warn`] and [`error`] are still printed.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether informational output is suppressed
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Print an informational line on stdout, or stderr while `--output-file` is active
+///
+/// Keeps [`info`], [`header`] and [`success`] lines out of the output file,
+/// so it gets the result alone.
+fn diagnostic(line: &str) {
+    if has_output_file() {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+fn result_file() -> MutexGuard<'static, Option<ResultFile>> {
+    RESULT_FILE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Whether results currently go to an `--output-file`
+pub fn has_output_file() -> bool {
+    result_file().is_some()
+}
+
+/// Send results written through [`outln!`] and [`ResultWriter`] to `path`
+///
+/// They are collected in a temporary file next to `path`; [`finish_output_file`]
+/// moves it into place, or prints it when the command failed.
+pub fn start_output_file(path: &Path) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("--output-file must name a file: {}", path.display()))?;
+    let tmp = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    let file = std::fs::File::create(&tmp)
+        .with_context(|| format!("Failed to create {}", tmp.display()))?;
+    *result_file() = Some(ResultFile {
+        target: path.to_path_buf(),
+        tmp,
+        file: std::io::BufWriter::new(file),
+    });
+    Ok(())
+}
+
+/// Close the `--output-file` started by [`start_output_file`], if any
+///
+/// On success the temporary file is renamed over the target, so a failed or
+/// interrupted run never replaces it. On failure the target is left alone and
+/// whatever the command wrote (a JSON error, say) is copied to stdout instead.
+pub fn finish_output_file(success: bool) -> anyhow::Result<()> {
+    use anyhow::Context;
+
+    let Some(ResultFile { target, tmp, file }) = result_file().take() else {
+        return Ok(());
+    };
+    let written = file
+        .into_inner()
+        .map_err(|e| e.into_error())
+        .and_then(|file| file.sync_all());
+    if success {
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&tmp);
+            return Err(e).with_context(|| format!("Failed to write {}", target.display()));
+        }
+        return std::fs::rename(&tmp, &target)
+            .with_context(|| format!("Failed to write {}", target.display()));
+    }
+    let contents = std::fs::read(&tmp);
+    let _ = std::fs::remove_file(&tmp);
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(&contents?)?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Write part of a command's result to stdout, or to the `--output-file`
+///
+/// Panics when the write fails, as `print!` does.
+pub fn write_result(args: std::fmt::Arguments) {
+    match result_file().as_mut() {
+        Some(result) => result
+            .file
+            .write_fmt(args)
+            .unwrap_or_else(|e| panic!("failed writing to {}: {}", result.target.display(), e)),
+        None => print!("{}", args),
+    }
+}
+
+/// `print!` for command results: goes to the `--output-file` when one is active
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::output::write_result(format_args!($($arg)*))
+    };
+}
+
+/// `println!` for command results: goes to the `--output-file` when one is active
+macro_rules! outln {
+    () => {
+        $crate::output::write_result(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::write_result(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+pub(crate) use {out, outln};
+
+/// [`std::io::Write`] handle for command results, following [`write_result`]
+pub struct ResultWriter;
+
+impl Write for ResultWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match result_file().as_mut() {
+            Some(result) => result.file.write(buf),
+            None => std::io::stdout().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match result_file().as_mut() {
+            Some(result) => result.file.flush(),
+            None => std::io::stdout().flush(),
+        }
+    }
+}
+
+/// `prefix` wrapped in `color` (when `colored`), then `msg`
+fn tagged(color: &str, prefix: &str, msg: &str, colored: bool) -> String {
+    if colored {
+        format!("{}{}{} {}", color, prefix, NC, msg)
+    } else {
+        format!("{} {}", prefix, msg)
+    }
 }
 
 /// Print info message
 pub fn info(msg: &str) {
-    let color = if is_terminal() { GREEN } else { "" };
-    let reset = if is_terminal() { NC } else { "" };
-    println!("{}[INFO]{} {}", color, reset, msg);
+    if is_quiet() {
+        return;
+    }
+    diagnostic(&tagged(GREEN, "[INFO]", msg, use_color()));
 }
 
 /// Print warning message
 pub fn warn(msg: &str) {
-    let color = if is_terminal() { YELLOW } else { "" };
-    let reset = if is_terminal() { NC } else { "" };
-    eprintln!("{}[WARN]{} {}", color, reset, msg);
+    eprintln!("{}", tagged(YELLOW, "[WARN]", msg, use_color()));
 }
 
 /// Print error message
 pub fn error(msg: &str) {
-    let color = if is_terminal() { RED } else { "" };
-    let reset = if is_terminal() { NC } else { "" };
-    eprintln!("{}[ERROR]{} {}", color, reset, msg);
+    eprintln!("{}", tagged(RED, "[ERROR]", msg, use_color()));
 }
 
 /// Print success message
 pub fn success(msg: &str) {
-    let color = if is_terminal() { MAGENTA } else { "" };
-    let reset = if is_terminal() { NC } else { "" };
-    println!("{}[OK]{} {}", color, reset, msg);
+    if is_quiet() {
+        return;
+    }
+    diagnostic(&tagged(MAGENTA, "[OK]", msg, use_color()));
 }
 
 /// Print section header
 pub fn header(msg: &str) {
-    let bold = if is_terminal() { BOLD } else { "" };
-    let reset = if is_terminal() { NC } else { "" };
-    println!("{}===>{} {}", bold, reset, msg);
-    println!();
+    if is_quiet() {
+        return;
+    }
+    diagnostic(&tagged(BOLD, "===>", msg, use_color()));
+    diagnostic("");
 }
 
 /// Print command being executed
 pub fn cmd(cmd: &str) {
-    let color = if is_terminal() { CYAN } else { "" };
-    let reset = if is_terminal() { NC } else { "" };
-    eprintln!("{}[CMD]{} {}", color, reset, cmd);
+    if is_quiet() {
+        return;
+    }
+    eprintln!("{}", tagged(CYAN, "[CMD]", cmd, use_color()));
+}
+
+/// Join fields into one CSV record (RFC 4180)
+///
+/// Fields containing a comma, double quote or line break are quoted, with
+/// embedded quotes doubled. No trailing newline.
+pub fn csv_record<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
 /// Exit codes (matching Magellan's conventions)
@@ -70,22 +280,110 @@ pub const EXIT_FILE_NOT_FOUND: i32 = 4;
 pub const EXIT_VALIDATION: i32 = 5;
 pub const EXIT_NOT_FOUND: i32 = 6;
 
+/// Payload unwound by [`exit`] when exits are scoped to one command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandExit(pub i32);
+
+thread_local! {
+    static COMMAND_SCOPED_EXIT: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+    static BACKEND: std::cell::Cell<Option<&'static str>> = const { std::cell::Cell::new(None) };
+    static CAPTURED: std::cell::RefCell<Option<Vec<serde_json::Value>>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Record the storage backend of the opened database
+///
+/// Reported as `backend` in every [`JsonResponse`] built afterwards.
+pub fn set_backend(name: &'static str) {
+    BACKEND.with(|backend| backend.set(Some(name)));
+}
+
+/// Make [`exit`] end only the running command instead of the process
+///
+/// Used by `mirage repl`, which catches the resulting [`CommandExit`] so a
+/// failing command does not end the session.
+pub fn set_command_scoped_exit(enabled: bool) {
+    COMMAND_SCOPED_EXIT.with(|scoped| scoped.set(enabled));
+}
+
+/// Whether [`exit`] currently ends only the running command
+pub fn command_scoped_exit() -> bool {
+    COMMAND_SCOPED_EXIT.with(|scoped| scoped.get())
+}
+
+/// Print a JSON response to stdout, or keep its `data` while [`capture`] runs
+pub fn print_json<T: serde::Serialize>(response: &JsonResponse<T>, pretty: bool) {
+    let value = || serde_json::to_value(&response.data).unwrap_or_default();
+    if !CAPTURED.with(|captured| captured.borrow_mut().as_mut().map(|values| values.push(value())).is_some()) {
+        if pretty {
+            outln!("{}", response.to_pretty_json());
+        } else {
+            outln!("{}", response.to_json());
+        }
+    }
+}
+
+/// Print a command's text result, or keep it as a JSON string while [`capture`] runs
+pub fn print_text(text: &str) {
+    let value = || serde_json::Value::String(text.to_string());
+    if !CAPTURED.with(|captured| captured.borrow_mut().as_mut().map(|values| values.push(value())).is_some()) {
+        outln!("{}", text);
+    }
+}
+
+/// Run `f`, collecting what it emits through [`print_json`] and [`print_text`]
+///
+/// Used by `--functions-from-file` batches, which run the single-function
+/// handler in-process and report each result inside one JSON response.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, Vec<serde_json::Value>) {
+    let outer = CAPTURED.with(|captured| captured.borrow_mut().replace(Vec::new()));
+    let result = f();
+    let values = CAPTURED.with(|captured| std::mem::replace(&mut *captured.borrow_mut(), outer));
+    (result, values.unwrap_or_default())
+}
+
+/// Exit with the given code
+///
+/// Ends the process after closing any `--output-file` (see
+/// [`finish_output_file`]), unless [`set_command_scoped_exit`] is on; then it
+/// unwinds with a [`CommandExit`] payload (without running the panic hook).
+pub fn exit(code: i32) -> ! {
+    if COMMAND_SCOPED_EXIT.with(|scoped| scoped.get()) {
+        std::panic::resume_unwind(Box::new(CommandExit(code)));
+    }
+    if let Err(e) = finish_output_file(code == EXIT_SUCCESS) {
+        error(&format!("{:#}", e));
+        std::process::exit(EXIT_ERROR);
+    }
+    std::process::exit(code)
+}
+
+/// Remediation hint for a database that failed to open
+pub fn open_failure_hint(error: &anyhow::Error) -> &'static str {
+    if error.downcast_ref::<crate::storage::MirageSchemaMissing>().is_some() {
+        R_HINT_INIT_SCHEMA
+    } else if error.downcast_ref::<crate::storage::MirageSchemaOutdated>().is_some() {
+        R_HINT_UPGRADE_SCHEMA
+    } else {
+        R_HINT_INDEX
+    }
+}
+
 /// Exit with usage error
 pub fn exit_usage(msg: &str) -> ! {
     error(msg);
-    std::process::exit(EXIT_USAGE);
+    exit(EXIT_USAGE);
 }
 
 /// Exit with file not found error
 pub fn exit_file_not_found(path: &str) -> ! {
     error(&format!("File not found: {}", path));
-    std::process::exit(EXIT_FILE_NOT_FOUND);
+    exit(EXIT_FILE_NOT_FOUND);
 }
 
 /// Exit with database error
 pub fn exit_database(msg: &str) -> ! {
     error(&format!("Database error: {}", msg));
-    std::process::exit(EXIT_DATABASE);
+    exit(EXIT_DATABASE);
 }
 
 // ============================================================================
@@ -100,10 +398,101 @@ pub const E_PATH_NOT_FOUND: &str = "E004";
 pub const E_PATH_EXPLOSION: &str = "E005";
 pub const E_INVALID_INPUT: &str = "E006";
 pub const E_CFG_ERROR: &str = "E007";
+pub const E_FUNCTION_UNCHANGED: &str = "E008";
+pub const E_SCHEMA_MISSING: &str = "E009";
+pub const E_IO_ERROR: &str = "E010";
+pub const E_DATABASE_WRITE: &str = "E011";
+
+/// An error code and the process exit code it is reported with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct ErrorCodeInfo {
+    /// Error code string, as in `JsonError::code`
+    pub code: &'static str,
+    /// Name of the constant defining the code
+    pub name: &'static str,
+    /// Process exit status used alongside this code
+    pub exit_code: i32,
+    pub description: &'static str,
+}
+
+/// Exit-code contract: every error code with its exit status
+///
+/// Listed by `mirage --list-error-codes` so scripts can map exit codes to
+/// remediation without parsing stderr. Add new `E_*` codes here too.
+pub const ERROR_CODES: &[ErrorCodeInfo] = &[
+    ErrorCodeInfo {
+        code: E_DATABASE_NOT_FOUND,
+        name: "E_DATABASE_NOT_FOUND",
+        exit_code: EXIT_DATABASE,
+        description: "Database missing, unreadable or not a Magellan/Mirage database",
+    },
+    ErrorCodeInfo {
+        code: E_FUNCTION_NOT_FOUND,
+        name: "E_FUNCTION_NOT_FOUND",
+        exit_code: EXIT_DATABASE,
+        description: "No function with this name or ID is indexed",
+    },
+    ErrorCodeInfo {
+        code: E_BLOCK_NOT_FOUND,
+        name: "E_BLOCK_NOT_FOUND",
+        exit_code: EXIT_ERROR,
+        description: "Block ID (or source line) does not exist in the function's CFG",
+    },
+    ErrorCodeInfo {
+        code: E_PATH_NOT_FOUND,
+        name: "E_PATH_NOT_FOUND",
+        exit_code: EXIT_FILE_NOT_FOUND,
+        description: "Path ID is not in the path cache",
+    },
+    ErrorCodeInfo {
+        code: E_PATH_EXPLOSION,
+        name: "E_PATH_EXPLOSION",
+        exit_code: EXIT_ERROR,
+        description: "Path enumeration exceeded its limits",
+    },
+    ErrorCodeInfo {
+        code: E_INVALID_INPUT,
+        name: "E_INVALID_INPUT",
+        exit_code: EXIT_USAGE,
+        description: "Invalid argument or unsupported combination of options",
+    },
+    ErrorCodeInfo {
+        code: E_CFG_ERROR,
+        name: "E_CFG_ERROR",
+        exit_code: EXIT_DATABASE,
+        description: "Stored CFG data could not be loaded or analyzed",
+    },
+    ErrorCodeInfo {
+        code: E_FUNCTION_UNCHANGED,
+        name: "E_FUNCTION_UNCHANGED",
+        exit_code: EXIT_VALIDATION,
+        description: "Function is unchanged relative to the --only-changed baseline",
+    },
+    ErrorCodeInfo {
+        code: E_SCHEMA_MISSING,
+        name: "E_SCHEMA_MISSING",
+        exit_code: EXIT_DATABASE,
+        description: "Magellan database without Mirage's tables; run 'mirage init-schema'",
+    },
+    ErrorCodeInfo {
+        code: E_IO_ERROR,
+        name: "E_IO_ERROR",
+        exit_code: EXIT_ERROR,
+        description: "Input or output file could not be read or written",
+    },
+    ErrorCodeInfo {
+        code: E_DATABASE_WRITE,
+        name: "E_DATABASE_WRITE",
+        exit_code: EXIT_DATABASE,
+        description: "Database could not be created or written",
+    },
+];
 
 /// Common remediation messages
 pub const R_HINT_INDEX: &str = "Run 'magellan watch' to create the database";
-pub const R_HINT_LIST_FUNCTIONS: &str = "Run 'mirage cfg --list-functions' to see available functions";
+pub const R_HINT_INIT_SCHEMA: &str = "Run 'mirage init-schema' to add Mirage's tables, or 'magellan watch' to index CFGs";
+pub const R_HINT_UPGRADE_SCHEMA: &str = "Run 'mirage init-schema' to upgrade Mirage's tables; read-only commands never change the schema";
+pub const R_HINT_LIST_FUNCTIONS: &str = "Run 'mirage functions' to see available functions";
 pub const R_HINT_MAX_LENGTH: &str = "Use --max-length N to bound path exploration";
 pub const R_HINT_VERIFY_PATH: &str = "Run 'mirage verify --list' to see valid paths";
 
@@ -113,6 +502,11 @@ pub struct JsonResponse<T> {
     pub schema_version: String,
     pub execution_id: String,
     pub tool: String,
+    /// Version of the mirage binary that produced the response
+    pub mirage_version: String,
+    /// Storage backend of the database, if one was opened
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
     pub timestamp: String,
     pub data: T,
 }
@@ -131,6 +525,8 @@ impl<T: serde::Serialize> JsonResponse<T> {
             schema_version: "1.0.1".to_string(),
             execution_id: exec_id,
             tool: "mirage".to_string(),
+            mirage_version: env!("CARGO_PKG_VERSION").to_string(),
+            backend: BACKEND.with(|backend| backend.get()).map(str::to_string),
             timestamp,
             data,
         }
@@ -143,6 +539,41 @@ impl<T: serde::Serialize> JsonResponse<T> {
     pub fn to_pretty_json(&self) -> String {
         serde_json::to_string_pretty(self).unwrap_or_default()
     }
+
+    /// Serialize directly to a writer instead of building a String
+    pub fn write_to<W: std::io::Write>(&self, writer: W, pretty: bool) -> serde_json::Result<()> {
+        if pretty {
+            serde_json::to_writer_pretty(writer, self)
+        } else {
+            serde_json::to_writer(writer, self)
+        }
+    }
+}
+
+/// Serializes an iterator as a JSON array, one element at a time
+///
+/// Used with [`JsonResponse::write_to`] to stream large results without
+/// collecting them first. The iterator is consumed by the first serialization;
+/// serializing again yields an empty array.
+pub struct JsonSeq<I>(std::cell::RefCell<Option<I>>);
+
+impl<I> JsonSeq<I> {
+    pub fn new(iter: I) -> Self {
+        JsonSeq(std::cell::RefCell::new(Some(iter)))
+    }
+}
+
+impl<I, T> serde::Serialize for JsonSeq<I>
+where
+    I: Iterator<Item = T>,
+    T: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.borrow_mut().take() {
+            Some(iter) => serializer.collect_seq(iter),
+            None => serializer.collect_seq(std::iter::empty::<T>()),
+        }
+    }
 }
 
 /// Error response format for JSON mode
@@ -179,6 +610,26 @@ impl JsonError {
         ).with_remediation(R_HINT_INDEX)
     }
 
+    /// Error for a database that failed to open
+    ///
+    /// A Magellan database without Mirage's tables, or with tables a read-only
+    /// open cannot upgrade, gets its own code; anything else is reported as
+    /// [`JsonError::database_not_found`].
+    pub fn database_open_failed(path: &str, error: &anyhow::Error) -> Self {
+        if error.downcast_ref::<crate::storage::MirageSchemaMissing>().is_some() {
+            Self::new(
+                "SchemaMissing",
+                &format!("Mirage schema not found in Magellan database: {}", path),
+                E_SCHEMA_MISSING
+            ).with_remediation(R_HINT_INIT_SCHEMA)
+        } else if let Some(outdated) = error.downcast_ref::<crate::storage::MirageSchemaOutdated>() {
+            Self::new("SchemaOutdated", &outdated.to_string(), E_SCHEMA_MISSING)
+                .with_remediation(R_HINT_UPGRADE_SCHEMA)
+        } else {
+            Self::database_not_found(path)
+        }
+    }
+
     /// Function not found error with remediation
     pub fn function_not_found(name: &str) -> Self {
         Self::new(
@@ -207,10 +658,90 @@ impl JsonError {
     }
 }
 
+/// Limits on block statements emitted in command output
+///
+/// Both limits default to `None` (no truncation).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatementLimits {
+    /// Maximum characters per statement, including the trailing ellipsis
+    pub max_len: Option<usize>,
+    /// Maximum statements per block; the rest collapse to a "... (k more)" marker
+    pub max_per_block: Option<usize>,
+}
+
+impl StatementLimits {
+    /// Apply both limits to one block's statements
+    pub fn apply(&self, statements: &[String]) -> Vec<String> {
+        let shown = self.max_per_block.unwrap_or(usize::MAX).min(statements.len());
+        let mut out: Vec<String> = statements[..shown].iter().map(|s| self.truncate(s)).collect();
+        if shown < statements.len() {
+            out.push(format!("... ({} more)", statements.len() - shown));
+        }
+        out
+    }
+
+    fn truncate(&self, statement: &str) -> String {
+        match self.max_len {
+            Some(max) if statement.chars().count() > max => {
+                let mut truncated: String = statement.chars().take(max.saturating_sub(1)).collect();
+                truncated.push('…');
+                truncated
+            }
+            _ => statement.to_string(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_statement_limits() {
+        let statements: Vec<String> = vec![
+            "let x = compute_something_long(a, b, c)".to_string(),
+            "y = x".to_string(),
+            "z = y + 1".to_string(),
+        ];
+
+        // Default leaves statements untouched
+        assert_eq!(StatementLimits::default().apply(&statements), statements);
+
+        let limits = StatementLimits { max_len: Some(10), max_per_block: Some(2) };
+        let out = limits.apply(&statements);
+        assert_eq!(out, vec!["let x = c…", "y = x", "... (1 more)"]);
+        assert!(out[..2].iter().all(|s| s.chars().count() <= 10));
+
+        // Multi-byte characters are counted, not bytes
+        let limits = StatementLimits { max_len: Some(3), max_per_block: None };
+        assert_eq!(limits.apply(&["ééééé".to_string()]), vec!["éé…"]);
+    }
+
+    #[test]
+    fn test_no_color_output_has_no_escapes() {
+        assert!(!color_enabled(true, None, true), "--no-color wins on a terminal");
+        assert!(!color_enabled(false, Some("1".as_ref()), true), "NO_COLOR disables color");
+        assert!(color_enabled(false, Some("".as_ref()), true), "Empty NO_COLOR is ignored");
+        assert!(!color_enabled(false, None, false), "Non-terminal stdout disables color");
+        assert!(color_enabled(false, None, true));
+
+        assert_eq!(tagged(GREEN, "[INFO]", "msg", true), "\x1b[0;32m[INFO]\x1b[0m msg");
+        set_no_color(true);
+        assert!(!use_color());
+        for (color, prefix) in [(GREEN, "[INFO]"), (YELLOW, "[WARN]"), (RED, "[ERROR]"), (BOLD, "===>")] {
+            let line = tagged(color, prefix, "msg", use_color());
+            assert!(!line.contains('\x1b'), "{:?} contains an escape", line);
+            assert_eq!(line, format!("{} msg", prefix));
+        }
+        set_no_color(false);
+    }
+
+    #[test]
+    fn test_csv_record_quotes_when_needed() {
+        assert_eq!(csv_record(&["a", "b c", ""]), "a,b c,");
+        assert_eq!(csv_record(&["x,y", "say \"hi\"", "two\nlines"]), "\"x,y\",\"say \"\"hi\"\"\",\"two\nlines\"");
+    }
+
     #[test]
     fn test_json_response() {
         let data = vec!["item1", "item2"];
@@ -218,5 +749,46 @@ mod tests {
         let json = response.to_json();
         assert!(json.contains("\"tool\":\"mirage\""));
         assert!(json.contains("\"data\":[\"item1\",\"item2\"]"));
+        assert!(json.contains(&format!("\"mirage_version\":\"{}\"", env!("CARGO_PKG_VERSION"))));
+    }
+
+    #[test]
+    fn test_json_response_reports_backend() {
+        assert_eq!(JsonResponse::new(()).backend, None);
+        set_backend("sqlite");
+        let json = JsonResponse::new(()).to_json();
+        assert!(json.contains("\"backend\":\"sqlite\""));
+    }
+
+    #[test]
+    fn test_command_scoped_exit_unwinds() {
+        set_command_scoped_exit(true);
+        let result = std::panic::catch_unwind(|| exit(EXIT_DATABASE));
+        set_command_scoped_exit(false);
+
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<CommandExit>(), Some(&CommandExit(EXIT_DATABASE)));
+    }
+
+    #[test]
+    fn test_capture_collects_response_data() {
+        let ((), values) = capture(|| {
+            print_json(&JsonResponse::new(vec![1, 2]), false);
+            print_text("digraph {}");
+        });
+        assert_eq!(values, vec![serde_json::json!([1, 2]), serde_json::json!("digraph {}")]);
+        assert!(CAPTURED.with(|captured| captured.borrow().is_none()), "Capture ends with the closure");
+    }
+
+    #[test]
+    fn test_json_seq_streams_array() {
+        let response = JsonResponse::new(JsonSeq::new((1..=3).map(|n| n * 10)));
+        let mut buf = Vec::new();
+        response.write_to(&mut buf, false).unwrap();
+
+        let json = String::from_utf8(buf).unwrap();
+        assert!(json.contains("\"data\":[10,20,30]"));
+        // The iterator is consumed by the first write
+        assert!(response.to_json().contains("\"data\":[]"));
     }
 }
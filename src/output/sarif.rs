@@ -0,0 +1,264 @@
+// SARIF 2.1.0 output for static-analysis consumers (e.g. GitHub code scanning)
+
+use crate::cfg::SourceLocation;
+use serde::Serialize;
+
+/// JSON schema URI for SARIF 2.1.0
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF format version
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// Rule ID reported for unreachable blocks
+pub const RULE_UNREACHABLE_BLOCK: &str = "mirage/unreachable-block";
+
+/// An unreachable block to report as a SARIF result
+#[derive(Debug, Clone)]
+pub struct UnreachableFinding {
+    pub function: String,
+    pub block_id: usize,
+    pub statements: Vec<String>,
+    pub terminator: String,
+    pub location: Option<SourceLocation>,
+}
+
+/// Top-level SARIF log
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: String,
+    pub version: String,
+    pub runs: Vec<SarifRun>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifRun {
+    pub tool: SarifTool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifTool {
+    pub driver: SarifDriver,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifDriver {
+    pub name: String,
+    pub version: String,
+    pub information_uri: String,
+    pub rules: Vec<SarifRule>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+    pub id: String,
+    pub short_description: SarifMessage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifMessage {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub level: String,
+    pub message: SarifMessage,
+    pub locations: Vec<SarifLocation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub physical_location: Option<SarifPhysicalLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub logical_locations: Vec<SarifLogicalLocation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation {
+    pub artifact_location: SarifArtifactLocation,
+    pub region: SarifRegion,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SarifArtifactLocation {
+    pub uri: String,
+}
+
+/// Source region; line/column when known, otherwise a byte range
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_offset: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_length: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLogicalLocation {
+    pub fully_qualified_name: String,
+    pub kind: String,
+}
+
+impl SarifLog {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn to_pretty_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Build a SARIF log with one result per unreachable block
+///
+/// Blocks with a source location get a `physicalLocation`; blocks without
+/// one are reported with only a `logicalLocation` naming the function.
+pub fn unreachable_sarif(findings: &[UnreachableFinding]) -> SarifLog {
+    let results = findings.iter().map(|finding| {
+        let detail = if finding.statements.is_empty() {
+            format!("terminator: {}", finding.terminator)
+        } else {
+            finding.statements.join("; ")
+        };
+
+        SarifResult {
+            rule_id: RULE_UNREACHABLE_BLOCK.to_string(),
+            level: "warning".to_string(),
+            message: SarifMessage {
+                text: format!(
+                    "Block {} in '{}' is unreachable: {}",
+                    finding.block_id, finding.function, detail
+                ),
+            },
+            locations: vec![match &finding.location {
+                Some(loc) => SarifLocation {
+                    physical_location: Some(physical_location(loc)),
+                    logical_locations: Vec::new(),
+                },
+                None => SarifLocation {
+                    physical_location: None,
+                    logical_locations: vec![SarifLogicalLocation {
+                        fully_qualified_name: finding.function.clone(),
+                        kind: "function".to_string(),
+                    }],
+                },
+            }],
+        }
+    }).collect();
+
+    SarifLog {
+        schema: SARIF_SCHEMA.to_string(),
+        version: SARIF_VERSION.to_string(),
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: "mirage".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    information_uri: env!("CARGO_PKG_REPOSITORY").to_string(),
+                    rules: vec![SarifRule {
+                        id: RULE_UNREACHABLE_BLOCK.to_string(),
+                        short_description: SarifMessage {
+                            text: "Basic block is not reachable from the function entry".to_string(),
+                        },
+                    }],
+                },
+            },
+            results,
+        }],
+    }
+}
+
+fn physical_location(loc: &SourceLocation) -> SarifPhysicalLocation {
+    let region = if loc.start_line > 0 {
+        SarifRegion {
+            start_line: Some(loc.start_line),
+            start_column: Some(loc.start_column),
+            end_line: Some(loc.end_line),
+            end_column: Some(loc.end_column),
+            byte_offset: None,
+            byte_length: None,
+        }
+    } else {
+        SarifRegion {
+            start_line: None,
+            start_column: None,
+            end_line: None,
+            end_column: None,
+            byte_offset: Some(loc.byte_start),
+            byte_length: Some(loc.byte_end.saturating_sub(loc.byte_start)),
+        }
+    };
+
+    SarifPhysicalLocation {
+        artifact_location: SarifArtifactLocation {
+            uri: loc.file_path.to_string_lossy().replace('\\', "/"),
+        },
+        region,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unreachable_sarif_structure() {
+        let findings = vec![
+            UnreachableFinding {
+                function: "my_crate::f".to_string(),
+                block_id: 3,
+                statements: vec!["x = 1".to_string()],
+                terminator: "Return".to_string(),
+                location: Some(SourceLocation::new("src/lib.rs", 10, 20, 4, 5, 4, 15)),
+            },
+            UnreachableFinding {
+                function: "my_crate::g".to_string(),
+                block_id: 7,
+                statements: vec![],
+                terminator: "Unreachable".to_string(),
+                location: None,
+            },
+        ];
+
+        let json: serde_json::Value = serde_json::from_str(&unreachable_sarif(&findings).to_json()).unwrap();
+
+        assert_eq!(json["$schema"], SARIF_SCHEMA);
+        assert_eq!(json["version"], "2.1.0");
+        assert_eq!(json["runs"][0]["tool"]["driver"]["name"], "mirage");
+        assert_eq!(json["runs"][0]["tool"]["driver"]["version"], env!("CARGO_PKG_VERSION"));
+
+        let results = json["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], RULE_UNREACHABLE_BLOCK);
+        assert!(results[0]["message"]["text"].as_str().unwrap().contains("x = 1"));
+
+        let physical = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(physical["artifactLocation"]["uri"], "src/lib.rs");
+        assert_eq!(physical["region"]["startLine"], 4);
+        assert!(results[0]["locations"][0].get("logicalLocations").is_none());
+
+        // No source location: logical location only
+        let location = &results[1]["locations"][0];
+        assert!(location.get("physicalLocation").is_none());
+        assert_eq!(location["logicalLocations"][0]["fullyQualifiedName"], "my_crate::g");
+    }
+}
@@ -0,0 +1,183 @@
+//! Changed-function detection against a baseline database
+//!
+//! Used by `--only-changed <baseline_db>` to scope commands to functions that
+//! differ from a baseline index (e.g. the target branch of a PR). Functions are
+//! matched across databases by `(file_path, name)` since entity IDs are not
+//! stable between separate indexing runs.
+//!
+//! A function is "changed" if its stored `function_hash` differs from the
+//! baseline, or if it is absent from the baseline. Databases whose cfg_blocks
+//! table has no `function_hash` column (Magellan's schema) fall back to a
+//! BLAKE3 digest of the function's block kinds and terminators.
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags, params};
+use std::collections::HashMap;
+
+/// Open a baseline database read-only
+pub fn open_baseline(path: &str) -> Result<Connection> {
+    if !std::path::Path::new(path).exists() {
+        anyhow::bail!("Baseline database not found: {}", path);
+    }
+    Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Failed to open baseline database: {}", path))
+}
+
+/// Find functions in `conn` that are new or changed relative to `baseline`
+///
+/// Only functions with CFG blocks are considered. Returns function IDs from
+/// `conn`, sorted ascending.
+pub fn find_changed_functions(conn: &Connection, baseline: &Connection) -> Result<Vec<i64>> {
+    let current = function_hashes(conn).context("Failed to read current function hashes")?;
+    let baseline = function_hashes(baseline).context("Failed to read baseline function hashes")?;
+
+    let mut changed: Vec<i64> = current
+        .iter()
+        .filter(|(key, (_, hash))| match baseline.get(*key) {
+            Some((_, baseline_hash)) => hash != baseline_hash,
+            None => true,
+        })
+        .map(|(_, (id, _))| *id)
+        .collect();
+    changed.sort_unstable();
+
+    Ok(changed)
+}
+
+/// Map `(file_path, name)` to `(function_id, hash)` for every function with CFG blocks
+fn function_hashes(conn: &Connection) -> Result<HashMap<(String, String), (i64, String)>> {
+    let has_hash_column: bool = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('cfg_blocks') WHERE name = 'function_hash'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, name, COALESCE(file_path, '') FROM graph_entities
+         WHERE id IN (SELECT DISTINCT function_id FROM cfg_blocks)",
+    )?;
+    let functions = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut hashes = HashMap::new();
+    for (id, name, file_path) in functions {
+        let hash = if has_hash_column {
+            conn.query_row(
+                "SELECT function_hash FROM cfg_blocks WHERE function_id = ? LIMIT 1",
+                params![id],
+                |row| row.get::<_, Option<String>>(0),
+            )?
+        } else {
+            None
+        };
+
+        let hash = match hash {
+            Some(hash) => hash,
+            None => structural_hash(conn, id)?,
+        };
+        hashes.insert((file_path, name), (id, hash));
+    }
+
+    Ok(hashes)
+}
+
+/// BLAKE3 digest of a function's block kinds and terminators in block order
+fn structural_hash(conn: &Connection, function_id: i64) -> Result<String> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT kind, terminator FROM cfg_blocks WHERE function_id = ? ORDER BY id",
+    )?;
+    let rows = stmt.query_map(params![function_id], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+
+    let mut hasher = blake3::Hasher::new();
+    for row in rows {
+        let (kind, terminator) = row?;
+        hasher.update(kind.as_bytes());
+        hasher.update(b"\t");
+        hasher.update(terminator.as_bytes());
+        hasher.update(b"\n");
+    }
+
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal Magellan-style database with the given (name, file, block terminators)
+    fn create_db(functions: &[(&str, &str, &[&str])]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE graph_entities (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                file_path TEXT,
+                data TEXT NOT NULL
+            );
+            CREATE TABLE cfg_blocks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                function_id INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                terminator TEXT NOT NULL
+            );",
+        ).unwrap();
+
+        for (name, file, terminators) in functions {
+            conn.execute(
+                "INSERT INTO graph_entities (kind, name, file_path, data) VALUES ('Symbol', ?, ?, '{}')",
+                params![name, file],
+            ).unwrap();
+            let id = conn.last_insert_rowid();
+            for terminator in terminators.iter() {
+                conn.execute(
+                    "INSERT INTO cfg_blocks (function_id, kind, terminator) VALUES (?, 'block', ?)",
+                    params![id, terminator],
+                ).unwrap();
+            }
+        }
+
+        conn
+    }
+
+    #[test]
+    fn test_find_changed_functions_structural() {
+        let baseline = create_db(&[
+            ("same", "lib.rs", &["goto", "return"]),
+            ("edited", "lib.rs", &["return"]),
+        ]);
+        let current = create_db(&[
+            ("added", "lib.rs", &["return"]),
+            ("same", "lib.rs", &["goto", "return"]),
+            ("edited", "lib.rs", &["conditional", "return", "return"]),
+        ]);
+
+        // IDs in current: added = 1, same = 2, edited = 3
+        assert_eq!(find_changed_functions(&current, &baseline).unwrap(), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_find_changed_functions_uses_function_hash() {
+        let baseline = create_db(&[("f", "lib.rs", &["return"])]);
+        let current = create_db(&[("f", "lib.rs", &["return"])]);
+        for conn in [&baseline, &current] {
+            conn.execute("ALTER TABLE cfg_blocks ADD COLUMN function_hash TEXT", []).unwrap();
+        }
+        baseline.execute("UPDATE cfg_blocks SET function_hash = 'old'", []).unwrap();
+        current.execute("UPDATE cfg_blocks SET function_hash = 'new'", []).unwrap();
+
+        // Same structure, but the stored hash differs
+        assert_eq!(find_changed_functions(&current, &baseline).unwrap(), vec![1]);
+
+        current.execute("UPDATE cfg_blocks SET function_hash = 'old'", []).unwrap();
+        assert!(find_changed_functions(&current, &baseline).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_open_baseline_missing_file() {
+        assert!(open_baseline("/nonexistent/baseline.db").is_err());
+    }
+}
@@ -0,0 +1,265 @@
+//! Dominator tree caching keyed by function hash
+//!
+//! Immediate dominators are stored in `cfg_dominators`, one row per reachable
+//! non-root block plus a reflexive row for the root. Every row carries the
+//! `function_hash` it was computed from, so a cached tree is only returned
+//! when the function content is unchanged.
+
+use anyhow::{Context, Result};
+use petgraph::graph::NodeIndex;
+use rusqlite::{Connection, params};
+use std::collections::HashMap;
+
+use crate::cfg::dominators::DominatorTree;
+use crate::cfg::Cfg;
+
+/// Store a dominator tree in the database, replacing any previous entry
+///
+/// # Arguments
+///
+/// * `conn` - Database connection
+/// * `function_id` - ID of the function the tree belongs to
+/// * `function_hash` - Hash of the function content the tree was computed from
+/// * `cfg` - CFG the tree was computed from (used to map nodes to block IDs)
+/// * `tree` - Dominator tree to store
+///
+/// # Transactions
+///
+/// Uses IMMEDIATE transaction mode, like `store_paths`.
+pub fn store_dominators(
+    conn: &mut Connection,
+    function_id: i64,
+    function_hash: &str,
+    cfg: &Cfg,
+    tree: &DominatorTree,
+) -> Result<()> {
+    conn.execute("BEGIN IMMEDIATE TRANSACTION", [])
+        .context("Failed to begin transaction for store_dominators")?;
+
+    conn.execute(
+        "DELETE FROM cfg_dominators WHERE function_id = ?1",
+        params![function_id],
+    ).context("Failed to clear existing cfg_dominators")?;
+
+    insert_dominator_rows(conn, function_id, function_hash, cfg, tree)?;
+
+    conn.execute("COMMIT", [])
+        .context("Failed to commit transaction for store_dominators")?;
+
+    Ok(())
+}
+
+/// Insert dominator rows without managing a transaction
+///
+/// Shared by `store_dominators` and `store_cfg`, which already holds a
+/// transaction when it persists the dominator tree alongside the blocks.
+pub(crate) fn insert_dominator_rows(
+    conn: &Connection,
+    function_id: i64,
+    function_hash: &str,
+    cfg: &Cfg,
+    tree: &DominatorTree,
+) -> Result<()> {
+    let mut insert_stmt = conn.prepare_cached(
+        "INSERT INTO cfg_dominators (function_id, block_id, dominator_id, is_strict, function_hash)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    ).context("Failed to prepare cfg_dominators insert statement")?;
+
+    // The root dominates itself; store it as the only non-strict row so the
+    // root survives a round trip even for a single-block CFG
+    let root_id = cfg[tree.root()].id as i64;
+    insert_stmt.execute(params![function_id, root_id, root_id, false, function_hash])
+        .context("Failed to insert root dominator row")?;
+
+    for node in cfg.node_indices() {
+        if let Some(idom) = tree.immediate_dominator(node) {
+            insert_stmt.execute(params![
+                function_id,
+                cfg[node].id as i64,
+                cfg[idom].id as i64,
+                true,
+                function_hash,
+            ]).with_context(|| format!("Failed to insert dominator row for block {}", cfg[node].id))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Load a cached dominator tree from the database
+///
+/// Returns `Ok(None)` on a cache miss: no rows for the function, rows
+/// computed from a different `function_hash`, or rows referring to blocks
+/// that are no longer in `cfg`. Callers fall back to computing the tree.
+pub fn load_dominators_from_db(
+    conn: &Connection,
+    function_id: i64,
+    function_hash: &str,
+    cfg: &Cfg,
+) -> Result<Option<DominatorTree>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT block_id, dominator_id, is_strict, function_hash
+         FROM cfg_dominators
+         WHERE function_id = ?1",
+    ).context("Failed to prepare cfg_dominators query")?;
+
+    let rows = stmt.query_map(params![function_id], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, bool>(2)?,
+            row.get::<_, Option<String>>(3)?,
+        ))
+    }).context("Failed to query cfg_dominators")?;
+
+    let node_by_id: HashMap<i64, NodeIndex> = cfg.node_indices()
+        .map(|n| (cfg[n].id as i64, n))
+        .collect();
+
+    let mut root = None;
+    let mut immediate_dominator: HashMap<NodeIndex, Option<NodeIndex>> =
+        cfg.node_indices().map(|n| (n, None)).collect();
+
+    for row in rows {
+        let (block_id, dominator_id, is_strict, hash) =
+            row.context("Failed to read cfg_dominators row")?;
+
+        if hash.as_deref() != Some(function_hash) {
+            return Ok(None);
+        }
+
+        let (Some(&node), Some(&dominator)) = (node_by_id.get(&block_id), node_by_id.get(&dominator_id)) else {
+            return Ok(None);
+        };
+
+        if is_strict {
+            immediate_dominator.insert(node, Some(dominator));
+        } else {
+            root = Some(node);
+        }
+    }
+
+    Ok(root.map(|root| DominatorTree::from_immediate_dominators(root, immediate_dominator)))
+}
+
+/// Invalidate the cached dominator tree for a function
+pub fn invalidate_function_dominators(conn: &Connection, function_id: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM cfg_dominators WHERE function_id = ?1",
+        params![function_id],
+    ).context("Failed to delete cfg_dominators")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{BasicBlock, BlockKind, EdgeType, Terminator};
+    use petgraph::graph::DiGraph;
+
+    fn create_test_db() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        conn.execute(
+            "CREATE TABLE magellan_meta (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                magellan_schema_version INTEGER NOT NULL,
+                sqlitegraph_schema_version INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        ).unwrap();
+
+        conn.execute(
+            "CREATE TABLE graph_entities (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                file_path TEXT,
+                data TEXT NOT NULL
+            )",
+            [],
+        ).unwrap();
+
+        conn.execute(
+            "INSERT INTO magellan_meta (id, magellan_schema_version, sqlitegraph_schema_version, created_at)
+             VALUES (1, 7, 3, 0)",
+            [],
+        ).unwrap();
+
+        crate::storage::create_schema(&mut conn, crate::storage::TEST_MAGELLAN_SCHEMA_VERSION).unwrap();
+
+        conn.execute(
+            "INSERT INTO graph_entities (kind, name, file_path, data) VALUES (?, ?, ?, ?)",
+            rusqlite::params!("function", "test_func", "test.rs", "{}"),
+        ).unwrap();
+
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+
+        conn
+    }
+
+    /// Diamond CFG: 0 -> {1, 2} -> 3
+    fn create_diamond_cfg() -> Cfg {
+        let mut g = DiGraph::new();
+        let block = |id, kind, terminator| BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: None,
+        };
+
+        let b0 = g.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1], otherwise: 2 }));
+        let b1 = g.add_node(block(1, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b2 = g.add_node(block(2, BlockKind::Normal, Terminator::Goto { target: 3 }));
+        let b3 = g.add_node(block(3, BlockKind::Exit, Terminator::Return));
+
+        g.add_edge(b0, b1, EdgeType::TrueBranch);
+        g.add_edge(b0, b2, EdgeType::FalseBranch);
+        g.add_edge(b1, b3, EdgeType::Fallthrough);
+        g.add_edge(b2, b3, EdgeType::Fallthrough);
+
+        g
+    }
+
+    #[test]
+    fn test_store_and_load_dominators_roundtrip() {
+        let mut conn = create_test_db();
+        let cfg = create_diamond_cfg();
+        let tree = DominatorTree::new(&cfg).unwrap();
+
+        store_dominators(&mut conn, 1, "hash_a", &cfg, &tree).unwrap();
+        let loaded = load_dominators_from_db(&conn, 1, "hash_a", &cfg).unwrap().unwrap();
+
+        assert_eq!(loaded.root(), tree.root());
+        for node in cfg.node_indices() {
+            assert_eq!(loaded.immediate_dominator(node), tree.immediate_dominator(node));
+            assert_eq!(loaded.children(node), tree.children(node));
+        }
+    }
+
+    #[test]
+    fn test_load_dominators_hash_mismatch_is_miss() {
+        let mut conn = create_test_db();
+        let cfg = create_diamond_cfg();
+        let tree = DominatorTree::new(&cfg).unwrap();
+
+        store_dominators(&mut conn, 1, "hash_a", &cfg, &tree).unwrap();
+
+        assert!(load_dominators_from_db(&conn, 1, "hash_b", &cfg).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_invalidate_function_dominators() {
+        let mut conn = create_test_db();
+        let cfg = create_diamond_cfg();
+        let tree = DominatorTree::new(&cfg).unwrap();
+
+        store_dominators(&mut conn, 1, "hash_a", &cfg, &tree).unwrap();
+        invalidate_function_dominators(&conn, 1).unwrap();
+
+        assert!(load_dominators_from_db(&conn, 1, "hash_a", &cfg).unwrap().is_none());
+    }
+}
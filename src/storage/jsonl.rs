@@ -0,0 +1,316 @@
+//! JSON Lines interchange format for CFG data
+//!
+//! `export --function-data` writes one [`FunctionRecord`] per line: the
+//! function entity, its blocks and edges as Mirage loads them, and its cached
+//! paths. `import` rebuilds a fresh SQLite database from such a file with
+//! `create_schema` and `store_cfg`, so the format is independent of the
+//! storage backend and SQLite version it came from.
+//!
+//! Round-trips are lossless for the CFG as Mirage sees it: exporting an
+//! imported database yields the same records. Magellan's raw terminator
+//! strings are not kept verbatim (`fallthrough` is written back as `goto`),
+//! but both load to the same blocks and edges.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use crate::cfg::{BasicBlock, BlockId, Cfg, EdgeType, Path};
+
+use super::MirageDb;
+
+/// One function's CFG data, serialized as a single JSON line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionRecord {
+    pub function_id: i64,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    /// The entity's `data` object, kept so imported functions resolve by name
+    pub data: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_hash: Option<String>,
+    pub blocks: Vec<BasicBlock>,
+    /// `(from, to, type)` by block ID
+    pub edges: Vec<(BlockId, BlockId, EdgeType)>,
+    /// Cached paths; empty when none were cached
+    #[serde(default)]
+    pub paths: Vec<Path>,
+}
+
+impl FunctionRecord {
+    /// Rebuild the function's CFG from its blocks and edges
+    pub fn to_cfg(&self) -> Result<Cfg> {
+        let mut cfg = Cfg::new();
+        let mut nodes = HashMap::new();
+        for block in &self.blocks {
+            nodes.insert(block.id, cfg.add_node(block.clone()));
+        }
+        for &(from, to, edge_type) in &self.edges {
+            let (Some(&from_node), Some(&to_node)) = (nodes.get(&from), nodes.get(&to)) else {
+                anyhow::bail!("Edge {} -> {} of function {} references a missing block", from, to, self.name);
+            };
+            cfg.add_edge(from_node, to_node, edge_type);
+        }
+        Ok(cfg)
+    }
+}
+
+/// Write every function that has CFG blocks as JSON Lines
+///
+/// Functions are written in ID order, paths in block order. Cached paths from
+/// an older path_id scheme are dropped, as on any cache read. Returns the
+/// number of records written.
+pub fn export_function_data(db: &mut MirageDb, out: &mut impl Write) -> Result<usize> {
+    let mut functions = db.storage().function_ids().context("Failed to list functions")?;
+    functions.sort_by_key(|&(id, _)| id);
+
+    let mut written = 0;
+    for (function_id, name) in functions {
+        if db.storage().get_cfg_blocks(function_id)?.is_empty() {
+            continue;
+        }
+        let cfg = super::load_cfg_from_db(db, function_id)
+            .with_context(|| format!("Failed to load CFG for function '{}'", name))?;
+
+        let mut paths = if db.is_sqlite() {
+            super::paths::get_cached_paths(db.conn_mut()?, function_id)?
+        } else {
+            db.storage().get_cached_paths(function_id)?.unwrap_or_default()
+        };
+        paths.sort_by(|a, b| a.blocks.cmp(&b.blocks));
+
+        let entity = db.storage().get_entity(function_id);
+        let record = FunctionRecord {
+            function_id,
+            name,
+            file_path: entity.as_ref().and_then(|e| e.file_path.clone()),
+            data: entity.map(|e| e.data).unwrap_or_else(|| serde_json::json!({})),
+            function_hash: db.get_function_hash(function_id),
+            blocks: cfg.node_weights().cloned().collect(),
+            edges: cfg.edge_indices()
+                .filter_map(|e| cfg.edge_endpoints(e).map(|(a, b)| (cfg[a].id, cfg[b].id, cfg[e])))
+                .collect(),
+            paths,
+        };
+
+        serde_json::to_writer(&mut *out, &record).context("Failed to serialize function record")?;
+        writeln!(out)?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+/// An import line that is not a valid [`FunctionRecord`]
+///
+/// Kept apart from storage errors so callers can report bad input as a
+/// usage error rather than a database failure.
+#[derive(Debug, thiserror::Error)]
+#[error("Line {line}: {reason}")]
+pub struct InvalidFunctionData {
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Build a new SQLite database at `db_path` from JSON Lines records
+///
+/// Refuses to overwrite an existing file, and removes the partial database
+/// if any record fails to import. Blank lines are skipped, and a malformed
+/// line fails with [`InvalidFunctionData`]. Returns the number of functions
+/// imported.
+#[tracing::instrument(level = "debug", skip(input), fields(db_path = %db_path.display()))]
+pub fn import_function_data(db_path: &std::path::Path, input: impl BufRead) -> Result<usize> {
+    super::create_minimal_database(db_path)?;
+    let result = import_records(db_path, input);
+    if result.is_err() {
+        let _ = std::fs::remove_file(db_path);
+    }
+    result
+}
+
+/// Body of [`import_function_data`], run against the freshly created database
+fn import_records(db_path: &std::path::Path, input: impl BufRead) -> Result<usize> {
+    let mut conn = Connection::open(db_path)
+        .with_context(|| format!("Failed to open database: {}", db_path.display()))?;
+
+    let mut imported = 0;
+    for (index, line) in input.lines().enumerate() {
+        let line = line.context("Failed to read input")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |reason: String| InvalidFunctionData { line: index + 1, reason };
+        let record: FunctionRecord = serde_json::from_str(&line)
+            .map_err(|e| invalid(format!("invalid function record: {}", e)))?;
+        let cfg = record.to_cfg().map_err(|e| invalid(format!("{:#}", e)))?;
+        import_record(&mut conn, &record, &cfg)
+            .with_context(|| format!("Line {}: failed to import function '{}'", index + 1, record.name))?;
+        imported += 1;
+    }
+
+    Ok(imported)
+}
+
+/// Insert one record's entity, CFG and cached paths
+///
+/// Uses `store_cfg`, deprecated for indexing since Magellan writes
+/// `cfg_blocks`, because here there is no Magellan run to do it.
+#[allow(deprecated)]
+#[tracing::instrument(level = "debug", skip_all, fields(function = %record.name))]
+fn import_record(conn: &mut Connection, record: &FunctionRecord, cfg: &Cfg) -> Result<()> {
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, name, file_path, data) VALUES (?, 'Symbol', ?, ?, ?)",
+        params![record.function_id, record.name, record.file_path, record.data.to_string()],
+    ).context("Failed to insert function entity")?;
+
+    super::store_cfg(conn, record.function_id, record.function_hash.as_deref().unwrap_or_default(), cfg)?;
+
+    if !record.paths.is_empty() {
+        super::paths::store_paths(conn, record.function_id, &record.paths)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cfg::{BlockKind, SourceLocation, Terminator};
+
+    fn insert_function(conn: &mut Connection, name: &str, blocks: &[(&str, &str)]) -> i64 {
+        conn.execute(
+            "INSERT INTO graph_entities (kind, name, file_path, data) VALUES ('Symbol', ?, 'src/lib.rs', ?)",
+            params![name, serde_json::json!({"kind": "Function"}).to_string()],
+        ).unwrap();
+        let function_id = conn.last_insert_rowid();
+        for (i, (kind, terminator)) in blocks.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO cfg_blocks (function_id, kind, terminator, byte_start, byte_end,
+                                         start_line, start_col, end_line, end_col)
+                 VALUES (?, ?, ?, ?, ?, ?, 1, ?, 1)",
+                params![function_id, kind, terminator, i as i64 * 10, i as i64 * 10 + 9, i as i64 + 1, i as i64 + 1],
+            ).unwrap();
+        }
+        function_id
+    }
+
+    #[test]
+    fn test_round_trip_preserves_status_and_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("source.db");
+        super::super::create_minimal_database(&source).unwrap();
+        {
+            let mut conn = Connection::open(&source).unwrap();
+            insert_function(&mut conn, "straight", &[("entry", "fallthrough"), ("return", "return")]);
+            let branchy = insert_function(&mut conn, "branchy", &[
+                ("entry", "conditional"),
+                ("if", "fallthrough"),
+                ("else", "fallthrough"),
+                ("return", "return"),
+            ]);
+            let paths = vec![Path::new(vec![0, 1, 3], crate::cfg::PathKind::Normal)];
+            super::super::paths::store_paths(&mut conn, branchy, &paths).unwrap();
+        }
+
+        let mut source_db = MirageDb::open(&source).unwrap();
+        let mut exported = Vec::new();
+        assert_eq!(export_function_data(&mut source_db, &mut exported).unwrap(), 2);
+
+        let imported = dir.path().join("imported.db");
+        assert_eq!(import_function_data(&imported, exported.as_slice()).unwrap(), 2);
+        let mut imported_db = MirageDb::open(&imported).unwrap();
+
+        let (before, after) = (source_db.status().unwrap(), imported_db.status().unwrap());
+        assert_eq!(after.cfg_blocks, before.cfg_blocks);
+        assert_eq!(after.cfg_paths, before.cfg_paths);
+        assert_eq!(imported_db.resolve_function_name("branchy").unwrap(), 2);
+
+        let mut reexported = Vec::new();
+        export_function_data(&mut imported_db, &mut reexported).unwrap();
+        assert_eq!(String::from_utf8(reexported).unwrap(), String::from_utf8(exported).unwrap());
+    }
+
+    #[test]
+    fn test_import_refuses_existing_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("existing.db");
+        std::fs::write(&path, b"").unwrap();
+        assert!(import_function_data(&path, "".as_bytes()).is_err());
+        assert!(path.exists(), "Existing file is left alone");
+
+        let fresh = dir.path().join("fresh.db");
+        let error = import_function_data(&fresh, "\nnot json\n".as_bytes()).unwrap_err();
+        assert_eq!(error.downcast_ref::<InvalidFunctionData>().map(|e| e.line), Some(2));
+        assert!(!fresh.exists(), "Partial database is removed");
+    }
+
+    /// Records the name of every span opened while it is installed
+    struct SpanNames(std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanNames {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.0.lock().unwrap().push(attrs.metadata().name());
+        }
+    }
+
+    #[test]
+    fn test_import_and_query_emit_phase_spans() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let block = |id, kind, terminator| BasicBlock { id, kind, statements: vec![], terminator, source_location: None };
+        let record = serde_json::to_string(&FunctionRecord {
+            function_id: 1,
+            name: "f".to_string(),
+            file_path: None,
+            data: serde_json::json!({}),
+            function_hash: None,
+            blocks: vec![block(0, BlockKind::Entry, Terminator::Goto { target: 1 }), block(1, BlockKind::Exit, Terminator::Return)],
+            edges: vec![(0, 1, EdgeType::Fallthrough)],
+            paths: vec![Path::new(vec![0, 1], crate::cfg::PathKind::Normal)],
+        }).unwrap();
+
+        let names = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(SpanNames(names.clone()));
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("traced.db");
+        tracing::subscriber::with_default(subscriber, || {
+            import_function_data(&db_path, record.as_bytes()).unwrap();
+            let db = MirageDb::open(&db_path).unwrap();
+            let cfg = super::super::load_cfg_from_db(&db, 1).unwrap();
+            crate::cfg::enumerate_paths(&cfg, &crate::cfg::PathLimits::default());
+        });
+
+        let names = names.lock().unwrap();
+        for expected in ["import_function_data", "import_record", "store_cfg", "store_paths", "open_database", "load_cfg", "enumerate_paths"] {
+            assert!(names.contains(&expected), "missing span {}: {:?}", expected, *names);
+        }
+    }
+
+    #[test]
+    fn test_to_cfg_rejects_dangling_edge() {
+        let record = FunctionRecord {
+            function_id: 1,
+            name: "f".to_string(),
+            file_path: None,
+            data: serde_json::json!({}),
+            function_hash: None,
+            blocks: vec![BasicBlock {
+                id: 0,
+                kind: BlockKind::Entry,
+                statements: vec![],
+                terminator: Terminator::Return,
+                source_location: Some(SourceLocation::new("lib.rs", 0, 1, 1, 1, 1, 1)),
+            }],
+            edges: vec![(0, 5, EdgeType::Fallthrough)],
+            paths: vec![],
+        };
+        assert!(record.to_cfg().is_err());
+    }
+}
@@ -124,6 +124,14 @@ impl StorageTrait for KvStorage {
         self.backend.get_node(snapshot, entity_id).ok()
     }
 
+    /// List functions by walking the entity index
+    ///
+    /// Native-v3 has no SQL, so every entity is loaded and checked with
+    /// `is_function_entity`.
+    fn function_ids(&self) -> Result<Vec<(i64, String)>> {
+        super::function_ids_from_graph(self.backend())
+    }
+
     /// Get cached paths for a function from KV backend
     ///
     /// Uses KV store for path caching with key format `cfg:paths:{function_id}`.
@@ -5,10 +5,14 @@
 // - cfg_edges: Control flow between blocks
 // - cfg_paths: Enumerated execution paths
 // - cfg_path_elements: Blocks in each path
-// - cfg_dominators: Dominance relationships
+// - cfg_dominators: Cached dominator trees (keyed by function_hash)
 // - cfg_post_dominators: Reverse dominance
 
+pub mod changed;
+pub mod dominators;
+pub mod jsonl;
 pub mod paths;
+pub mod prune;
 
 // Backend-agnostic storage trait and implementations (Phase 069-01)
 #[cfg(feature = "backend-sqlite")]
@@ -23,7 +27,7 @@ pub mod sqlite_backend;
 pub mod kv_backend;
 
 use anyhow::{Context, Result};
-use rusqlite::{Connection, OptionalExtension, params};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params};
 use std::path::Path;
 
 // GraphBackend imports for dual backend support
@@ -111,6 +115,23 @@ pub trait StorageTrait {
     /// * `None` - Entity not found
     fn get_entity(&self, entity_id: i64) -> Option<sqlitegraph::GraphEntity>;
 
+    /// List every function as `(function_id, name)`
+    ///
+    /// A function is an entity stored the Magellan v7 way (`kind = 'Symbol'`
+    /// with `data.kind = 'Function'`) or with the legacy `kind = 'function'`.
+    /// Results are ordered by name, then ID, on every backend.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(function_id, name)>)` - Functions in name order
+    /// * `Err(...)` - Error if the entity query fails
+    fn function_ids(&self) -> Result<Vec<(i64, String)>>;
+
+    /// Number of functions `function_ids` would list
+    fn function_count(&self) -> Result<usize> {
+        Ok(self.function_ids()?.len())
+    }
+
     /// Get cached paths for a function (optional)
     ///
     /// Returns cached enumerated paths if available.
@@ -135,7 +156,7 @@ pub trait StorageTrait {
 /// This struct represents the data returned by `StorageTrait::get_cfg_blocks`.
 /// It is a simplified version of Magellan's CfgBlock that contains only the
 /// fields needed by Mirage for CFG analysis.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct CfgBlockData {
     /// Block ID (from cfg_blocks table)
     pub id: i64,
@@ -175,6 +196,16 @@ pub enum Backend {
 }
 
 impl Backend {
+    /// Short name of the backend, as reported in JSON output
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "backend-sqlite")]
+            Backend::Sqlite(_) => "sqlite",
+            #[cfg(feature = "backend-native-v3")]
+            Backend::NativeV3(_) => "native-v3",
+        }
+    }
+
     /// Detect backend format from database file and open appropriate backend
     ///
     /// Uses `magellan::migrate_backend_cmd::detect_backend_format` for
@@ -223,6 +254,70 @@ impl Backend {
         }
     }
 
+    /// Like `detect_and_open`, but reads the index as of `snapshot`
+    ///
+    /// `SnapshotId::current()` behaves exactly like `detect_and_open`. Any
+    /// other snapshot is an error: the SQLite backend keeps no history, and
+    /// the native-v3 CFG store does not yet read historical snapshots.
+    pub fn detect_and_open_at(db_path: &Path, snapshot: SnapshotId) -> Result<Self> {
+        Self::check_snapshot(snapshot)?;
+        Self::detect_and_open(db_path)
+    }
+
+    /// Check that the compiled-in backend can serve `snapshot`
+    ///
+    /// Only `SnapshotId::current()` is available today, so this needs no
+    /// database and can reject `--snapshot` before anything is opened.
+    pub fn check_snapshot(snapshot: SnapshotId) -> Result<()> {
+        if snapshot == SnapshotId::current() {
+            return Ok(());
+        }
+
+        #[cfg(feature = "backend-sqlite")]
+        {
+            Err(anyhow::anyhow!(
+                "Snapshot {} requested, but the SQLite backend does not support historical snapshots \
+                 (only the current index state, snapshot 0, is available)",
+                snapshot.0
+            ))
+        }
+
+        #[cfg(feature = "backend-native-v3")]
+        {
+            Err(anyhow::anyhow!(
+                "Snapshot {} requested, but reading historical snapshots is not yet supported \
+                 for native-v3 CFG data (only the current index state, snapshot 0, is available)",
+                snapshot.0
+            ))
+        }
+    }
+
+    /// Like `detect_and_open`, but opens SQLite storage read-only
+    ///
+    /// Never takes a write lock, so it works while `magellan watch` writes.
+    /// The native-v3 backend has no read-only mode and is opened normally.
+    pub fn detect_and_open_readonly(db_path: &Path) -> Result<Self> {
+        use magellan::migrate_backend_cmd::detect_backend_format;
+
+        let sqlite_detected = detect_backend_format(db_path).is_ok();
+
+        #[cfg(feature = "backend-sqlite")]
+        let backend = if sqlite_detected {
+            SqliteStorage::open_readonly(db_path).map(Backend::Sqlite)
+        } else {
+            Err(anyhow::anyhow!("Native-V3 backend detected but SQLite feature enabled"))
+        };
+
+        #[cfg(feature = "backend-native-v3")]
+        let backend = if sqlite_detected {
+            Err(anyhow::anyhow!("SQLite backend detected but Native-V3 feature enabled"))
+        } else {
+            KvStorage::open(db_path).map(Backend::NativeV3)
+        };
+
+        backend
+    }
+
     /// Delegate get_cfg_blocks to inner backend
     pub fn get_cfg_blocks(&self, function_id: i64) -> Result<Vec<CfgBlockData>> {
         match self {
@@ -243,6 +338,26 @@ impl Backend {
         }
     }
 
+    /// Delegate function_ids to inner backend
+    pub fn function_ids(&self) -> Result<Vec<(i64, String)>> {
+        match self {
+            #[cfg(feature = "backend-sqlite")]
+            Backend::Sqlite(s) => s.function_ids(),
+            #[cfg(feature = "backend-native-v3")]
+            Backend::NativeV3(k) => k.function_ids(),
+        }
+    }
+
+    /// Delegate function_count to inner backend
+    pub fn function_count(&self) -> Result<usize> {
+        match self {
+            #[cfg(feature = "backend-sqlite")]
+            Backend::Sqlite(s) => s.function_count(),
+            #[cfg(feature = "backend-native-v3")]
+            Backend::NativeV3(k) => k.function_count(),
+        }
+    }
+
     /// Delegate get_cached_paths to inner backend
     pub fn get_cached_paths(&self, function_id: i64) -> Result<Option<Vec<crate::cfg::Path>>> {
         match self {
@@ -264,11 +379,50 @@ impl StorageTrait for Backend {
         self.get_entity(entity_id)
     }
 
+    fn function_ids(&self) -> Result<Vec<(i64, String)>> {
+        self.function_ids()
+    }
+
+    fn function_count(&self) -> Result<usize> {
+        self.function_count()
+    }
+
     fn get_cached_paths(&self, function_id: i64) -> Result<Option<Vec<crate::cfg::Path>>> {
         self.get_cached_paths(function_id)
     }
 }
 
+/// Whether a graph entity is a function, by either Magellan convention
+///
+/// Matches `StorageTrait::function_ids`: `kind = "Symbol"` with
+/// `data.kind = "Function"` (Magellan v7), or the legacy `kind = "function"`.
+pub fn is_function_entity(entity: &sqlitegraph::GraphEntity) -> bool {
+    entity.kind == "function"
+        || (entity.kind == "Symbol"
+            && entity.data.get("kind").and_then(|kind| kind.as_str()) == Some("Function"))
+}
+
+/// List functions through the entity index of any `GraphBackend`
+///
+/// Backends without SQL access (native-v3) enumerate functions this way.
+/// Returns `(function_id, name)` ordered by name, then ID, like
+/// `StorageTrait::function_ids`.
+pub fn function_ids_from_graph(backend: &dyn GraphBackend) -> Result<Vec<(i64, String)>> {
+    let snapshot = SnapshotId::current();
+    let entity_ids = backend
+        .entity_ids()
+        .map_err(|e| anyhow::anyhow!("Failed to query entities from backend: {}", e))?;
+
+    let mut functions: Vec<(i64, String)> = entity_ids
+        .into_iter()
+        .filter_map(|id| backend.get_node(snapshot, id).ok())
+        .filter(is_function_entity)
+        .map(|entity| (entity.id, entity.name))
+        .collect();
+    functions.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+    Ok(functions)
+}
+
 /// Database backend format detected in a graph database file.
 ///
 /// This is the legacy format detection enum. For new code, use the
@@ -315,7 +469,7 @@ impl BackendFormat {
 }
 
 /// Mirage schema version
-pub const MIRAGE_SCHEMA_VERSION: i32 = 1;
+pub const MIRAGE_SCHEMA_VERSION: i32 = 5;
 
 /// Minimum Magellan schema version we require
 /// Magellan v7+ includes cfg_blocks table with AST-based CFG
@@ -330,6 +484,29 @@ pub const REQUIRED_MAGELLAN_SCHEMA_VERSION: i32 = TEST_MAGELLAN_SCHEMA_VERSION;
 /// SQLiteGraph schema version we require
 pub const REQUIRED_SQLITEGRAPH_SCHEMA_VERSION: i32 = 3;
 
+/// A Magellan database that Mirage has not been set up in yet
+///
+/// Returned (inside the `anyhow::Error`) by [`MirageDb::open`] when the
+/// Magellan tables are present but neither Mirage's tables nor the CFG
+/// tables exist. [`init_schema`] fixes it.
+#[derive(Debug, thiserror::Error)]
+#[error("Mirage schema not found in Magellan database: {path}")]
+pub struct MirageSchemaMissing {
+    pub path: String,
+}
+
+/// A database whose Mirage tables need creating or migrating, opened read-only
+///
+/// Returned (inside the `anyhow::Error`) by [`MirageDb::open_readonly`], which
+/// never writes; [`init_schema`] or any read-write open upgrades the schema.
+#[derive(Debug, thiserror::Error)]
+#[error("Mirage schema version {found} in {path} is older than version {MIRAGE_SCHEMA_VERSION}")]
+pub struct MirageSchemaOutdated {
+    pub path: String,
+    /// Mirage schema version found, 0 when Mirage's tables do not exist yet
+    pub found: i32,
+}
+
 /// Database connection wrapper
 ///
 /// Uses Backend enum for CFG queries (Phase 069-02) and GraphBackend for entity queries.
@@ -346,6 +523,9 @@ pub struct MirageDb {
     /// Snapshot ID for consistent reads
     snapshot_id: SnapshotId,
 
+    /// Whether the database was opened with `open_readonly`
+    read_only: bool,
+
     // SQLite-specific connection (only available with sqlite feature)
     // DEPRECATED: Use storage field instead for new code
     #[cfg(feature = "backend-sqlite")]
@@ -356,6 +536,7 @@ impl std::fmt::Debug for MirageDb {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MirageDb")
             .field("snapshot_id", &self.snapshot_id)
+            .field("read_only", &self.read_only)
             .field("storage", &self.storage)
             .field("graph_backend", &"<GraphBackend>")
             .finish()
@@ -372,14 +553,44 @@ impl MirageDb {
     /// Phase 069-02: Uses Backend::detect_and_open() for CFG queries
     /// and open_graph() for entity queries (GraphBackend).
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let path = path.as_ref();
+        Self::open_with_mode(path.as_ref(), false, SnapshotId::current())
+    }
+
+    /// Open database at the given path, reading the index as of `snapshot`
+    ///
+    /// Fails if the backend cannot serve that snapshot; see
+    /// `Backend::detect_and_open_at`.
+    pub fn open_at<P: AsRef<Path>>(path: P, snapshot: SnapshotId) -> Result<Self> {
+        Self::open_with_mode(path.as_ref(), false, snapshot)
+    }
+
+    /// Open database at the given path without taking write locks
+    ///
+    /// Used by query commands that never write (`status`, `cfg`, `dominators`,
+    /// `loops`, ...), so they can run while another process such as
+    /// `magellan watch` holds the write lock. The SQLite connection is opened
+    /// with `SQLITE_OPEN_READ_ONLY`; any write through it fails.
+    ///
+    /// The schema is never created or migrated here: if the Mirage tables are
+    /// missing or out of date, this fails with [`MirageSchemaOutdated`].
+    pub fn open_readonly<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_with_mode(path.as_ref(), true, SnapshotId::current())
+    }
+
+    #[tracing::instrument(name = "open_database", level = "debug", skip_all, fields(path = %path.display(), read_only = read_only))]
+    fn open_with_mode(path: &Path, read_only: bool, snapshot_id: SnapshotId) -> Result<Self> {
         if !path.exists() {
             anyhow::bail!("Database not found: {}", path.display());
         }
 
         // Phase 069-02: Use Backend::detect_and_open() for storage layer
-        let storage = Backend::detect_and_open(path)
-            .context("Failed to open storage backend")?;
+        let storage = if snapshot_id != SnapshotId::current() {
+            Backend::detect_and_open_at(path, snapshot_id)
+        } else if read_only {
+            Backend::detect_and_open_readonly(path)
+        } else {
+            Backend::detect_and_open(path)
+        }.context("Failed to open storage backend")?;
 
         // Detect backend format from file header for GraphBackend creation
         let detected_backend = BackendFormat::detect(path)
@@ -409,7 +620,7 @@ impl MirageDb {
         }
 
         // Select appropriate GraphConfig based on detected backend
-        let cfg = match detected_backend {
+        let mut cfg = match detected_backend {
             BackendFormat::SQLite => GraphConfig::sqlite(),
             BackendFormat::NativeV3 => GraphConfig::native(),
             BackendFormat::Unknown => {
@@ -420,18 +631,26 @@ impl MirageDb {
             }
         };
 
+        // Read-only opens must not run sqlitegraph's schema initialization
+        if read_only {
+            cfg.sqlite.without_migrations = true;
+        }
+
         // Use open_graph factory to create GraphBackend for entity queries
         let graph_backend = open_graph(path, &cfg)
             .context("Failed to open graph database")?;
 
-        let snapshot_id = SnapshotId::current();
-
         // For SQLite backend, open Connection and validate schema
         #[cfg(feature = "backend-sqlite")]
-        let conn = {
+        let conn = if read_only {
+            let mut conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+                .context("Failed to open read-only SQLite connection")?;
+            Self::validate_schema_sqlite(&mut conn, path, false)?;
+            Some(conn)
+        } else {
             let mut conn = Connection::open(path)
                 .context("Failed to open SQLite connection")?;
-            Self::validate_schema_sqlite(&mut conn, path)?;
+            Self::validate_schema_sqlite(&mut conn, path, true)?;
             Some(conn)
         };
 
@@ -446,14 +665,18 @@ impl MirageDb {
             storage,
             graph_backend,
             snapshot_id,
+            read_only,
             #[cfg(feature = "backend-sqlite")]
             conn,
         })
     }
 
     /// Validate database schema for SQLite backend
+    ///
+    /// With `upgrade`, Mirage's tables are created or migrated as needed;
+    /// without it, a schema that needs either is a [`MirageSchemaOutdated`] error.
     #[cfg(feature = "backend-sqlite")]
-    fn validate_schema_sqlite(conn: &mut Connection, _path: &Path) -> Result<()> {
+    fn validate_schema_sqlite(conn: &mut Connection, path: &Path, upgrade: bool) -> Result<()> {
         // Check if mirage_meta table exists
         let mirage_meta_exists: bool = conn.query_row(
             "SELECT 1 FROM sqlite_master WHERE type='table' AND name='mirage_meta'",
@@ -502,6 +725,10 @@ impl MirageDb {
             |row| row.get(0),
         ).optional()?.unwrap_or(0) == 1;
 
+        if !cfg_blocks_exists && !mirage_meta_exists {
+            return Err(MirageSchemaMissing { path: path.display().to_string() }.into());
+        }
+
         if !cfg_blocks_exists {
             anyhow::bail!(
                 "CFG blocks table not found. Magellan schema v7+ required. \
@@ -509,6 +736,10 @@ impl MirageDb {
             );
         }
 
+        if !upgrade && mirage_version < MIRAGE_SCHEMA_VERSION {
+            return Err(MirageSchemaOutdated { path: path.display().to_string(), found: mirage_version }.into());
+        }
+
         // If mirage_meta doesn't exist, this is a pure Magellan database.
         // Initialize Mirage tables to extend it.
         if !mirage_meta_exists {
@@ -600,6 +831,11 @@ impl MirageDb {
         self.conn.is_some()
     }
 
+    /// Check if the database was opened with `open_readonly`
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
     /// Check if the database backend is SQLite
     ///
     /// For native-v3, this always returns false.
@@ -618,8 +854,203 @@ struct Migration {
 
 /// Get all registered migrations
 fn migrations() -> Vec<Migration> {
-    // No migrations yet - framework is ready for future schema changes
-    vec![]
+    vec![
+        Migration {
+            version: 2,
+            description: "add nullable weight column to cfg_edges",
+            up: migrate_v2_edge_weights,
+        },
+        Migration {
+            version: 3,
+            description: "key cfg_dominators by function and function_hash",
+            up: migrate_v3_dominator_cache,
+        },
+        Migration {
+            version: 4,
+            description: "record the path_id scheme version of cached paths",
+            up: migrate_v4_path_id_version,
+        },
+        Migration {
+            version: 5,
+            description: "number cached paths for readable path IDs",
+            up: migrate_v5_path_ordinals,
+        },
+    ]
+}
+
+/// v2: branch probability weights on cfg_edges (NULL means uniform)
+fn migrate_v2_edge_weights(conn: &mut Connection) -> Result<()> {
+    let has_edges_table: bool = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='cfg_edges'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0;
+
+    if !has_edges_table {
+        return Ok(());
+    }
+
+    let has_weight: bool = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('cfg_edges') WHERE name = 'weight'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0;
+
+    if !has_weight {
+        conn.execute("ALTER TABLE cfg_edges ADD COLUMN weight REAL", [])?;
+    }
+
+    Ok(())
+}
+
+/// v3: cfg_dominators becomes a per-function cache validated by function_hash
+///
+/// Block IDs in cached dominator rows are CFG-local, so the foreign keys to
+/// cfg_blocks are dropped. Existing rows are kept with NULL function_id and
+/// function_hash, which never match a cache lookup.
+fn migrate_v3_dominator_cache(conn: &mut Connection) -> Result<()> {
+    let has_dominators_table: bool = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='cfg_dominators'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0;
+
+    if !has_dominators_table {
+        return Ok(());
+    }
+
+    let has_function_hash: bool = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('cfg_dominators') WHERE name = 'function_hash'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0;
+
+    if has_function_hash {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        "ALTER TABLE cfg_dominators RENAME TO cfg_dominators_v2;
+         CREATE TABLE cfg_dominators (
+             function_id INTEGER,
+             block_id INTEGER NOT NULL,
+             dominator_id INTEGER NOT NULL,
+             is_strict BOOLEAN NOT NULL,
+             function_hash TEXT,
+             PRIMARY KEY (function_id, block_id, dominator_id, is_strict)
+         );
+         INSERT INTO cfg_dominators (block_id, dominator_id, is_strict)
+             SELECT block_id, dominator_id, is_strict FROM cfg_dominators_v2;
+         DROP TABLE cfg_dominators_v2;
+         CREATE INDEX IF NOT EXISTS idx_cfg_dominators_function ON cfg_dominators(function_id);",
+    )?;
+
+    Ok(())
+}
+
+/// v4: cached paths record the path_id scheme they were hashed with
+///
+/// Paths cached before v4 were all hashed with scheme 1, the only one that
+/// existed, so existing rows and mirage_meta are stamped with it.
+fn migrate_v4_path_id_version(conn: &mut Connection) -> Result<()> {
+    for table in ["mirage_meta", "cfg_paths"] {
+        let has_table: bool = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name=?",
+            params![table],
+            |row| row.get::<_, i64>(0),
+        )? > 0;
+        if !has_table {
+            continue;
+        }
+
+        let has_column: bool = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = 'path_id_version'",
+            params![table],
+            |row| row.get::<_, i64>(0),
+        )? > 0;
+        if !has_column {
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN path_id_version INTEGER", table), [])?;
+            conn.execute(&format!("UPDATE {} SET path_id_version = 1", table), [])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// v5: cached paths carry the ordinal behind their readable ID (`function#pN`)
+///
+/// Existing rows are numbered the same way new ones are, by block sequence
+/// within each function.
+fn migrate_v5_path_ordinals(conn: &mut Connection) -> Result<()> {
+    use crate::cfg::paths::path_ordinals;
+    use crate::cfg::PathKind;
+    use std::collections::BTreeMap;
+
+    let has_paths_table: bool = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='cfg_paths'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0;
+    if !has_paths_table {
+        return Ok(());
+    }
+
+    let has_ordinal: bool = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('cfg_paths') WHERE name = 'path_ordinal'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0;
+    if has_ordinal {
+        return Ok(());
+    }
+
+    conn.execute("ALTER TABLE cfg_paths ADD COLUMN path_ordinal INTEGER", [])?;
+
+    // Pre-release layouts keyed paths by row id and cannot be numbered
+    let has_path_id: bool = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('cfg_paths') WHERE name = 'path_id'",
+        [],
+        |row| row.get::<_, i64>(0),
+    )? > 0;
+    if !has_path_id {
+        return Ok(());
+    }
+
+    // Block sequences of every cached path, grouped by function
+    let mut by_function: BTreeMap<i64, BTreeMap<String, Vec<usize>>> = BTreeMap::new();
+    {
+        let mut stmt = conn.prepare(
+            "SELECT p.function_id, p.path_id, pe.block_id
+             FROM cfg_paths p
+             JOIN cfg_path_elements pe ON p.path_id = pe.path_id
+             ORDER BY p.path_id, pe.sequence_order",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })?;
+        for row in rows {
+            let (function_id, path_id, block_id) = row?;
+            by_function.entry(function_id).or_default()
+                .entry(path_id).or_default()
+                .push(block_id as usize);
+        }
+    }
+
+    let tx = conn.transaction()?;
+    for paths in by_function.into_values() {
+        let paths: Vec<crate::cfg::Path> = paths.into_iter()
+            .map(|(path_id, blocks)| crate::cfg::Path::with_id(path_id, blocks, PathKind::Normal))
+            .collect();
+        for (path, ordinal) in paths.iter().zip(path_ordinals(&paths)) {
+            tx.execute(
+                "UPDATE cfg_paths SET path_ordinal = ?1 WHERE path_id = ?2",
+                params![ordinal as i64, path.path_id],
+            )?;
+        }
+    }
+    tx.commit()?;
+
+    Ok(())
 }
 
 /// Run schema migrations to bring database up to current version
@@ -664,6 +1095,48 @@ pub fn migrate_schema(conn: &mut Connection) -> Result<()> {
     Ok(())
 }
 
+/// Add the Mirage tables to an existing Magellan database
+///
+/// Does not index anything. Returns true if the tables were created, false
+/// if the database already had them (they are migrated to the current
+/// version if needed).
+pub fn init_schema<P: AsRef<Path>>(path: P) -> Result<bool> {
+    let path = path.as_ref();
+    if !path.exists() {
+        anyhow::bail!("Database not found: {}", path.display());
+    }
+
+    let mut conn = Connection::open(path).context("Failed to open SQLite connection")?;
+    let magellan_version: i32 = conn.query_row(
+        "SELECT magellan_schema_version FROM magellan_meta WHERE id = 1",
+        [],
+        |row| row.get(0),
+    ).optional().context("Not a Magellan database: magellan_meta is missing")?
+        .context("Not a Magellan database: magellan_meta is empty")?;
+
+    if magellan_version < MIN_MAGELLAN_SCHEMA_VERSION {
+        anyhow::bail!(
+            "Magellan schema version {} is too old (minimum {}). \
+             Please update Magellan and run 'magellan watch' to rebuild CFGs.",
+            magellan_version, MIN_MAGELLAN_SCHEMA_VERSION
+        );
+    }
+
+    let mirage_meta_exists = conn.query_row(
+        "SELECT 1 FROM sqlite_master WHERE type='table' AND name='mirage_meta'",
+        [],
+        |row| row.get::<_, i32>(0),
+    ).optional()?.is_some();
+
+    if mirage_meta_exists {
+        migrate_schema(&mut conn)?;
+        return Ok(false);
+    }
+
+    create_schema(&mut conn, magellan_version)?;
+    Ok(true)
+}
+
 /// Create Mirage schema tables in an existing Magellan database
 ///
 /// The magellan_schema_version parameter should be the actual version
@@ -676,7 +1149,8 @@ pub fn create_schema(conn: &mut Connection, _magellan_schema_version: i32) -> Re
             mirage_schema_version INTEGER NOT NULL,
             magellan_schema_version INTEGER NOT NULL,
             rustc_version TEXT,
-            created_at INTEGER NOT NULL
+            created_at INTEGER NOT NULL,
+            path_id_version INTEGER
         )",
         [],
     )?;
@@ -710,11 +1184,13 @@ pub fn create_schema(conn: &mut Connection, _magellan_schema_version: i32) -> Re
 
     // Create cfg_edges table (kept for backward compatibility with tests and existing databases)
     // Note: New code should compute edges in memory using build_edges_from_terminators()
+    // weight is the branch probability; NULL means uniform across successors
     conn.execute(
         "CREATE TABLE IF NOT EXISTS cfg_edges (
             from_id INTEGER NOT NULL,
             to_id INTEGER NOT NULL,
             edge_type TEXT NOT NULL,
+            weight REAL,
             PRIMARY KEY (from_id, to_id, edge_type),
             FOREIGN KEY (from_id) REFERENCES cfg_blocks(id),
             FOREIGN KEY (to_id) REFERENCES cfg_blocks(id)
@@ -735,6 +1211,8 @@ pub fn create_schema(conn: &mut Connection, _magellan_schema_version: i32) -> Re
             exit_block INTEGER NOT NULL,
             length INTEGER NOT NULL,
             created_at INTEGER NOT NULL,
+            path_id_version INTEGER,
+            path_ordinal INTEGER,
             FOREIGN KEY (function_id) REFERENCES graph_entities(id)
         )",
         [],
@@ -760,16 +1238,18 @@ pub fn create_schema(conn: &mut Connection, _magellan_schema_version: i32) -> Re
     // Create cfg_dominators table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS cfg_dominators (
+            function_id INTEGER,
             block_id INTEGER NOT NULL,
             dominator_id INTEGER NOT NULL,
             is_strict BOOLEAN NOT NULL,
-            PRIMARY KEY (block_id, dominator_id, is_strict),
-            FOREIGN KEY (block_id) REFERENCES cfg_blocks(id),
-            FOREIGN KEY (dominator_id) REFERENCES cfg_blocks(id)
+            function_hash TEXT,
+            PRIMARY KEY (function_id, block_id, dominator_id, is_strict)
         )",
         [],
     )?;
 
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_cfg_dominators_function ON cfg_dominators(function_id)", [])?;
+
     // Create cfg_post_dominators table
     conn.execute(
         "CREATE TABLE IF NOT EXISTS cfg_post_dominators (
@@ -786,9 +1266,9 @@ pub fn create_schema(conn: &mut Connection, _magellan_schema_version: i32) -> Re
     // Initialize mirage_meta
     let now = chrono::Utc::now().timestamp();
     conn.execute(
-        "INSERT OR REPLACE INTO mirage_meta (id, mirage_schema_version, magellan_schema_version, created_at)
-         VALUES (1, ?, ?, ?)",
-        params![MIRAGE_SCHEMA_VERSION, REQUIRED_MAGELLAN_SCHEMA_VERSION, now],
+        "INSERT OR REPLACE INTO mirage_meta (id, mirage_schema_version, magellan_schema_version, created_at, path_id_version)
+         VALUES (1, ?, ?, ?, ?)",
+        params![MIRAGE_SCHEMA_VERSION, REQUIRED_MAGELLAN_SCHEMA_VERSION, now, crate::cfg::PATH_ID_VERSION],
     )?;
 
     Ok(())
@@ -804,6 +1284,11 @@ pub struct DatabaseStatus {
     pub cfg_dominators: i64,
     pub mirage_schema_version: i32,
     pub magellan_schema_version: i32,
+    /// path_id scheme this build of Mirage hashes paths with
+    pub path_id_version: i64,
+    /// path_id scheme of the paths cached in this database, if recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_path_id_version: Option<i64>,
 }
 
 impl MirageDb {
@@ -853,6 +1338,8 @@ impl MirageDb {
             |row| row.get(0),
         ).unwrap_or(0);
 
+        let cached_path_id_version = paths::cached_path_id_version(conn).unwrap_or(None);
+
         #[allow(deprecated)]
         Ok(DatabaseStatus {
             cfg_blocks,
@@ -861,6 +1348,8 @@ impl MirageDb {
             cfg_dominators,
             mirage_schema_version,
             magellan_schema_version,
+            path_id_version: crate::cfg::PATH_ID_VERSION,
+            cached_path_id_version,
         })
     }
 
@@ -897,6 +1386,8 @@ impl MirageDb {
             cfg_dominators,
             mirage_schema_version,
             magellan_schema_version,
+            path_id_version: crate::cfg::PATH_ID_VERSION,
+            cached_path_id_version: None,
         })
     }
 
@@ -1294,6 +1785,7 @@ fn load_cfg_from_rows(
         let kind = match kind_str.as_str() {
             "entry" => BlockKind::Entry,
             "return" => BlockKind::Exit,
+            "panic" => BlockKind::Panic,
             "if" | "else" | "loop" | "while" | "for" | "match_arm" | "block" => BlockKind::Normal,
             _ => {
                 // Fallback: treat unknown kinds as Normal
@@ -1333,6 +1825,10 @@ fn load_cfg_from_rows(
                         end_column: end_c,
                     })
                 }
+                // Byte range only: line/column resolved on demand (see SourceMap)
+                (_, _, Some(bs), Some(be)) => {
+                    Some(SourceLocation::from_bytes_with_source(path.clone(), None, *bs as usize, *be as usize))
+                }
                 _ => None,
             }
         } else {
@@ -1356,6 +1852,9 @@ fn load_cfg_from_rows(
     build_edges_from_terminators(&mut graph, &block_rows, &db_id_to_node)
         .context("Failed to build edges from terminator data")?;
 
+    // Separate panic/abort landing blocks from normal control flow
+    crate::cfg::classify_panic_blocks(&mut graph);
+
     Ok(graph)
 }
 
@@ -1554,6 +2053,7 @@ pub fn resolve_function_name_with_conn(conn: &Connection, name_or_id: &str) -> R
 /// - For native-v3 backend: uses Magellan's KV store via get_cfg_blocks_kv()
 /// - Requires Magellan schema v7+ for cfg_blocks table
 /// - Edges are constructed in memory from terminator data, not queried from cfg_edges table
+#[tracing::instrument(name = "load_cfg", level = "debug", skip(db))]
 pub fn load_cfg_from_db(db: &MirageDb, function_id: i64) -> Result<crate::cfg::Cfg> {
     db.load_cfg(function_id)
 }
@@ -1612,39 +2112,65 @@ pub fn load_cfg_from_db_with_conn(conn: &Connection, function_id: i64) -> Result
 ///
 /// # Algorithm
 ///
-/// 1. Begin IMMEDIATE transaction for atomicity
+/// 1. Open a savepoint for atomicity
 /// 2. Clear existing cfg_blocks and cfg_edges for this function_id (incremental update)
 /// 3. Insert each BasicBlock as a row in cfg_blocks:
 ///    - Serialize terminator as JSON string
 ///    - Store source location byte ranges if available
-/// 4. Insert each edge as a row in cfg_edges (for backward compatibility)
-/// 5. Commit transaction
+/// 4. Insert each edge as a row in cfg_edges with its branch weight (for backward compatibility)
+/// 5. Replace the function's cached dominator tree in cfg_dominators
+/// 6. Release the savepoint
 ///
 /// # Notes
 ///
 /// - DEPRECATED: Magellan handles CFG storage via cfg_blocks. Edges are now computed in memory.
 /// - This function is kept for backward compatibility with existing tests.
-/// - Uses BEGIN IMMEDIATE to acquire write lock early (prevents write conflicts)
-/// - Existing blocks/edges are cleared for incremental updates
+/// - Runs in a savepoint, so it joins the caller's transaction when there is one.
+///   Bulk writers should store many functions inside one transaction instead of
+///   paying for a commit per function. On error the savepoint is rolled back,
+///   leaving no half-written CFG; the caller's earlier work is kept.
+/// - Existing blocks, edges and cached paths are cleared first and the cached
+///   dominator tree is replaced, so re-indexing a function is idempotent
 /// - Block IDs are AUTOINCREMENT in the database
 #[deprecated(note = "Magellan handles CFG storage via cfg_blocks. Edges are computed in memory.")]
+#[tracing::instrument(level = "debug", skip(conn, function_hash, cfg), fields(blocks = cfg.node_count()))]
 pub fn store_cfg(
-    conn: &mut Connection,
+    conn: &Connection,
     function_id: i64,
-    _function_hash: &str,  // Unused: Magellan manages its own caching
+    function_hash: &str,
+    cfg: &crate::cfg::Cfg,
+) -> Result<()> {
+    conn.execute_batch("SAVEPOINT store_cfg")
+        .context("Failed to begin savepoint")?;
+
+    match write_cfg_rows(conn, function_id, function_hash, cfg) {
+        Ok(()) => conn.execute_batch("RELEASE store_cfg")
+            .context("Failed to release savepoint"),
+        Err(e) => {
+            // Undo this function's rows only; the caller's transaction stays open
+            let _ = conn.execute_batch("ROLLBACK TO store_cfg; RELEASE store_cfg");
+            Err(e)
+        }
+    }
+}
+
+/// Body of [`store_cfg`], run inside its savepoint
+fn write_cfg_rows(
+    conn: &Connection,
+    function_id: i64,
+    function_hash: &str,
     cfg: &crate::cfg::Cfg,
 ) -> Result<()> {
     use crate::cfg::{BlockKind, EdgeType, Terminator};
     use petgraph::visit::EdgeRef;
 
-    conn.execute("BEGIN IMMEDIATE TRANSACTION", [])
-        .context("Failed to begin transaction")?;
-
-    // Clear existing blocks and edges for this function (incremental update)
+    // Clear what was stored for the previous version of this function, so a
+    // re-index that shrinks the CFG leaves no stale blocks, edges or paths.
+    // The dominator cache is replaced below.
     conn.execute(
-        "DELETE FROM cfg_edges WHERE from_id IN (
-            SELECT id FROM cfg_blocks WHERE function_id = ?
-         )",
+        "DELETE FROM cfg_edges
+         WHERE from_id IN (SELECT id FROM cfg_blocks WHERE function_id = ?1)
+            OR to_id IN (SELECT id FROM cfg_blocks WHERE function_id = ?1)",
         params![function_id],
     ).context("Failed to clear existing cfg_edges")?;
 
@@ -1653,6 +2179,18 @@ pub fn store_cfg(
         params![function_id],
     ).context("Failed to clear existing cfg_blocks")?;
 
+    // Path elements first: they reference cfg_paths
+    conn.execute(
+        "DELETE FROM cfg_path_elements
+         WHERE path_id IN (SELECT path_id FROM cfg_paths WHERE function_id = ?1)",
+        params![function_id],
+    ).context("Failed to clear existing cfg_path_elements")?;
+
+    conn.execute(
+        "DELETE FROM cfg_paths WHERE function_id = ?",
+        params![function_id],
+    ).context("Failed to clear existing cfg_paths")?;
+
     // Insert each block and collect database IDs
     let mut block_id_map: std::collections::HashMap<petgraph::graph::NodeIndex, i64> =
         std::collections::HashMap::new();
@@ -1698,6 +2236,7 @@ pub fn store_cfg(
             BlockKind::Entry => "entry",
             BlockKind::Normal => "block",
             BlockKind::Exit => "return",
+            BlockKind::Panic => "panic",
         };
 
         insert_block.execute(params![
@@ -1717,8 +2256,9 @@ pub fn store_cfg(
     }
 
     // Insert each edge (for backward compatibility, though edges are now computed in memory)
+    let weights = crate::cfg::compute_edge_weights(cfg);
     let mut insert_edge = conn.prepare_cached(
-        "INSERT INTO cfg_edges (from_id, to_id, edge_type) VALUES (?, ?, ?)",
+        "INSERT INTO cfg_edges (from_id, to_id, edge_type, weight) VALUES (?, ?, ?, ?)",
     ).context("Failed to prepare edge insert statement")?;
 
     for edge in cfg.edge_references() {
@@ -1736,18 +2276,76 @@ pub fn store_cfg(
             EdgeType::Call => "Call",
             EdgeType::Exception => "Exception",
             EdgeType::Return => "Return",
+            EdgeType::Unwind => "Unwind",
+            EdgeType::Resume => "Resume",
         };
 
-        insert_edge.execute(params![from_db_id, to_db_id, edge_type_str])
+        let weight = weights.get(&(cfg[edge.source()].id, cfg[edge.target()].id)).copied();
+
+        insert_edge.execute(params![from_db_id, to_db_id, edge_type_str, weight])
             .context("Failed to insert cfg_edge")?;
     }
 
-    conn.execute("COMMIT", [])
-        .context("Failed to commit transaction")?;
+    // Cache the dominator tree for this function content
+    conn.execute(
+        "DELETE FROM cfg_dominators WHERE function_id = ?",
+        params![function_id],
+    ).context("Failed to clear existing cfg_dominators")?;
+
+    if let Some(tree) = crate::cfg::DominatorTree::new(cfg) {
+        dominators::insert_dominator_rows(conn, function_id, function_hash, cfg, &tree)?;
+    }
 
     Ok(())
 }
 
+/// Load the branch weights stored with a function's CFG edges
+///
+/// Keys use the block IDs of `cfg` as loaded by [`load_cfg_from_db`], which
+/// number the function's `cfg_blocks` rows in `id` order. Edges whose
+/// `weight` is NULL (or that have no `cfg_edges` row, as in databases Magellan
+/// wrote) get uniform weights across their block's successors.
+pub fn load_edge_weights(
+    conn: &Connection,
+    function_id: i64,
+    cfg: &crate::cfg::Cfg,
+) -> Result<crate::cfg::weights::EdgeWeights> {
+    let mut weights = crate::cfg::weights::uniform_edge_weights(cfg);
+
+    let block_ids: std::collections::HashMap<i64, crate::cfg::BlockId> = conn
+        .prepare_cached("SELECT id FROM cfg_blocks WHERE function_id = ? ORDER BY id ASC")
+        .context("Failed to prepare cfg_blocks query")?
+        .query_map(params![function_id], |row| row.get::<_, i64>(0))
+        .context("Failed to query cfg_blocks")?
+        .enumerate()
+        .map(|(block_id, db_id)| db_id.map(|db_id| (db_id, block_id)))
+        .collect::<Result<_, _>>()
+        .context("Failed to collect cfg_blocks rows")?;
+
+    let mut stmt = conn.prepare_cached(
+        "SELECT e.from_id, e.to_id, e.weight
+         FROM cfg_edges e
+         JOIN cfg_blocks b ON b.id = e.from_id
+         WHERE b.function_id = ? AND e.weight IS NOT NULL",
+    ).context("Failed to prepare cfg_edges query")?;
+    let rows = stmt.query_map(params![function_id], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, f64>(2)?))
+    }).context("Failed to query cfg_edges")?;
+
+    for row in rows {
+        let (from_id, to_id, weight) = row.context("Failed to read cfg_edges row")?;
+        let key = block_ids.get(&from_id).zip(block_ids.get(&to_id));
+        if let Some((&from, &to)) = key {
+            // Only edges the loaded CFG actually has
+            if let Some(slot) = weights.get_mut(&(from, to)) {
+                *slot = weight;
+            }
+        }
+    }
+
+    Ok(weights)
+}
+
 /// Check if a function is already indexed in the database
 ///
 /// # Arguments
@@ -2381,7 +2979,7 @@ mod tests {
         cfg.add_edge(b0, b1, EdgeType::Fallthrough);
 
         // Store the CFG
-        store_cfg(&mut conn, function_id, "test_hash_123", &cfg).unwrap();
+        store_cfg(&conn, function_id, "test_hash_123", &cfg).unwrap();
 
         // Verify blocks were stored
         let block_count: i64 = conn.query_row(
@@ -2476,7 +3074,7 @@ mod tests {
         });
         cfg1.add_edge(b0, b1, EdgeType::Fallthrough);
 
-        store_cfg(&mut conn, function_id, "hash_v1", &cfg1).unwrap();
+        store_cfg(&conn, function_id, "hash_v1", &cfg1).unwrap();
 
         let block_count_v1: i64 = conn.query_row(
             "SELECT COUNT(*) FROM cfg_blocks WHERE function_id = ?",
@@ -2512,7 +3110,7 @@ mod tests {
         cfg2.add_edge(b0, b1, EdgeType::Fallthrough);
         cfg2.add_edge(b1, b2, EdgeType::Fallthrough);
 
-        store_cfg(&mut conn, function_id, "hash_v2", &cfg2).unwrap();
+        store_cfg(&conn, function_id, "hash_v2", &cfg2).unwrap();
 
         let block_count_v2: i64 = conn.query_row(
             "SELECT COUNT(*) FROM cfg_blocks WHERE function_id = ?",
@@ -3177,3 +3775,279 @@ mod tests {
         assert_ne!(BackendFormat::NativeV2, BackendFormat::Unknown);
     }
 }
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod store_cfg_tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_schema_v4_numbers_cached_paths() {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        // A v4 database with two cached paths and no path_ordinal column
+        conn.execute_batch(
+            "CREATE TABLE mirage_meta (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                mirage_schema_version INTEGER NOT NULL,
+                magellan_schema_version INTEGER NOT NULL,
+                rustc_version TEXT,
+                created_at INTEGER NOT NULL,
+                path_id_version INTEGER
+            );
+            CREATE TABLE cfg_paths (
+                path_id TEXT PRIMARY KEY,
+                function_id INTEGER NOT NULL,
+                path_kind TEXT NOT NULL,
+                entry_block INTEGER NOT NULL,
+                exit_block INTEGER NOT NULL,
+                length INTEGER NOT NULL,
+                created_at INTEGER NOT NULL,
+                path_id_version INTEGER
+            );
+            CREATE TABLE cfg_path_elements (
+                path_id TEXT NOT NULL,
+                sequence_order INTEGER NOT NULL,
+                block_id INTEGER NOT NULL,
+                PRIMARY KEY (path_id, sequence_order)
+            );
+            INSERT INTO mirage_meta (id, mirage_schema_version, magellan_schema_version, created_at, path_id_version)
+                VALUES (1, 4, 7, 0, 1);
+            INSERT INTO cfg_paths VALUES ('a', 1, 'Normal', 0, 3, 2, 0, 1);
+            INSERT INTO cfg_paths VALUES ('b', 1, 'Normal', 0, 2, 3, 0, 1);
+            INSERT INTO cfg_path_elements VALUES ('a', 0, 0), ('a', 1, 3);
+            INSERT INTO cfg_path_elements VALUES ('b', 0, 0), ('b', 1, 1), ('b', 2, 2);",
+        ).unwrap();
+
+        migrate_schema(&mut conn).unwrap();
+
+        // [0, 1, 2] sorts before [0, 3]
+        assert_eq!(paths::find_path_by_ordinal(&conn, 1, 1).unwrap(), Some("b".to_string()));
+        assert_eq!(paths::find_path_by_ordinal(&conn, 1, 2).unwrap(), Some("a".to_string()));
+
+        let version: i32 = conn.query_row(
+            "SELECT mirage_schema_version FROM mirage_meta WHERE id = 1",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(version, MIRAGE_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_store_cfg_failure_leaves_no_partial_cfg() {
+        use crate::cfg::{BasicBlock, BlockKind, Cfg, EdgeType, SourceLocation, Terminator};
+
+        // A block without a location fails to insert
+        let two_blocks = |second_location: Option<SourceLocation>| {
+            let mut cfg = Cfg::new();
+            let b0 = cfg.add_node(BasicBlock {
+                id: 0,
+                kind: BlockKind::Entry,
+                statements: vec![],
+                terminator: Terminator::Goto { target: 1 },
+                source_location: Some(SourceLocation::new("f.rs", 0, 10, 1, 1, 1, 11)),
+            });
+            let b1 = cfg.add_node(BasicBlock {
+                id: 1,
+                kind: BlockKind::Exit,
+                statements: vec![],
+                terminator: Terminator::Return,
+                source_location: second_location,
+            });
+            cfg.add_edge(b0, b1, EdgeType::Fallthrough);
+            cfg
+        };
+        let good = two_blocks(Some(SourceLocation::new("f.rs", 10, 20, 2, 1, 2, 11)));
+        let bad = two_blocks(None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("partial.db");
+        create_minimal_database(&db_path).unwrap();
+        let mut conn = Connection::open(&db_path).unwrap();
+        conn.execute_batch(
+            "CREATE TRIGGER require_location BEFORE INSERT ON cfg_blocks
+             WHEN NEW.byte_start IS NULL
+             BEGIN SELECT RAISE(ABORT, 'block without location'); END;",
+        ).unwrap();
+        for name in ["first", "second"] {
+            conn.execute(
+                "INSERT INTO graph_entities (kind, name, file_path, data) VALUES ('function', ?, 'f.rs', '{}')",
+                params![name],
+            ).unwrap();
+        }
+        let block_ids = |conn: &Connection, function_id: i64| -> Vec<i64> {
+            conn.prepare("SELECT id FROM cfg_blocks WHERE function_id = ? ORDER BY id").unwrap()
+                .query_map(params![function_id], |row| row.get(0)).unwrap()
+                .map(|id| id.unwrap())
+                .collect()
+        };
+
+        // A failed re-store keeps the previous CFG, not a mix of old and new rows
+        store_cfg(&conn, 1, "v1", &good).unwrap();
+        let stored = block_ids(&conn, 1);
+        assert_eq!(stored.len(), 2);
+        assert!(store_cfg(&conn, 1, "v2", &bad).is_err());
+        assert_eq!(block_ids(&conn, 1), stored);
+
+        // Inside a caller's transaction, only the failed function is undone
+        let tx = conn.transaction().unwrap();
+        store_cfg(&tx, 2, "v1", &good).unwrap();
+        assert!(store_cfg(&tx, 1, "v2", &bad).is_err());
+        tx.commit().unwrap();
+        assert_eq!(block_ids(&conn, 1), stored);
+        assert_eq!(block_ids(&conn, 2).len(), 2);
+    }
+
+    #[test]
+    fn test_migrate_schema_v1_adds_edge_weight_column() {
+        let mut conn = Connection::open_in_memory().unwrap();
+
+        // A v1 database: mirage_meta at version 1 and cfg_edges without weight
+        conn.execute(
+            "CREATE TABLE mirage_meta (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                mirage_schema_version INTEGER NOT NULL,
+                magellan_schema_version INTEGER NOT NULL,
+                rustc_version TEXT,
+                created_at INTEGER NOT NULL
+            )",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO mirage_meta (id, mirage_schema_version, magellan_schema_version, created_at)
+             VALUES (1, 1, ?, 0)",
+            params![REQUIRED_MAGELLAN_SCHEMA_VERSION],
+        ).unwrap();
+        conn.execute(
+            "CREATE TABLE cfg_edges (
+                from_id INTEGER NOT NULL,
+                to_id INTEGER NOT NULL,
+                edge_type TEXT NOT NULL
+            )",
+            [],
+        ).unwrap();
+
+        migrate_schema(&mut conn).unwrap();
+
+        let weight_columns: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('cfg_edges') WHERE name = 'weight'",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(weight_columns, 1);
+
+        let version: i32 = conn.query_row(
+            "SELECT mirage_schema_version FROM mirage_meta WHERE id = 1",
+            [],
+            |row| row.get(0),
+        ).unwrap();
+        assert_eq!(version, MIRAGE_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_load_edge_weights_reads_stored_weights() {
+        use crate::cfg::{BasicBlock, BlockKind, Cfg, EdgeType, SourceLocation, Terminator};
+
+        // Three switch values go to block 1, only `otherwise` to block 2
+        let mut cfg = Cfg::new();
+        let block = |id, kind, terminator| BasicBlock {
+            id,
+            kind,
+            statements: vec![],
+            terminator,
+            source_location: Some(SourceLocation::new("f.rs", id * 10, id * 10 + 9, id + 1, 1, id + 1, 10)),
+        };
+        let b0 = cfg.add_node(block(0, BlockKind::Entry, Terminator::SwitchInt { targets: vec![1, 1, 1], otherwise: 2 }));
+        let b1 = cfg.add_node(block(1, BlockKind::Exit, Terminator::Return));
+        let b2 = cfg.add_node(block(2, BlockKind::Exit, Terminator::Return));
+        cfg.add_edge(b0, b1, EdgeType::TrueBranch);
+        cfg.add_edge(b0, b2, EdgeType::FalseBranch);
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("weights.db");
+        create_minimal_database(&db_path).unwrap();
+        let conn = Connection::open(&db_path).unwrap();
+        conn.execute(
+            "INSERT INTO graph_entities (kind, name, file_path, data) VALUES ('function', 'f', 'f.rs', '{}')",
+            [],
+        ).unwrap();
+        store_cfg(&conn, 1, "v1", &cfg).unwrap();
+
+        // The loaded CFG has lost the switch values; the stored weights keep them
+        let loaded = load_cfg_from_db_with_conn(&conn, 1).unwrap();
+        let weights = load_edge_weights(&conn, 1, &loaded).unwrap();
+        assert_eq!(weights.get(&(0, 1)), Some(&0.75));
+        assert_eq!(weights.get(&(0, 2)), Some(&0.25));
+
+        // NULL weights are uniform
+        conn.execute("UPDATE cfg_edges SET weight = NULL", []).unwrap();
+        let weights = load_edge_weights(&conn, 1, &loaded).unwrap();
+        assert_eq!(weights.get(&(0, 1)), Some(&0.5));
+        assert_eq!(weights.get(&(0, 2)), Some(&0.5));
+    }
+
+    #[test]
+    fn test_store_cfg_shrinking_function_leaves_no_stale_rows() {
+        use crate::cfg::{enumerate_paths, BasicBlock, BlockKind, Cfg, DominatorTree, EdgeType, PathLimits, SourceLocation, Terminator};
+
+        let linear = |len: usize| {
+            let mut cfg = Cfg::new();
+            let nodes: Vec<_> = (0..len)
+                .map(|id| {
+                    let (kind, terminator) = match id {
+                        0 => (BlockKind::Entry, Terminator::Goto { target: 1 }),
+                        _ if id + 1 == len => (BlockKind::Exit, Terminator::Return),
+                        _ => (BlockKind::Normal, Terminator::Goto { target: id + 1 }),
+                    };
+                    let line = id + 1;
+                    cfg.add_node(BasicBlock {
+                        id,
+                        kind,
+                        statements: vec![],
+                        terminator,
+                        source_location: Some(SourceLocation::new("f.rs", id * 10, id * 10 + 9, line, 1, line, 10)),
+                    })
+                })
+                .collect();
+            for pair in nodes.windows(2) {
+                cfg.add_edge(pair[0], pair[1], EdgeType::Fallthrough);
+            }
+            cfg
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("shrink.db");
+        create_minimal_database(&db_path).unwrap();
+        let mut conn = Connection::open(&db_path).unwrap();
+        conn.execute(
+            "INSERT INTO graph_entities (kind, name, file_path, data) VALUES ('function', 'f', 'f.rs', '{}')",
+            [],
+        ).unwrap();
+
+        let before = linear(5);
+        store_cfg(&conn, 1, "v1", &before).unwrap();
+        paths::store_paths(&mut conn, 1, &enumerate_paths(&before, &PathLimits::default())).unwrap();
+        dominators::store_dominators(&mut conn, 1, "v1", &before, &DominatorTree::new(&before).unwrap()).unwrap();
+
+        store_cfg(&conn, 1, "v2", &linear(3)).unwrap();
+
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM cfg_blocks WHERE function_id = 1"), 3, "Not 5 + 3");
+        assert_eq!(count("SELECT COUNT(*) FROM cfg_edges"), 2);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM cfg_edges
+                   WHERE from_id NOT IN (SELECT id FROM cfg_blocks) OR to_id NOT IN (SELECT id FROM cfg_blocks)"),
+            0,
+            "No dangling edges"
+        );
+        assert_eq!(count("SELECT COUNT(*) FROM cfg_paths"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM cfg_path_elements"), 0);
+        assert_eq!(count("SELECT COUNT(*) FROM cfg_dominators"), 3, "Only the new CFG's dominator tree");
+        assert_eq!(count("SELECT COUNT(*) FROM cfg_dominators WHERE block_id >= 3 OR dominator_id >= 3"), 0);
+
+        // Storing the same CFG again changes nothing
+        store_cfg(&conn, 1, "v2", &linear(3)).unwrap();
+        assert_eq!(count("SELECT COUNT(*) FROM cfg_blocks WHERE function_id = 1"), 3);
+        assert_eq!(count("SELECT COUNT(*) FROM cfg_edges"), 2);
+    }
+}
@@ -4,12 +4,18 @@
 //! Paths are stored with their BLAKE3 hash as the primary key, providing automatic
 //! deduplication and tamper detection. Cache entries are invalidated when function
 //! content changes (detected via function_hash comparison).
+//!
+//! Each cached path also records the path_id scheme it was hashed with
+//! ([`PATH_ID_VERSION`]), as does `mirage_meta`. A cache written by another
+//! scheme is treated as a miss, so callers re-enumerate instead of getting
+//! path ids that no longer match.
 
 use anyhow::{Context, Result};
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
 use std::collections::HashMap;
 
-use crate::cfg::{BlockId, Path, PathKind};
+use crate::cfg::{BlockId, Path, PathKind, PATH_ID_VERSION};
+use crate::cfg::paths::path_ordinals;
 
 /// Path cache manager (placeholder for future cache management features)
 ///
@@ -57,6 +63,7 @@ impl Default for PathCache {
 ///
 /// Uses IMMEDIATE transaction mode to prevent write conflicts in concurrent access.
 /// Transaction is automatically rolled back on error.
+#[tracing::instrument(level = "debug", skip(conn, paths), fields(paths = paths.len()))]
 pub fn store_paths(conn: &mut Connection, function_id: i64, paths: &[Path]) -> Result<()> {
     // Begin transaction for atomicity
     conn.execute("BEGIN IMMEDIATE TRANSACTION", [])
@@ -64,8 +71,8 @@ pub fn store_paths(conn: &mut Connection, function_id: i64, paths: &[Path]) -> R
 
     // Prepare insert statements for efficiency
     let mut insert_path_stmt = conn.prepare_cached(
-        "INSERT INTO cfg_paths (path_id, function_id, path_kind, entry_block, exit_block, length, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        "INSERT INTO cfg_paths (path_id, function_id, path_kind, entry_block, exit_block, length, created_at, path_id_version, path_ordinal)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
     ).context("Failed to prepare cfg_paths insert statement")?;
 
     let mut insert_element_stmt = conn.prepare_cached(
@@ -75,7 +82,7 @@ pub fn store_paths(conn: &mut Connection, function_id: i64, paths: &[Path]) -> R
 
     let now = chrono::Utc::now().timestamp();
 
-    for path in paths {
+    for (path, ordinal) in paths.iter().zip(path_ordinals(paths)) {
         // Convert PathKind to string for storage
         let kind_str = path_kind_to_str(path.kind);
 
@@ -88,6 +95,8 @@ pub fn store_paths(conn: &mut Connection, function_id: i64, paths: &[Path]) -> R
             path.exit as i64,
             path.len() as i64,
             now,
+            PATH_ID_VERSION,
+            ordinal as i64,
         ]).with_context(|| format!("Failed to insert path {}", path.path_id))?;
 
         // Insert each block in the path
@@ -103,6 +112,8 @@ pub fn store_paths(conn: &mut Connection, function_id: i64, paths: &[Path]) -> R
         }
     }
 
+    record_path_id_version(conn)?;
+
     // Commit transaction
     conn.execute("COMMIT", [])
         .context("Failed to commit transaction for store_paths")?;
@@ -170,14 +181,14 @@ pub fn store_paths_batch(conn: &mut Connection, function_id: i64, paths: &[Path]
 
     let now = chrono::Utc::now().timestamp();
 
-    for path in paths {
+    for (path, ordinal) in paths.iter().zip(path_ordinals(paths)) {
         let kind_str = path_kind_to_str(path.kind);
 
         // Insert path metadata
         {
             let mut insert_path_stmt = conn.prepare_cached(
-                "INSERT INTO cfg_paths (path_id, function_id, path_kind, entry_block, exit_block, length, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                "INSERT INTO cfg_paths (path_id, function_id, path_kind, entry_block, exit_block, length, created_at, path_id_version, path_ordinal)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             ).context("Failed to prepare cfg_paths insert statement")?;
 
             insert_path_stmt.execute(params![
@@ -188,6 +199,8 @@ pub fn store_paths_batch(conn: &mut Connection, function_id: i64, paths: &[Path]
                 path.exit as i64,
                 path.len() as i64,
                 now,
+                PATH_ID_VERSION,
+                ordinal as i64,
             ]).with_context(|| format!("Failed to insert path {}", path.path_id))?;
         }
 
@@ -199,6 +212,8 @@ pub fn store_paths_batch(conn: &mut Connection, function_id: i64, paths: &[Path]
     let _ = conn.execute(&format!("PRAGMA synchronous = {}", old_sync), []);
     // Note: journal_mode setting is left as-is since we can't reliably restore it
 
+    record_path_id_version(conn)?;
+
     // Commit transaction
     conn.execute("COMMIT", [])
         .context("Failed to commit transaction for store_paths_batch")?;
@@ -291,7 +306,18 @@ fn str_to_path_kind(s: &str) -> Result<PathKind> {
 /// # Empty Result
 ///
 /// Returns Ok(vec![]) for cache miss (no paths stored), not an error.
+///
+/// # Path ID Scheme
+///
+/// Paths hashed with a different path_id scheme than [`PATH_ID_VERSION`] are
+/// a cache miss too: the function's stale paths are deleted and Ok(vec![]) is
+/// returned, so the caller re-enumerates them.
 pub fn get_cached_paths(conn: &mut Connection, function_id: i64) -> Result<Vec<Path>> {
+    if !path_id_version_is_current(conn, function_id)? {
+        invalidate_function_paths(conn, function_id)?;
+        return Ok(vec![]);
+    }
+
     // Query paths and their elements
     let mut stmt = conn.prepare_cached(
         "SELECT p.path_id, p.path_kind, p.entry_block, p.exit_block,
@@ -345,6 +371,85 @@ pub fn get_cached_paths(conn: &mut Connection, function_id: i64) -> Result<Vec<P
     Ok(paths)
 }
 
+/// Number of cached paths per function, for every function with any
+pub fn cached_path_counts(conn: &Connection) -> Result<HashMap<i64, usize>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT function_id, COUNT(*) FROM cfg_paths GROUP BY function_id",
+    ).context("Failed to prepare cached_path_counts query")?;
+
+    let counts = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)? as usize))
+    }).context("Failed to execute cached_path_counts query")?
+        .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+    Ok(counts)
+}
+
+/// When each function's paths were last cached, as Unix seconds
+///
+/// Maps function_id to the newest `created_at` among its cached paths. With
+/// `since`, only functions cached at or after that time are returned.
+pub fn paths_cached_since(conn: &Connection, since: Option<i64>) -> Result<HashMap<i64, i64>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT function_id, MAX(created_at) FROM cfg_paths
+         GROUP BY function_id
+         HAVING ?1 IS NULL OR MAX(created_at) >= ?1",
+    ).context("Failed to prepare paths_cached_since query")?;
+
+    let times = stmt.query_map(params![since], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+    }).context("Failed to execute paths_cached_since query")?
+        .collect::<rusqlite::Result<HashMap<_, _>>>()?;
+    Ok(times)
+}
+
+/// Path ID of a function's cached path with the given ordinal
+///
+/// Ordinals are the `N` of readable path IDs (`function#pN`), assigned by
+/// [`path_ordinals`] when the paths were stored. Returns `None` when no
+/// cached path has that ordinal.
+pub fn find_path_by_ordinal(conn: &Connection, function_id: i64, ordinal: usize) -> Result<Option<String>> {
+    let path_id = conn.query_row(
+        "SELECT path_id FROM cfg_paths WHERE function_id = ?1 AND path_ordinal = ?2",
+        params![function_id, ordinal as i64],
+        |row| row.get::<_, String>(0),
+    ).optional().context("Failed to look up path by ordinal")?;
+    Ok(path_id)
+}
+
+/// path_id scheme recorded in mirage_meta, if any
+pub fn cached_path_id_version(conn: &Connection) -> Result<Option<i64>> {
+    let version = conn.query_row(
+        "SELECT path_id_version FROM mirage_meta WHERE id = 1",
+        [],
+        |row| row.get::<_, Option<i64>>(0),
+    ).optional()?.flatten();
+    Ok(version)
+}
+
+/// Record the current path_id scheme in mirage_meta
+fn record_path_id_version(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "UPDATE mirage_meta SET path_id_version = ?1 WHERE id = 1",
+        params![PATH_ID_VERSION],
+    ).context("Failed to record path_id_version")?;
+    Ok(())
+}
+
+/// Check that mirage_meta and every cached path of a function use the current scheme
+fn path_id_version_is_current(conn: &Connection, function_id: i64) -> Result<bool> {
+    if cached_path_id_version(conn)?.is_some_and(|version| version != PATH_ID_VERSION) {
+        return Ok(false);
+    }
+
+    let stale: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM cfg_paths
+         WHERE function_id = ?1 AND path_id_version IS NOT ?2",
+        params![function_id, PATH_ID_VERSION],
+        |row| row.get(0),
+    ).context("Failed to check path_id_version of cached paths")?;
+    Ok(stale == 0)
+}
+
 /// Helper struct for reconstructing paths from database rows
 struct PathData {
     path_id: String,
@@ -568,6 +673,65 @@ mod tests {
         assert_eq!(element_count, 8, "Should have 8 elements (3+3+2)");
     }
 
+    #[test]
+    fn test_cached_path_counts() {
+        let mut conn = create_test_db();
+        assert!(cached_path_counts(&conn).unwrap().is_empty());
+
+        store_paths(&mut conn, 1, &create_mock_paths()).unwrap();
+        let counts = cached_path_counts(&conn).unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[&1], 3);
+    }
+
+    #[test]
+    fn test_paths_cached_since() {
+        let mut conn = create_test_db();
+        for name in ["second", "third"] {
+            conn.execute(
+                "INSERT INTO graph_entities (kind, name, file_path, data) VALUES ('function', ?, 'test.rs', '{}')",
+                params![name],
+            ).unwrap();
+        }
+        let paths = create_mock_paths();
+        store_paths(&mut conn, 1, &paths[..2]).unwrap();
+        store_paths(&mut conn, 2, &paths[2..]).unwrap();
+        store_paths(&mut conn, 3, &[Path::new(vec![5, 6], PathKind::Normal)]).unwrap();
+
+        // Function 1 was cached twice; its newest path counts
+        conn.execute("UPDATE cfg_paths SET created_at = 1000 WHERE function_id = 1", []).unwrap();
+        conn.execute("UPDATE cfg_paths SET created_at = 2500 WHERE path_id = ?", params![paths[1].path_id]).unwrap();
+        conn.execute("UPDATE cfg_paths SET created_at = 2000 WHERE function_id = 2", []).unwrap();
+        conn.execute("UPDATE cfg_paths SET created_at = 3000 WHERE function_id = 3", []).unwrap();
+
+        let all = paths_cached_since(&conn, None).unwrap();
+        assert_eq!(all, HashMap::from([(1, 2500), (2, 2000), (3, 3000)]));
+
+        let recent = paths_cached_since(&conn, Some(2500)).unwrap();
+        assert_eq!(recent, HashMap::from([(1, 2500), (3, 3000)]));
+        assert!(paths_cached_since(&conn, Some(3001)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_path_by_ordinal() {
+        let mut conn = create_test_db();
+        let paths = create_mock_paths();
+        store_paths(&mut conn, 1, &paths).unwrap();
+
+        // Ordinals follow block order: [0, 1, 2] < [0, 1, 3] < [0, 2]
+        assert_eq!(find_path_by_ordinal(&conn, 1, 1).unwrap(), Some(paths[0].path_id.clone()));
+        assert_eq!(find_path_by_ordinal(&conn, 1, 3).unwrap(), Some(paths[2].path_id.clone()));
+        assert_eq!(find_path_by_ordinal(&conn, 1, 4).unwrap(), None);
+        assert_eq!(find_path_by_ordinal(&conn, 2, 1).unwrap(), None);
+
+        // The batch writer assigns the same ordinals, whatever the input order
+        let reversed: Vec<Path> = paths.iter().rev().cloned().collect();
+        invalidate_function_paths(&mut conn, 1).unwrap();
+        store_paths_batch(&mut conn, 1, &reversed).unwrap();
+        assert_eq!(find_path_by_ordinal(&conn, 1, 1).unwrap(), Some(paths[0].path_id.clone()));
+        assert_eq!(find_path_by_ordinal(&conn, 1, 2).unwrap(), Some(paths[1].path_id.clone()));
+    }
+
     #[test]
     fn test_store_paths_path_metadata() {
         let mut conn = create_test_db();
@@ -778,9 +942,9 @@ mod tests {
 
         // Insert a path with invalid kind directly
         conn.execute(
-            "INSERT INTO cfg_paths (path_id, function_id, path_kind, entry_block, exit_block, length, created_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
-            params!("invalid_path_id", function_id, "InvalidKind", 0, 0, 1, 0),
+            "INSERT INTO cfg_paths (path_id, function_id, path_kind, entry_block, exit_block, length, created_at, path_id_version)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params!("invalid_path_id", function_id, "InvalidKind", 0, 0, 1, 0, PATH_ID_VERSION),
         ).unwrap();
 
         // Insert a path element so the JOIN returns rows
@@ -826,6 +990,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_cached_paths_invalidated_by_path_id_version() {
+        let mut conn = create_test_db();
+        let function_id: i64 = 1;
+        let count = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM cfg_paths", [], |row| row.get(0)).unwrap()
+        };
+
+        store_paths(&mut conn, function_id, &create_mock_paths()).unwrap();
+        assert_eq!(cached_path_id_version(&conn).unwrap(), Some(PATH_ID_VERSION));
+        assert_eq!(get_cached_paths(&mut conn, function_id).unwrap().len(), 3);
+
+        // mirage_meta says the cache was written by another scheme
+        conn.execute("UPDATE mirage_meta SET path_id_version = ?", params![PATH_ID_VERSION + 1]).unwrap();
+        assert!(get_cached_paths(&mut conn, function_id).unwrap().is_empty());
+        assert_eq!(count(&conn), 0, "Stale paths are deleted");
+
+        // Storing again records the current scheme and hits the cache
+        store_paths(&mut conn, function_id, &create_mock_paths()).unwrap();
+        assert_eq!(cached_path_id_version(&conn).unwrap(), Some(PATH_ID_VERSION));
+        assert_eq!(get_cached_paths(&mut conn, function_id).unwrap().len(), 3);
+
+        // A single path hashed with another scheme invalidates the function's cache
+        conn.execute("UPDATE cfg_paths SET path_id_version = NULL WHERE rowid = (SELECT MIN(rowid) FROM cfg_paths)", []).unwrap();
+        assert!(get_cached_paths(&mut conn, function_id).unwrap().is_empty());
+        assert_eq!(count(&conn), 0);
+    }
+
     // Task 4: invalidate_function_paths tests
 
     #[test]
@@ -0,0 +1,298 @@
+//! Garbage collection of orphaned CFG rows
+//!
+//! Re-indexing and function deletion can leave `cfg_*` rows whose
+//! `function_id` no longer exists in `graph_entities`. Nothing reads them
+//! again, so they only grow the database. `prune` deletes them, along with
+//! edges between deleted blocks and the elements of deleted paths.
+//!
+//! With `stale_paths`, cached paths that no longer match their function are
+//! dropped too. When both `cfg_paths` and `cfg_blocks` carry a
+//! `function_hash` column, a path is stale if its hash differs from the
+//! function's current one. Magellan's schema has neither column, so there a
+//! path is stale if it visits a block index the function no longer has.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+/// Rows removed (or, for a dry run, that would be removed) per table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PruneReport {
+    pub cfg_blocks: usize,
+    pub cfg_edges: usize,
+    pub cfg_paths: usize,
+    /// Paths removed by `stale_paths` (not included in `cfg_paths`)
+    pub stale_paths: usize,
+    pub cfg_path_elements: usize,
+    pub cfg_dominators: usize,
+}
+
+impl PruneReport {
+    /// Total number of rows across all tables
+    pub fn total(&self) -> usize {
+        self.cfg_blocks + self.cfg_edges + self.cfg_paths + self.stale_paths + self.cfg_path_elements + self.cfg_dominators
+    }
+}
+
+/// Delete orphaned CFG rows, and stale cached paths if requested
+///
+/// Runs in one transaction. With `dry_run`, the deletions are rolled back, so
+/// the report shows exactly what a real run would remove. Tables missing from
+/// the database are skipped.
+pub fn prune(conn: &mut Connection, stale_paths: bool, dry_run: bool) -> Result<PruneReport> {
+    let tx = conn.transaction().context("Failed to begin prune transaction")?;
+    let mut report = PruneReport::default();
+
+    let has_blocks = table_exists(&tx, "cfg_blocks")?;
+    let has_paths = table_exists(&tx, "cfg_paths")?;
+
+    // Edges first: they reference blocks
+    if has_blocks && table_exists(&tx, "cfg_edges")? {
+        report.cfg_edges = tx.execute(
+            "DELETE FROM cfg_edges
+             WHERE from_id NOT IN (SELECT id FROM cfg_blocks WHERE function_id IN (SELECT id FROM graph_entities))
+                OR to_id NOT IN (SELECT id FROM cfg_blocks WHERE function_id IN (SELECT id FROM graph_entities))",
+            [],
+        )?;
+    }
+
+    if has_paths {
+        let orphaned = path_ids(
+            &tx,
+            "SELECT path_id FROM cfg_paths WHERE function_id NOT IN (SELECT id FROM graph_entities)",
+        )?;
+        let (paths, elements) = delete_paths(&tx, &orphaned)?;
+        report.cfg_paths = paths;
+        report.cfg_path_elements += elements;
+
+        if stale_paths && has_blocks {
+            let stale = find_stale_paths(&tx)?;
+            let (paths, elements) = delete_paths(&tx, &stale)?;
+            report.stale_paths = paths;
+            report.cfg_path_elements += elements;
+        }
+    }
+
+    if table_exists(&tx, "cfg_path_elements")? {
+        let cascade = if has_paths {
+            "DELETE FROM cfg_path_elements WHERE path_id NOT IN (SELECT path_id FROM cfg_paths)"
+        } else {
+            "DELETE FROM cfg_path_elements"
+        };
+        report.cfg_path_elements += tx.execute(cascade, [])?;
+    }
+
+    if table_exists(&tx, "cfg_dominators")? {
+        // Legacy rows without a function_id are never looked up; leave them to migration
+        report.cfg_dominators = tx.execute(
+            "DELETE FROM cfg_dominators
+             WHERE function_id IS NOT NULL AND function_id NOT IN (SELECT id FROM graph_entities)",
+            [],
+        )?;
+    }
+
+    if has_blocks {
+        report.cfg_blocks = tx.execute(
+            "DELETE FROM cfg_blocks WHERE function_id NOT IN (SELECT id FROM graph_entities)",
+            [],
+        )?;
+    }
+
+    if dry_run {
+        tx.rollback().context("Failed to roll back dry-run prune")?;
+    } else {
+        tx.commit().context("Failed to commit prune")?;
+    }
+
+    Ok(report)
+}
+
+/// Path IDs of cached paths that no longer match their function
+fn find_stale_paths(conn: &Connection) -> Result<Vec<String>> {
+    if column_exists(conn, "cfg_paths", "function_hash")? && column_exists(conn, "cfg_blocks", "function_hash")? {
+        return path_ids(
+            conn,
+            "SELECT path_id FROM cfg_paths p
+             WHERE p.function_hash IS NOT (
+                 SELECT function_hash FROM cfg_blocks WHERE function_id = p.function_id LIMIT 1
+             )",
+        );
+    }
+
+    // No hashes: block IDs in paths index the function's blocks in ID order
+    path_ids(
+        conn,
+        "SELECT p.path_id FROM cfg_paths p
+         WHERE max(p.entry_block, p.exit_block,
+                   COALESCE((SELECT max(block_id) FROM cfg_path_elements e WHERE e.path_id = p.path_id), 0))
+               >= (SELECT COUNT(*) FROM cfg_blocks b WHERE b.function_id = p.function_id)",
+    )
+}
+
+/// Delete paths and their elements; returns (paths, elements) removed
+fn delete_paths(conn: &Connection, path_ids: &[String]) -> Result<(usize, usize)> {
+    let has_elements = table_exists(conn, "cfg_path_elements")?;
+    let mut paths = 0;
+    let mut elements = 0;
+    for path_id in path_ids {
+        if has_elements {
+            elements += conn.execute("DELETE FROM cfg_path_elements WHERE path_id = ?", params![path_id])?;
+        }
+        paths += conn.execute("DELETE FROM cfg_paths WHERE path_id = ?", params![path_id])?;
+    }
+    Ok((paths, elements))
+}
+
+fn path_ids(conn: &Connection, query: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(query)?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(ids)
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+        params![table],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+fn column_exists(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let count: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?",
+        params![table, column],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Database with one live function (id 1, three blocks) and rows for a deleted function 99
+    fn create_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE graph_entities (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                file_path TEXT,
+                data TEXT NOT NULL
+            );
+            CREATE TABLE cfg_blocks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                function_id INTEGER NOT NULL,
+                kind TEXT NOT NULL,
+                terminator TEXT NOT NULL
+            );
+            CREATE TABLE cfg_edges (
+                from_id INTEGER NOT NULL,
+                to_id INTEGER NOT NULL,
+                edge_type TEXT NOT NULL
+            );
+            CREATE TABLE cfg_paths (
+                path_id TEXT PRIMARY KEY,
+                function_id INTEGER NOT NULL,
+                path_kind TEXT NOT NULL,
+                entry_block INTEGER NOT NULL,
+                exit_block INTEGER NOT NULL,
+                length INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE cfg_path_elements (
+                path_id TEXT NOT NULL,
+                sequence_order INTEGER NOT NULL,
+                block_id INTEGER NOT NULL
+            );
+
+            INSERT INTO graph_entities (id, kind, name, data) VALUES (1, 'Symbol', 'live', '{}');
+            INSERT INTO cfg_blocks (id, function_id, kind, terminator) VALUES
+                (1, 1, 'entry', 'fallthrough'), (2, 1, 'normal', 'conditional'), (3, 1, 'return', 'return'),
+                (4, 99, 'entry', 'fallthrough'), (5, 99, 'return', 'return');
+            INSERT INTO cfg_edges (from_id, to_id, edge_type) VALUES
+                (1, 2, 'fallthrough'), (2, 3, 'true'), (4, 5, 'fallthrough');
+            INSERT INTO cfg_paths VALUES
+                ('live', 1, 'Normal', 0, 2, 3, 0),
+                ('gone', 99, 'Normal', 0, 1, 2, 0);
+            INSERT INTO cfg_path_elements VALUES
+                ('live', 0, 0), ('live', 1, 1), ('live', 2, 2),
+                ('gone', 0, 0), ('gone', 1, 1),
+                ('dangling', 0, 0);",
+        ).unwrap();
+        conn
+    }
+
+    fn count(conn: &Connection, table: &str) -> usize {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0))
+            .unwrap() as usize
+    }
+
+    #[test]
+    fn test_prune_removes_exactly_orphaned_rows() {
+        let mut conn = create_db();
+
+        let expected = PruneReport {
+            cfg_blocks: 2,
+            cfg_edges: 1,
+            cfg_paths: 1,
+            stale_paths: 0,
+            cfg_path_elements: 3,
+            cfg_dominators: 0,
+        };
+
+        // Dry run reports the same counts but changes nothing
+        assert_eq!(prune(&mut conn, false, true).unwrap(), expected);
+        assert_eq!(count(&conn, "cfg_blocks"), 5);
+        assert_eq!(count(&conn, "cfg_path_elements"), 6);
+
+        assert_eq!(prune(&mut conn, false, false).unwrap(), expected);
+        let live_blocks: Vec<i64> = conn
+            .prepare("SELECT id FROM cfg_blocks ORDER BY id").unwrap()
+            .query_map([], |row| row.get(0)).unwrap()
+            .map(|id| id.unwrap())
+            .collect();
+        assert_eq!(live_blocks, vec![1, 2, 3]);
+        assert_eq!(count(&conn, "cfg_edges"), 2);
+        assert_eq!(count(&conn, "cfg_paths"), 1);
+        assert_eq!(count(&conn, "cfg_path_elements"), 3);
+
+        // Nothing left to prune
+        assert_eq!(prune(&mut conn, false, false).unwrap().total(), 0);
+    }
+
+    #[test]
+    fn test_prune_stale_paths() {
+        let mut conn = create_db();
+        prune(&mut conn, false, false).unwrap();
+
+        // The live function shrinks to two blocks; its cached path visits block 2
+        conn.execute("DELETE FROM cfg_blocks WHERE id = 3", []).unwrap();
+        conn.execute("DELETE FROM cfg_edges WHERE to_id = 3", []).unwrap();
+        assert_eq!(prune(&mut conn, false, false).unwrap().stale_paths, 0, "Only with stale_paths");
+
+        let report = prune(&mut conn, true, false).unwrap();
+        assert_eq!(report.stale_paths, 1);
+        assert_eq!(report.cfg_path_elements, 3);
+        assert_eq!(count(&conn, "cfg_paths"), 0);
+    }
+
+    #[test]
+    fn test_prune_stale_paths_by_function_hash() {
+        let mut conn = create_db();
+        conn.execute_batch(
+            "ALTER TABLE cfg_blocks ADD COLUMN function_hash TEXT;
+             ALTER TABLE cfg_paths ADD COLUMN function_hash TEXT;
+             UPDATE cfg_blocks SET function_hash = 'new';
+             UPDATE cfg_paths SET function_hash = 'old';",
+        ).unwrap();
+
+        let report = prune(&mut conn, true, false).unwrap();
+        assert_eq!(report.cfg_paths, 1, "Orphaned path");
+        assert_eq!(report.stale_paths, 1, "Live path with an outdated hash");
+    }
+}
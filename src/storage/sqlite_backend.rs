@@ -22,11 +22,15 @@
 //! ```
 
 use anyhow::Result;
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OpenFlags, params};
 use std::path::Path;
 
 use super::{CfgBlockData, StorageTrait};
 
+/// SQL condition selecting function entities (see `StorageTrait::function_ids`)
+const FUNCTION_ENTITY_FILTER: &str =
+    "kind = 'function' OR (kind = 'Symbol' AND json_extract(data, '$.kind') = 'Function')";
+
 /// SQLite backend implementation
 ///
 /// Wraps a rusqlite Connection and implements StorageTrait
@@ -63,6 +67,16 @@ impl SqliteStorage {
         Ok(Self { conn })
     }
 
+    /// Open SQLite database read-only
+    ///
+    /// Queries work as with `open`, but the connection never takes a write
+    /// lock, so it does not block (or get blocked by) a concurrent writer.
+    pub fn open_readonly(db_path: &Path) -> Result<Self> {
+        let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| anyhow::anyhow!("Failed to open SQLite database read-only: {}", e))?;
+        Ok(Self { conn })
+    }
+
     /// Get a reference to the underlying Connection
     ///
     /// This is useful for legacy code that needs direct SQL access.
@@ -152,6 +166,31 @@ impl StorageTrait for SqliteStorage {
             .ok()
     }
 
+    /// List functions from graph_entities, ordered by name then ID
+    fn function_ids(&self) -> Result<Vec<(i64, String)>> {
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT id, name FROM graph_entities WHERE {} ORDER BY name, id",
+            FUNCTION_ENTITY_FILTER
+        )).map_err(|e| anyhow::anyhow!("Failed to prepare function list query: {}", e))?;
+
+        let functions = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| anyhow::anyhow!("Failed to execute function list query: {}", e))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow::anyhow!("Failed to read function row: {}", e))?;
+        Ok(functions)
+    }
+
+    /// Count functions without loading their names
+    fn function_count(&self) -> Result<usize> {
+        let count: i64 = self.conn.query_row(
+            &format!("SELECT COUNT(*) FROM graph_entities WHERE {}", FUNCTION_ENTITY_FILTER),
+            [],
+            |row| row.get(0),
+        ).map_err(|e| anyhow::anyhow!("Failed to count functions: {}", e))?;
+        Ok(count as usize)
+    }
+
     /// Get cached paths for a function from SQLite backend
     ///
     /// Queries the cfg_paths table for cached enumerated paths.
@@ -320,4 +359,47 @@ mod tests {
         let entity = storage.get_entity(999);
         assert!(entity.is_none(), "Should return None for non-existent entity");
     }
+
+    #[test]
+    fn test_sqlite_storage_function_ids_match_graph_enumeration() {
+        use sqlitegraph::{GraphBackend, NodeSpec, SqliteGraphBackend};
+
+        let temp_file = create_test_db();
+        let extra = [
+            ("function", "legacy_fn", "{}"),
+            ("Symbol", "Config", "{\"kind\": \"Struct\"}"),
+            ("Symbol", "alpha", "{\"kind\": \"Function\"}"),
+        ];
+        {
+            let conn = Connection::open(temp_file.path()).unwrap();
+            for (kind, name, data) in extra {
+                conn.execute(
+                    "INSERT INTO graph_entities (kind, name, file_path, data) VALUES (?, ?, '/tmp/test.rs', ?)",
+                    params![kind, name, data],
+                ).unwrap();
+            }
+        }
+        let storage = SqliteStorage::open(temp_file.path()).unwrap();
+
+        let functions = storage.function_ids().unwrap();
+        assert_eq!(
+            functions,
+            vec![(4, "alpha".to_string()), (2, "legacy_fn".to_string()), (1, "test_function".to_string())],
+            "Both function conventions are listed, by name; the struct is not"
+        );
+        assert_eq!(storage.function_count().unwrap(), 3);
+
+        // The entity-walking fallback used by native-v3 agrees on the same entities
+        let graph = SqliteGraphBackend::in_memory().unwrap();
+        let entities = [("Symbol", "test_function", "{\"kind\": \"Function\"}")].into_iter().chain(extra);
+        for (kind, name, data) in entities {
+            graph.insert_node(NodeSpec {
+                kind: kind.to_string(),
+                name: name.to_string(),
+                file_path: Some("/tmp/test.rs".to_string()),
+                data: serde_json::from_str(data).unwrap(),
+            }).unwrap();
+        }
+        assert_eq!(crate::storage::function_ids_from_graph(&graph).unwrap(), functions);
+    }
 }
@@ -502,13 +502,13 @@ mod tests {
                 [],
             ).unwrap();
 
-            // Create Mirage schema at version 1
+            // Create Mirage schema at the current version
             create_schema(&mut conn_newer, 7).unwrap();
 
-            // Manually bump to version 2 (simulating a newer database)
+            // Manually bump past the current version (simulating a newer database)
             conn_newer.execute(
                 "UPDATE mirage_meta SET mirage_schema_version = ? WHERE id = 1",
-                [2i32],
+                [MIRAGE_SCHEMA_VERSION + 1],
             ).unwrap();
         }
 
@@ -516,7 +516,7 @@ mod tests {
         let result = MirageDb::open(db_file_newer.path());
 
         assert!(result.is_err(),
-                "Opening a database with schema version 2 should fail when we only support version 1");
+                "Opening a database with a newer schema version should fail");
 
         if let Err(e) = result {
             let err = e.to_string();
@@ -613,7 +613,7 @@ mod tests {
 
         // Verify status reflects our test data
         assert_eq!(status.cfg_blocks, 2, "Should have 2 cfg_blocks");
-        assert_eq!(status.mirage_schema_version, 1, "Mirage schema should be v1");
+        assert_eq!(status.mirage_schema_version, 5, "Mirage schema should be v5");
         assert_eq!(status.magellan_schema_version, 7, "Magellan schema should be v7");
     }
 }
@@ -45,6 +45,17 @@ impl TestContext {
                 }
             });
 
+        // The fixture is at Mirage schema v1 and read-only commands never
+        // migrate, so upgrade it the way a user would
+        let status = Command::new(&mirage_bin)
+            .arg("--db")
+            .arg(&db_path)
+            .arg("init-schema")
+            .output()
+            .expect("Failed to run mirage init-schema")
+            .status;
+        assert!(status.success(), "init-schema should upgrade the test database");
+
         Self {
             mirage_bin,
             db_path,
@@ -267,6 +278,169 @@ fn test_status_command_json() {
     assert!(output.success(), "status --output json should succeed");
     assert!(output.stdout_contains("{"), "JSON output should contain opening brace");
     assert!(output.stdout_contains("}"), "JSON output should contain closing brace");
+
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).expect("status should print valid JSON");
+    assert_eq!(json["mirage_version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(json["backend"], "sqlite");
+}
+
+#[test]
+fn test_init_schema_on_magellan_only_database() {
+    let ctx = TestContext::new();
+    std::fs::remove_file(&ctx.db_path).unwrap();
+    let conn = rusqlite::Connection::open(&ctx.db_path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE magellan_meta (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            magellan_schema_version INTEGER NOT NULL,
+            sqlitegraph_schema_version INTEGER NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+        INSERT INTO magellan_meta VALUES (1, 7, 3, 0);
+        CREATE TABLE graph_entities (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            name TEXT NOT NULL,
+            file_path TEXT,
+            data TEXT NOT NULL
+        );",
+    ).unwrap();
+    drop(conn);
+
+    let output = ctx.run_command(&["status", "--output", "json"]);
+    assert!(!output.success(), "Mirage schema is missing");
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["code"], "E009");
+
+    let output = ctx.run_command(&["init-schema", "--output", "json"]);
+    assert!(output.success(), "init-schema should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["created"], true);
+
+    let output = ctx.run_command(&["status", "--output", "json"]);
+    assert!(output.success(), "status should succeed after init-schema: {}", output.stdout);
+}
+
+#[test]
+fn test_snapshot_rejected_without_opening_database() {
+    let ctx = TestContext::new();
+
+    let output = ctx.run_command(&["--snapshot", "0", "status", "--output", "json"]);
+    assert!(output.success(), "snapshot 0 is the current state: {}", output.stdout);
+
+    // A missing database would be E001 if --snapshot opened it
+    let missing = ctx._temp_dir.path().join("missing.db");
+    let output = Command::new(&ctx.mirage_bin)
+        .arg("--db").arg(&missing)
+        .args(["--snapshot", "7", "status", "--output", "json"])
+        .output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["data"]["error"], "SnapshotUnavailable");
+    assert!(!missing.exists());
+}
+
+#[test]
+fn test_status_output_file() {
+    let ctx = TestContext::new();
+    let out_path = ctx._temp_dir.path().join("status.json");
+    let output = ctx.run_command(&["status", "--output", "json", "--output-file", out_path.to_str().unwrap()]);
+
+    assert!(output.success(), "status --output-file should succeed: {}", output.stderr);
+    assert!(output.stdout.is_empty(), "nothing should leak to stdout, got: {}", output.stdout);
+
+    let contents = std::fs::read_to_string(&out_path).expect("output file should be written");
+    let json: serde_json::Value = serde_json::from_str(&contents).expect("output file should hold valid JSON");
+    assert_eq!(json["tool"], "mirage");
+
+    // The temporary file was renamed into place, not left behind
+    let leftovers: Vec<_> = std::fs::read_dir(ctx._temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "temporary files left behind: {:?}", leftovers);
+}
+
+#[test]
+fn test_output_file_gets_result_only() {
+    let ctx = TestContext::new();
+    let dir = TempDir::new().unwrap();
+    let db = import_cached_diamonds(&ctx, &dir, 1);
+
+    // The [OK] line from a successful human run belongs on stderr, not in the file
+    let out_path = dir.path().join("validate.txt");
+    let output = Command::new(&ctx.mirage_bin)
+        .arg("--db").arg(&db)
+        .args(["validate", "--output-file"]).arg(&out_path)
+        .output().unwrap();
+    assert!(output.status.success(), "validate should succeed: {}", String::from_utf8_lossy(&output.stderr));
+    let contents = std::fs::read_to_string(&out_path).expect("output file should be written");
+    assert!(!contents.contains("[OK]") && !contents.contains("[INFO]"), "diagnostics leaked into the file: {}", contents);
+    assert!(contents.contains("Functions checked: 1"), "result missing from the file: {}", contents);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[OK]"));
+}
+
+#[test]
+fn test_output_file_kept_on_failure() {
+    let ctx = TestContext::new();
+    let out_path = ctx._temp_dir.path().join("loops.json");
+    std::fs::write(&out_path, "previous result").unwrap();
+
+    let output = ctx.run_command(&[
+        "loops", "--function", "no_such_function", "--output", "json",
+        "--output-file", out_path.to_str().unwrap(),
+    ]);
+    assert!(!output.success());
+    assert_eq!(std::fs::read_to_string(&out_path).unwrap(), "previous result");
+
+    // The JSON error the file would have held is printed instead
+    let json: serde_json::Value = serde_json::from_str(output.stdout.trim())
+        .unwrap_or_else(|e| panic!("error not printed as JSON ({}): {:?}", e, output.stdout));
+    assert!(json["data"]["code"].is_string(), "not an error response: {}", json);
+
+    let leftovers: Vec<_> = std::fs::read_dir(ctx._temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "temporary files left behind: {:?}", leftovers);
+}
+
+#[test]
+fn test_no_color_output() {
+    let ctx = TestContext::new();
+    for args in [&["status", "--no-color"][..], &["cfg", "--function", "no_such_function", "--no-color"]] {
+        let output = ctx.run_command(args);
+        assert!(!output.stdout.contains('\x1b'), "ANSI escape in stdout: {:?}", output.stdout);
+        assert!(!output.stderr.contains('\x1b'), "ANSI escape in stderr: {:?}", output.stderr);
+    }
+}
+
+#[test]
+fn test_quiet_output() {
+    let ctx = TestContext::new();
+
+    let output = ctx.run_command(&["loops", "--function", "test_function", "--quiet"]);
+    assert!(output.success(), "loops --quiet should succeed: {}", output.stderr);
+    assert!(output.stdout_contains("Natural Loops: 0"), "Result is still printed: {:?}", output.stdout);
+    assert!(!output.stdout_contains("[INFO]"), "Info line in quiet output: {:?}", output.stdout);
+
+    let output = ctx.run_command(&["hotspots", "--quiet"]);
+    assert!(!output.stdout_contains("===>"), "Header in quiet output: {:?}", output.stdout);
+
+    // Errors still reach stderr; the hint is dropped
+    let output = ctx.run_command(&["cfg", "--function", "no_such_function", "--quiet"]);
+    assert!(!output.success());
+    assert!(output.stderr_contains("not found"), "Error is still reported: {:?}", output.stderr);
+    assert!(!output.stdout_contains("Hint:"), "Hint in quiet output: {:?}", output.stdout);
+
+    // JSON output carries no diagnostics with or without --quiet
+    for args in [&["loops", "--function", "test_function", "--output", "json"][..], &["hotspots", "--output", "json"]] {
+        let output = ctx.run_command(args);
+        serde_json::from_str::<serde_json::Value>(&output.stdout)
+            .unwrap_or_else(|e| panic!("{:?} printed non-JSON: {} ({:?})", args, e, output.stdout));
+    }
 }
 
 #[test]
@@ -280,6 +454,692 @@ fn test_cfg_command() {
             "cfg command should succeed or show not found error");
 }
 
+#[test]
+fn test_cfg_dot_rankdir() {
+    let ctx = TestContext::new();
+
+    let output = ctx.run_command(&["cfg", "--function", "test_function", "--format", "dot", "--rankdir", "LR"]);
+    assert!(output.success(), "cfg --rankdir LR failed: {}", output.stderr);
+    assert!(output.stdout_contains("rankdir=LR;"), "expected rankdir=LR in: {}", output.stdout);
+    assert!(output.stdout_contains("splines=curved;"), "expected default splines in: {}", output.stdout);
+
+    let output = ctx.run_command(&["cfg", "--function", "test_function", "--format", "dot", "--rankdir", "sideways"]);
+    assert!(!output.success(), "invalid --rankdir should be rejected");
+    assert!(output.stderr_contains("invalid value 'sideways'"), "unclear error: {}", output.stderr);
+    assert!(output.stderr_contains("LR"), "error should list the accepted values: {}", output.stderr);
+}
+
+#[test]
+fn test_cfg_functions_from_file() {
+    let ctx = TestContext::new();
+
+    let conn = rusqlite::Connection::open(&ctx.db_path).unwrap();
+    conn.execute(
+        "INSERT INTO graph_entities (kind, name, file_path, data)
+         VALUES ('Symbol', 'other_function', 'src/other.rs', '{\"kind\": \"Function\"}')",
+        [],
+    ).unwrap();
+    conn.execute(
+        "INSERT INTO cfg_blocks (function_id, kind, terminator, byte_start, byte_end,
+                                 start_line, start_col, end_line, end_col)
+         VALUES (2, 'entry', 'return', 0, 10, 1, 0, 1, 10)",
+        [],
+    ).unwrap();
+    drop(conn);
+
+    let list = ctx._temp_dir.path().join("functions.txt");
+    std::fs::write(&list, "# review list\ntest_function\n\nmissing_function\nother_function\n").unwrap();
+
+    let output = ctx.run_command(&["cfg", "--functions-from-file", list.to_str().unwrap(), "--output", "json"]);
+    assert!(output.success(), "cfg --functions-from-file failed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    let data = &json["data"];
+    assert_eq!(data["requested"], 3);
+
+    let results = data["results"].as_array().unwrap();
+    let names: Vec<&str> = results.iter().map(|r| r["function"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["test_function", "other_function"]);
+    assert_eq!(results[0]["data"]["blocks"].as_array().unwrap().len(), 3);
+    assert_eq!(results[1]["data"]["blocks"].as_array().unwrap().len(), 1);
+
+    let errors = data["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["function"], "missing_function");
+    assert!(errors[0]["error"].as_str().unwrap().contains("not found"));
+}
+
+#[test]
+fn test_cfg_functions_from_file_timings() {
+    let ctx = TestContext::new();
+
+    let conn = rusqlite::Connection::open(&ctx.db_path).unwrap();
+    for id in 2..6 {
+        conn.execute_batch(&format!(
+            "INSERT INTO graph_entities (id, kind, name, file_path, data)
+             VALUES ({id}, 'Symbol', 'f{id}', 'src/test.rs', '{{\"kind\": \"Function\"}}');
+             INSERT INTO cfg_blocks (function_id, kind, terminator, byte_start, byte_end,
+                                     start_line, start_col, end_line, end_col)
+             VALUES ({id}, 'entry', 'conditional', 0, 10, 1, 0, 1, 10),
+                    ({id}, 'normal', 'goto', 10, 20, 2, 0, 2, 10),
+                    ({id}, 'return', 'return', 20, 30, 3, 0, 3, 10);"
+        )).unwrap();
+    }
+    drop(conn);
+
+    let list = ctx._temp_dir.path().join("functions.txt");
+    std::fs::write(&list, "f2\ntest_function\nf3\nmissing\nf4\nf5\n").unwrap();
+    let list = list.to_str().unwrap();
+
+    let plain = ctx.run_command(&["cfg", "--functions-from-file", list, "--output", "json"]);
+    let timed = ctx.run_command(&["cfg", "--functions-from-file", list, "--timings", "--output", "json"]);
+    assert!(timed.success(), "cfg --timings failed: {}", timed.stderr);
+
+    let plain: serde_json::Value = serde_json::from_str(&plain.stdout).unwrap();
+    let timed: serde_json::Value = serde_json::from_str(&timed.stdout).unwrap();
+    assert_eq!(timed["data"]["results"], plain["data"]["results"]);
+    assert_eq!(timed["data"]["errors"], plain["data"]["errors"]);
+    assert_eq!(timed["data"]["results"].as_array().unwrap().len(), 5);
+
+    assert!(plain["data"].get("timings").is_none(), "timings are opt-in");
+    let timings = &timed["data"]["timings"];
+    assert_eq!(timings["jobs"], 1);
+    assert!(timings["wall_ms"].is_u64() && timings["function_ms"].is_u64());
+    assert!(timings.get("speedup").is_none());
+
+    // cfg batches run serially, so --jobs is rejected rather than ignored
+    let parallel = ctx.run_command(&["cfg", "--functions-from-file", list, "--jobs", "4"]);
+    assert!(!parallel.success());
+}
+
+/// Import `count` diamond functions `f1..` with their two paths already cached
+///
+/// The function hash column Magellan adds is filled in too, so `paths`
+/// reads those cached paths instead of enumerating.
+fn import_cached_diamonds(ctx: &TestContext, dir: &TempDir, count: usize) -> PathBuf {
+    let records: Vec<String> = (1..=count).map(|id| {
+        let block = |block: usize, kind: &str, terminator: serde_json::Value| {
+            serde_json::json!({"id": block, "kind": kind, "statements": [], "terminator": terminator, "source_location": null})
+        };
+        serde_json::json!({
+            "function_id": id, "name": format!("f{}", id), "data": {"kind": "Function"},
+            "blocks": [
+                block(0, "Entry", serde_json::json!({"SwitchInt": {"targets": [1], "otherwise": 2}})),
+                block(1, "Normal", serde_json::json!({"Goto": {"target": 3}})),
+                block(2, "Normal", serde_json::json!({"Goto": {"target": 3}})),
+                block(3, "Exit", serde_json::json!("Return")),
+            ],
+            "edges": [[0, 1, "TrueBranch"], [0, 2, "FalseBranch"], [1, 3, "Fallthrough"], [2, 3, "Fallthrough"]],
+            "paths": [
+                {"path_id": format!("p{}a", id), "blocks": [0, 1, 3], "kind": "Normal", "entry": 0, "exit": 3},
+                {"path_id": format!("p{}b", id), "blocks": [0, 2, 3], "kind": "Normal", "entry": 0, "exit": 3},
+            ],
+        }).to_string()
+    }).collect();
+    let jsonl = dir.path().join("functions.jsonl");
+    std::fs::write(&jsonl, records.join("\n")).unwrap();
+    let db = dir.path().join("batch.db");
+    let status = Command::new(&ctx.mirage_bin).arg("--db").arg(&db).arg("import").arg(&jsonl).status().unwrap();
+    assert!(status.success(), "import failed");
+    rusqlite::Connection::open(&db).unwrap().execute_batch(
+        "ALTER TABLE cfg_blocks ADD COLUMN function_hash TEXT;
+         UPDATE cfg_blocks SET function_hash = 'h' || function_id;",
+    ).unwrap();
+    db
+}
+
+#[test]
+fn test_import_export_error_codes() {
+    let ctx = TestContext::new();
+    let dir = TempDir::new().unwrap();
+    let run = |db: &std::path::Path, args: &[&str]| {
+        let output = Command::new(&ctx.mirage_bin)
+            .arg("--db").arg(db)
+            .args(args)
+            .args(["--output", "json"])
+            .output().unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        (output.status.code(), json["data"]["code"].as_str().unwrap_or_default().to_string())
+    };
+
+    // Malformed input and an existing target are usage errors
+    let jsonl = dir.path().join("bad.jsonl");
+    std::fs::write(&jsonl, "not json\n").unwrap();
+    let fresh = dir.path().join("fresh.db");
+    assert_eq!(run(&fresh, &["import", jsonl.to_str().unwrap()]), (Some(2), "E006".to_string()));
+    assert!(!fresh.exists());
+    assert_eq!(run(&ctx.db_path, &["import", jsonl.to_str().unwrap()]), (Some(2), "E006".to_string()));
+
+    // Failing to write the export file is an I/O error, not a database one
+    let db = import_cached_diamonds(&ctx, &dir, 1);
+    let unwritable = dir.path().to_str().unwrap();
+    assert_eq!(run(&db, &["export", "--function-data", unwritable]), (Some(1), "E010".to_string()));
+}
+
+#[test]
+fn test_paths_functions_from_file_jobs_match_serial() {
+    let ctx = TestContext::new();
+    let dir = TempDir::new().unwrap();
+
+    let db = import_cached_diamonds(&ctx, &dir, 4);
+
+    let list = dir.path().join("functions.txt");
+    std::fs::write(&list, "f1\nf2\nmissing\nf3\nf4\n").unwrap();
+    let run = |extra: &[&str]| {
+        let output = Command::new(&ctx.mirage_bin)
+            .arg("--db").arg(&db)
+            .args(["paths", "--functions-from-file"]).arg(&list)
+            .args(extra)
+            .args(["--output", "json"])
+            .output().unwrap();
+        assert!(output.status.success(), "paths batch failed: {}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    let serial = run(&[]);
+    let parallel = run(&["--jobs", "4"]);
+    let results = serial["data"]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0]["data"]["total_paths"], 2);
+    assert_eq!(parallel["data"]["results"], serial["data"]["results"]);
+    assert_eq!(parallel["data"]["errors"], serial["data"]["errors"]);
+}
+
+#[test]
+fn test_paths_max_paths_bypasses_cache() {
+    let ctx = TestContext::new();
+    let dir = TempDir::new().unwrap();
+    let db = import_cached_diamonds(&ctx, &dir, 1);
+
+    let run = |extra: &[&str]| {
+        let output = Command::new(&ctx.mirage_bin)
+            .arg("--db").arg(&db)
+            .args(["paths", "--function", "f1", "--output", "json"])
+            .args(extra)
+            .output().unwrap();
+        assert!(output.status.success(), "paths failed: {}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    // The cache holds both paths; a lower cap must not be answered from it
+    assert_eq!(run(&[])["data"]["total_paths"], 2);
+    let capped = run(&["--max-paths", "1"]);
+    assert_eq!(capped["data"]["total_paths"], 1);
+    assert_eq!(run(&[])["data"]["total_paths"], 2);
+}
+
+#[test]
+fn test_paths_summary_only_leaves_cache_alone() {
+    let ctx = TestContext::new();
+    let dir = TempDir::new().unwrap();
+    let db = import_cached_diamonds(&ctx, &dir, 1);
+    let cached_paths = || -> i64 {
+        rusqlite::Connection::open(&db).unwrap()
+            .query_row("SELECT COUNT(*) FROM cfg_paths", [], |row| row.get(0)).unwrap()
+    };
+    rusqlite::Connection::open(&db).unwrap().execute_batch(
+        "DELETE FROM cfg_path_elements; DELETE FROM cfg_paths;",
+    ).unwrap();
+
+    let summary = |extra: &[&str]| {
+        let output = Command::new(&ctx.mirage_bin)
+            .arg("--db").arg(&db)
+            .args(["paths", "--function", "f1", "--summary-only", "--output", "json"])
+            .args(extra)
+            .output().unwrap();
+        assert!(output.status.success(), "paths failed: {}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    // Counted without filters, enumerated with them; neither fills the cache
+    let counted = summary(&[]);
+    assert_eq!(counted["data"]["total"], 2);
+    assert_eq!(counted["data"]["min_len"], 3);
+    assert_eq!(summary(&["--ends-in", "normal-return"])["data"]["total"], 2);
+    assert_eq!(summary(&["--show-errors"])["data"]["total"], 0);
+    assert_eq!(cached_paths(), 0);
+}
+
+#[test]
+fn test_paths_max_loop_iterations_bypasses_cache() {
+    let ctx = TestContext::new();
+    let dir = TempDir::new().unwrap();
+    let db = import_cached_diamonds(&ctx, &dir, 1);
+
+    // Drop one cached path so a cache hit stands out from a fresh enumeration
+    rusqlite::Connection::open(&db).unwrap().execute_batch(
+        "DELETE FROM cfg_path_elements WHERE path_id = 'p1b';
+         DELETE FROM cfg_paths WHERE path_id = 'p1b';",
+    ).unwrap();
+
+    let total = |extra: &[&str]| {
+        let output = Command::new(&ctx.mirage_bin)
+            .arg("--db").arg(&db)
+            .args(["paths", "--function", "f1", "--output", "json"])
+            .args(extra)
+            .output().unwrap();
+        assert!(output.status.success(), "paths failed: {}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()["data"]["total_paths"].clone()
+    };
+
+    assert_eq!(total(&[]), 1);
+    assert_eq!(total(&["--max-loop-iterations", "2"]), 2);
+}
+
+#[test]
+fn test_paths_max_length_bypasses_cache() {
+    let ctx = TestContext::new();
+    let dir = TempDir::new().unwrap();
+    let db = import_cached_diamonds(&ctx, &dir, 1);
+    let total = |extra: &[&str]| {
+        let output = Command::new(&ctx.mirage_bin)
+            .arg("--db").arg(&db)
+            .args(["paths", "--function", "f1", "--output", "json"])
+            .args(extra)
+            .output().unwrap();
+        assert!(output.status.success(), "paths failed: {}", String::from_utf8_lossy(&output.stderr));
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()["data"]["total_paths"].clone()
+    };
+
+    // Drop one cached path so a cache hit stands out from a fresh enumeration
+    rusqlite::Connection::open(&db).unwrap().execute_batch(
+        "DELETE FROM cfg_path_elements WHERE path_id = 'p1b';
+         DELETE FROM cfg_paths WHERE path_id = 'p1b';",
+    ).unwrap();
+
+    // Pruned runs neither read the cache nor replace what default runs see
+    assert_eq!(total(&["--max-length", "2"]), 0);
+    assert_eq!(total(&[]), 1);
+    assert_eq!(total(&["--max-length", "5"]), 2);
+}
+
+#[test]
+fn test_cfg_all_json() {
+    let ctx = TestContext::new();
+
+    // Index a second function next to test_function
+    let conn = rusqlite::Connection::open(&ctx.db_path).unwrap();
+    conn.execute(
+        "INSERT INTO graph_entities (kind, name, file_path, data)
+         VALUES ('Symbol', 'other_function', 'src/other.rs', '{\"kind\": \"Function\"}')",
+        [],
+    ).unwrap();
+    conn.execute(
+        "INSERT INTO cfg_blocks (function_id, kind, terminator, byte_start, byte_end,
+                                 start_line, start_col, end_line, end_col)
+         VALUES (2, 'entry', 'return', 0, 10, 1, 0, 1, 10)",
+        [],
+    ).unwrap();
+    drop(conn);
+
+    let output = ctx.run_command(&["cfg", "--all", "--output", "json"]);
+    assert!(output.success(), "cfg --all should succeed: {}", output.stderr);
+
+    let json: serde_json::Value = serde_json::from_str(&output.stdout)
+        .expect("cfg --all JSON output should parse");
+    let names: Vec<&str> = json["data"]
+        .as_array()
+        .expect("data should be an array of CFG exports")
+        .iter()
+        .map(|export| export["function_name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["other_function", "test_function"]);
+
+    // --filter keeps only matching functions
+    let output = ctx.run_command(&["cfg", "--all", "--filter", "other", "--output", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"].as_array().unwrap().len(), 1);
+    assert_eq!(json["data"][0]["function_name"], "other_function");
+}
+
+#[test]
+fn test_terminators_command_json() {
+    let ctx = TestContext::new();
+    let output = ctx.run_command(&["terminators", "--all", "--output", "json"]);
+
+    assert!(output.success(), "terminators --all should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["function_count"], 1);
+    assert_eq!(json["data"]["functions"][0]["function"], "test_function");
+}
+
+#[test]
+fn test_block_at_command_json() {
+    let ctx = TestContext::new();
+    let output = ctx.run_command(&["block-at", "--function", "test_function", "--line", "5", "--output", "json"]);
+
+    assert!(output.success(), "block-at should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["line"], 5);
+
+    // Line 5 is the end of the conditional block and the start of the return block
+    let blocks = json["data"]["blocks"].as_array().unwrap();
+    let ids: Vec<u64> = blocks.iter().map(|b| b["block_id"].as_u64().unwrap()).collect();
+    assert_eq!(ids, vec![1, 2], "Ordered by byte offset");
+    assert_eq!(blocks[1]["terminator"], "Return");
+
+    let output = ctx.run_command(&["block-at", "--function", "test_function", "--line", "99"]);
+    assert!(!output.success(), "No block covers line 99");
+}
+
+#[test]
+fn test_unreachable_with_source() {
+    let ctx = TestContext::new();
+    let source_path = ctx._temp_dir.path().join("dead.rs");
+    std::fs::write(&source_path, "fn dead_code_fn() {\n    return;\n    never_runs();\n}\n").unwrap();
+
+    // Entry returns immediately; the second block has no way in
+    let conn = rusqlite::Connection::open(&ctx.db_path).unwrap();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, name, file_path, data) VALUES (2, 'function', 'dead_code_fn', ?, '{}')",
+        [source_path.to_str().unwrap()],
+    ).unwrap();
+    conn.execute_batch(
+        "INSERT INTO cfg_blocks (function_id, kind, terminator, byte_start, byte_end,
+                                 start_line, start_col, end_line, end_col)
+         VALUES (2, 'entry', 'return', 0, 31, 1, 0, 2, 11),
+                (2, 'normal', 'return', 32, 49, 3, 4, 3, 17);",
+    ).unwrap();
+    drop(conn);
+
+    let output = ctx.run_command(&["unreachable", "--with-source", "--output", "json"]);
+    assert!(output.success(), "unreachable --with-source should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    let blocks = json["data"]["blocks"].as_array().unwrap();
+    assert_eq!(blocks.len(), 1);
+
+    let lines = blocks[0]["source"]["lines"].as_array().expect("snippet should be included");
+    let covered: Vec<&serde_json::Value> = lines.iter().filter(|l| l["covered"] == true).collect();
+    assert_eq!(covered.len(), 1);
+    assert_eq!(covered[0]["line"], 3);
+    assert_eq!(covered[0]["text"], "    never_runs();");
+
+    // A missing file drops the snippet with a note instead of failing
+    std::fs::remove_file(&source_path).unwrap();
+    let output = ctx.run_command(&["unreachable", "--with-source", "--output", "json"]);
+    assert!(output.success());
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    let block = &json["data"]["blocks"][0];
+    assert!(block.get("source").is_none());
+    assert!(block["source_note"].as_str().unwrap().contains("not found"));
+}
+
+#[test]
+fn test_redundant_command_json() {
+    let ctx = TestContext::new();
+    let output = ctx.run_command(&["redundant", "--function", "test_function", "--output", "json"]);
+
+    assert!(output.success(), "redundant should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+
+    // The conditional block's only arm is the return block
+    let branches = json["data"]["branches"].as_array().unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0]["block_id"], 1);
+    assert_eq!(branches[0]["kind"], "arms_merge");
+    assert_eq!(branches[0]["target"], 2);
+}
+
+#[test]
+fn test_coverage_command_json() {
+    let ctx = TestContext::new();
+    let output = ctx.run_command(&["coverage", "--function", "test_function", "--output", "json"]);
+
+    assert!(output.success(), "coverage should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["covered_count"], json["data"]["edge_count"]);
+    assert_eq!(json["data"]["coverage_percent"], 100.0);
+    assert!(json["data"]["uncovered"].as_array().unwrap().is_empty());
+
+    // Two blocks cannot hold the 0 -> 1 -> 2 path, so no edge is covered
+    let output = ctx.run_command(&["coverage", "--function", "test_function", "--max-length", "2", "--output", "json"]);
+    assert!(output.success(), "coverage should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["covered_count"], 0);
+    let uncovered = json["data"]["uncovered"].as_array().unwrap();
+    assert_eq!(uncovered[0]["from_block"], 0);
+    assert_eq!(uncovered[0]["to_block"], 1);
+    assert!(uncovered[0]["edge_type"].is_string());
+}
+
+#[test]
+fn test_analysis_errors_are_json() {
+    let ctx = TestContext::new();
+
+    // `spin` never returns: its last block jumps nowhere, so it has no exit block
+    let conn = rusqlite::Connection::open(&ctx.db_path).unwrap();
+    conn.execute_batch(
+        "INSERT INTO graph_entities (id, kind, name, file_path, data)
+         VALUES (2, 'Symbol', 'spin', 'src/test.rs', '{\"kind\": \"Function\"}');
+         INSERT INTO cfg_blocks (function_id, kind, terminator, byte_start, byte_end,
+                                 start_line, start_col, end_line, end_col)
+         VALUES (2, 'entry', 'fallthrough', 0, 10, 1, 0, 1, 10),
+                (2, 'loop', 'goto', 10, 20, 2, 0, 2, 10);",
+    ).unwrap();
+    drop(conn);
+
+    let cases: [&[&str]; 3] = [
+        &["dominators", "--function", "spin", "--post"],
+        &["frontiers", "--function", "spin", "--post"],
+        &["control-deps", "--function", "spin", "--block", "1"],
+    ];
+    for args in cases {
+        let output = ctx.run_command(&[args, &["--output", "json"]].concat());
+        assert!(!output.success(), "{:?} should fail", args);
+        let json: serde_json::Value = serde_json::from_str(&output.stdout)
+            .unwrap_or_else(|e| panic!("{:?} should print a JSON error ({}): {}", args, e, output.stdout));
+        assert_eq!(json["data"]["error"], "DominatorTreeError", "{:?}", args);
+        assert_eq!(json["data"]["code"], "E007", "{:?}", args);
+        assert_eq!(output.status.code(), Some(3), "{:?}: E007 exits with the database status", args);
+    }
+}
+
+#[test]
+fn test_functions_command() {
+    let ctx = TestContext::new();
+    let conn = rusqlite::Connection::open(&ctx.db_path).unwrap();
+    conn.execute_batch(
+        "INSERT INTO graph_entities (id, kind, name, file_path, data)
+         VALUES (2, 'Symbol', 'helper', 'src/util.rs', '{\"kind\": \"Function\", \"fqn\": \"util::helper\"}'),
+                (3, 'Symbol', 'Config', 'src/util.rs', '{\"kind\": \"Struct\"}');
+         INSERT INTO cfg_blocks (function_id, kind, terminator, byte_start, byte_end,
+                                 start_line, start_col, end_line, end_col)
+         VALUES (2, 'entry', 'return', 0, 10, 1, 0, 1, 10);",
+    ).unwrap();
+    drop(conn);
+
+    let output = ctx.run_command(&["functions", "--output", "json"]);
+    assert!(output.success(), "functions should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["count"], 2, "the struct is not a function");
+    let functions = json["data"]["functions"].as_array().unwrap();
+    assert_eq!(functions[0]["name"], "helper");
+    assert_eq!(functions[0]["fqn"], "util::helper");
+    assert_eq!(functions[0]["block_count"], 1);
+    assert_eq!(functions[0]["edge_count"], 0);
+    assert_eq!(functions[1]["name"], "test_function");
+    assert_eq!(functions[1]["file_path"], "src/test.rs");
+    assert_eq!(functions[1]["block_count"], 3);
+    assert_eq!(functions[1]["edge_count"], 2);
+    assert_eq!(functions[1]["paths_cached"], false);
+
+    let output = ctx.run_command(&["functions", "--sort", "blocks", "--limit", "1", "--output", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["count"], 1);
+    assert_eq!(json["data"]["functions"][0]["name"], "test_function");
+
+    let output = ctx.run_command(&["functions", "--filter", "util::", "--output", "json"]);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["count"], 1, "--filter matches the FQN too");
+    assert_eq!(json["data"]["functions"][0]["id"], 2);
+}
+
+#[test]
+fn test_functions_csv() {
+    let ctx = TestContext::new();
+    let conn = rusqlite::Connection::open(&ctx.db_path).unwrap();
+    conn.execute(
+        "INSERT INTO graph_entities (id, kind, name, file_path, data)
+         VALUES (2, 'Symbol', 'helper', 'src/a,b.rs', '{\"kind\": \"Function\", \"fqn\": \"util::helper\"}')",
+        [],
+    ).unwrap();
+    drop(conn);
+
+    let output = ctx.run_command(&["functions", "--format", "csv"]);
+    assert!(output.success(), "functions --format csv should succeed: {}", output.stderr);
+    let lines: Vec<&str> = output.stdout.lines().collect();
+    assert_eq!(lines, [
+        "id,name,fqn,file_path,block_count,edge_count,paths_cached,created_at",
+        "2,helper,util::helper,\"src/a,b.rs\",0,0,false,",
+        "1,test_function,,src/test.rs,3,2,false,",
+    ]);
+
+    // Functions without CFG blocks are skipped; every terminator kind gets a column
+    let output = ctx.run_command(&["terminators", "--all", "--format", "csv"]);
+    let lines: Vec<&str> = output.stdout.lines().collect();
+    assert_eq!(lines, ["function,Goto,SwitchInt,Return,Unreachable,Call,Abort", "test_function,1,1,1,0,0,0"]);
+}
+
+#[test]
+fn test_validate_command() {
+    let ctx = TestContext::new();
+
+    let output = ctx.run_command(&["validate", "--output", "json"]);
+    assert!(output.success(), "a well-formed index should validate: {}", output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["functions_checked"], 1);
+    assert_eq!(json["data"]["invalid_count"], 0);
+
+    // Two returning blocks with no edge between them: the second is detached
+    let conn = rusqlite::Connection::open(&ctx.db_path).unwrap();
+    conn.execute_batch(
+        "INSERT INTO graph_entities (id, kind, name, file_path, data)
+         VALUES (2, 'Symbol', 'torn', 'src/test.rs', '{\"kind\": \"Function\"}');
+         INSERT INTO cfg_blocks (function_id, kind, terminator, byte_start, byte_end,
+                                 start_line, start_col, end_line, end_col)
+         VALUES (2, 'entry', 'return', 0, 10, 1, 0, 1, 10),
+                (2, 'return', 'return', 10, 20, 2, 0, 2, 10);",
+    ).unwrap();
+    drop(conn);
+
+    let output = ctx.run_command(&["validate", "--output", "json"]);
+    assert_eq!(output.status.code(), Some(5), "violations exit with the validation status");
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["functions_checked"], 2);
+    assert_eq!(json["data"]["invalid_count"], 1);
+    let invalid = &json["data"]["invalid_functions"][0];
+    assert_eq!(invalid["name"], "torn");
+    let kinds: Vec<&str> = invalid["violations"].as_array().unwrap()
+        .iter()
+        .map(|v| v["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, ["multiple_entries", "unreachable_exit", "disconnected_component"]);
+    assert_eq!(invalid["violations"][2]["blocks"], serde_json::json!([1]));
+}
+
+#[test]
+fn test_exits_command() {
+    let ctx = TestContext::new();
+    let output = ctx.run_command(&["exits", "--function", "test_function"]);
+
+    assert!(output.success() || output.stderr_contains("not found") || output.stderr_contains("No function"),
+            "exits command should succeed or show not found error");
+}
+
+#[test]
+fn test_repl_runs_commands_from_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let ctx = TestContext::new();
+    let mut child = Command::new(&ctx.mirage_bin)
+        .args(["repl", "--output", "json", "--db"])
+        .arg(&ctx.db_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run mirage");
+
+    // The failing command in the middle must not end the session
+    child.stdin.take().unwrap()
+        .write_all(b"status\n# comment\nloops --function no_such_function\nterminators --function test_function\nquit\nstatus\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "repl should exit cleanly: {}", String::from_utf8_lossy(&output.stderr));
+    let responses: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each command should print one JSON line"))
+        .collect();
+    assert_eq!(responses.len(), 3, "quit should stop before the last status: {}", stdout);
+    assert!(responses[0]["data"].get("cfg_blocks").is_some());
+    assert_eq!(responses[1]["data"]["error"], "FunctionNotFound");
+    assert_eq!(responses[2]["data"]["function"], "test_function");
+}
+
+#[test]
+fn test_repl_rejects_output_file() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let ctx = TestContext::new();
+    let out_path = ctx._temp_dir.path().join("status.json");
+    let mut child = Command::new(&ctx.mirage_bin)
+        .args(["repl", "--output", "json", "--db"])
+        .arg(&ctx.db_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run mirage");
+
+    let line = format!("status --output-file {}\nstatus\n", out_path.display());
+    child.stdin.take().unwrap().write_all(line.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--output-file is not supported in the REPL"));
+    assert!(!out_path.exists(), "the REPL must not write the output file");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 1, "only the second status should print");
+}
+
+#[test]
+fn test_repl_runs_functions_from_file() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let ctx = TestContext::new();
+    let list = ctx._temp_dir.path().join("functions.txt");
+    std::fs::write(&list, "test_function\nmissing_function\n").unwrap();
+
+    let mut child = Command::new(&ctx.mirage_bin)
+        .args(["repl", "--output", "json", "--db"])
+        .arg(&ctx.db_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run mirage");
+    child.stdin.take().unwrap()
+        .write_all(format!("cfg --functions-from-file {}\nstatus\n", list.display()).as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "repl should exit cleanly: {}", String::from_utf8_lossy(&output.stderr));
+    let responses: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each command should print one JSON line"))
+        .collect();
+    assert_eq!(responses.len(), 2, "the batch prints one response: {}", stdout);
+    let batch = &responses[0]["data"];
+    assert_eq!(batch["results"][0]["function"], "test_function");
+    assert_eq!(batch["results"][0]["data"]["blocks"].as_array().unwrap().len(), 3);
+    assert_eq!(batch["errors"][0]["function"], "missing_function");
+    assert!(responses[1]["data"].get("cfg_blocks").is_some(), "the session carries on after the batch");
+}
+
 #[test]
 fn test_paths_command() {
     let ctx = TestContext::new();
@@ -290,6 +1150,18 @@ fn test_paths_command() {
             "paths command should succeed or show not found error");
 }
 
+#[test]
+fn test_paths_rejects_empty_length_band() {
+    let ctx = TestContext::new();
+    let output = ctx.run_command(&[
+        "paths", "--function", "test_function", "--min-length", "5", "--max-length", "3", "--output", "json",
+    ]);
+
+    assert_eq!(output.status.code(), Some(2), "Empty band is a usage error");
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["error"], "InvalidInput");
+}
+
 #[test]
 fn test_dominators_command() {
     let ctx = TestContext::new();
@@ -299,6 +1171,67 @@ fn test_dominators_command() {
             "dominators command should succeed or show not found error");
 }
 
+#[test]
+fn test_dominators_must_pass_through_strict() {
+    let ctx = TestContext::new();
+    for post in [false, true] {
+        let must_pass = |strict: bool| {
+            let mut args = vec!["dominators", "--function", "test_function", "--must-pass-through", "1", "--output", "json"];
+            if post {
+                args.push("--post");
+            }
+            if strict {
+                args.push("--strict");
+            }
+            let output = ctx.run_command(&args);
+            assert!(output.success(), "dominators {:?} should succeed: {}", args, output.stderr);
+            let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+            json["data"]["must_pass_through"]["must_pass"].as_array().unwrap().clone()
+        };
+
+        // A block dominates itself, so it is in its own set unless --strict
+        let inclusive = must_pass(false);
+        let strict = must_pass(true);
+        assert!(inclusive.contains(&serde_json::json!(1)), "post={}: {:?}", post, inclusive);
+        assert!(!strict.contains(&serde_json::json!(1)), "post={}: {:?}", post, strict);
+        assert_eq!(strict.len(), inclusive.len() - 1);
+    }
+}
+
+#[test]
+fn test_dominators_must_pass_through_non_numeric_json() {
+    let ctx = TestContext::new();
+    for post in [false, true] {
+        let mut args = vec!["dominators", "--function", "test_function", "--must-pass-through", "abc", "--output", "json"];
+        if post {
+            args.push("--post");
+        }
+        let output = ctx.run_command(&args);
+        assert_eq!(output.status.code(), Some(2), "post={}: {}", post, output.stderr);
+        let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+        assert_eq!(json["data"]["error"], "InvalidInput");
+        assert_eq!(json["data"]["code"], "E006");
+    }
+}
+
+#[test]
+fn test_dominators_format_dot() {
+    let ctx = TestContext::new();
+
+    let output = ctx.run_command(&["dominators", "--function", "test_function", "--format", "dot"]);
+    assert!(output.success(), "dominators --format dot failed: {}", output.stderr);
+    assert!(output.stdout.starts_with("digraph DomTree {"), "unexpected output: {}", output.stdout);
+    assert!(output.stdout_contains("\"0\" -> \"1\";"));
+    assert!(output.stdout_contains("\"1\" -> \"2\";"));
+    assert!(!output.stdout_contains("constraint=false"));
+
+    // The post-dominator tree is rooted at the exit
+    let output = ctx.run_command(&["dominators", "--function", "test_function", "--format", "dot", "--post", "--with-cfg"]);
+    assert!(output.success(), "dominators --format dot --post failed: {}", output.stderr);
+    assert!(output.stdout_contains("\"2\" -> \"1\";"));
+    assert!(output.stdout_contains("constraint=false"));
+}
+
 #[test]
 fn test_loops_command() {
     let ctx = TestContext::new();
@@ -308,6 +1241,19 @@ fn test_loops_command() {
             "loops command should succeed or show not found error");
 }
 
+#[test]
+fn test_unreachable_conditional() {
+    let ctx = TestContext::new();
+
+    // Stored CFGs carry no literal conditions, so nothing extra is reported
+    let plain = ctx.run_command(&["unreachable", "--output", "json"]);
+    let conditional = ctx.run_command(&["unreachable", "--conditional", "--output", "json"]);
+    assert!(conditional.success(), "unreachable --conditional should succeed: {}", conditional.stderr);
+    let plain: serde_json::Value = serde_json::from_str(&plain.stdout).unwrap();
+    let conditional: serde_json::Value = serde_json::from_str(&conditional.stdout).unwrap();
+    assert_eq!(conditional["data"]["unreachable_count"], plain["data"]["unreachable_count"]);
+}
+
 #[test]
 fn test_unreachable_command() {
     let ctx = TestContext::new();
@@ -336,6 +1282,75 @@ fn test_frontiers_command() {
             "frontiers command should succeed or show not found error");
 }
 
+#[test]
+fn test_control_deps_command_json() {
+    let ctx = TestContext::new();
+    let output = ctx.run_command(&["control-deps", "--function", "test_function", "--block", "2", "--output", "json"]);
+
+    assert!(output.success(), "control-deps should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["block"], 2);
+    // Straight-line CFG: nothing decides whether the return runs
+    assert_eq!(json["data"]["control_dependences"], serde_json::json!([]));
+
+    let output = ctx.run_command(&["control-deps", "--function", "test_function", "--block", "99"]);
+    assert!(!output.success(), "Unknown block should fail");
+}
+
+#[test]
+fn test_cleanup_command_json() {
+    let ctx = TestContext::new();
+    let output = ctx.run_command(&["cleanup", "--function", "test_function", "--block", "0", "--output", "json"]);
+
+    assert!(output.success(), "cleanup should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["block"], 0);
+    // Straight-line CFG: everything after the entry runs, in order
+    assert_eq!(json["data"]["guaranteed_after"], serde_json::json!([1, 2]));
+
+    let output = ctx.run_command(&["cleanup", "--function", "test_function", "--block", "99"]);
+    assert!(!output.success(), "Unknown block should fail");
+}
+
+#[test]
+fn test_longest_path_command_json() {
+    let ctx = TestContext::new();
+    let output = ctx.run_command(&["longest-path", "--function", "test_function", "--output", "json"]);
+
+    assert!(output.success(), "longest-path should succeed: {}", output.stderr);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json["data"]["blocks"], serde_json::json!([0, 1, 2]));
+    assert_eq!(json["data"]["length"], 3);
+    assert_eq!(json["data"]["exit"], 2);
+}
+
+#[test]
+fn test_kind_listing_commands() {
+    let ctx = TestContext::new();
+    let names = |command: &str| -> Vec<String> {
+        let output = ctx.run_command(&[command, "--output", "json"]);
+        assert!(output.success(), "{} should succeed: {}", command, output.stderr);
+        let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+        json["data"]["values"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|value| {
+                assert!(!value["description"].as_str().unwrap().is_empty());
+                value["name"].as_str().unwrap().to_string()
+            })
+            .collect()
+    };
+
+    // Update these lists (and downstream consumers) when a variant is added
+    assert_eq!(
+        names("edge-types"),
+        ["TrueBranch", "FalseBranch", "Fallthrough", "LoopBack", "LoopExit", "Exception", "Call", "Return", "Unwind", "Resume"]
+    );
+    assert_eq!(names("terminator-kinds"), ["Goto", "SwitchInt", "Return", "Unreachable", "Call", "Abort"]);
+    assert_eq!(names("block-kinds"), ["Entry", "Normal", "Exit", "Panic"]);
+}
+
 #[test]
 fn test_cycles_command() {
     let ctx = TestContext::new();
@@ -470,3 +1485,55 @@ fn test_invalid_database() {
     assert!(!output.status.success() || stderr.contains("not found") || stdout.contains("not found"),
             "Invalid database path should show error");
 }
+
+#[test]
+fn test_paths_db2_comparison() {
+    let ctx = TestContext::new();
+    let dir = TempDir::new().unwrap();
+    let block = |id: usize, kind: &str, terminator: serde_json::Value| {
+        serde_json::json!({"id": id, "kind": kind, "statements": [], "terminator": terminator, "source_location": null})
+    };
+    let import = |name: &str, blocks: Vec<serde_json::Value>, edges: serde_json::Value| {
+        let record = serde_json::json!({
+            "function_id": 1, "name": "parse", "data": {"kind": "Function"}, "blocks": blocks, "edges": edges,
+        });
+        let jsonl = dir.path().join(format!("{}.jsonl", name));
+        std::fs::write(&jsonl, format!("{}\n", record)).unwrap();
+        let db = dir.path().join(format!("{}.db", name));
+        let status = Command::new(&ctx.mirage_bin).arg("--db").arg(&db).arg("import").arg(&jsonl).status().unwrap();
+        assert!(status.success(), "import of {} failed", name);
+        db
+    };
+
+    // Version 1: 0 -> 1 -> 2; version 2 adds a branch that skips block 1
+    let v1 = import("v1", vec![
+        block(0, "Entry", serde_json::json!({"Goto": {"target": 1}})),
+        block(1, "Normal", serde_json::json!({"Goto": {"target": 2}})),
+        block(2, "Exit", serde_json::json!("Return")),
+    ], serde_json::json!([[0, 1, "Fallthrough"], [1, 2, "Fallthrough"]]));
+    let v2 = import("v2", vec![
+        block(0, "Entry", serde_json::json!({"SwitchInt": {"targets": [1], "otherwise": 2}})),
+        block(1, "Normal", serde_json::json!({"Goto": {"target": 2}})),
+        block(2, "Exit", serde_json::json!("Return")),
+    ], serde_json::json!([[0, 1, "TrueBranch"], [0, 2, "FalseBranch"], [1, 2, "Fallthrough"]]));
+
+    let output = Command::new(&ctx.mirage_bin)
+        .arg("--db").arg(&v1)
+        .args(["paths", "--function", "parse", "--output", "json", "--db2"]).arg(&v2)
+        .output().unwrap();
+    assert!(output.status.success(), "paths --db2 failed: {}", String::from_utf8_lossy(&output.stderr));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["data"]["added"], serde_json::json!([[0, 2]]));
+    assert_eq!(json["data"]["removed"], serde_json::json!([]));
+    assert_eq!(json["data"]["unchanged"], serde_json::json!([[0, 1, 2]]));
+    assert_eq!(json["data"]["added_count"], 1);
+
+    // A function missing from the second database is reported against that database
+    let output = Command::new(&ctx.mirage_bin)
+        .arg("--db").arg(&ctx.db_path)
+        .args(["paths", "--function", "test_function", "--db2"]).arg(&v2)
+        .output().unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not found in database") && stderr.contains("v2.db"), "Unclear error: {}", stderr);
+}